@@ -13,6 +13,7 @@
 //! - Build typed XSD schema trees for generating XML request bodies
 //! - Detect SOAP 1.1 and SOAP 1.2 bindings
 //! - Circular dependency protection during import resolution
+//! - Validate SOAP responses against the operation's output schema
 //!
 //! ## Quick Start
 //!
@@ -45,7 +46,9 @@ pub mod imports;
 pub mod parser;
 pub mod schema;
 pub mod types;
+pub mod validator;
 
 pub use imports::{ImportDeclaration, ImportResolver, ImportType};
 pub use parser::WsdlParser;
 pub use types::{ApiService, SchemaNode, ServiceOperation};
+pub use validator::{ResponseSchema, SchemaValidator, SchemaViolation};
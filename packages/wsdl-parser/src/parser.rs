@@ -3,7 +3,8 @@
 //! Parses WSDL 1.1 XML documents and extracts service definitions.
 
 use super::types::{ApiService, ServiceOperation};
-use super::schema::{SchemaParser, SchemaDefinition};
+use super::schema::{ComplexTypeDef, ElementDef, SchemaParser, SchemaDefinition};
+use super::validator::ResponseSchema;
 use anyhow::Result;
 use quick_xml::events::{Event, BytesStart};
 use quick_xml::Reader;
@@ -122,6 +123,64 @@ impl WsdlParser {
         Ok(api_services)
     }
     
+    /// Schema of an operation's response, for [`SchemaValidator`](crate::validator::SchemaValidator)
+    ///
+    /// Document/literal operations use the output part's element. For parts
+    /// declared with `type=` (RPC style) the response is a `{operation}Response`
+    /// wrapper holding one element per part.
+    pub fn response_schema(wsdl_xml: &str, operation_name: &str) -> Result<ResponseSchema> {
+        let defs = Self::parse_definitions(wsdl_xml)?;
+
+        let operation = defs
+            .port_types
+            .values()
+            .find_map(|pt| pt.operations.get(operation_name))
+            .ok_or_else(|| anyhow::anyhow!("Operation '{}' not found in WSDL", operation_name))?;
+        let message = defs
+            .messages
+            .get(&operation.output_message)
+            .ok_or_else(|| anyhow::anyhow!("Operation '{}' has no output message", operation_name))?;
+
+        let element = match message.parts.first().and_then(|p| p.element.as_ref()) {
+            Some(element_name) => defs
+                .schemas
+                .iter()
+                .find_map(|s| s.elements.get(element_name))
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Element '{}' not found in WSDL schemas", element_name))?,
+            None => ElementDef {
+                name: format!("{}Response", operation_name),
+                type_name: None,
+                min_occurs: None,
+                max_occurs: None,
+                inline_type: Some(ComplexTypeDef {
+                    name: None,
+                    base_type: None,
+                    sequence: message
+                        .parts
+                        .iter()
+                        .map(|part| ElementDef {
+                            name: part.name.clone(),
+                            type_name: part.type_name.clone(),
+                            min_occurs: None,
+                            max_occurs: None,
+                            inline_type: None,
+                            inline_simple_type: None,
+                        })
+                        .collect(),
+                    choice: vec![],
+                    attributes: vec![],
+                }),
+                inline_simple_type: None,
+            },
+        };
+
+        Ok(ResponseSchema {
+            element,
+            schemas: defs.schemas,
+        })
+    }
+    
     /// Parse a WSDL document with automatic import resolution
     /// 
     /// This method extends the basic parse() by automatically fetching and resolving
@@ -893,8 +952,9 @@ impl WsdlParser {
                 for (op_name, operation) in &port_type.operations {
                     let soap_action = binding.operations.get(op_name).map(|bo| bo.soap_action.clone());
                     
-                    // Build schema tree from input message
+                    // Build schema trees from the input and output messages
                     let full_schema = Self::build_schema_for_operation(operation, defs);
+                    let output_schema = Self::build_output_schema_for_operation(operation, defs);
                     
                     log::debug!("Creating operation '{}' with endpoint: {:?}", op_name, endpoint);
                     
@@ -907,6 +967,7 @@ impl WsdlParser {
                         port_name: ports.first().cloned(),
                         original_endpoint: endpoint.clone(),
                         full_schema,
+                        output_schema,
                         action: soap_action,
                     });
                 }
@@ -959,6 +1020,58 @@ impl WsdlParser {
         None
     }
 
+    /// Schema tree of the response, mirroring [`Self::response_schema`]: the
+    /// output part's element, or an `{operation}Response` wrapper with one
+    /// child per `type=` part
+    fn build_output_schema_for_operation(operation: &WsdlOperation, defs: &WsdlDefinitions) -> Option<super::types::SchemaNode> {
+        let message = defs.messages.get(&operation.output_message)?;
+
+        if let Some(element_name) = message.parts.first().and_then(|p| p.element.as_ref()) {
+            return defs
+                .schemas
+                .iter()
+                .find_map(|schema| SchemaParser::build_schema_tree(element_name, schema));
+        }
+
+        let children = message
+            .parts
+            .iter()
+            .map(|part| {
+                let type_name = part.type_name.clone().unwrap_or_else(|| "string".to_string());
+                let mut node = defs
+                    .schemas
+                    .iter()
+                    .find_map(|schema| SchemaParser::build_schema_tree_from_type(&type_name, schema))
+                    .unwrap_or_else(|| super::types::SchemaNode {
+                        name: String::new(),
+                        // Built-in XSD type such as xsd:string
+                        node_type: type_name.clone(),
+                        kind: "simple".to_string(),
+                        min_occurs: None,
+                        max_occurs: None,
+                        documentation: None,
+                        children: None,
+                        options: None,
+                        is_optional: None,
+                    });
+                node.name = part.name.clone();
+                node
+            })
+            .collect();
+
+        Some(super::types::SchemaNode {
+            name: format!("{}Response", operation.name),
+            node_type: format!("{}Response", operation.name),
+            kind: "complex".to_string(),
+            min_occurs: None,
+            max_occurs: None,
+            documentation: None,
+            children: Some(children),
+            options: None,
+            is_optional: None,
+        })
+    }
+
     // Utility functions
     fn local_name(e: &BytesStart) -> String {
         let name_bytes = e.name();
@@ -1029,6 +1142,14 @@ mod tests {
         assert_eq!(services[0].name, "HelloServiceSoap");
         assert_eq!(services[0].operations.len(), 1);
         assert_eq!(services[0].operations[0].name, "sayHello");
+
+        // RPC-style output parts become children of a {operation}Response wrapper
+        let output = services[0].operations[0].output_schema.as_ref().unwrap();
+        assert_eq!(output.name, "sayHelloResponse");
+        let children = output.children.as_ref().unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].name, "greeting");
+        assert_eq!(children[0].node_type, "string");
     }
 
    #[test]
@@ -1046,6 +1167,7 @@ mod tests {
        // Check one operation's schema
        let op = &service.operations[0];
        assert!(op.full_schema.is_some(), "Operation {} should have full_schema", op.name);
+       assert!(op.output_schema.is_some(), "Operation {} should have output_schema", op.name);
        assert!(op.target_namespace.is_some(), "Operation {} should have target_namespace", op.name);
 
        log::debug!("Operation {}: full_schema={:?}, target_namespace={:?}", op.name, op.full_schema.is_some(), op.target_namespace);
@@ -25,11 +25,15 @@ pub struct ElementDef {
     pub min_occurs: Option<String>,
     pub max_occurs: Option<String>,
     pub inline_type: Option<ComplexTypeDef>,
+    /// Anonymous `<simpleType>` declared inside the element
+    pub inline_simple_type: Option<SimpleTypeDef>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ComplexTypeDef {
     pub name: Option<String>,
+    /// Base type of a `<complexContent><extension>`
+    pub base_type: Option<String>,
     pub sequence: Vec<ElementDef>,
     pub choice: Vec<ElementDef>,
     pub attributes: Vec<AttributeDef>,
@@ -140,6 +144,7 @@ impl SchemaParser {
             name,
             type_name,
             inline_type: None,
+            inline_simple_type: None,
             min_occurs,
             max_occurs,
         }))
@@ -153,6 +158,7 @@ impl SchemaParser {
         let max_occurs = Self::get_attr(start, "maxOccurs").ok();
         
         let mut inline_type = None;
+        let mut inline_simple_type = None;
         
         loop {
             match reader.read_event_into(buf) {
@@ -160,6 +166,10 @@ impl SchemaParser {
                     let mut ct_buf = Vec::new();
                     inline_type = Self::parse_complex_type(reader, &e, &mut ct_buf)?;
                 }
+                Ok(Event::Start(e)) if Self::local_name(&e) == "simpleType" => {
+                    let mut st_buf = Vec::new();
+                    inline_simple_type = Self::parse_simple_type(reader, &e, &mut st_buf)?;
+                }
                 Ok(Event::End(e)) if String::from_utf8_lossy(e.name().as_ref()).ends_with("element") => break,
                 Ok(Event::Eof) => break,
                 _ => {}
@@ -173,6 +183,7 @@ impl SchemaParser {
             min_occurs,
             max_occurs,
             inline_type,
+            inline_simple_type,
         }))
     }
     
@@ -180,6 +191,7 @@ impl SchemaParser {
     fn parse_complex_type(reader: &mut Reader<&[u8]>, start: &BytesStart, buf: &mut Vec<u8>) -> Result<Option<ComplexTypeDef>> {
         let name = Self::get_attr(start, "name").ok();
         
+        let mut base_type = None;
         let mut sequence = Vec::new();
         let mut choice = Vec::new();
         let mut attributes = Vec::new();
@@ -199,6 +211,9 @@ impl SchemaParser {
                                 attributes.push(attr);
                             }
                         }
+                        "extension" => {
+                            base_type = Self::get_attr(&e, "base").ok().map(|b| Self::strip_namespace_prefix(&b));
+                        }
                         _ => {}
                     }
                 }
@@ -206,6 +221,9 @@ impl SchemaParser {
                     let tag_name = Self::local_name(&e);
                     
                     match tag_name.as_str() {
+                        "extension" => {
+                            base_type = Self::get_attr(&e, "base").ok().map(|b| Self::strip_namespace_prefix(&b));
+                        }
                         "sequence" => {
                             sequence = Self::parse_sequence(reader, buf)?;
                         }
@@ -229,6 +247,7 @@ impl SchemaParser {
         
         Ok(Some(ComplexTypeDef {
             name,
+            base_type,
             sequence,
             choice,
             attributes,
@@ -253,6 +272,14 @@ impl SchemaParser {
                         elements.push(element);
                     }
                 }
+                Ok(Event::Start(e)) if Self::local_name(&e) == "choice" => {
+                    // Alternatives of a nested choice are each optional within the sequence
+                    let mut choice_buf = Vec::new();
+                    for mut element in Self::parse_choice(reader, &mut choice_buf)? {
+                        element.min_occurs = Some("0".to_string());
+                        elements.push(element);
+                    }
+                }
                 Ok(Event::End(e)) => {
                     let name_bytes = e.name();
                     let name_full = String::from_utf8_lossy(name_bytes.as_ref());
@@ -275,7 +302,12 @@ impl SchemaParser {
         
         loop {
             match reader.read_event_into(buf) {
-                Ok(Event::Start(e)) | Ok(Event::Empty(e)) if Self::local_name(&e) == "element" => {
+                Ok(Event::Empty(e)) if Self::local_name(&e) == "element" => {
+                    if let Some(element) = Self::parse_empty_element(&e)? {
+                        elements.push(element);
+                    }
+                }
+                Ok(Event::Start(e)) if Self::local_name(&e) == "element" => {
                     let mut elem_buf = Vec::new();
                     if let Some(element) = Self::parse_element(reader, &e, &mut elem_buf)? {
                         elements.push(element);
@@ -313,14 +345,14 @@ impl SchemaParser {
         
         loop {
             match reader.read_event_into(buf) {
-                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                    let tag_name = Self::local_name(&e);
-                    
-                    if tag_name.as_str() == "restriction" {
-                        base_type = Self::get_attr(&e, "base").ok().map(|b| Self::strip_namespace_prefix(&b));
-                        let mut rest_buf = Vec::new();
-                        restrictions = Self::parse_restrictions(reader, &mut rest_buf)?;
-                    }
+                Ok(Event::Start(e)) if Self::local_name(&e) == "restriction" => {
+                    base_type = Self::get_attr(&e, "base").ok().map(|b| Self::strip_namespace_prefix(&b));
+                    let mut rest_buf = Vec::new();
+                    restrictions = Self::parse_restrictions(reader, &mut rest_buf)?;
+                }
+                // `<restriction base="..."/>` has no facets to read
+                Ok(Event::Empty(e)) if Self::local_name(&e) == "restriction" => {
+                    base_type = Self::get_attr(&e, "base").ok().map(|b| Self::strip_namespace_prefix(&b));
                 }
                 Ok(Event::End(e)) => {
                     let name_bytes = e.name();
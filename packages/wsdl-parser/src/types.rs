@@ -44,6 +44,9 @@ pub struct ServiceOperation {
     pub original_endpoint: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub full_schema: Option<SchemaNode>,
+    /// Schema tree of the response body element, built from the output message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<SchemaNode>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub action: Option<String>, // SOAPAction header value
 }
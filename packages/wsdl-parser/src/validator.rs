@@ -0,0 +1,610 @@
+//! XSD validation of SOAP responses
+//!
+//! Checks a response body against the operation's output element as parsed by
+//! [`SchemaParser`](crate::schema::SchemaParser): element order and
+//! occurrence, required attributes, built-in simple types (numbers, booleans,
+//! dates) and simple type restrictions (enumeration, pattern, length, range).
+//!
+//! The check is intentionally lenient where the parsed schema is incomplete:
+//! unknown types, `any` content and unsupported regex syntax are not reported.
+
+use super::schema::{ComplexTypeDef, ElementDef, Restriction, SchemaDefinition, SimpleTypeDef};
+use anyhow::{anyhow, Result};
+use quick_xml::events::{BytesStart, Event};
+use once_cell::sync::Lazy;
+use quick_xml::Reader;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Maximum nesting checked, guarding against recursive types
+const MAX_DEPTH: usize = 64;
+
+/// The element a response must contain, with the schemas defining its types
+#[derive(Debug, Clone)]
+pub struct ResponseSchema {
+    pub element: ElementDef,
+    pub schemas: Vec<SchemaDefinition>,
+}
+
+/// A single schema violation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaViolation {
+    /// Element path, e.g. `/GetOrderResponse/Order[2]/Status`
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Schema Validator
+pub struct SchemaValidator;
+
+impl SchemaValidator {
+    /// Validate a response against the schema.
+    ///
+    /// `xml` may be a SOAP envelope (the first element of the Body is
+    /// validated) or the payload element itself. Returns every violation
+    /// found; an empty list means the response is valid.
+    pub fn validate(schema: &ResponseSchema, xml: &str) -> Result<Vec<SchemaViolation>> {
+        let root = XmlElement::parse(xml)?;
+        let payload = match root.name.as_str() {
+            "Envelope" => {
+                let body = root
+                    .children
+                    .iter()
+                    .find(|c| c.name == "Body")
+                    .ok_or_else(|| anyhow!("SOAP envelope has no Body"))?;
+                match body.children.first() {
+                    Some(payload) => payload,
+                    None => {
+                        return Ok(vec![SchemaViolation {
+                            path: "/Envelope/Body".to_string(),
+                            message: format!("Missing required element '{}'", schema.element.name),
+                        }])
+                    }
+                }
+            }
+            _ => &root,
+        };
+
+        let mut violations = Vec::new();
+        if payload.name == "Fault" {
+            violations.push(SchemaViolation {
+                path: format!("/{}", payload.name),
+                message: format!("Response is a SOAP Fault, expected '{}'", schema.element.name),
+            });
+        } else if payload.name != schema.element.name {
+            violations.push(SchemaViolation {
+                path: format!("/{}", payload.name),
+                message: format!("Expected element '{}' but found '{}'", schema.element.name, payload.name),
+            });
+        } else {
+            let mut checker = Checker { schema, violations: &mut violations };
+            checker.check_element(&schema.element, payload, &format!("/{}", payload.name), 0);
+        }
+        Ok(violations)
+    }
+}
+
+impl ResponseSchema {
+    fn complex_type(&self, name: &str) -> Option<&ComplexTypeDef> {
+        self.schemas.iter().find_map(|s| s.complex_types.get(name))
+    }
+
+    fn simple_type(&self, name: &str) -> Option<&SimpleTypeDef> {
+        self.schemas.iter().find_map(|s| s.simple_types.get(name))
+    }
+}
+
+/// The content model of an element
+enum Content<'s> {
+    Complex(&'s ComplexTypeDef),
+    Simple {
+        restrictions: Vec<&'s Restriction>,
+        builtin: Option<String>,
+    },
+    /// Unknown type: anything goes
+    Any,
+}
+
+struct Checker<'s, 'v> {
+    schema: &'s ResponseSchema,
+    violations: &'v mut Vec<SchemaViolation>,
+}
+
+impl<'s> Checker<'s, '_> {
+    fn report(&mut self, path: &str, message: String) {
+        self.violations.push(SchemaViolation { path: path.to_string(), message });
+    }
+
+    fn check_element(&mut self, def: &'s ElementDef, element: &XmlElement, path: &str, depth: usize) {
+        if depth > MAX_DEPTH || element.is_nil() {
+            return;
+        }
+
+        match self.content_of(def) {
+            Content::Complex(complex) => self.check_complex(complex, element, path, depth),
+            Content::Simple { restrictions, builtin } => {
+                if let Some(child) = element.children.first() {
+                    self.report(path, format!("Unexpected element '{}' in simple content", child.name));
+                }
+                self.check_value(&element.text, builtin.as_deref(), &restrictions, path);
+            }
+            Content::Any => {}
+        }
+    }
+
+    fn content_of(&self, def: &'s ElementDef) -> Content<'s> {
+        if let Some(inline) = &def.inline_type {
+            return Content::Complex(inline);
+        }
+        if let Some(simple) = &def.inline_simple_type {
+            return self.simple_content(simple);
+        }
+        match def.type_name.as_deref() {
+            Some(name) => self.named_content(name),
+            // No type at all is xs:anyType
+            None => Content::Any,
+        }
+    }
+
+    fn named_content(&self, name: &str) -> Content<'s> {
+        if let Some(complex) = self.schema.complex_type(name) {
+            Content::Complex(complex)
+        } else if let Some(simple) = self.schema.simple_type(name) {
+            self.simple_content(simple)
+        } else if is_builtin(name) {
+            Content::Simple { restrictions: vec![], builtin: Some(name.to_string()) }
+        } else {
+            Content::Any
+        }
+    }
+
+    /// Collect the restrictions of a simple type and its base chain
+    fn simple_content(&self, simple: &'s SimpleTypeDef) -> Content<'s> {
+        let mut restrictions: Vec<&Restriction> = simple.restrictions.iter().collect();
+        let mut base = simple.base_type.clone();
+        for _ in 0..MAX_DEPTH {
+            let Some(name) = base.take() else { break };
+            match self.schema.simple_type(&name) {
+                Some(parent) => {
+                    restrictions.extend(parent.restrictions.iter());
+                    base = parent.base_type.clone();
+                }
+                None => {
+                    base = Some(name);
+                    break;
+                }
+            }
+        }
+        Content::Simple { restrictions, builtin: base.filter(|b| is_builtin(b)) }
+    }
+
+    /// Sequence particles of a complex type, including those of its base types
+    fn particles(&self, complex: &'s ComplexTypeDef) -> (Vec<&'s ElementDef>, Vec<&'s ElementDef>) {
+        let mut chain = vec![complex];
+        let mut current = complex;
+        while let Some(base) = current.base_type.as_deref().and_then(|b| self.schema.complex_type(b)) {
+            if chain.len() > MAX_DEPTH {
+                break;
+            }
+            chain.push(base);
+            current = base;
+        }
+
+        let mut sequence = Vec::new();
+        let mut choice = Vec::new();
+        for def in chain.iter().rev() {
+            sequence.extend(def.sequence.iter());
+            choice.extend(def.choice.iter());
+        }
+        (sequence, choice)
+    }
+
+    fn check_complex(&mut self, complex: &'s ComplexTypeDef, element: &XmlElement, path: &str, depth: usize) {
+        for attribute in &complex.attributes {
+            if attribute.use_type.as_deref() == Some("required") && element.attribute(&attribute.name).is_none() {
+                self.report(path, format!("Missing required attribute '{}'", attribute.name));
+            }
+            if let (Some(value), Some(type_name)) = (element.attribute(&attribute.name), attribute.type_name.as_deref()) {
+                if let Content::Simple { restrictions, builtin } = self.named_content(type_name) {
+                    let attribute_path = format!("{}/@{}", path, attribute.name);
+                    self.check_value(value, builtin.as_deref(), &restrictions, &attribute_path);
+                }
+            }
+        }
+
+        let (sequence, choice) = self.particles(complex);
+        if sequence.is_empty() && choice.is_empty() {
+            // simpleContent, `any` or an empty model the parser did not capture
+            return;
+        }
+
+        let mut counts = vec![0usize; sequence.len()];
+        let mut position = 0;
+        let mut chosen: Vec<&str> = Vec::new();
+        let mut misplaced: Vec<&str> = Vec::new();
+
+        for (index, child) in element.children.iter().enumerate() {
+            let child_path = child_path(path, element, index);
+
+            if let Some(def) = choice.iter().find(|d| d.name == child.name) {
+                if !chosen.contains(&def.name.as_str()) {
+                    chosen.push(&def.name);
+                }
+                self.check_element(def, child, &child_path, depth + 1);
+                continue;
+            }
+
+            match sequence[position..].iter().position(|d| d.name == child.name) {
+                Some(offset) => {
+                    position += offset;
+                    counts[position] += 1;
+                    self.check_element(sequence[position], child, &child_path, depth + 1);
+                }
+                None if sequence[..position].iter().any(|d| d.name == child.name) => {
+                    misplaced.push(&child.name);
+                    self.report(&child_path, format!("Element '{}' is out of order", child.name));
+                }
+                None => self.report(&child_path, format!("Unexpected element '{}'", child.name)),
+            }
+        }
+
+        for (def, count) in sequence.iter().zip(&counts) {
+            let (min, max) = occurs(def);
+            if *count < min && !misplaced.contains(&def.name.as_str()) {
+                let message = if *count == 0 {
+                    format!("Missing required element '{}'", def.name)
+                } else {
+                    format!("Element '{}' occurs {} times, at least {} required", def.name, count, min)
+                };
+                self.report(path, message);
+            }
+            if let Some(max) = max.filter(|max| count > max) {
+                self.report(path, format!("Element '{}' occurs {} times, at most {} allowed", def.name, count, max));
+            }
+        }
+
+        let choice_required = !choice.is_empty() && choice.iter().all(|d| occurs(d).0 > 0);
+        if choice_required && chosen.is_empty() {
+            let names: Vec<String> = choice.iter().map(|d| format!("'{}'", d.name)).collect();
+            self.report(path, format!("Missing required element: expected one of {}", names.join(", ")));
+        } else if chosen.len() > 1 {
+            self.report(path, format!("Only one of {} is allowed", chosen.join(", ")));
+        }
+    }
+
+    fn check_value(&mut self, raw: &str, builtin: Option<&str>, restrictions: &[&Restriction], path: &str) {
+        let value = raw.trim();
+
+        if let Some(builtin) = builtin {
+            if !builtin_accepts(builtin, value) {
+                self.report(path, format!("Value '{}' is not a valid xs:{}", value, builtin));
+                return;
+            }
+        }
+
+        for restriction in restrictions {
+            match restriction {
+                Restriction::Enumeration(allowed) => {
+                    if !allowed.iter().any(|a| a == value) {
+                        self.report(path, format!("Value '{}' is not one of [{}]", value, allowed.join(", ")));
+                    }
+                }
+                Restriction::Pattern(pattern) => match Regex::new(&format!("^(?:{})$", pattern)) {
+                    Ok(regex) if !regex.is_match(value) => {
+                        self.report(path, format!("Value '{}' does not match pattern '{}'", value, pattern));
+                    }
+                    Ok(_) => {}
+                    Err(_) => log::debug!("Skipping unsupported XSD pattern '{}'", pattern),
+                },
+                Restriction::MinLength(min) => {
+                    let length = value.chars().count();
+                    if min.parse::<usize>().is_ok_and(|min| length < min) {
+                        self.report(path, format!("Length {} is less than minLength {}", length, min));
+                    }
+                }
+                Restriction::MaxLength(max) => {
+                    let length = value.chars().count();
+                    if max.parse::<usize>().is_ok_and(|max| length > max) {
+                        self.report(path, format!("Length {} is greater than maxLength {}", length, max));
+                    }
+                }
+                Restriction::MinInclusive(min) => {
+                    if compare_values(value, min).is_some_and(|o| o.is_lt()) {
+                        self.report(path, format!("Value {} is less than minInclusive {}", value, min));
+                    }
+                }
+                Restriction::MaxInclusive(max) => {
+                    if compare_values(value, max).is_some_and(|o| o.is_gt()) {
+                        self.report(path, format!("Value {} is greater than maxInclusive {}", value, max));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// (minOccurs, maxOccurs); `None` max is unbounded
+fn occurs(def: &ElementDef) -> (usize, Option<usize>) {
+    let min = def.min_occurs.as_deref().and_then(|m| m.parse().ok()).unwrap_or(1);
+    let max = match def.max_occurs.as_deref() {
+        Some("unbounded") => None,
+        Some(max) => Some(max.parse().unwrap_or(1)),
+        None => Some(1),
+    };
+    (min, max)
+}
+
+/// Path of a child, indexed when the name repeats among its siblings
+fn child_path(parent_path: &str, parent: &XmlElement, index: usize) -> String {
+    let name = &parent.children[index].name;
+    let same_name = parent.children.iter().filter(|c| &c.name == name).count();
+    if same_name > 1 {
+        let position = parent.children[..=index].iter().filter(|c| &c.name == name).count();
+        format!("{}/{}[{}]", parent_path, name, position)
+    } else {
+        format!("{}/{}", parent_path, name)
+    }
+}
+
+/// Compare numerically when both parse as numbers, otherwise lexically
+/// (which orders ISO dates correctly)
+fn compare_values(value: &str, bound: &str) -> Option<std::cmp::Ordering> {
+    match (value.parse::<f64>(), bound.parse::<f64>()) {
+        (Ok(v), Ok(b)) => v.partial_cmp(&b),
+        _ => Some(value.cmp(bound)),
+    }
+}
+
+fn is_builtin(name: &str) -> bool {
+    matches!(
+        name,
+        "string" | "normalizedString" | "token" | "anyURI" | "QName" | "base64Binary" | "hexBinary"
+            | "int" | "integer" | "long" | "short" | "byte" | "nonNegativeInteger" | "positiveInteger"
+            | "nonPositiveInteger" | "negativeInteger" | "unsignedInt" | "unsignedLong" | "unsignedShort"
+            | "unsignedByte" | "decimal" | "double" | "float" | "boolean" | "date" | "dateTime" | "time"
+            | "duration" | "anyType" | "anySimpleType"
+    )
+}
+
+fn builtin_accepts(name: &str, value: &str) -> bool {
+    let integer = |min: i128, max: i128| value.parse::<i128>().is_ok_and(|v| (min..=max).contains(&v));
+    match name {
+        "int" => integer(i32::MIN as i128, i32::MAX as i128),
+        "long" => integer(i64::MIN as i128, i64::MAX as i128),
+        "short" => integer(i16::MIN as i128, i16::MAX as i128),
+        "byte" => integer(i8::MIN as i128, i8::MAX as i128),
+        "integer" => integer(i128::MIN, i128::MAX),
+        "nonNegativeInteger" | "unsignedLong" => integer(0, i128::MAX),
+        "positiveInteger" => integer(1, i128::MAX),
+        "nonPositiveInteger" => integer(i128::MIN, 0),
+        "negativeInteger" => integer(i128::MIN, -1),
+        "unsignedInt" => integer(0, u32::MAX as i128),
+        "unsignedShort" => integer(0, u16::MAX as i128),
+        "unsignedByte" => integer(0, u8::MAX as i128),
+        "decimal" => !value.is_empty() && value.trim_start_matches(['+', '-']).chars().all(|c| c.is_ascii_digit() || c == '.')
+            && value.matches('.').count() <= 1
+            && value.chars().any(|c| c.is_ascii_digit()),
+        "double" | "float" => matches!(value, "INF" | "-INF" | "NaN") || value.parse::<f64>().is_ok(),
+        "boolean" => matches!(value, "true" | "false" | "1" | "0"),
+        "date" => DATE.is_match(value),
+        "dateTime" => DATE_TIME.is_match(value),
+        "time" => TIME.is_match(value),
+        _ => true,
+    }
+}
+
+static DATE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^-?\d{4,}-\d{2}-\d{2}(Z|[+-]\d{2}:\d{2})?$").unwrap());
+static DATE_TIME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^-?\d{4,}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d+)?(Z|[+-]\d{2}:\d{2})?$").unwrap()
+});
+static TIME: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\d{2}:\d{2}:\d{2}(\.\d+)?(Z|[+-]\d{2}:\d{2})?$").unwrap());
+
+/// Minimal element tree with namespace prefixes removed
+#[derive(Debug, Default)]
+struct XmlElement {
+    name: String,
+    /// (qualified name, local name, value); `xmlns` declarations excluded
+    attributes: Vec<(String, String, String)>,
+    text: String,
+    children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    fn parse(xml: &str) -> Result<XmlElement> {
+        let mut reader = Reader::from_str(xml);
+        let mut stack: Vec<XmlElement> = Vec::new();
+        let mut buf = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) => stack.push(Self::from_start(&e)),
+                Ok(Event::Empty(e)) => {
+                    let element = Self::from_start(&e);
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                Ok(Event::Text(e)) => {
+                    if let Some(current) = stack.last_mut() {
+                        let text = e.unescape().map_err(|e| anyhow!("XML parse error: {}", e))?;
+                        current.text.push_str(&text);
+                    }
+                }
+                Ok(Event::CData(e)) => {
+                    if let Some(current) = stack.last_mut() {
+                        current.text.push_str(&String::from_utf8_lossy(&e));
+                    }
+                }
+                Ok(Event::End(_)) => {
+                    let element = stack.pop().ok_or_else(|| anyhow!("XML parse error: unexpected end tag"))?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                Ok(Event::Eof) => return Err(anyhow!("XML parse error: no root element")),
+                Err(e) => return Err(anyhow!("XML parse error: {}", e)),
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+
+    fn from_start(start: &BytesStart) -> XmlElement {
+        let attributes = start
+            .attributes()
+            .flatten()
+            .filter(|a| a.key.as_namespace_binding().is_none())
+            .map(|a| {
+                let qualified = String::from_utf8_lossy(a.key.as_ref()).to_string();
+                let local = String::from_utf8_lossy(a.key.local_name().as_ref()).to_string();
+                let value = a
+                    .unescape_value()
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|_| String::from_utf8_lossy(&a.value).to_string());
+                (qualified, local, value)
+            })
+            .collect();
+
+        XmlElement {
+            name: String::from_utf8_lossy(start.local_name().as_ref()).to_string(),
+            attributes,
+            ..Default::default()
+        }
+    }
+
+    fn attribute(&self, local_name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(_, local, _)| local == local_name)
+            .map(|(_, _, value)| value.as_str())
+    }
+
+    /// `xsi:nil="true"`
+    fn is_nil(&self) -> bool {
+        self.attributes
+            .iter()
+            .any(|(qualified, local, value)| qualified.contains(':') && local == "nil" && (value == "true" || value == "1"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WsdlParser;
+
+    const WSDL: &str = r#"<?xml version="1.0"?>
+<definitions targetNamespace="urn:orders" xmlns:tns="urn:orders" xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <types>
+    <xsd:schema targetNamespace="urn:orders">
+      <xsd:simpleType name="Status">
+        <xsd:restriction base="xsd:string">
+          <xsd:enumeration value="OPEN"/>
+          <xsd:enumeration value="SHIPPED"/>
+        </xsd:restriction>
+      </xsd:simpleType>
+      <xsd:complexType name="Line">
+        <xsd:sequence>
+          <xsd:element name="sku">
+            <xsd:simpleType>
+              <xsd:restriction base="xsd:string">
+                <xsd:pattern value="[A-Z]{3}-\d+"/>
+                <xsd:maxLength value="8"/>
+              </xsd:restriction>
+            </xsd:simpleType>
+          </xsd:element>
+          <xsd:element name="qty" type="xsd:int"/>
+        </xsd:sequence>
+        <xsd:attribute name="id" type="xsd:int" use="required"/>
+      </xsd:complexType>
+      <xsd:element name="GetOrderResponse">
+        <xsd:complexType>
+          <xsd:sequence>
+            <xsd:element name="orderId" type="xsd:string"/>
+            <xsd:element name="status" type="tns:Status"/>
+            <xsd:element name="line" type="tns:Line" maxOccurs="unbounded"/>
+            <xsd:element name="note" type="xsd:string" minOccurs="0"/>
+          </xsd:sequence>
+        </xsd:complexType>
+      </xsd:element>
+    </xsd:schema>
+  </types>
+  <message name="GetOrderOut"><part name="parameters" element="tns:GetOrderResponse"/></message>
+  <message name="GetOrderIn"><part name="id" type="xsd:string"/></message>
+  <portType name="Orders">
+    <operation name="GetOrder">
+      <input message="tns:GetOrderIn"/>
+      <output message="tns:GetOrderOut"/>
+    </operation>
+  </portType>
+</definitions>"#;
+
+    fn envelope(body: &str) -> String {
+        format!(
+            r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"><soap:Body>{}</soap:Body></soap:Envelope>"#,
+            body
+        )
+    }
+
+    fn validate(body: &str) -> Vec<String> {
+        let schema = WsdlParser::response_schema(WSDL, "GetOrder").unwrap();
+        SchemaValidator::validate(&schema, &envelope(body))
+            .unwrap()
+            .iter()
+            .map(|v| v.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_response() {
+        let body = r#"<o:GetOrderResponse xmlns:o="urn:orders"><o:orderId>42</o:orderId><o:status>OPEN</o:status><o:line id="1"><o:sku>ABC-1</o:sku><o:qty>2</o:qty></o:line><o:line id="2"><o:sku>XYZ-22</o:sku><o:qty>1</o:qty></o:line></o:GetOrderResponse>"#;
+        assert_eq!(validate(body), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_reports_violations_with_paths() {
+        let body = r#"<GetOrderResponse><status>LOST</status><line><sku>abc-1</sku><qty>many</qty></line><line id="2"><sku>ABC-123456</sku><qty>1</qty></line><extra/></GetOrderResponse>"#;
+        assert_eq!(
+            validate(body),
+            vec![
+                "/GetOrderResponse/status: Value 'LOST' is not one of [OPEN, SHIPPED]",
+                "/GetOrderResponse/line[1]: Missing required attribute 'id'",
+                "/GetOrderResponse/line[1]/sku: Value 'abc-1' does not match pattern '[A-Z]{3}-\\d+'",
+                "/GetOrderResponse/line[1]/qty: Value 'many' is not a valid xs:int",
+                "/GetOrderResponse/line[2]/sku: Length 10 is greater than maxLength 8",
+                "/GetOrderResponse/extra: Unexpected element 'extra'",
+                "/GetOrderResponse: Missing required element 'orderId'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrong_root_fault_and_order() {
+        assert_eq!(
+            validate("<Other/>"),
+            vec!["/Other: Expected element 'GetOrderResponse' but found 'Other'"]
+        );
+        assert_eq!(
+            validate("<soap:Fault><faultcode>soap:Server</faultcode></soap:Fault>"),
+            vec!["/Fault: Response is a SOAP Fault, expected 'GetOrderResponse'"]
+        );
+        let body = r#"<GetOrderResponse><orderId>1</orderId><line id="1"><sku>ABC-1</sku><qty>1</qty></line><status>OPEN</status></GetOrderResponse>"#;
+        assert_eq!(
+            validate(body),
+            vec![
+                "/GetOrderResponse/status: Element 'status' is out of order",
+            ]
+        );
+        assert!(WsdlParser::response_schema(WSDL, "Missing").is_err());
+    }
+}
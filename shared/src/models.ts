@@ -8,6 +8,7 @@ export interface ServiceOperation {
     portName?: string;
     originalEndpoint?: string;
     fullSchema?: SchemaNode | null; // Deep complex type tree for XML generation
+    outputSchema?: SchemaNode | null; // Response element tree, used to generate mock responses
     action?: string; // SOAP action from binding operation
 }
 
@@ -111,7 +112,7 @@ export interface GraphQLConfig {
 
 // Assertion Types
 export interface Assertion {
-    type: 'Simple Contains' | 'Simple Not Contains' | 'Response SLA' | 'XPath Match' | 'SOAP Fault' | 'HTTP Status' | 'Script';
+    type: 'Simple Contains' | 'Simple Not Contains' | 'Response SLA' | 'XPath Match' | 'JSONPath Match' | 'OpenAPI Compliance' | 'Schema Compliance' | 'SOAP Fault' | 'HTTP Status' | 'Script';
     name?: string;
     id?: string;
     description?: string;
@@ -121,7 +122,16 @@ export interface Assertion {
         ignoreCase?: boolean; // For Contains
         sla?: string; // For SLA (ms)
         xpath?: string; // For XPath
-        expectedContent?: string; // For XPath
+        ignoreNamespaces?: boolean; // XPath: match local names only
+        namespaces?: Record<string, string>; // XPath: prefix -> namespace URI
+        jsonPath?: string; // For JSONPath (RFC 9535)
+        expectedContent?: string; // For XPath / JSONPath
+        // OpenAPI Compliance
+        openApiSpec?: string; // Spec file path, or the spec itself (JSON/YAML)
+        openApiOperation?: string; // operationId or "METHOD /path"
+        // Schema Compliance
+        wsdl?: string; // WSDL file path, or the WSDL itself
+        wsdlOperation?: string; // Operation whose output element is checked
         // SOAP Fault
         expectFault?: boolean; // true = expect fault, false = expect success
         faultCode?: string; // Optional: e.g. "Client", "Server"
@@ -133,8 +143,9 @@ export interface Assertion {
 }
 
 export interface RequestExtractor {
-    type: 'XPath' | 'JSONPath' | 'Regex' | 'Header';
-    source: 'body' | 'header';
+    /** Cookie: path is the cookie name. SoapFault: path is 'code' | 'string' | 'actor' | 'detail' */
+    type: 'XPath' | 'JSONPath' | 'Regex' | 'Header' | 'StatusCode' | 'Cookie' | 'ElapsedTime' | 'SoapFault';
+    source: 'body' | 'header' | 'status';
     path: string;
     variable: string;
     id: string;
@@ -227,6 +238,8 @@ export interface ApiRequest {
     extractors?: RequestExtractor[];
     headers?: Record<string, string>;
     id?: string;
+    /** WSDL target namespace of the operation, bound to `tns` in XPath assertions */
+    targetNamespace?: string;
     wsSecurity?: WSSecurityConfig;
     attachments?: RequestAttachment[];
     /** Response from the last execution, persisted across restarts */
@@ -324,6 +337,7 @@ export interface ApiOperation {
     originalEndpoint?: string;
     id?: string;
     fullSchema?: SchemaNode | null; // Deep complex type tree for XML generation
+    outputSchema?: SchemaNode | null; // Response element tree, used to generate mock responses
     displayName?: string; // For display-only renaming in UI (preserves original name for WSDL binding)
     sampleMetadata?: SampleRequestMetadata;
 }
@@ -577,8 +591,10 @@ export interface PerformanceSuite {
     delayBetweenRequests: number;
     /** Number of warmup runs to discard before measuring */
     warmupRuns: number;
-    /** Concurrency level for parallel execution (1 = sequential) */
+    /** Virtual users when no loadProfile is set (each runs `iterations` times) */
     concurrency: number;
+    /** How virtual users are driven; overrides iterations/concurrency */
+    loadProfile?: LoadProfile;
     /** Created timestamp */
     createdAt: number;
     /** Last modified timestamp */
@@ -593,6 +609,20 @@ export interface PerformanceSuite {
     collapsedSections?: string[];
 }
 
+/** Virtual-user load model for a performance suite */
+export type LoadProfile =
+    /** Fixed pool of VUs, each repeating the scenario `iterations` times and/or for `durationMs` */
+    | { type: 'constantVus'; vus: number; iterations?: number; durationMs?: number }
+    /** VU count ramps linearly from the previous target (0 at start) to each stage's target */
+    | { type: 'stages'; stages: LoadStage[] }
+    /** Start `rate` iterations per second; iterations beyond `maxVus` in flight are dropped */
+    | { type: 'arrivalRate'; rate: number; durationMs: number; maxVus?: number };
+
+export interface LoadStage {
+    durationMs: number;
+    target: number;
+}
+
 /** Single request within a performance suite */
 export interface PerformanceRequest {
     id: string;
@@ -606,6 +636,8 @@ export interface PerformanceRequest {
     headers?: Record<string, string>;
     /** Extractors for passing values between requests */
     extractors: RequestExtractor[];
+    /** Checked against every response; a failure counts as a failed request */
+    assertions?: Assertion[];
     /** Expected max response time in ms */
     slaThreshold?: number;
     /** Order in the sequence */
@@ -632,6 +664,96 @@ export interface PerformanceRun {
     summary: PerformanceStats;
     /** Environment used if any */
     environment?: string;
+    /** Per-second metrics (absent for older runs) */
+    timeSeries?: PerformanceTimeSeries;
+    /** Run that `regressions` were measured against (scheduled runs) */
+    baselineRunId?: string;
+    regressions?: PerformanceRegression[];
+}
+
+/** Limits used by `compare_performance_runs` (all optional) */
+export interface RegressionThresholds {
+    /** Allowed p50/p95/p99 increase in percent (default 20) */
+    maxLatencyIncrease?: number;
+    /** Allowed throughput drop in percent (default 20) */
+    maxThroughputDrop?: number;
+    /** Allowed error rate increase in percentage points (default 1) */
+    maxErrorRateIncrease?: number;
+    /** Significance level of the Mann-Whitney test (default 0.05) */
+    alpha?: number;
+    /** Only flag significant latency increases (default true) */
+    requireSignificance?: boolean;
+}
+
+export interface MetricDelta {
+    baseline: number;
+    current: number;
+    /** Null when the baseline is 0 */
+    changePercent: number | null;
+}
+
+/** Comparison of one request (or the whole run) between two runs */
+export interface RequestComparison {
+    requestId: string;
+    requestName: string;
+    baselineSamples: number;
+    currentSamples: number;
+    p50: MetricDelta;
+    p95: MetricDelta;
+    p99: MetricDelta;
+    /** Requests per second */
+    throughput: MetricDelta;
+    /** Failed requests in percent */
+    errorRate: MetricDelta;
+    mannWhitney: { u: number; z: number; pValue: number } | null;
+    significant: boolean;
+    /** Metrics that crossed a threshold */
+    regressions: string[];
+}
+
+/** Result of `compare_performance_runs` */
+export interface RunComparison {
+    baselineRunId: string;
+    currentRunId: string;
+    thresholds: Required<RegressionThresholds>;
+    overall: RequestComparison;
+    requests: RequestComparison[];
+    added: string[];
+    removed: string[];
+    passed: boolean;
+}
+
+/** A summary metric that worsened beyond a schedule's threshold */
+export interface PerformanceRegression {
+    /** PerformanceStats field, e.g. "p95" */
+    metric: string;
+    baseline: number;
+    current: number;
+    /** Relative change, positive when worse */
+    changePercent: number;
+}
+
+/** Metrics of one time bucket of a performance run */
+export interface PerformanceTimeBucket {
+    /** Start of the bucket (epoch ms) */
+    timestamp: number;
+    requests: number;
+    errors: number;
+    /** Requests per second */
+    throughput: number;
+    errorRate: number;
+    avgResponseTime: number;
+    p50: number;
+    p95: number;
+    p99: number;
+}
+
+/** Time-bucketed metrics of a performance run */
+export interface PerformanceTimeSeries {
+    intervalMs: number;
+    overall: PerformanceTimeBucket[];
+    /** Buckets per request id */
+    requests: Record<string, PerformanceTimeBucket[]>;
 }
 
 /** Result for a single request execution */
@@ -641,12 +763,16 @@ export interface PerformanceResult {
     interfaceName?: string;
     operationName?: string;
     iteration: number;
+    /** Virtual user that sent the request (1-based) */
+    vu?: number;
     duration: number;
     status: number;
     success: boolean;
     slaBreached: boolean;
     error?: string;
     extractedValues?: Record<string, string>;
+    /** Number of the request's assertions that failed */
+    assertionFailures?: number;
     timestamp: number;
 }
 
@@ -666,6 +792,8 @@ export interface PerformanceStats {
     /** 99th percentile */
     p99: number;
     slaBreachCount: number;
+    /** Requests that failed because of an assertion (included in failureCount) */
+    assertionFailureCount?: number;
     totalDuration: number;
 }
 
@@ -685,10 +813,24 @@ export interface PerformanceSchedule {
     description?: string;
     /** Whether this schedule is active */
     enabled: boolean;
+    /** What `suiteId` refers to (default 'performance') */
+    target?: 'performance' | 'testSuite';
+    /** Project directory of a 'testSuite' schedule */
+    projectPath?: string;
+    /** Environment whose variables the run resolves */
+    environment?: string;
+    /** Run to compare against (default: the suite's latest completed run) */
+    baselineRunId?: string;
+    /** Percent a metric may worsen before it is flagged (default 20) */
+    regressionThreshold?: number;
     /** Timestamp of last run */
     lastRun?: number;
     /** Result status of last run */
     lastRunStatus?: 'completed' | 'failed' | 'aborted';
+    /** Performance run id of the last run */
+    lastRunId?: string;
+    /** Regressions flagged on the last run */
+    lastRegressions?: PerformanceRegression[];
     /** Next scheduled run time */
     nextRun?: number;
     /** Creation timestamp */
@@ -706,9 +848,14 @@ export interface DistributedWorker {
     maxConcurrent: number;
     platform?: string;
     nodeVersion?: string;
+    /** APInox version of a Rust worker */
+    version?: string;
+    address?: string;
     connectedAt: number;
     lastHeartbeat?: number;
     assignedIterations?: { start: number; end: number };
+    /** VUs of the current (or last) distributed run */
+    assignedVus?: number;
     completedIterations?: number;
 }
 
@@ -959,13 +1106,48 @@ export interface ProxyServerConfig {
 }
 
 export interface MockMatchCondition {
-    /** 'url' | 'operation' | 'soapAction' | 'header' | 'contains' | 'xpath' | 'templateName' */
+    /** 'url' | 'operation' | 'soapAction' | 'header' | 'contains' | 'xpath' | 'templateName' | 'method' | 'path' (template such as '/orders/{id}') */
     type: string;
     pattern: string;
     isRegex?: boolean;
     headerName?: string;
 }
 
+/** Random latency added before a mock or proxy response */
+export type LatencyDistribution =
+    | { type: 'fixed'; ms: number }
+    | { type: 'uniform'; minMs: number; maxMs: number }
+    | { type: 'normal'; meanMs: number; stdDevMs: number }
+    | { type: 'exponential'; meanMs: number };
+
+/** Fault injection for a mock rule, the mock server (set_mock_fault_profile) or the proxy (start_proxy).
+ *  Percentages are of responses (0-100); at most one fault is picked per response. */
+export interface FaultProfile {
+    enabled?: boolean;
+    /** Same seed, same sequence of faults */
+    seed?: number;
+    latency?: LatencyDistribution;
+    errorPercent?: number;
+    /** Default 503 */
+    errorStatus?: number;
+    soapFaultPercent?: number;
+    resetPercent?: number;
+    timeoutPercent?: number;
+    /** Default 30000 */
+    timeoutMs?: number;
+    truncatePercent?: number;
+    /** Slow drip */
+    bytesPerSecond?: number;
+}
+
+/** Parameters and request body of the OpenAPI operation a generated rule mocks (snake_case, as parsed) */
+export interface MockRequestValidation {
+    parameters: any[];
+    requestBody?: any;
+    /** $ref targets of the parameter and body schemas */
+    schemas: Record<string, any>;
+}
+
 export interface MockRule {
     id: string;
     name: string;
@@ -976,12 +1158,38 @@ export interface MockRule {
     contentType?: string;
     responseHeaders?: Record<string, string>;
     delayMs?: number;
+    /** Render responseBody, header values and statusTemplate against the request */
+    templated?: boolean;
+    /** Templated status code; falls back to statusCode when it doesn't render a number */
+    statusTemplate?: string;
+    /** Scenario (state machine) this rule belongs to */
+    scenario?: string;
+    /** Only match while the scenario is in this state (initially 'Started') */
+    requiredState?: string;
+    /** Move the scenario to this state after matching */
+    newState?: string;
+    /** Values stored in the scenario after matching (templates), read as {{scenario.key}} */
+    scenarioValues?: Record<string, string>;
+    /** Reject requests violating this OpenAPI operation with a 400 problem-details body */
+    requestValidation?: MockRequestValidation;
+    /** Faults injected into this rule's responses instead of the global profile */
+    faultProfile?: FaultProfile;
     hitCount?: number;
     recordedAt?: number;
     recordedFrom?: string;
     tags?: string[];
 }
 
+/** Current state of a mock scenario (get_mock_scenarios) */
+export interface MockScenario {
+    name: string;
+    state: string;
+    values: Record<string, string>;
+    /** States some rule of the scenario requires or moves to */
+    possibleStates: string[];
+    ruleCount: number;
+}
+
 export interface MockRuleCollection {
     name: string;
     description: string;
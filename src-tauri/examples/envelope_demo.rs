@@ -72,6 +72,7 @@ fn main() {
         input: Some(json!("GetUserRequest")),
         output: json!("GetUserResponse"),
         full_schema: Some(schema),
+        output_schema: None,
         description: None,
         port_name: None,
     };
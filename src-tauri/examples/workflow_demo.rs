@@ -43,6 +43,8 @@ async fn demo_sequential() -> anyhow::Result<()> {
                 steps: vec![],
             },
             retry: None,
+            on_error: None,
+            finally: None,
         },
         WorkflowStep {
             name: "Step 2".to_string(),
@@ -50,6 +52,8 @@ async fn demo_sequential() -> anyhow::Result<()> {
                 steps: vec![],
             },
             retry: None,
+            on_error: None,
+            finally: None,
         },
     ];
     
@@ -76,6 +80,7 @@ async fn demo_conditional() -> anyhow::Result<()> {
         variable: "status".to_string(),
         operator: ConditionOperator::Equals,
         value: "success".to_string(),
+        expression: None,
     };
     
     let steps = vec![
@@ -88,6 +93,8 @@ async fn demo_conditional() -> anyhow::Result<()> {
                         name: "Success Branch".to_string(),
                         node: WorkflowNode::Sequential { steps: vec![] },
                         retry: None,
+                        on_error: None,
+                        finally: None,
                     },
                 ],
                 else_steps: Some(vec![
@@ -95,10 +102,14 @@ async fn demo_conditional() -> anyhow::Result<()> {
                         name: "Failure Branch".to_string(),
                         node: WorkflowNode::Sequential { steps: vec![] },
                         retry: None,
+                        on_error: None,
+                        finally: None,
                     },
                 ]),
             },
             retry: None,
+            on_error: None,
+            finally: None,
         },
     ];
     
@@ -123,16 +134,20 @@ async fn demo_loop() -> anyhow::Result<()> {
         WorkflowStep {
             name: "Repeat Loop".to_string(),
             node: WorkflowNode::Loop {
-                loop_type: LoopType::Repeat { count: 3 },
+                loop_type: LoopType::Repeat { count: 3, index_var: None },
                 steps: vec![
                     WorkflowStep {
                         name: "Loop Body".to_string(),
                         node: WorkflowNode::Sequential { steps: vec![] },
                         retry: None,
+                        on_error: None,
+                        finally: None,
                     },
                 ],
             },
             retry: None,
+            on_error: None,
+            finally: None,
         },
     ];
     
@@ -157,10 +172,14 @@ async fn demo_loop() -> anyhow::Result<()> {
                         name: "Process Item".to_string(),
                         node: WorkflowNode::Sequential { steps: vec![] },
                         retry: None,
+                        on_error: None,
+                        finally: None,
                     },
                 ],
             },
             retry: None,
+            on_error: None,
+            finally: None,
         },
     ];
     
@@ -183,18 +202,24 @@ async fn demo_loop() -> anyhow::Result<()> {
                         variable: "counter".to_string(),
                         operator: ConditionOperator::LessThan,
                         value: "3".to_string(),
+                        expression: None,
                     },
                     max_iterations: Some(3),
+                    index_var: None,
                 },
                 steps: vec![
                     WorkflowStep {
                         name: "Loop Body".to_string(),
                         node: WorkflowNode::Sequential { steps: vec![] },
                         retry: None,
+                        on_error: None,
+                        finally: None,
                     },
                 ],
             },
             retry: None,
+            on_error: None,
+            finally: None,
         },
     ];
     
@@ -219,6 +244,8 @@ async fn demo_retry() -> anyhow::Result<()> {
                 delay_ms: 100,
                 backoff_multiplier: Some(2.0),
             }),
+            on_error: None,
+            finally: None,
         },
     ];
     
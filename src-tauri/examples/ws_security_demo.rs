@@ -39,6 +39,7 @@ fn main() {
         input: Some(json!("GetCustomerRequest")),
         output: json!("GetCustomerResponse"),
         full_schema: Some(schema),
+        output_schema: None,
         description: None,
         port_name: None,
     };
@@ -0,0 +1,8 @@
+// Headless APInox runner for CI pipelines — see `apinox_lib::cli` for usage.
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let code = apinox_lib::cli::run(args).await;
+    std::process::exit(code);
+}
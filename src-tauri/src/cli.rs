@@ -0,0 +1,672 @@
+/// Headless command-line runner
+///
+/// Backs the `apinox-cli` binary so saved projects can be executed from CI
+/// pipelines without starting the webview:
+///
+/// ```text
+/// apinox-cli run <project-dir> [--suite <name>]... [--case <name>]...
+///                [--env <name>] [--var key=value]... [--endpoint <url>]
+///                [--report <format>:<path>]...
+/// apinox-cli worker <host:port> [--id <name>] [--max-vus <n>] [--token <secret>]
+/// apinox-cli compare <baseline.json> <current.json> [thresholds] [--output <path>]
+/// ```
+///
+/// Exit codes: `0` when every test case passed, `1` when at least one failed,
+/// `2` for usage or loading errors. `worker` serves distributed performance
+/// runs until the coordinator goes away. `compare` exits with `1` when the
+/// current performance run regressed against the baseline.
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::performance::comparison::{compare_runs, MetricDelta, RegressionThresholds, RunComparison};
+use crate::performance::distributed::{self, WorkerOptions};
+use crate::performance::types::PerformanceRun;
+use crate::reporting::{self, ReportCase, ReportFormat, ReportSuite, TestReport};
+use crate::testing::data_source;
+use crate::testing::frontend_runner::{self, RunContext};
+use crate::testing::frontend_types::{FrontendTestCase, TestCaseExecutionResult};
+
+pub const EXIT_PASSED: i32 = 0;
+pub const EXIT_FAILED: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str = "\
+Usage:
+  apinox-cli run <project-dir> [options]
+  apinox-cli worker <host:port> [--id <name>] [--max-vus <n>] [--token <secret>]
+  apinox-cli compare <baseline.json> <current.json> [compare options]
+
+Options:
+  --suite <name>       Run only the named test suite (repeatable, matches name or id)
+  --case <name>        Run only the named test case (repeatable, matches name or id)
+  --env <name>         Resolve variables from the named environment
+  --var <key=value>    Set a variable, overriding environment values (repeatable)
+  --endpoint <url>     Fallback endpoint for requests without one
+  --report <fmt>:<path>
+                       Write a junit, tap or html report (repeatable)
+  -h, --help           Show this help
+
+Worker options:
+  --id <name>          Worker id shown by the coordinator
+  --max-vus <n>        Relative capacity used to split runs (default 10)
+  --token <secret>     Shared token the coordinator was started with
+
+Compare options (runs are PerformanceRun JSON files):
+  --max-latency-increase <pct>     Allowed p50/p95/p99 increase (default 20)
+  --max-throughput-drop <pct>      Allowed throughput drop (default 20)
+  --max-error-rate-increase <pts>  Allowed error rate increase in points (default 1)
+  --alpha <p>                      Significance level (default 0.05)
+  --no-significance                Flag latency increases even if not significant
+  --output <path>                  Write the comparison as JSON";
+
+/// Capacity a worker advertises when `--max-vus` is not given
+const DEFAULT_WORKER_VUS: u32 = 10;
+
+/// How long a worker keeps retrying to reach the coordinator
+const WORKER_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Parsed options for `apinox-cli compare`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompareOptions {
+    pub baseline: PathBuf,
+    pub current: PathBuf,
+    pub thresholds: RegressionThresholds,
+    pub output: Option<PathBuf>,
+}
+
+/// Parsed options for `apinox-cli run`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunOptions {
+    pub project_dir: String,
+    pub suites: Vec<String>,
+    pub cases: Vec<String>,
+    pub environment: Option<String>,
+    pub variables: HashMap<String, String>,
+    pub endpoint: Option<String>,
+    pub reports: Vec<(ReportFormat, PathBuf)>,
+}
+
+/// Result of a single suite executed by the CLI
+#[derive(Debug, Clone)]
+pub struct SuiteRunResult {
+    pub name: String,
+    pub cases: Vec<TestCaseExecutionResult>,
+}
+
+impl SuiteRunResult {
+    pub fn passed(&self) -> bool {
+        self.cases.iter().all(|c| c.passed)
+    }
+}
+
+/// Entry point used by the `apinox-cli` binary. Returns the process exit code.
+pub async fn run(args: Vec<String>) -> i32 {
+    match args.first().map(String::as_str) {
+        Some("run") => match parse_run_args(&args[1..]) {
+            Ok(options) => match run_project(&options).await {
+                Ok(suites) => {
+                    if suites.iter().all(SuiteRunResult::passed) {
+                        EXIT_PASSED
+                    } else {
+                        EXIT_FAILED
+                    }
+                }
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    EXIT_USAGE
+                }
+            },
+            Err(e) => {
+                eprintln!("error: {}\n\n{}", e, USAGE);
+                EXIT_USAGE
+            }
+        },
+        Some("worker") => match parse_worker_args(&args[1..]) {
+            Ok(options) => match distributed::run_worker(options).await {
+                Ok(()) => EXIT_PASSED,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    EXIT_FAILED
+                }
+            },
+            Err(e) => {
+                eprintln!("error: {}\n\n{}", e, USAGE);
+                EXIT_USAGE
+            }
+        },
+        Some("compare") => match parse_compare_args(&args[1..]) {
+            Ok(options) => match run_compare(&options) {
+                Ok(comparison) if comparison.passed => EXIT_PASSED,
+                Ok(_) => EXIT_FAILED,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    EXIT_USAGE
+                }
+            },
+            Err(e) => {
+                eprintln!("error: {}\n\n{}", e, USAGE);
+                EXIT_USAGE
+            }
+        },
+        Some("-h") | Some("--help") | Some("help") => {
+            println!("{}", USAGE);
+            EXIT_PASSED
+        }
+        Some(other) => {
+            eprintln!("error: unknown command '{}'\n\n{}", other, USAGE);
+            EXIT_USAGE
+        }
+        None => {
+            eprintln!("{}", USAGE);
+            EXIT_USAGE
+        }
+    }
+}
+
+/// Parse the arguments following `run`
+pub fn parse_run_args(args: &[String]) -> Result<RunOptions, String> {
+    let mut options = RunOptions::default();
+    let mut project_dir = None;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let mut value_for = |flag: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("Missing value for {}", flag))
+        };
+
+        match arg.as_str() {
+            "--suite" => options.suites.push(value_for("--suite")?),
+            "--case" => options.cases.push(value_for("--case")?),
+            "--env" => options.environment = Some(value_for("--env")?),
+            "--endpoint" => options.endpoint = Some(value_for("--endpoint")?),
+            "--report" => {
+                let spec = value_for("--report")?;
+                let (format, path) = spec
+                    .split_once(':')
+                    .ok_or_else(|| format!("Invalid --report '{}', expected <format>:<path>", spec))?;
+                let format = format.parse::<ReportFormat>().map_err(|e| e.to_string())?;
+                options.reports.push((format, PathBuf::from(path)));
+            }
+            "--var" => {
+                let pair = value_for("--var")?;
+                let (key, value) = pair
+                    .split_once('=')
+                    .ok_or_else(|| format!("Invalid --var '{}', expected key=value", pair))?;
+                options.variables.insert(key.trim().to_string(), value.to_string());
+            }
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown option '{}'", flag));
+            }
+            positional => {
+                if project_dir.is_some() {
+                    return Err(format!("Unexpected argument '{}'", positional));
+                }
+                project_dir = Some(positional.to_string());
+            }
+        }
+    }
+
+    options.project_dir = project_dir.ok_or("Missing <project-dir>")?;
+    Ok(options)
+}
+
+/// Parse the arguments following `worker`
+pub fn parse_worker_args(args: &[String]) -> Result<WorkerOptions, String> {
+    let mut options = WorkerOptions {
+        coordinator: String::new(),
+        id: None,
+        max_concurrent: DEFAULT_WORKER_VUS,
+        connect_timeout: WORKER_CONNECT_TIMEOUT,
+        token: None,
+    };
+    let mut coordinator = None;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let mut value_for = |flag: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("Missing value for {}", flag))
+        };
+
+        match arg.as_str() {
+            "--id" => options.id = Some(value_for("--id")?),
+            "--token" => options.token = Some(value_for("--token")?),
+            "--max-vus" => {
+                let value = value_for("--max-vus")?;
+                options.max_concurrent = value
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| format!("Invalid --max-vus '{}', expected a positive number", value))?;
+            }
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown option '{}'", flag));
+            }
+            positional => {
+                if coordinator.is_some() {
+                    return Err(format!("Unexpected argument '{}'", positional));
+                }
+                coordinator = Some(positional.to_string());
+            }
+        }
+    }
+
+    options.coordinator = coordinator.ok_or("Missing <host:port>")?;
+    Ok(options)
+}
+
+/// Parse the arguments following `compare`
+pub fn parse_compare_args(args: &[String]) -> Result<CompareOptions, String> {
+    let mut options = CompareOptions::default();
+    let mut files = Vec::new();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let mut number_for = |flag: &str| -> Result<f64, String> {
+            let value = iter.next().ok_or_else(|| format!("Missing value for {}", flag))?;
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite() && *n >= 0.0)
+                .ok_or_else(|| format!("Invalid {} '{}', expected a non-negative number", flag, value))
+        };
+
+        match arg.as_str() {
+            "--max-latency-increase" => options.thresholds.max_latency_increase = number_for(arg)?,
+            "--max-throughput-drop" => options.thresholds.max_throughput_drop = number_for(arg)?,
+            "--max-error-rate-increase" => options.thresholds.max_error_rate_increase = number_for(arg)?,
+            "--alpha" => options.thresholds.alpha = number_for(arg)?,
+            "--no-significance" => options.thresholds.require_significance = false,
+            "--output" => {
+                let path = iter.next().ok_or("Missing value for --output")?;
+                options.output = Some(PathBuf::from(path));
+            }
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown option '{}'", flag));
+            }
+            positional => files.push(PathBuf::from(positional)),
+        }
+    }
+
+    match <[PathBuf; 2]>::try_from(files) {
+        Ok([baseline, current]) => {
+            options.baseline = baseline;
+            options.current = current;
+            Ok(options)
+        }
+        Err(_) => Err("Expected <baseline.json> and <current.json>".to_string()),
+    }
+}
+
+/// Compare two saved performance runs and print the differences
+pub fn run_compare(options: &CompareOptions) -> Result<RunComparison, String> {
+    let load = |path: &PathBuf| -> Result<PerformanceRun, String> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content).map_err(|e| format!("{} is not a performance run: {}", path.display(), e))
+    };
+    let comparison = compare_runs(&load(&options.baseline)?, &load(&options.current)?, &options.thresholds);
+
+    println!("Comparing run {} against baseline {}", comparison.current_run_id, comparison.baseline_run_id);
+    for request in std::iter::once(&comparison.overall).chain(&comparison.requests) {
+        println!(
+            "  [{}] {}: p50 {}, p95 {}, p99 {}, throughput {}, errors {}{}",
+            if request.regressions.is_empty() { "PASS" } else { "FAIL" },
+            request.request_name,
+            format_delta(&request.p50),
+            format_delta(&request.p95),
+            format_delta(&request.p99),
+            format_delta(&request.throughput),
+            format_delta(&request.error_rate),
+            match &request.mann_whitney {
+                Some(test) => format!(" (p={:.4})", test.p_value),
+                None => String::new(),
+            }
+        );
+        if !request.regressions.is_empty() {
+            println!("      regressed: {}", request.regressions.join(", "));
+        }
+    }
+    for name in &comparison.added {
+        println!("  [NEW]  {}", name);
+    }
+    for name in &comparison.removed {
+        println!("  [GONE] {}", name);
+    }
+    println!("\n{}", if comparison.passed { "No regressions" } else { "Regressions detected" });
+
+    if let Some(path) = &options.output {
+        let json = serde_json::to_string_pretty(&comparison).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(comparison)
+}
+
+fn format_delta(delta: &MetricDelta) -> String {
+    match delta.change_percent {
+        Some(change) => format!("{:.1} -> {:.1} ({:+.1}%)", delta.baseline, delta.current, change),
+        None => format!("{:.1} -> {:.1}", delta.baseline, delta.current),
+    }
+}
+
+/// Load the project, resolve variables and run the selected suites.
+pub async fn run_project(options: &RunOptions) -> Result<Vec<SuiteRunResult>, String> {
+    let project_dir = crate::project_storage::resolve_project_dir(&options.project_dir)?;
+    let project = crate::project_storage::load_project_internal(&project_dir.to_string_lossy()).await?;
+
+    let mut variables = crate::settings_manager::get_global_variables()
+        .await
+        .unwrap_or_default();
+    if let Some(env_name) = &options.environment {
+        variables.extend(crate::settings_manager::get_resolved_environment(env_name.clone()).await?);
+    }
+    variables.extend(options.variables.clone());
+
+    let fallback_endpoint = options
+        .endpoint
+        .clone()
+        .or_else(|| variables.get("endpoint_url").cloned());
+
+    let suites = select_suites(&project, &options.suites)?;
+    check_case_filters(&suites, &options.cases)?;
+    let project_name = project.get("name").and_then(|v| v.as_str()).unwrap_or("project");
+    println!("Running {} test suite(s) from '{}'", suites.len(), project_name);
+
+    let mut results = Vec::new();
+    for suite in suites {
+        let suite_name = suite.get("name").and_then(|v| v.as_str()).unwrap_or("Unnamed suite");
+        println!("\nSuite: {}", suite_name);
+
+        let mut cases = Vec::new();
+        for case_value in suite.get("testCases").and_then(|v| v.as_array()).into_iter().flatten() {
+            if !matches_filter(case_value, &options.cases) {
+                continue;
+            }
+
+            let test_case: FrontendTestCase = serde_json::from_value(case_value.clone())
+                .map_err(|e| format!("Invalid test case in suite '{}': {}", suite_name, e))?;
+
+            let rows = data_source::iterations(test_case.data_source.as_ref(), Some(&project_dir))
+                .map_err(|e| format!("Test case '{}': {:#}", test_case.name, e))?;
+
+            for row in rows {
+                // Each case (and data row) starts from the same resolved variables
+                let mut case_variables = variables.clone();
+                if let Some(row) = &row {
+                    case_variables.extend(row.values.clone());
+                }
+                let mut context = RunContext::new(case_variables);
+
+                let mut result = frontend_runner::run_test_case(&test_case, fallback_endpoint.as_deref(), &mut context).await;
+                result.data_row = row;
+                print_case_result(&result);
+                cases.push(result);
+            }
+        }
+
+        results.push(SuiteRunResult {
+            name: suite_name.to_string(),
+            cases,
+        });
+    }
+
+    print_summary(&results);
+
+    if !options.reports.is_empty() {
+        let report = build_report(project_name, &results);
+        for (format, path) in &options.reports {
+            reporting::write_report(&report, *format, path)
+                .map_err(|e| format!("Failed to write report {}: {}", path.display(), e))?;
+            println!("Report written to {}", path.display());
+        }
+    }
+
+    Ok(results)
+}
+
+fn build_report(project_name: &str, results: &[SuiteRunResult]) -> TestReport {
+    let mut report = TestReport::new(project_name);
+    report.suites = results
+        .iter()
+        .map(|suite| ReportSuite::new(suite.name.clone(), suite.cases.iter().map(ReportCase::from).collect()))
+        .collect();
+    report
+}
+
+/// The project's test suites matching `filters` (by name or id), in project
+/// order. Fails on filters matching no suite.
+fn select_suites<'a>(project: &'a Value, filters: &[String]) -> Result<Vec<&'a Value>, String> {
+    let all = project
+        .get("testSuites")
+        .and_then(|v| v.as_array())
+        .map(|v| v.as_slice())
+        .unwrap_or(&[]);
+
+    let selected: Vec<&Value> = all.iter().filter(|s| matches_filter(s, filters)).collect();
+
+    if let Some(missing) = filters
+        .iter()
+        .find(|f| !all.iter().any(|s| matches_filter(s, std::slice::from_ref(*f))))
+    {
+        return Err(format!("Test suite '{}' not found in project", missing));
+    }
+
+    Ok(selected)
+}
+
+/// Fail on `--case` filters matching no test case of the selected suites
+fn check_case_filters(suites: &[&Value], filters: &[String]) -> Result<(), String> {
+    let cases: Vec<&Value> = suites
+        .iter()
+        .flat_map(|s| s.get("testCases").and_then(|v| v.as_array()).into_iter().flatten())
+        .collect();
+    match filters
+        .iter()
+        .find(|f| !cases.iter().any(|c| matches_filter(c, std::slice::from_ref(*f))))
+    {
+        Some(missing) => Err(format!("Test case '{}' not found in the selected suites", missing)),
+        None => Ok(()),
+    }
+}
+
+/// True when the item's name or id is in `filters` (or no filters were given)
+fn matches_filter(item: &Value, filters: &[String]) -> bool {
+    if filters.is_empty() {
+        return true;
+    }
+    let name = item.get("name").and_then(|v| v.as_str());
+    let id = item.get("id").and_then(|v| v.as_str());
+    filters.iter().any(|f| Some(f.as_str()) == name || Some(f.as_str()) == id)
+}
+
+fn print_case_result(result: &TestCaseExecutionResult) {
+    let name = match &result.data_row {
+        Some(row) => row.label(&result.name),
+        None => result.name.clone(),
+    };
+    println!(
+        "  [{}] {} ({}ms)",
+        if result.passed { "PASS" } else { "FAIL" },
+        name,
+        result.duration_ms
+    );
+
+    for step in result.steps.iter().filter(|s| !s.result.passed) {
+        println!(
+            "      step '{}' failed: {}",
+            step.step_name,
+            step.result.error.as_deref().unwrap_or("unknown error")
+        );
+    }
+}
+
+fn print_summary(results: &[SuiteRunResult]) {
+    let total: usize = results.iter().map(|s| s.cases.len()).sum();
+    let failed: usize = results
+        .iter()
+        .map(|s| s.cases.iter().filter(|c| !c.passed).count())
+        .sum();
+
+    println!(
+        "\n{} test case(s), {} passed, {} failed",
+        total,
+        total - failed,
+        failed
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::performance::types::PerformanceStats;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_run_options() {
+        let options = parse_run_args(&args(&[
+            "./my-project", "--suite", "Smoke", "--suite", "Orders",
+            "--env", "Dev", "--var", "token=abc=123", "--endpoint", "http://localhost:8080",
+            "--report", "junit:out/results.xml", "--report", "html:C:\\reports\\run.html",
+        ]))
+        .unwrap();
+
+        assert_eq!(options.project_dir, "./my-project");
+        assert_eq!(options.suites, vec!["Smoke", "Orders"]);
+        assert_eq!(options.environment.as_deref(), Some("Dev"));
+        assert_eq!(options.variables.get("token").map(String::as_str), Some("abc=123"));
+        assert_eq!(options.endpoint.as_deref(), Some("http://localhost:8080"));
+        assert_eq!(
+            options.reports,
+            vec![
+                (ReportFormat::Junit, PathBuf::from("out/results.xml")),
+                (ReportFormat::Html, PathBuf::from("C:\\reports\\run.html")),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_missing_project_and_unknown_flags() {
+        assert!(parse_run_args(&args(&["--env", "Dev"])).is_err());
+        assert!(parse_run_args(&args(&["proj", "--bogus"])).is_err());
+        assert!(parse_run_args(&args(&["proj", "--suite"])).is_err());
+        assert!(parse_run_args(&args(&["proj", "--var", "novalue"])).is_err());
+        assert!(parse_run_args(&args(&["proj", "--report", "pdf:out.pdf"])).is_err());
+        assert!(parse_run_args(&args(&["proj", "--report", "junit"])).is_err());
+    }
+
+    #[test]
+    fn parses_worker_options() {
+        let options =
+            parse_worker_args(&args(&["10.0.0.5:8787", "--id", "agent-1", "--max-vus", "50", "--token", "s3cret"]))
+                .unwrap();
+        assert_eq!(options.coordinator, "10.0.0.5:8787");
+        assert_eq!(options.id.as_deref(), Some("agent-1"));
+        assert_eq!(options.max_concurrent, 50);
+        assert_eq!(options.token.as_deref(), Some("s3cret"));
+
+        assert_eq!(parse_worker_args(&args(&["localhost:8787"])).unwrap().max_concurrent, DEFAULT_WORKER_VUS);
+        assert!(parse_worker_args(&args(&[])).is_err());
+        assert!(parse_worker_args(&args(&["host:1", "--max-vus", "0"])).is_err());
+        assert!(parse_worker_args(&args(&["host:1", "other:2"])).is_err());
+    }
+
+    #[test]
+    fn parses_compare_options() {
+        let options = parse_compare_args(&args(&[
+            "base.json", "--max-latency-increase", "10", "current.json", "--no-significance", "--output", "diff.json",
+        ]))
+        .unwrap();
+        assert_eq!(options.baseline, PathBuf::from("base.json"));
+        assert_eq!(options.current, PathBuf::from("current.json"));
+        assert_eq!(options.thresholds.max_latency_increase, 10.0);
+        assert!(!options.thresholds.require_significance);
+        assert_eq!(options.thresholds.max_throughput_drop, 20.0);
+        assert_eq!(options.output, Some(PathBuf::from("diff.json")));
+
+        assert!(parse_compare_args(&args(&["base.json"])).is_err());
+        assert!(parse_compare_args(&args(&["a", "b", "--alpha", "-1"])).is_err());
+        assert!(parse_compare_args(&args(&["a", "b", "c"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn compare_gate_exit_codes() {
+        let dir = std::env::temp_dir().join(format!("apinox-compare-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let write_run = |name: &str, error: Option<&str>| {
+            let results: Vec<Value> = (0..50)
+                .map(|i| {
+                    serde_json::json!({
+                        "requestId": "r1", "requestName": "Ping", "iteration": i, "duration": 100 + i % 7,
+                        "status": 200, "success": error.is_none() || i % 5 != 0, "slaBreached": false,
+                        "error": error, "timestamp": 1000 + i
+                    })
+                })
+                .collect();
+            let path = dir.join(format!("{}.json", name));
+            let run = serde_json::json!({
+                "id": name, "suiteId": "s1", "suiteName": "Suite", "startTime": 0, "endTime": 5000,
+                "status": "completed", "results": results, "summary": PerformanceStats::calculate(&[])
+            });
+            std::fs::write(&path, run.to_string()).unwrap();
+            path.to_string_lossy().to_string()
+        };
+        let (base, same, failing) = (write_run("base", None), write_run("same", None), write_run("failing", Some("500")));
+
+        assert_eq!(run(args(&["compare", &base, &same])).await, EXIT_PASSED);
+        assert_eq!(run(args(&["compare", &base, &failing])).await, EXIT_FAILED);
+        assert_eq!(run(args(&["compare", &base, "missing.json"])).await, EXIT_USAGE);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn selects_suites_by_name_or_id() {
+        let project = serde_json::json!({
+            "testSuites": [
+                { "id": "s1", "name": "Smoke", "testCases": [] },
+                { "id": "s2", "name": "Orders", "testCases": [] }
+            ]
+        });
+
+        // Suites run in project order, not by name
+        let all = select_suites(&project, &[]).unwrap();
+        assert_eq!(all.iter().map(|s| &s["name"]).collect::<Vec<_>>(), ["Smoke", "Orders"]);
+
+        let selected = select_suites(&project, &["s2".to_string()]).unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0]["name"], "Orders");
+
+        assert!(select_suites(&project, &["Missing".to_string()]).is_err());
+    }
+
+    #[test]
+    fn unmatched_case_filter_is_an_error() {
+        let project = serde_json::json!({
+            "testSuites": [
+                { "id": "s1", "name": "Smoke", "testCases": [{ "id": "c1", "name": "Ping" }] },
+                { "id": "s2", "name": "Orders", "testCases": [{ "id": "c2", "name": "Create" }] }
+            ]
+        });
+        let all = select_suites(&project, &[]).unwrap();
+        assert!(check_case_filters(&all, &[]).is_ok());
+        assert!(check_case_filters(&all, &["Ping".to_string(), "c2".to_string()]).is_ok());
+
+        // Only cases of the selected suites count
+        let smoke = select_suites(&project, &["Smoke".to_string()]).unwrap();
+        let err = check_case_filters(&smoke, &["Create".to_string()]).unwrap_err();
+        assert!(err.contains("'Create'"));
+    }
+
+    #[tokio::test]
+    async fn unknown_command_is_a_usage_error() {
+        assert_eq!(run(args(&["explode"])).await, EXIT_USAGE);
+        assert_eq!(run(vec![]).await, EXIT_USAGE);
+    }
+}
@@ -3,7 +3,12 @@ use serde::Serialize;
 use tauri::{AppHandle, State};
 use uuid::Uuid;
 
+use crate::mock::faults::FaultProfile;
 use crate::mock::server::run_mock;
+use crate::mock::state::MockScenario;
+use crate::mock::{generator, openapi};
+use crate::parsers::openapi_parser::OpenApiSpec;
+use crate::parsers::wsdl::ApiService;
 use crate::proxy_models::{MockRule, MockRuleCollection};
 use crate::{ensure_proxy_state, LazyProxyAppState, ProxyAppState};
 
@@ -14,6 +19,7 @@ pub struct MockStatus {
     pub port: Option<u16>,
     pub rule_count: usize,
     pub record_mode: bool,
+    pub fault_profile: Option<FaultProfile>,
 }
 
 #[tauri::command]
@@ -35,6 +41,10 @@ pub async fn start_mock(
     ms.config.target_url = target_url;
     ms.config.passthrough_enabled = passthrough_enabled;
     ms.config.enabled = true;
+    // Every run starts with all scenarios in their initial state, and seeded
+    // fault sequences from the beginning
+    ms.reset_scenarios(None);
+    ms.faults.reset(None);
 
     let mock_state = state.mock.clone();
     let handle = tokio::spawn(async move {
@@ -71,9 +81,46 @@ pub async fn get_mock_status(state: State<'_, LazyProxyAppState>, app: AppHandle
         port: if ms.running { Some(ms.config.port) } else { None },
         rule_count: ms.config.rules.len(),
         record_mode: ms.config.record_mode,
+        fault_profile: ms.config.fault_profile.clone(),
     })
 }
 
+/// Current state and stored values of every mock scenario
+#[tauri::command]
+pub async fn get_mock_scenarios(state: State<'_, LazyProxyAppState>, app: AppHandle) -> Result<Vec<MockScenario>, String> {
+    let state = ensure_proxy_state(state, &app).await?;
+    let scenarios = state.mock.lock().await.list_scenarios();
+    Ok(scenarios)
+}
+
+/// Reset one scenario (or all when `name` is omitted) to "Started"
+#[tauri::command]
+pub async fn reset_mock_scenarios(
+    name: Option<String>,
+    state: State<'_, LazyProxyAppState>,
+    app: AppHandle,
+) -> Result<Vec<MockScenario>, String> {
+    let state = ensure_proxy_state(state, &app).await?;
+    let mut ms = state.mock.lock().await;
+    ms.reset_scenarios(name.as_deref());
+    log::info!("[Mock] Reset scenario {}", name.as_deref().unwrap_or("(all)"));
+    Ok(ms.list_scenarios())
+}
+
+/// Move a scenario to `scenario_state`, keeping its stored values
+#[tauri::command]
+pub async fn set_mock_scenario_state(
+    name: String,
+    scenario_state: String,
+    state: State<'_, LazyProxyAppState>,
+    app: AppHandle,
+) -> Result<Vec<MockScenario>, String> {
+    let state = ensure_proxy_state(state, &app).await?;
+    let mut ms = state.mock.lock().await;
+    ms.scenarios.entry(name).or_default().state = scenario_state;
+    Ok(ms.list_scenarios())
+}
+
 #[tauri::command]
 pub async fn get_mock_rules(state: State<'_, LazyProxyAppState>, app: AppHandle) -> Result<Vec<MockRule>, String> {
     let state = ensure_proxy_state(state, &app).await?;
@@ -98,6 +145,62 @@ pub async fn add_mock_rule(
     Ok(rule)
 }
 
+/// Create one mock rule per operation of a parsed WSDL service, each
+/// returning a sample response built from the operation's output schema.
+/// Rules generated earlier for the same operations are replaced.
+#[tauri::command]
+pub async fn generate_mock_rules_from_service(
+    service: ApiService,
+    state: State<'_, LazyProxyAppState>,
+    app: AppHandle,
+) -> Result<Vec<MockRule>, String> {
+    let state = ensure_proxy_state(state, &app).await?;
+    let rules = generator::generate_rules(&service);
+    if rules.is_empty() {
+        return Err(format!("No mock rules could be generated for service '{}'", service.name));
+    }
+
+    replace_generated_rules(&state, generator::GENERATED_TAG, &rules).await?;
+    log::info!("[Mock] Generated {} rules for service {}", rules.len(), service.name);
+    Ok(rules)
+}
+
+/// Create mock rules for every declared response of every operation in a
+/// parsed OpenAPI spec, matched on method and path template. With
+/// `validate_requests`, requests that violate the spec get a 400
+/// problem-details response. Rules generated earlier for the same
+/// operations are replaced.
+#[tauri::command]
+pub async fn generate_mock_rules_from_openapi(
+    spec: OpenApiSpec,
+    validate_requests: Option<bool>,
+    state: State<'_, LazyProxyAppState>,
+    app: AppHandle,
+) -> Result<Vec<MockRule>, String> {
+    let state = ensure_proxy_state(state, &app).await?;
+    let rules = openapi::generate_rules(&spec, validate_requests.unwrap_or(false));
+    if rules.is_empty() {
+        return Err(format!("OpenAPI spec '{}' declares no operations", spec.title));
+    }
+
+    replace_generated_rules(&state, openapi::GENERATED_TAG, &rules).await?;
+    log::info!("[Mock] Generated {} rules for OpenAPI spec {}", rules.len(), spec.title);
+    Ok(rules)
+}
+
+/// Add generated rules, dropping rules generated earlier (same `tag`) under
+/// the same names, and persist them
+async fn replace_generated_rules(state: &ProxyAppState, tag: &str, rules: &[MockRule]) -> Result<(), String> {
+    {
+        let mut ms = state.mock.lock().await;
+        ms.config.rules.retain(|existing| {
+            !(existing.tags.iter().any(|t| t == tag) && rules.iter().any(|r| r.name == existing.name))
+        });
+        ms.config.rules.extend(rules.iter().cloned());
+    }
+    save_rules(state).await
+}
+
 #[tauri::command]
 pub async fn update_mock_rule(
     id: String,
@@ -149,6 +252,22 @@ pub async fn set_mock_record_mode(
     Ok(())
 }
 
+/// Set (or clear) the fault profile applied to responses of rules without
+/// their own profile and to passthrough requests. Takes effect immediately;
+/// a seeded profile starts its sequence over.
+#[tauri::command]
+pub async fn set_mock_fault_profile(
+    profile: Option<FaultProfile>,
+    state: State<'_, LazyProxyAppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let state = ensure_proxy_state(state, &app).await?;
+    let mut ms = state.mock.lock().await;
+    ms.config.fault_profile = profile;
+    ms.faults.reset(Some("mock"));
+    Ok(())
+}
+
 /// Persist mock rules to disk. Called from the webview after mutations.
 #[tauri::command]
 pub async fn save_mock_rules(state: State<'_, LazyProxyAppState>, app: AppHandle) -> Result<(), String> {
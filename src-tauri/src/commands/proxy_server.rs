@@ -1,6 +1,7 @@
 use serde::Serialize;
 use tauri::{AppHandle, State};
 
+use crate::mock::faults::FaultProfile;
 use crate::proxy_models::ProxyConfig;
 use crate::proxy::server::run_proxy;
 use crate::{ensure_proxy_state, LazyProxyAppState};
@@ -12,6 +13,7 @@ pub struct ProxyStatus {
     pub port: Option<u16>,
     pub mode: String,
     pub target_url: String,
+    pub fault_profile: Option<FaultProfile>,
 }
 
 #[tauri::command]
@@ -19,6 +21,7 @@ pub async fn start_proxy(
     port: u16,
     mode: String,
     target_url: String,
+    fault_profile: Option<FaultProfile>,
     state: State<'_, LazyProxyAppState>,
     app: AppHandle,
 ) -> Result<(), String> {
@@ -43,8 +46,11 @@ pub async fn start_proxy(
         port,
         target_url,
         mode,
+        fault_profile,
     };
     ps.config = config.clone();
+    // Seeded fault sequences start over with every run
+    state.mock.lock().await.faults.reset(Some("proxy"));
 
     let replacer = state.replacer.clone();
     let cert_manager = state.cert_manager.clone();
@@ -89,5 +95,6 @@ pub async fn get_proxy_status(state: State<'_, LazyProxyAppState>, app: AppHandl
         port: if ps.running { Some(ps.config.port) } else { None },
         mode: ps.config.mode.clone(),
         target_url: ps.config.target_url.clone(),
+        fault_profile: ps.config.fault_profile.clone(),
     })
 }
@@ -24,6 +24,9 @@ pub struct HttpResponse {
     pub status: u16,
     pub status_text: String,
     pub headers: HashMap<String, String>,
+    /// Response headers in received order; names may repeat (`Set-Cookie`)
+    #[serde(skip)]
+    pub header_list: Vec<(String, String)>,
     pub body: String,
     pub time_taken_ms: u64,
     pub error: Option<String>,
@@ -95,6 +98,7 @@ impl HttpClient {
                 status: 0,
                 status_text: "Error".to_string(),
                 headers: HashMap::new(),
+                header_list: Vec::new(),
                 body: String::new(),
                 time_taken_ms: start.elapsed().as_millis() as u64,
                 error: Some(e.to_string()),
@@ -149,12 +153,12 @@ impl HttpClient {
         let status_text = status.canonical_reason().unwrap_or("Unknown").to_string();
 
         // Extract headers
-        let mut headers = HashMap::new();
-        for (key, value) in response.headers() {
-            if let Ok(value_str) = value.to_str() {
-                headers.insert(key.to_string(), value_str.to_string());
-            }
-        }
+        let header_list: Vec<(String, String)> = response
+            .headers()
+            .iter()
+            .filter_map(|(key, value)| value.to_str().ok().map(|v| (key.to_string(), v.to_string())))
+            .collect();
+        let headers: HashMap<String, String> = header_list.iter().cloned().collect();
 
         // Read body
         let body = response.text().await
@@ -167,6 +171,7 @@ impl HttpClient {
             status: status_code,
             status_text,
             headers,
+            header_list,
             body,
             time_taken_ms,
             error: None,
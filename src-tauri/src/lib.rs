@@ -26,6 +26,7 @@ pub mod soap;
 pub mod parsers;
 pub mod testing;
 pub mod workflow;
+pub mod reporting;
 
 // Proxy/mock/cert modules (from APIprox integration)
 pub mod performance;
@@ -39,6 +40,9 @@ pub mod certificates;
 pub mod storage;
 pub mod commands;
 
+// Headless runner used by the apinox-cli binary
+pub mod cli;
+
 #[cfg(target_os = "macos")]
 use tauri_plugin_decorum::WebviewWindowExt;
 
@@ -605,13 +609,22 @@ pub fn run() {
             testing::commands::run_test_case,
             testing::commands::run_test_suite,
             testing::commands::get_test_run_updates,
+            reporting::commands::export_test_report,
             performance::commands::run_performance_suite,
             performance::commands::get_performance_run_updates,
             performance::commands::abort_performance_suite,
             performance::commands::start_coordinator,
             performance::commands::stop_coordinator,
             performance::commands::get_coordinator_status,
+            performance::commands::get_performance_schedules,
+            performance::commands::run_performance_schedule_now,
+            performance::commands::preview_cron_schedule,
+            performance::commands::compare_performance_runs,
             workflow::commands::run_workflow,
+            workflow::commands::run_saved_workflow,
+            workflow::commands::start_workflow_run,
+            workflow::commands::get_workflow_run_updates,
+            workflow::commands::abort_workflow_run,
             workflow::commands::get_workflows,
             workflow::commands::save_workflow,
             workflow::commands::delete_workflow,
@@ -631,10 +644,16 @@ pub fn run() {
             commands::mock_server::stop_mock,
             commands::mock_server::get_mock_status,
             commands::mock_server::get_mock_rules,
+            commands::mock_server::get_mock_scenarios,
+            commands::mock_server::reset_mock_scenarios,
+            commands::mock_server::set_mock_scenario_state,
             commands::mock_server::add_mock_rule,
+            commands::mock_server::generate_mock_rules_from_service,
+            commands::mock_server::generate_mock_rules_from_openapi,
             commands::mock_server::update_mock_rule,
             commands::mock_server::delete_mock_rule,
             commands::mock_server::set_mock_record_mode,
+            commands::mock_server::set_mock_fault_profile,
             commands::mock_server::save_mock_rules,
             commands::mock_server::export_mock_collection,
             commands::mock_server::import_mock_collection,
@@ -668,6 +687,7 @@ pub fn run() {
             init_logging(app)?;
             init_window(app);
             app.manage(LazyProxyAppState::new());
+            performance::scheduler::start();
             Ok(())
         })
         .on_window_event(|_window, event| {
@@ -0,0 +1,408 @@
+// Fault and chaos injection for the mock server and proxy
+//
+// A `FaultProfile` (per mock rule, or global in `MockConfig`/`ProxyConfig`)
+// is turned into a `FaultPlan` for each response by `FaultInjector`, which
+// keeps one RNG per scope so a seeded profile yields the same sequence of
+// faults on every run. Faults that change the response (HTTP errors, SOAP
+// faults) are applied by the servers before the traffic event is emitted;
+// the plan then travels in the response's extensions to `deliver`, which
+// applies latency and the transport faults (resets, timeouts, truncation,
+// throttling).
+
+use std::collections::HashMap;
+use std::io;
+use std::time::Duration;
+
+use bytes::Bytes;
+use http_body_util::channel::Channel;
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full};
+use hyper::Response;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::proxy_models::MockRule;
+use crate::soap::envelope_builder::SoapVersion;
+use crate::utils::CONTENT_TYPE_PLAIN;
+
+/// Body type of responses leaving the mock server and proxy
+pub type FaultBody = BoxBody<Bytes, io::Error>;
+
+/// Throttled bodies are sent in chunks this far apart
+const DRIP_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Random latency added before a response is sent
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LatencyDistribution {
+    #[serde(rename_all = "camelCase")]
+    Fixed { ms: u64 },
+    #[serde(rename_all = "camelCase")]
+    Uniform { min_ms: u64, max_ms: u64 },
+    /// Gaussian around `mean_ms`, never below zero
+    #[serde(rename_all = "camelCase")]
+    Normal { mean_ms: f64, std_dev_ms: f64 },
+    /// Long-tailed: mostly fast, occasionally very slow
+    #[serde(rename_all = "camelCase")]
+    Exponential { mean_ms: f64 },
+}
+
+impl LatencyDistribution {
+    fn sample(&self, rng: &mut StdRng) -> Duration {
+        let ms = match *self {
+            LatencyDistribution::Fixed { ms } => ms as f64,
+            LatencyDistribution::Uniform { min_ms, max_ms } => rng.gen_range(min_ms.min(max_ms)..=max_ms.max(min_ms)) as f64,
+            LatencyDistribution::Normal { mean_ms, std_dev_ms } => {
+                // Box-Muller transform
+                let u1: f64 = 1.0 - rng.gen::<f64>();
+                let u2: f64 = rng.gen();
+                mean_ms + std_dev_ms * (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+            }
+            LatencyDistribution::Exponential { mean_ms } => -mean_ms * (1.0 - rng.gen::<f64>()).ln(),
+        };
+        Duration::from_millis(ms.max(0.0).round() as u64)
+    }
+}
+
+/// How a mock rule, the mock server or the proxy misbehaves. Percentages are
+/// of responses (0-100); at most one fault is picked per response.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FaultProfile {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Seed for the random choices; without one every run differs
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub latency: Option<LatencyDistribution>,
+    /// Responses replaced by `error_status`
+    #[serde(default)]
+    pub error_percent: f64,
+    /// Status of injected HTTP errors (default 503)
+    #[serde(default)]
+    pub error_status: Option<u16>,
+    /// Responses replaced by a SOAP fault (500, in the request's SOAP version)
+    #[serde(default)]
+    pub soap_fault_percent: f64,
+    /// Connections closed without a response
+    #[serde(default)]
+    pub reset_percent: f64,
+    /// Requests held for `timeout_ms`, then closed without a response
+    #[serde(default)]
+    pub timeout_percent: f64,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Responses cut off halfway through the body
+    #[serde(default)]
+    pub truncate_percent: f64,
+    /// Send bodies no faster than this (slow drip)
+    #[serde(default)]
+    pub bytes_per_second: Option<u64>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+const DEFAULT_ERROR_STATUS: u16 = 503;
+const DEFAULT_TIMEOUT_MS: u64 = 30_000;
+
+/// A fault picked for one response
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    HttpError(u16),
+    SoapFault,
+    Reset,
+    Timeout(Duration),
+    Truncate,
+}
+
+/// Everything a profile decided for one response
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FaultPlan {
+    pub latency: Duration,
+    pub fault: Option<Fault>,
+    pub bytes_per_second: Option<u64>,
+}
+
+impl FaultPlan {
+    /// The response replacing the real one for `HttpError` and `SoapFault`
+    /// faults; the SOAP version follows the request's Content-Type
+    pub fn replacement(&self, request_headers: &HashMap<String, String>) -> Option<(u16, HashMap<String, String>, String)> {
+        match self.fault {
+            Some(Fault::HttpError(status)) => Some((
+                status,
+                HashMap::from([("content-type".to_string(), CONTENT_TYPE_PLAIN.to_string())]),
+                format!("Injected fault: HTTP {}", status),
+            )),
+            Some(Fault::SoapFault) => {
+                let is_soap12 = request_headers
+                    .iter()
+                    .any(|(k, v)| k.eq_ignore_ascii_case("content-type") && v.contains("application/soap+xml"));
+                let version = if is_soap12 { SoapVersion::Soap12 } else { SoapVersion::Soap11 };
+                Some((
+                    500,
+                    HashMap::from([("content-type".to_string(), version.content_type().to_string())]),
+                    soap_fault(version),
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn soap_fault(version: SoapVersion) -> String {
+    let fault = match version {
+        SoapVersion::Soap11 => {
+            "<soap:Fault><faultcode>soap:Server</faultcode><faultstring>Injected fault</faultstring></soap:Fault>"
+        }
+        SoapVersion::Soap12 => {
+            "<soap:Fault><soap:Code><soap:Value>soap:Receiver</soap:Value></soap:Code>\
+             <soap:Reason><soap:Text xml:lang=\"en\">Injected fault</soap:Text></soap:Reason></soap:Fault>"
+        }
+    };
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<soap:Envelope xmlns:soap=\"{}\"><soap:Body>{}</soap:Body></soap:Envelope>",
+        version.namespace(),
+        fault
+    )
+}
+
+/// Random state of the fault profiles in use, one RNG per scope ("mock",
+/// "proxy" or a rule id)
+#[derive(Debug, Default)]
+pub struct FaultInjector {
+    rngs: HashMap<String, (Option<u64>, StdRng)>,
+}
+
+impl FaultInjector {
+    /// Decide latency and fault for the next response under `profile`
+    pub fn plan(&mut self, scope: &str, profile: &FaultProfile) -> FaultPlan {
+        if !profile.enabled {
+            return FaultPlan::default();
+        }
+        // A changed seed restarts the sequence
+        let (seed, rng) = self
+            .rngs
+            .entry(scope.to_string())
+            .or_insert_with(|| (profile.seed, new_rng(profile.seed)));
+        if *seed != profile.seed {
+            *seed = profile.seed;
+            *rng = new_rng(profile.seed);
+        }
+
+        let latency = profile.latency.as_ref().map(|l| l.sample(rng)).unwrap_or_default();
+        let roll = rng.gen::<f64>() * 100.0;
+        let candidates = [
+            (profile.reset_percent, Fault::Reset),
+            (profile.timeout_percent, Fault::Timeout(Duration::from_millis(profile.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS)))),
+            (profile.error_percent, Fault::HttpError(profile.error_status.unwrap_or(DEFAULT_ERROR_STATUS))),
+            (profile.soap_fault_percent, Fault::SoapFault),
+            (profile.truncate_percent, Fault::Truncate),
+        ];
+        let mut threshold = 0.0;
+        let fault = candidates.into_iter().find_map(|(percent, fault)| {
+            threshold += percent.max(0.0);
+            (roll < threshold).then_some(fault)
+        });
+        if let Some(fault) = &fault {
+            log::info!("[Faults] Injecting {:?} ({})", fault, scope);
+        }
+
+        FaultPlan { latency, fault, bytes_per_second: profile.bytes_per_second.filter(|b| *b > 0) }
+    }
+
+    /// Plan with the matched rule's own profile, else with the server's
+    /// `global` profile (scope `global_scope`); `None` when neither is set
+    pub fn plan_for(&mut self, rule: Option<&MockRule>, global_scope: &str, global: Option<&FaultProfile>) -> Option<FaultPlan> {
+        match rule.and_then(|r| r.fault_profile.as_ref().map(|profile| (r.id.as_str(), profile))) {
+            Some((scope, profile)) => Some(self.plan(scope, profile)),
+            None => global.map(|profile| self.plan(global_scope, profile)),
+        }
+    }
+
+    /// Forget the random state of one scope, or of all of them, so seeded
+    /// profiles start their sequence again
+    pub fn reset(&mut self, scope: Option<&str>) {
+        match scope {
+            Some(scope) => {
+                self.rngs.remove(scope);
+            }
+            None => self.rngs.clear(),
+        }
+    }
+}
+
+fn new_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Attach a plan for `deliver` to pick up
+pub fn with_plan<B>(mut response: Response<B>, plan: Option<FaultPlan>) -> Response<B> {
+    if let Some(plan) = plan {
+        response.extensions_mut().insert(plan);
+    }
+    response
+}
+
+/// Send a response according to its fault plan: wait out the latency, then
+/// fail the connection (reset, timeout) or stream the body (truncated and/or
+/// throttled). Responses without a plan are sent as they are.
+pub async fn deliver(response: Response<Full<Bytes>>) -> Result<Response<FaultBody>, io::Error> {
+    let plan = response.extensions().get::<FaultPlan>().cloned().unwrap_or_default();
+    if !plan.latency.is_zero() {
+        tokio::time::sleep(plan.latency).await;
+    }
+
+    let truncate = match plan.fault {
+        // Failing the service makes hyper drop the connection without a response
+        Some(Fault::Reset) => return Err(io::Error::new(io::ErrorKind::ConnectionReset, "Injected connection reset")),
+        Some(Fault::Timeout(wait)) => {
+            tokio::time::sleep(wait).await;
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "Injected timeout"));
+        }
+        Some(Fault::Truncate) => true,
+        _ => false,
+    };
+    if !truncate && plan.bytes_per_second.is_none() {
+        return Ok(response.map(|body| body.map_err(|never| match never {}).boxed()));
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match body.collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(never) => match never {},
+    };
+    // Content-Length still announces the whole body, so a truncated response
+    // ends early on the wire
+    let end = if truncate { bytes.len() / 2 } else { bytes.len() };
+    let chunk_size = plan
+        .bytes_per_second
+        .map(|rate| (rate * DRIP_INTERVAL.as_millis() as u64 / 1000).max(1) as usize)
+        .unwrap_or(end.max(1));
+
+    let (mut sender, channel) = Channel::<Bytes, io::Error>::new(1);
+    tokio::spawn(async move {
+        let mut offset = 0;
+        while offset < end {
+            let next = (offset + chunk_size).min(end);
+            if sender.send_data(bytes.slice(offset..next)).await.is_err() {
+                return;
+            }
+            offset = next;
+            if plan.bytes_per_second.is_some() && offset < end {
+                tokio::time::sleep(DRIP_INTERVAL).await;
+            }
+        }
+        if truncate {
+            sender.abort(io::Error::new(io::ErrorKind::ConnectionAborted, "Injected truncated body"));
+        }
+    });
+    Ok(Response::from_parts(parts, channel.boxed()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> FaultProfile {
+        serde_json::from_value(serde_json::json!({
+            "seed": 42,
+            "latency": { "type": "uniform", "minMs": 10, "maxMs": 50 },
+            "errorPercent": 20,
+            "soapFaultPercent": 10,
+            "resetPercent": 10,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_seeded_profile_is_deterministic() {
+        let profile = profile();
+        let run = |injector: &mut FaultInjector| (0..200).map(|_| injector.plan("rule-1", &profile)).collect::<Vec<_>>();
+        let mut injector = FaultInjector::default();
+        let first = run(&mut injector);
+        injector.reset(None);
+        assert_eq!(run(&mut injector), first);
+
+        assert!(first.iter().all(|p| (10..=50).contains(&(p.latency.as_millis() as u64))));
+        let count = |f: &dyn Fn(&Option<Fault>) -> bool| first.iter().filter(|p| f(&p.fault)).count();
+        let errors = count(&|f| matches!(f, Some(Fault::HttpError(503))));
+        let resets = count(&|f| matches!(f, Some(Fault::Reset)));
+        let clean = count(&|f| f.is_none());
+        assert!((20..=60).contains(&errors), "{}", errors);
+        assert!((5..=35).contains(&resets), "{}", resets);
+        assert!((100..=160).contains(&clean), "{}", clean);
+
+        let disabled = FaultProfile { enabled: false, ..profile };
+        assert_eq!(injector.plan("rule-1", &disabled), FaultPlan::default());
+    }
+
+    #[test]
+    fn test_latency_distributions() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let normal = LatencyDistribution::Normal { mean_ms: 100.0, std_dev_ms: 10.0 };
+        let mean = (0..1000).map(|_| normal.sample(&mut rng).as_millis() as f64).sum::<f64>() / 1000.0;
+        assert!((95.0..105.0).contains(&mean), "{}", mean);
+
+        let exponential = LatencyDistribution::Exponential { mean_ms: 50.0 };
+        let samples: Vec<u128> = (0..1000).map(|_| exponential.sample(&mut rng).as_millis()).collect();
+        let mean = samples.iter().sum::<u128>() as f64 / 1000.0;
+        assert!((40.0..60.0).contains(&mean), "{}", mean);
+        assert!(samples.iter().any(|ms| *ms > 150));
+
+        assert_eq!(LatencyDistribution::Fixed { ms: 25 }.sample(&mut rng), Duration::from_millis(25));
+    }
+
+    #[test]
+    fn test_replacement_follows_soap_version() {
+        let plan = FaultPlan { fault: Some(Fault::SoapFault), ..Default::default() };
+        let headers = HashMap::from([("Content-Type".to_string(), "application/soap+xml; charset=utf-8".to_string())]);
+        let (status, response_headers, body) = plan.replacement(&headers).unwrap();
+        assert_eq!(status, 500);
+        assert!(response_headers["content-type"].starts_with("application/soap+xml"));
+        assert!(body.contains(SoapVersion::Soap12.namespace()) && body.contains("soap:Receiver"));
+
+        let (_, _, body) = plan.replacement(&HashMap::new()).unwrap();
+        assert!(body.contains("<faultcode>soap:Server</faultcode>"));
+        assert!(FaultPlan { fault: Some(Fault::Reset), ..Default::default() }.replacement(&HashMap::new()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_deliver_truncates_and_drips() {
+        let body = "x".repeat(100);
+        let response = |plan: FaultPlan| {
+            with_plan(
+                Response::builder().header("content-length", 100).body(Full::new(Bytes::from(body.clone()))).unwrap(),
+                Some(plan),
+            )
+        };
+
+        let truncated = deliver(response(FaultPlan { fault: Some(Fault::Truncate), ..Default::default() })).await.unwrap();
+        let mut body_stream = truncated.into_body();
+        let mut received = 0;
+        let error = loop {
+            match body_stream.frame().await {
+                Some(Ok(frame)) => received += frame.into_data().map(|d| d.len()).unwrap_or(0),
+                Some(Err(e)) => break Some(e),
+                None => break None,
+            }
+        };
+        assert_eq!(received, 50);
+        assert_eq!(error.unwrap().kind(), io::ErrorKind::ConnectionAborted);
+
+        let started = std::time::Instant::now();
+        let dripped = deliver(response(FaultPlan { bytes_per_second: Some(400), ..Default::default() })).await.unwrap();
+        let collected = dripped.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(collected.len(), 100);
+        assert!(started.elapsed() >= Duration::from_millis(200));
+
+        let reset = deliver(response(FaultPlan { fault: Some(Fault::Reset), ..Default::default() })).await;
+        assert_eq!(reset.unwrap_err().kind(), io::ErrorKind::ConnectionReset);
+    }
+}
@@ -0,0 +1,187 @@
+// Mock rules generated from a parsed WSDL service
+//
+// One rule per operation, answering with a sample response envelope built
+// from the operation's output schema by `soap::envelope_builder`.
+
+use uuid::Uuid;
+
+use crate::parsers::wsdl::types::{ApiService, SchemaNode, ServiceOperation};
+use crate::proxy_models::{MockMatchCondition, MockRule};
+use crate::soap::envelope_builder::{EnvelopeBuilder, SoapVersion};
+
+/// Tag added to every generated rule
+pub const GENERATED_TAG: &str = "wsdl";
+
+/// Build one enabled mock rule per operation of `service`.
+/// Operations whose response envelope cannot be built are skipped.
+pub fn generate_rules(service: &ApiService) -> Vec<MockRule> {
+    // The parser names SOAP 1.2 bindings "{Service}Soap12"
+    let version = if service.name.ends_with("Soap12") { SoapVersion::Soap12 } else { SoapVersion::Soap11 };
+    service
+        .operations
+        .iter()
+        .filter_map(|operation| match generate_rule(&service.name, operation, version) {
+            Ok(rule) => Some(rule),
+            Err(e) => {
+                log::warn!("[Mock] Skipping operation '{}': {}", operation.name, e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn generate_rule(service_name: &str, operation: &ServiceOperation, version: SoapVersion) -> anyhow::Result<MockRule> {
+    let response_body = build_response(operation, version)?;
+
+    Ok(MockRule {
+        id: Uuid::new_v4().to_string(),
+        name: format!("{} {}", service_name, operation.name),
+        enabled: true,
+        conditions: vec![match_condition(operation, version)],
+        status_code: 200,
+        response_body,
+        content_type: Some(version.content_type().to_string()),
+        response_headers: None,
+        delay_ms: None,
+        templated: false,
+        status_template: None,
+        scenario: None,
+        required_state: None,
+        new_state: None,
+        scenario_values: None,
+        request_validation: None,
+        fault_profile: None,
+        hit_count: 0,
+        recorded_at: None,
+        recorded_from: None,
+        tags: vec![GENERATED_TAG.to_string(), service_name.to_string()],
+    })
+}
+
+/// Sample response envelope; operations parsed without an output schema get
+/// an empty `{operation}Response` element
+fn build_response(operation: &ServiceOperation, version: SoapVersion) -> anyhow::Result<String> {
+    let output = operation.output_schema.clone().unwrap_or_else(|| SchemaNode {
+        name: format!("{}Response", operation.name),
+        node_type: format!("{}Response", operation.name),
+        kind: "complex".to_string(),
+        min_occurs: None,
+        max_occurs: None,
+        documentation: None,
+        children: Some(vec![]),
+        options: None,
+        is_optional: None,
+    });
+    let response_operation = ServiceOperation { full_schema: Some(output), ..operation.clone() };
+    EnvelopeBuilder::new(version, response_operation).build()
+}
+
+/// SOAP 1.1 requests are told apart by their SOAPAction header; SOAP 1.2 puts
+/// the action in Content-Type, so those (and operations without an action)
+/// match on the body's request element instead
+fn match_condition(operation: &ServiceOperation, version: SoapVersion) -> MockMatchCondition {
+    match operation.action.as_deref().filter(|a| !a.is_empty()) {
+        Some(action) if version == SoapVersion::Soap11 => MockMatchCondition {
+            r#type: "soapAction".to_string(),
+            pattern: format!("^{}$", regex::escape(action)),
+            is_regex: true,
+            header_name: None,
+        },
+        _ => {
+            let element = operation.full_schema.as_ref().map_or(operation.name.as_str(), |s| s.name.as_str());
+            MockMatchCondition {
+                r#type: "xpath".to_string(),
+                pattern: format!(
+                    "/*[local-name()='Envelope']/*[local-name()='Body']/*[local-name()='{}']",
+                    element
+                ),
+                is_regex: false,
+                header_name: None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::server::find_matching_rule;
+    use crate::parsers::wsdl::WsdlParser;
+    use apinox_wsdl_parser::SchemaValidator;
+    use std::collections::HashMap;
+
+    const WSDL: &str = r#"<?xml version="1.0"?>
+<definitions name="OrderService" targetNamespace="urn:orders" xmlns:tns="urn:orders"
+    xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+    xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <types>
+    <xsd:schema targetNamespace="urn:orders">
+      <xsd:element name="GetOrder">
+        <xsd:complexType><xsd:sequence><xsd:element name="id" type="xsd:int"/></xsd:sequence></xsd:complexType>
+      </xsd:element>
+      <xsd:element name="GetOrderResponse">
+        <xsd:complexType><xsd:sequence>
+          <xsd:element name="id" type="xsd:int"/>
+          <xsd:element name="status" type="tns:OrderStatus"/>
+          <xsd:element name="placed" type="xsd:dateTime"/>
+          <xsd:element name="note" type="xsd:string" minOccurs="0"/>
+        </xsd:sequence></xsd:complexType>
+      </xsd:element>
+      <xsd:simpleType name="OrderStatus">
+        <xsd:restriction base="xsd:string">
+          <xsd:enumeration value="PENDING"/><xsd:enumeration value="COMPLETED"/>
+        </xsd:restriction>
+      </xsd:simpleType>
+    </xsd:schema>
+  </types>
+  <message name="GetOrderIn"><part name="parameters" element="tns:GetOrder"/></message>
+  <message name="GetOrderOut"><part name="parameters" element="tns:GetOrderResponse"/></message>
+  <portType name="OrderPort">
+    <operation name="GetOrder"><input message="tns:GetOrderIn"/><output message="tns:GetOrderOut"/></operation>
+  </portType>
+  <binding name="OrderBinding" type="tns:OrderPort">
+    <soap:binding transport="http://schemas.xmlsoap.org/soap/http"/>
+    <operation name="GetOrder"><soap:operation soapAction="urn:orders/GetOrder"/></operation>
+  </binding>
+  <service name="OrderService">
+    <port name="OrderPort" binding="tns:OrderBinding"><soap:address location="http://example.com/orders"/></port>
+  </service>
+</definitions>"#;
+
+    #[test]
+    fn test_generates_schema_valid_rule_per_operation() {
+        let services = WsdlParser::parse(WSDL).unwrap();
+        let rules = generate_rules(&services[0]);
+        assert_eq!(rules.len(), 1);
+
+        let rule = &rules[0];
+        assert_eq!(rule.name, "OrderServiceSoap GetOrder");
+        assert_eq!(rule.tags, ["wsdl", "OrderServiceSoap"]);
+        assert!(rule.response_body.contains("<tns:GetOrderResponse>"));
+        assert!(rule.response_body.contains("PENDING"));
+        assert!(!rule.response_body.contains("note"));
+
+        let schema = WsdlParser::response_schema(WSDL, "GetOrder").unwrap();
+        let violations = SchemaValidator::validate(&schema, &rule.response_body).unwrap();
+        assert!(violations.is_empty(), "{:?}", violations);
+
+        let headers = HashMap::from([("soapaction".to_string(), "\"urn:orders/GetOrder\"".to_string())]);
+        assert!(find_matching_rule(&rules, "POST", "/orders", &headers, "").is_some());
+        let headers = HashMap::from([("soapaction".to_string(), "\"urn:orders/GetOrderX\"".to_string())]);
+        assert!(find_matching_rule(&rules, "POST", "/orders", &headers, "").is_none());
+    }
+
+    #[test]
+    fn test_soap12_matches_on_body_element() {
+        let mut service = WsdlParser::parse(WSDL).unwrap().remove(0);
+        service.name = "OrderServiceSoap12".to_string();
+        let rule = generate_rules(&service).remove(0);
+        assert_eq!(rule.content_type.as_deref(), Some(SoapVersion::Soap12.content_type()));
+        assert!(rule.response_body.contains(SoapVersion::Soap12.namespace()));
+
+        let body = r#"<s:Envelope xmlns:s="http://www.w3.org/2003/05/soap-envelope"><s:Body><o:GetOrder xmlns:o="urn:orders"><o:id>1</o:id></o:GetOrder></s:Body></s:Envelope>"#;
+        let rules = [rule];
+        assert!(find_matching_rule(&rules, "POST", "/orders", &HashMap::new(), body).is_some());
+        assert!(find_matching_rule(&rules, "POST", "/orders", &HashMap::new(), "<Other/>").is_none());
+    }
+}
@@ -1,2 +1,6 @@
 pub mod state;
 pub mod server;
+pub mod generator;
+pub mod openapi;
+pub mod faults;
+pub mod template;
@@ -0,0 +1,275 @@
+// Mock rules generated from a parsed OpenAPI spec
+//
+// One rule per declared response of every operation, matched on method and
+// path template. Only an operation's lowest 2xx response is enabled; the
+// others are generated disabled, ready to be switched on to simulate errors.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::mock::template::split_url;
+use crate::parsers::openapi_parser::{OpenApiMediaType, OpenApiPath, OpenApiResponse, OpenApiSpec};
+use crate::parsers::openapi_validator::is_json;
+use crate::proxy_models::{MockMatchCondition, MockRequestValidation, MockRule};
+use crate::utils::CONTENT_TYPE_PLAIN;
+
+/// Tag added to every generated rule
+pub const GENERATED_TAG: &str = "openapi";
+
+/// Build the mock rules for every operation of `spec`. With
+/// `validate_requests`, requests violating the operation's parameters or
+/// request body are answered with a 400 problem-details body.
+pub fn generate_rules(spec: &OpenApiSpec, validate_requests: bool) -> Vec<MockRule> {
+    // Servers such as "https://api.example.com/v1" mount every path under "/v1"
+    let base_path = spec
+        .base_url
+        .as_deref()
+        .map(|url| split_url(url).0.trim_end_matches('/').to_string())
+        .unwrap_or_default();
+
+    // Concrete paths take precedence over templated ones (`/orders/search` before `/orders/{id}`)
+    let mut operations: Vec<&OpenApiPath> = spec.paths.iter().collect();
+    operations.sort_by_key(|op| op.path.matches('{').count());
+
+    operations
+        .into_iter()
+        .flat_map(|op| operation_rules(spec, op, &base_path, validate_requests))
+        .collect()
+}
+
+fn operation_rules(spec: &OpenApiSpec, op: &OpenApiPath, base_path: &str, validate_requests: bool) -> Vec<MockRule> {
+    // Operations declaring no responses still get a bodiless 200
+    let implicit_ok = [OpenApiResponse { status: "200".to_string(), description: None, content: vec![] }];
+    let responses = if op.responses.is_empty() { &implicit_ok[..] } else { &op.responses[..] };
+
+    let enabled_status = responses
+        .iter()
+        .map(|r| status_code(&r.status))
+        .filter(|s| (200..300).contains(s))
+        .min()
+        .unwrap_or_else(|| status_code(&responses[0].status));
+
+    let request_validation = validate_requests.then(|| MockRequestValidation {
+        parameters: op.parameters.clone(),
+        request_body: op.request_body.clone(),
+        schemas: referenced_schemas(spec, op),
+    });
+    let conditions = vec![
+        MockMatchCondition {
+            r#type: "method".to_string(),
+            pattern: op.method.clone(),
+            is_regex: false,
+            header_name: None,
+        },
+        MockMatchCondition {
+            r#type: "path".to_string(),
+            pattern: format!("{}{}", base_path, op.path),
+            is_regex: false,
+            header_name: None,
+        },
+    ];
+
+    let mut enabled_taken = false;
+    responses
+        .iter()
+        .map(|response| {
+            let status = status_code(&response.status);
+            let enabled = status == enabled_status && !enabled_taken;
+            enabled_taken |= enabled;
+            let (response_body, content_type) = match preferred_media(&response.content) {
+                Some(media) => sample_body(spec, media),
+                None => (String::new(), CONTENT_TYPE_PLAIN.to_string()),
+            };
+            MockRule {
+                id: Uuid::new_v4().to_string(),
+                name: format!("{} {} {}", op.method, op.path, response.status),
+                enabled,
+                conditions: conditions.clone(),
+                status_code: status,
+                response_body,
+                content_type: Some(content_type),
+                response_headers: None,
+                delay_ms: None,
+                templated: false,
+                status_template: None,
+                scenario: None,
+                required_state: None,
+                new_state: None,
+                scenario_values: None,
+                request_validation: request_validation.clone(),
+                fault_profile: None,
+                hit_count: 0,
+                recorded_at: None,
+                recorded_from: None,
+                tags: vec![GENERATED_TAG.to_string(), spec.title.clone()],
+            }
+        })
+        .collect()
+}
+
+/// "404" -> 404, ranges such as "4XX" -> 400, "default" -> 500
+fn status_code(status: &str) -> u16 {
+    if let Ok(code) = status.parse() {
+        return code;
+    }
+    match status.chars().next().and_then(|c| c.to_digit(10)) {
+        Some(class @ 1..=5) if status.len() == 3 => class as u16 * 100,
+        _ => 500,
+    }
+}
+
+/// Prefer a JSON media type, the one a sample can be generated for
+fn preferred_media(content: &[OpenApiMediaType]) -> Option<&OpenApiMediaType> {
+    content.iter().find(|m| is_json(&m.content_type)).or_else(|| content.first())
+}
+
+/// The media type's example, or a sample generated from its schema, with
+/// the Content-Type to send it with
+fn sample_body(spec: &OpenApiSpec, media: &OpenApiMediaType) -> (String, String) {
+    let content_type = match media.content_type.as_str() {
+        "*/*" | "application/*" => "application/json".to_string(),
+        other => other.to_string(),
+    };
+    let body = match (&media.example, &media.schema) {
+        // Non-JSON examples (XML, plain text) are given as strings
+        (Some(Value::String(text)), _) if !is_json(&content_type) => text.clone(),
+        (Some(example), _) => serde_json::to_string_pretty(example).unwrap_or_default(),
+        (None, Some(schema)) if is_json(&content_type) => {
+            serde_json::to_string_pretty(&spec.sample_for_schema(schema)).unwrap_or_default()
+        }
+        _ => String::new(),
+    };
+    (body, content_type)
+}
+
+/// The part of `spec.schemas` reachable from the operation's parameter and
+/// request body schemas
+fn referenced_schemas(spec: &OpenApiSpec, op: &OpenApiPath) -> BTreeMap<String, Value> {
+    fn collect(schema: &Value, spec: &OpenApiSpec, refs: &mut BTreeMap<String, Value>) {
+        match schema {
+            Value::Object(map) => {
+                if let Some(ref_path) = map.get("$ref").and_then(|r| r.as_str()) {
+                    if let Some(resolved) = spec.schemas.get(ref_path).filter(|_| !refs.contains_key(ref_path)) {
+                        refs.insert(ref_path.to_string(), resolved.clone());
+                        collect(resolved, spec, refs);
+                    }
+                }
+                map.values().for_each(|v| collect(v, spec, refs));
+            }
+            Value::Array(items) => items.iter().for_each(|v| collect(v, spec, refs)),
+            _ => {}
+        }
+    }
+
+    let mut refs = BTreeMap::new();
+    let body_schemas = op.request_body.iter().flat_map(|b| &b.content).filter_map(|m| m.schema.as_ref());
+    for schema in op.parameters.iter().filter_map(|p| p.schema.as_ref()).chain(body_schemas) {
+        collect(schema, spec, &mut refs);
+    }
+    refs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::server::find_matching_rule;
+    use crate::parsers::OpenApiParser;
+    use std::collections::HashMap;
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: Orders, version: "1" }
+servers:
+  - url: https://api.example.com/v1
+paths:
+  /orders/{id}:
+    get:
+      parameters:
+        - { name: id, in: path, required: true, schema: { type: integer } }
+      responses:
+        '200':
+          description: An order
+          content:
+            application/json:
+              schema: { $ref: '#/components/schemas/Order' }
+        '404':
+          description: Not found
+          content:
+            application/json:
+              example: { title: Not found }
+  /orders/latest:
+    get:
+      responses:
+        '200':
+          description: Latest order
+          content:
+            application/json:
+              schema: { $ref: '#/components/schemas/Order' }
+  /orders:
+    post:
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema: { $ref: '#/components/schemas/Order' }
+      responses:
+        '201': { description: Created }
+        4XX: { description: Rejected }
+components:
+  schemas:
+    Order:
+      type: object
+      required: [state]
+      properties:
+        id: { type: integer }
+        state: { type: string, enum: [OPEN, SHIPPED] }
+"##;
+
+    #[test]
+    fn test_generates_rule_per_declared_response() {
+        let spec = OpenApiParser::parse_auto(SPEC).unwrap();
+        let rules = generate_rules(&spec, false);
+        let summary: Vec<(&str, u16, bool)> =
+            rules.iter().map(|r| (r.name.as_str(), r.status_code, r.enabled)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("POST /orders 201", 201, true),
+                ("POST /orders 4XX", 400, false),
+                ("GET /orders/latest 200", 200, true),
+                ("GET /orders/{id} 200", 200, true),
+                ("GET /orders/{id} 404", 404, false),
+            ]
+        );
+
+        let get_order = &rules[3];
+        assert_eq!(get_order.conditions[1].pattern, "/v1/orders/{id}");
+        assert_eq!(get_order.tags, ["openapi", "Orders"]);
+        let body: Value = serde_json::from_str(&get_order.response_body).unwrap();
+        assert_eq!(body, serde_json::json!({"id": 0, "state": "OPEN"}));
+        assert!(rules[4].response_body.contains("Not found"));
+        assert_eq!(rules[0].response_body, "");
+
+        let found = find_matching_rule(&rules, "GET", "/v1/orders/latest", &HashMap::new(), "").unwrap();
+        assert_eq!(found.name, "GET /orders/latest 200");
+        let found = find_matching_rule(&rules, "GET", "/v1/orders/7", &HashMap::new(), "").unwrap();
+        assert_eq!(found.name, "GET /orders/{id} 200");
+        assert!(find_matching_rule(&rules, "DELETE", "/v1/orders/7", &HashMap::new(), "").is_none());
+    }
+
+    #[test]
+    fn test_request_validation_carries_operation_schemas() {
+        let spec = OpenApiParser::parse_auto(SPEC).unwrap();
+        let rules = generate_rules(&spec, true);
+        let create = &rules[0];
+        let validation = create.request_validation.as_ref().unwrap();
+        assert!(validation.request_body.as_ref().unwrap().required);
+        assert_eq!(validation.schemas.keys().collect::<Vec<_>>(), ["#/components/schemas/Order"]);
+        assert!(generate_rules(&spec, false).iter().all(|r| r.request_validation.is_none()));
+
+        assert_eq!(status_code("5XX"), 500);
+        assert_eq!(status_code("default"), 500);
+    }
+}
@@ -1,5 +1,4 @@
 use std::collections::HashMap;
-use std::convert::Infallible;
 use std::net::SocketAddr;
 
 use anyhow::{Context, Result};
@@ -14,9 +13,15 @@ use tauri::AppHandle;
 use tokio::net::TcpListener;
 use uuid::Uuid;
 
-use crate::mock::state::SharedMockState;
-use crate::proxy_models::{MockMatchCondition, MockRule, TrafficEvent};
-use crate::utils::{emit_traffic_event, match_pattern, XPathEvaluator, CONTENT_TYPE_XML, CONTENT_TYPE_PLAIN};
+use crate::mock::faults::{self, FaultPlan};
+use crate::mock::state::{MockState, ScenarioState, SharedMockState, SCENARIO_STARTED};
+use crate::mock::template::{self, MockRequest};
+use crate::parsers::openapi_validator::{self, RequestToValidate};
+use crate::proxy_models::{MockMatchCondition, MockRequestValidation, MockRule, TrafficEvent};
+use crate::utils::{emit_traffic_event, match_pattern, SchemaError, XPathEvaluator, CONTENT_TYPE_XML, CONTENT_TYPE_PLAIN};
+
+/// Media type of the body returned for requests failing `request_validation`
+const CONTENT_TYPE_PROBLEM: &str = "application/problem+json";
 
 /// Run the mock HTTP server. Loops forever; cancel via AbortHandle.
 pub async fn run_mock(state: SharedMockState, app: AppHandle) -> Result<()> {
@@ -45,7 +50,7 @@ pub async fn run_mock(state: SharedMockState, app: AppHandle) -> Result<()> {
             let svc = service_fn(move |req: Request<Incoming>| {
                 let state = state.clone();
                 let app = app.clone();
-                async move { Ok::<_, Infallible>(handle_mock_request(req, state, app).await) }
+                async move { faults::deliver(handle_mock_request(req, state, app).await).await }
             });
 
             if let Err(e) = hyper::server::conn::http1::Builder::new()
@@ -85,21 +90,17 @@ async fn handle_mock_request(
     };
     let req_body = String::from_utf8_lossy(&body_bytes).into_owned();
 
-    // Snapshot config under lock, then release before any async I/O
-    let (rules, passthrough_enabled, target_url, record_mode) = {
-        let s = state.lock().await;
-        (
-            s.config.rules.clone(),
-            s.config.passthrough_enabled,
-            s.config.target_url.clone(),
-            s.config.record_mode,
-        )
+    // Match and claim a rule in one go, then release the lock before any
+    // async I/O
+    let mut template_request = MockRequest::new(&method, &url, &req_headers, &req_body);
+    let (claimed, passthrough_enabled, target_url, record_mode) = {
+        let mut s = state.lock().await;
+        let claimed = claim_rule(&mut s, &mut template_request);
+        (claimed, s.config.passthrough_enabled, s.config.target_url.clone(), s.config.record_mode)
     };
 
-    // Find first matching rule
-    let matched = find_matching_rule(&rules, &method, &url, &req_headers, &req_body);
-
-    if let Some(rule) = matched {
+    if let Some(ClaimedRule { rule, violations, scenario_result, fault_plan }) = claimed {
+        let rule = &rule;
         // Optional delay
         if let Some(delay) = rule.delay_ms {
             if delay > 0 {
@@ -107,25 +108,35 @@ async fn handle_mock_request(
             }
         }
 
-        // Increment hit count in shared state
-        {
-            let mut s = state.lock().await;
-            if let Some(r) = s.config.rules.iter_mut().find(|r| r.id == rule.id) {
-                r.hit_count += 1;
-            }
-        }
-
-        let status = rule.status_code;
         let content_type = rule
             .content_type
             .clone()
             .unwrap_or_else(|| CONTENT_TYPE_XML.to_string());
-        let resp_body = rule.response_body.clone();
-
-        let mut resp_headers: HashMap<String, String> = rule
-            .response_headers
-            .clone()
-            .unwrap_or_default();
+        let rendered = scenario_result.and_then(|_| {
+            if !violations.is_empty() {
+                Ok(problem_response(&template_request, &violations))
+            } else if rule.templated {
+                render_response(rule, &template_request)
+            } else {
+                Ok((
+                    rule.status_code,
+                    rule.response_body.clone(),
+                    rule.response_headers.clone().unwrap_or_default(),
+                ))
+            }
+        });
+        let (status, resp_body, resp_headers) = rendered.unwrap_or_else(|e| {
+            log::warn!("[Mock] Template error in rule '{}': {}", rule.name, e);
+            (
+                500,
+                format!("Mock template error in rule '{}': {}", rule.name, e),
+                HashMap::from([("content-type".to_string(), CONTENT_TYPE_PLAIN.to_string())]),
+            )
+        });
+        let (status, resp_body, mut resp_headers) = match fault_plan.as_ref().and_then(|p| p.replacement(&req_headers)) {
+            Some((status, headers, body)) => (status, body, headers),
+            None => (status, resp_body, resp_headers),
+        };
         resp_headers
             .entry("content-type".to_string())
             .or_insert(content_type);
@@ -159,9 +170,10 @@ async fn handle_mock_request(
         }
         let body_bytes = Bytes::from(resp_body);
         hb = hb.header("content-length", body_bytes.len());
-        return hb
+        let response = hb
             .body(Full::new(body_bytes))
             .unwrap_or_else(|_| plain_response(StatusCode::INTERNAL_SERVER_ERROR, "Build error"));
+        return faults::with_plan(response, fault_plan);
     }
 
     // No rule matched
@@ -210,6 +222,12 @@ async fn passthrough(
         rb = rb.body(req_body.clone());
     }
 
+    let fault_plan: Option<FaultPlan> = {
+        let mut s = state.lock().await;
+        let s = &mut *s;
+        s.faults.plan_for(None, "mock", s.config.fault_profile.as_ref())
+    };
+
     let (status, resp_headers, resp_body) = match rb.send().await {
         Ok(resp) => {
             let s = resp.status().as_u16();
@@ -224,9 +242,14 @@ async fn passthrough(
         Err(e) => (502, HashMap::new(), format!("Passthrough error: {}", e)),
     };
 
+    // Record what the target really answered, not an injected fault
     if record_mode {
         record_response(state, &method, &url, status, &resp_headers, &resp_body).await;
     }
+    let (status, resp_headers, resp_body) = fault_plan
+        .as_ref()
+        .and_then(|p| p.replacement(&req_headers))
+        .unwrap_or((status, resp_headers, resp_body));
 
     let duration_ms = start.elapsed().as_millis() as u64;
     let now = Utc::now();
@@ -260,8 +283,10 @@ async fn passthrough(
     }
     let body_bytes = Bytes::from(resp_body);
     hb = hb.header("content-length", body_bytes.len());
-    hb.body(Full::new(body_bytes))
-        .unwrap_or_else(|_| plain_response(StatusCode::INTERNAL_SERVER_ERROR, "Build error"))
+    let response = hb
+        .body(Full::new(body_bytes))
+        .unwrap_or_else(|_| plain_response(StatusCode::INTERNAL_SERVER_ERROR, "Build error"));
+    faults::with_plan(response, fault_plan)
 }
 
 /// Record a passthrough response as a new (disabled) mock rule.
@@ -304,6 +329,14 @@ async fn record_response(
                 .collect(),
         ),
         delay_ms: None,
+        templated: false,
+        status_template: None,
+        scenario: None,
+        required_state: None,
+        new_state: None,
+        scenario_values: None,
+        request_validation: None,
+        fault_profile: None,
         hit_count: 0,
         tags: Vec::new(),
         recorded_at: Some(Utc::now().timestamp_millis()),
@@ -314,23 +347,162 @@ async fn record_response(
     s.config.rules.push(rule);
 }
 
+/// A rule matched by a request, with what claiming it decided
+struct ClaimedRule {
+    rule: MockRule,
+    violations: Vec<SchemaError>,
+    scenario_result: Result<()>,
+    fault_plan: Option<FaultPlan>,
+}
+
+/// Find the first rule matching `request` and claim it: count the hit, plan
+/// its faults and advance its scenario. A request rejected by validation
+/// claims nothing, so it gets its problem response untouched by faults.
+/// Matching and advancing happen under the same lock, so two concurrent
+/// requests can't both match a scenario state the first of them moves on from.
+fn claim_rule(state: &mut MockState, request: &mut MockRequest) -> Option<ClaimedRule> {
+    let rule = find_matching_rule_in_state(
+        &state.config.rules,
+        &state.scenarios,
+        &request.method,
+        &request.url,
+        &request.headers,
+        &request.body,
+    )?
+    .clone();
+
+    request.params = path_parameters(&rule, &request.path);
+    let violations = rule
+        .request_validation
+        .as_ref()
+        .map(|v| request_violations(v, request))
+        .unwrap_or_default();
+    if !violations.is_empty() {
+        return Some(ClaimedRule { rule, violations, scenario_result: Ok(()), fault_plan: None });
+    }
+
+    if let Some(r) = state.config.rules.iter_mut().find(|r| r.id == rule.id) {
+        r.hit_count += 1;
+    }
+    let fault_plan = state.faults.plan_for(Some(&rule), "mock", state.config.fault_profile.as_ref());
+    let scenario_result = apply_scenario(state, &rule, request);
+    Some(ClaimedRule { rule, violations, scenario_result, fault_plan })
+}
+
+/// Store the rule's `scenario_values` (rendered as templates) and move its
+/// scenario to `new_state`, leaving the updated values in `request.scenario`
+fn apply_scenario(state: &mut MockState, rule: &MockRule, request: &mut MockRequest) -> Result<()> {
+    let Some(name) = rule.scenario.as_deref() else {
+        return Ok(());
+    };
+    request.scenario = state.scenarios.get(name).map(|s| s.values.clone()).unwrap_or_default();
+    let values = rule
+        .scenario_values
+        .iter()
+        .flatten()
+        .map(|(key, value)| Ok((key.clone(), template::render(value, request)?)))
+        .collect::<Result<HashMap<_, _>>>()?;
+    request.scenario = state.advance_scenario(rule, values);
+    Ok(())
+}
+
+/// Render a templated rule's status, body and header values
+fn render_response(rule: &MockRule, request: &MockRequest) -> Result<(u16, String, HashMap<String, String>)> {
+    let status = match rule.status_template.as_deref().filter(|t| !t.trim().is_empty()) {
+        Some(template) => template::render(template, request)?
+            .trim()
+            .parse::<u16>()
+            .unwrap_or(rule.status_code),
+        None => rule.status_code,
+    };
+    let body = template::render(&rule.response_body, request)?;
+    let headers = rule
+        .response_headers
+        .iter()
+        .flatten()
+        .map(|(name, value)| Ok((name.clone(), template::render(value, request)?)))
+        .collect::<Result<_>>()?;
+    Ok((status, body, headers))
+}
+
+/// Check a request against the OpenAPI operation the rule was generated from
+fn request_violations(validation: &MockRequestValidation, request: &MockRequest) -> Vec<SchemaError> {
+    openapi_validator::validate_request(
+        &validation.parameters,
+        validation.request_body.as_ref(),
+        &validation.schemas,
+        &RequestToValidate {
+            path_params: &request.params,
+            query: &request.query,
+            headers: &request.headers,
+            body: &request.body,
+        },
+    )
+}
+
+/// RFC 9457 problem details listing every violation
+fn problem_response(request: &MockRequest, violations: &[SchemaError]) -> (u16, String, HashMap<String, String>) {
+    let messages: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+    let body = serde_json::json!({
+        "type": "about:blank",
+        "title": "Bad Request",
+        "status": 400,
+        "detail": messages.join("; "),
+        "instance": request.path,
+        "errors": violations
+            .iter()
+            .map(|v| serde_json::json!({ "path": v.path, "message": v.message }))
+            .collect::<Vec<_>>(),
+    });
+    (
+        400,
+        serde_json::to_string_pretty(&body).unwrap_or_default(),
+        HashMap::from([("content-type".to_string(), CONTENT_TYPE_PROBLEM.to_string())]),
+    )
+}
+
 // ---------------------------------------------------------------------------
 // Rule matching
 // ---------------------------------------------------------------------------
 
+/// First enabled rule whose conditions match, with every scenario in its
+/// initial state
 pub fn find_matching_rule<'a>(
     rules: &'a [MockRule],
     method: &str,
     url: &str,
     headers: &HashMap<String, String>,
     body: &str,
+) -> Option<&'a MockRule> {
+    find_matching_rule_in_state(rules, &HashMap::new(), method, url, headers, body)
+}
+
+/// First enabled rule whose conditions match and whose `required_state`, if
+/// any, is its scenario's current state
+pub fn find_matching_rule_in_state<'a>(
+    rules: &'a [MockRule],
+    scenarios: &HashMap<String, ScenarioState>,
+    method: &str,
+    url: &str,
+    headers: &HashMap<String, String>,
+    body: &str,
 ) -> Option<&'a MockRule> {
     rules
         .iter()
-        .filter(|r| r.enabled)
+        .filter(|r| r.enabled && in_required_state(r, scenarios))
         .find(|r| all_conditions_match(&r.conditions, method, url, headers, body))
 }
 
+fn in_required_state(rule: &MockRule, scenarios: &HashMap<String, ScenarioState>) -> bool {
+    match (rule.scenario.as_deref(), rule.required_state.as_deref()) {
+        (Some(name), Some(required)) => {
+            let current = scenarios.get(name).map_or(SCENARIO_STARTED, |s| s.state.as_str());
+            current == required
+        }
+        _ => true,
+    }
+}
+
 fn all_conditions_match(
     conditions: &[MockMatchCondition],
     method: &str,
@@ -348,7 +520,7 @@ fn all_conditions_match(
 
 fn condition_matches(
     cond: &MockMatchCondition,
-    _method: &str,
+    method: &str,
     url: &str,
     headers: &HashMap<String, String>,
     body: &str,
@@ -356,6 +528,11 @@ fn condition_matches(
     match cond.r#type.as_str() {
         "url" => match_pattern(url, &cond.pattern, cond.is_regex),
 
+        "method" if cond.is_regex => match_pattern(method, &cond.pattern, true),
+        "method" => method.eq_ignore_ascii_case(cond.pattern.trim()),
+
+        "path" => match_path_template(&cond.pattern, template::split_url(url).0).is_some(),
+
         "operation" | "soapAction" => {
             let action = headers
                 .get("soapaction")
@@ -394,6 +571,53 @@ fn condition_matches(
     }
 }
 
+/// Parameters captured by the rule's `path` conditions
+fn path_parameters(rule: &MockRule, path: &str) -> HashMap<String, String> {
+    rule.conditions
+        .iter()
+        .filter(|c| c.r#type == "path")
+        .filter_map(|c| match_path_template(&c.pattern, path))
+        .flatten()
+        .collect()
+}
+
+/// Match `path` against an OpenAPI path template such as `/orders/{id}` or
+/// `/files/{name}.{ext}`, returning the captured parameters
+pub(crate) fn match_path_template(template: &str, path: &str) -> Option<HashMap<String, String>> {
+    let template_segments: Vec<&str> = template.trim_end_matches('/').split('/').collect();
+    let path_segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    if template_segments.len() != path_segments.len() {
+        return None;
+    }
+
+    let mut params = HashMap::new();
+    for (segment_template, segment) in template_segments.iter().zip(&path_segments) {
+        if !segment_template.contains('{') {
+            if segment_template != segment {
+                return None;
+            }
+            continue;
+        }
+        let mut pattern = String::from("^");
+        let mut names = Vec::new();
+        let mut rest = *segment_template;
+        while let Some(open) = rest.find('{') {
+            let close = open + rest[open..].find('}')?;
+            pattern.push_str(&regex::escape(&rest[..open]));
+            pattern.push_str("(.+?)");
+            names.push(&rest[open + 1..close]);
+            rest = &rest[close + 1..];
+        }
+        pattern.push_str(&regex::escape(rest));
+        pattern.push('$');
+        let captures = regex::Regex::new(&pattern).ok()?.captures(segment)?;
+        for (i, name) in names.into_iter().enumerate() {
+            params.insert(name.to_string(), captures[i + 1].to_string());
+        }
+    }
+    Some(params)
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
@@ -426,6 +650,14 @@ mod tests {
             content_type: None,
             response_headers: None,
             delay_ms: None,
+            templated: false,
+            status_template: None,
+            scenario: None,
+            required_state: None,
+            new_state: None,
+            scenario_values: None,
+            request_validation: None,
+            fault_profile: None,
             hit_count: 0,
             recorded_at: None,
             recorded_from: None,
@@ -573,6 +805,99 @@ mod tests {
         assert!(find_matching_rule(&[rule], "POST", "/ws", &no_headers(), "not xml at all").is_none());
     }
 
+    // --- Method and path template matching ---
+
+    #[test]
+    fn method_and_path_template_match() {
+        let rule = make_rule(vec![cond("method", "get", false), cond("path", "/v1/orders/{id}", false)]);
+        let rules = [rule];
+        assert!(find_matching_rule(&rules, "GET", "/v1/orders/42?expand=lines", &no_headers(), "").is_some());
+        assert!(find_matching_rule(&rules, "GET", "http://host/v1/orders/42/", &no_headers(), "").is_some());
+        assert!(find_matching_rule(&rules, "POST", "/v1/orders/42", &no_headers(), "").is_none());
+        assert!(find_matching_rule(&rules, "GET", "/v1/orders", &no_headers(), "").is_none());
+        assert!(find_matching_rule(&rules, "GET", "/v1/orders/42/lines", &no_headers(), "").is_none());
+    }
+
+    #[test]
+    fn path_template_captures_parameters() {
+        let params = match_path_template("/files/{name}.{ext}", "/files/report.final.pdf").unwrap();
+        assert_eq!(params["name"], "report");
+        assert_eq!(params["ext"], "final.pdf");
+        assert_eq!(match_path_template("/", "/"), Some(HashMap::new()));
+        assert!(match_path_template("/files/{name}.pdf", "/files/report.txt").is_none());
+    }
+
+    #[test]
+    fn invalid_request_gets_problem_details() {
+        use crate::parsers::openapi_parser::OpenApiParameter;
+
+        let validation = MockRequestValidation {
+            parameters: vec![OpenApiParameter {
+                name: "id".to_string(),
+                location: "path".to_string(),
+                required: true,
+                param_type: Some("integer".to_string()),
+                description: None,
+                schema: Some(serde_json::json!({ "type": "integer" })),
+            }],
+            request_body: None,
+            schemas: Default::default(),
+        };
+        let rule = make_rule(vec![cond("path", "/orders/{id}", false)]);
+        let mut request = MockRequest::new("GET", "/orders/abc", &no_headers(), "");
+        request.params = path_parameters(&rule, &request.path);
+
+        let violations = request_violations(&validation, &request);
+        let (status, body, headers) = problem_response(&request, &violations);
+        assert_eq!(status, 400);
+        assert_eq!(headers["content-type"], CONTENT_TYPE_PROBLEM);
+        let problem: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(problem["instance"], "/orders/abc");
+        assert_eq!(problem["errors"][0]["path"], "path.id");
+        assert_eq!(problem["detail"], "path.id: expected integer, got string");
+
+        request.params.insert("id".to_string(), "7".to_string());
+        assert!(request_violations(&validation, &request).is_empty());
+    }
+
+    #[test]
+    fn rejected_request_claims_no_hit_fault_or_scenario_state() {
+        use crate::parsers::openapi_parser::OpenApiParameter;
+
+        let mut rule = make_rule(vec![cond("path", "/orders/{id}", false)]);
+        rule.scenario = Some("order".to_string());
+        rule.new_state = Some("Seen".to_string());
+        rule.request_validation = Some(MockRequestValidation {
+            parameters: vec![OpenApiParameter {
+                name: "id".to_string(),
+                location: "path".to_string(),
+                required: true,
+                param_type: Some("integer".to_string()),
+                description: None,
+                schema: Some(serde_json::json!({ "type": "integer" })),
+            }],
+            request_body: None,
+            schemas: Default::default(),
+        });
+        rule.fault_profile = Some(serde_json::from_value(serde_json::json!({ "errorPercent": 100.0 })).unwrap());
+        let mut state = MockState::default();
+        state.config.rules = vec![rule];
+
+        let mut request = MockRequest::new("GET", "/orders/abc", &no_headers(), "");
+        let claimed = claim_rule(&mut state, &mut request).unwrap();
+        assert!(!claimed.violations.is_empty());
+        assert!(claimed.fault_plan.is_none());
+        assert_eq!(state.config.rules[0].hit_count, 0);
+        assert_eq!(state.list_scenarios()[0].state, SCENARIO_STARTED);
+
+        let mut request = MockRequest::new("GET", "/orders/7", &no_headers(), "");
+        let claimed = claim_rule(&mut state, &mut request).unwrap();
+        assert!(claimed.violations.is_empty());
+        assert!(claimed.fault_plan.is_some());
+        assert_eq!(state.config.rules[0].hit_count, 1);
+        assert_eq!(state.list_scenarios()[0].state, "Seen");
+    }
+
     // --- First matching rule wins ---
 
     #[test]
@@ -585,4 +910,107 @@ mod tests {
         let matched = find_matching_rule(&rules, "GET", "/api/x", &no_headers(), "").unwrap();
         assert_eq!(matched.id, "test-rule");
     }
+
+    // --- Templated responses ---
+
+    #[test]
+    fn templated_rule_renders_status_body_and_headers() {
+        let mut rule = make_rule(vec![cond("url", "/orders", false)]);
+        rule.templated = true;
+        rule.response_body = "<OrderId>{{xpath \"//OrderId\"}}</OrderId>".to_string();
+        rule.status_template = Some("{{#if request.query.id}}200{{else}}404{{/if}}".to_string());
+        rule.response_headers = Some(HashMap::from([("x-order".to_string(), "{{request.query.id}}".to_string())]));
+
+        let request = MockRequest::new("POST", "/orders?id=9", &no_headers(), "<Order><OrderId>A-1</OrderId></Order>");
+        let (status, body, headers) = render_response(&rule, &request).unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(body, "<OrderId>A-1</OrderId>");
+        assert_eq!(headers["x-order"], "9");
+
+        let request = MockRequest::new("POST", "/orders", &no_headers(), "");
+        assert_eq!(render_response(&rule, &request).unwrap().0, 404);
+    }
+
+    // --- Scenarios ---
+
+    #[test]
+    fn scenario_moves_through_states() {
+        let scenario_rule = |id: &str, conditions, required: &str, next: Option<&str>, body: &str| {
+            let mut rule = make_rule(conditions);
+            rule.id = id.to_string();
+            rule.scenario = Some("order".to_string());
+            rule.required_state = Some(required.to_string());
+            rule.new_state = next.map(str::to_string);
+            rule.templated = true;
+            rule.response_body = body.to_string();
+            rule
+        };
+        let mut create = scenario_rule("create", vec![cond("url", "/orders", false), cond("contains", "<Create", false)], "Started", Some("Pending"), "<Id>{{scenario.orderId}}</Id>");
+        create.scenario_values = Some(HashMap::from([("orderId".to_string(), "{{xpath \"//Id\"}}".to_string())]));
+        let rules = vec![
+            create,
+            scenario_rule("pending", vec![cond("url", "/orders", false)], "Pending", Some("Completed"), "{{scenario.orderId}} PENDING"),
+            scenario_rule("completed", vec![cond("url", "/orders", false)], "Completed", None, "{{scenario.orderId}} COMPLETED"),
+        ];
+        let mut state = MockState::default();
+        state.config.rules = rules.clone();
+
+        let mut send = |body: &str| {
+            let rule = find_matching_rule_in_state(&rules, &state.scenarios, "POST", "/orders", &no_headers(), body)?;
+            let mut request = MockRequest::new("POST", "/orders", &no_headers(), body);
+            apply_scenario(&mut state, rule, &mut request).unwrap();
+            Some(render_response(rule, &request).unwrap().1)
+        };
+        assert_eq!(send("<Get/>"), None);
+        assert_eq!(send("<Create><Id>A-7</Id></Create>").unwrap(), "<Id>A-7</Id>");
+        assert_eq!(send("<Get/>").unwrap(), "A-7 PENDING");
+        assert_eq!(send("<Get/>").unwrap(), "A-7 COMPLETED");
+        assert_eq!(send("<Get/>").unwrap(), "A-7 COMPLETED");
+
+        let listed = state.list_scenarios();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].state, "Completed");
+        assert_eq!(listed[0].rule_count, 3);
+        assert_eq!(listed[0].possible_states, ["Started", "Pending", "Completed"]);
+
+        state.reset_scenarios(Some("order"));
+        assert_eq!(state.list_scenarios()[0].state, SCENARIO_STARTED);
+        assert!(state.list_scenarios()[0].values.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_claim_scenario_states_in_turn() {
+        let step = |id: &str, required: &str, next: &str| {
+            let mut rule = make_rule(vec![cond("url", "/jobs", false)]);
+            rule.id = id.to_string();
+            rule.scenario = Some("job".to_string());
+            rule.required_state = Some(required.to_string());
+            rule.new_state = Some(next.to_string());
+            rule
+        };
+        let state = crate::mock::state::new_shared();
+        state.lock().await.config.rules = vec![step("queued", "Started", "Running"), step("running", "Running", "Done")];
+
+        let requests: Vec<_> = (0..2)
+            .map(|_| {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    let mut request = MockRequest::new("GET", "/jobs", &no_headers(), "");
+                    let mut s = state.lock().await;
+                    claim_rule(&mut s, &mut request).map(|c| c.rule.id)
+                })
+            })
+            .collect();
+        let mut claimed = Vec::new();
+        for request in requests {
+            claimed.push(request.await.unwrap().unwrap());
+        }
+        claimed.sort();
+
+        // Each state is matched once; the third request finds no rule
+        assert_eq!(claimed, ["queued", "running"]);
+        let mut request = MockRequest::new("GET", "/jobs", &no_headers(), "");
+        assert!(claim_rule(&mut *state.lock().await, &mut request).is_none());
+        assert!(state.lock().await.config.rules.iter().all(|r| r.hit_count == 1));
+    }
 }
@@ -1,6 +1,38 @@
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
+use serde::Serialize;
 use tokio::sync::Mutex;
-use crate::proxy_models::MockConfig;
+use crate::mock::faults::FaultInjector;
+use crate::proxy_models::{MockConfig, MockRule};
+
+/// State every scenario starts in, and returns to on reset
+pub const SCENARIO_STARTED: &str = "Started";
+
+/// Current state and stored values of one mock scenario
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioState {
+    pub state: String,
+    pub values: HashMap<String, String>,
+}
+
+impl Default for ScenarioState {
+    fn default() -> Self {
+        Self { state: SCENARIO_STARTED.to_string(), values: HashMap::new() }
+    }
+}
+
+/// A scenario as reported to the webview
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockScenario {
+    pub name: String,
+    pub state: String,
+    pub values: HashMap<String, String>,
+    /// States some rule of the scenario requires or moves to
+    pub possible_states: Vec<String>,
+    pub rule_count: usize,
+}
 
 /// Runtime state for the mock HTTP server.
 #[derive(Debug, Default)]
@@ -8,6 +40,70 @@ pub struct MockState {
     pub config: MockConfig,
     pub running: bool,
     pub task: Option<tokio::task::AbortHandle>,
+    /// Scenarios that have left their initial state; others are `Started`
+    pub scenarios: HashMap<String, ScenarioState>,
+    /// Random state of the fault profiles, shared with the proxy
+    pub faults: FaultInjector,
+}
+
+impl MockState {
+    /// Apply a matched rule's scenario effects: store its values, then move
+    /// to its `new_state`. Returns the scenario's values afterwards.
+    pub fn advance_scenario(&mut self, rule: &MockRule, values: HashMap<String, String>) -> HashMap<String, String> {
+        let Some(name) = rule.scenario.as_deref() else {
+            return HashMap::new();
+        };
+        let scenario = self.scenarios.entry(name.to_string()).or_default();
+        scenario.values.extend(values);
+        if let Some(next) = rule.new_state.as_deref() {
+            if scenario.state != next {
+                log::info!("[Mock] Scenario '{}': {} -> {}", name, scenario.state, next);
+                scenario.state = next.to_string();
+            }
+        }
+        scenario.values.clone()
+    }
+
+    /// Reset one scenario, or all of them, to `Started` with no values
+    pub fn reset_scenarios(&mut self, name: Option<&str>) {
+        match name {
+            Some(name) => {
+                self.scenarios.remove(name);
+            }
+            None => self.scenarios.clear(),
+        }
+    }
+
+    /// Every scenario referenced by a rule or holding state, sorted by name
+    pub fn list_scenarios(&self) -> Vec<MockScenario> {
+        let mut listed: BTreeMap<&str, MockScenario> = BTreeMap::new();
+        for (name, scenario) in &self.scenarios {
+            listed.insert(name, MockScenario {
+                name: name.clone(),
+                state: scenario.state.clone(),
+                values: scenario.values.clone(),
+                possible_states: vec![SCENARIO_STARTED.to_string()],
+                rule_count: 0,
+            });
+        }
+        for rule in &self.config.rules {
+            let Some(name) = rule.scenario.as_deref() else { continue };
+            let entry = listed.entry(name).or_insert_with(|| MockScenario {
+                name: name.to_string(),
+                state: SCENARIO_STARTED.to_string(),
+                values: HashMap::new(),
+                possible_states: vec![SCENARIO_STARTED.to_string()],
+                rule_count: 0,
+            });
+            entry.rule_count += 1;
+            for state in [&rule.required_state, &rule.new_state].into_iter().flatten() {
+                if !entry.possible_states.contains(state) {
+                    entry.possible_states.push(state.clone());
+                }
+            }
+        }
+        listed.into_values().collect()
+    }
 }
 
 pub type SharedMockState = Arc<Mutex<MockState>>;
@@ -0,0 +1,407 @@
+// Response templates for mock rules
+//
+// A templated rule's body, header values and status are rendered against the
+// incoming request with a small Handlebars-like syntax:
+//
+//   {{request.method}} {{request.url}} {{request.path}} {{request.body}}
+//   {{request.headers.soapaction}} {{request.query.id}} {{request.params.id}}
+//   {{request.pathSegments.2}} {{scenario.orderId}}
+//   {{xpath "//*[local-name()='OrderId']"}}   {{jsonPath "$.order.id"}}
+//   {{eq a b}} {{ne a b}} {{contains a b}} {{default a b}}
+//   {{uuid}} {{now}} {{now+1d}} {{epoch}} {{randomInt(1,100)}} ...
+//   {{#each xpath "//Item/Sku"}}<Sku n="{{@index}}">{{this}}</Sku>{{/each}}
+//   {{#if eq request.method "POST"}}...{{else}}...{{/if}}   {{#unless ...}}
+//
+// Arguments are quoted literals, numbers or paths. Values are inserted as-is
+// (no XML or JSON escaping).
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use crate::utils::{JsonPathEvaluator, WildcardProcessor, XPathEvaluator};
+
+/// The parts of an incoming request a template can read
+#[derive(Debug, Clone, Default)]
+pub struct MockRequest {
+    pub method: String,
+    pub url: String,
+    /// URL path without the query string
+    pub path: String,
+    pub query: HashMap<String, String>,
+    /// Header names are lower-case
+    pub headers: HashMap<String, String>,
+    pub body: String,
+    /// Named path parameters captured by the matching rule
+    pub params: HashMap<String, String>,
+    /// Values stored in the matching rule's scenario
+    pub scenario: HashMap<String, String>,
+}
+
+impl MockRequest {
+    pub fn new(method: &str, url: &str, headers: &HashMap<String, String>, body: &str) -> Self {
+        let (path, query) = split_url(url);
+        Self {
+            method: method.to_string(),
+            url: url.to_string(),
+            path: path.to_string(),
+            query: url::form_urlencoded::parse(query.as_bytes()).into_owned().collect(),
+            headers: headers.iter().map(|(k, v)| (k.to_lowercase(), v.clone())).collect(),
+            body: body.to_string(),
+            params: HashMap::new(),
+            scenario: HashMap::new(),
+        }
+    }
+}
+
+/// Split a request target into its path and query string
+pub(crate) fn split_url(url: &str) -> (&str, &str) {
+    // Request targets are usually origin-form ("/path?q"), but proxies send absolute URLs
+    let without_origin = match url.find("://") {
+        Some(scheme_end) => url[scheme_end + 3..].find('/').map_or("/", |i| &url[scheme_end + 3 + i..]),
+        None => url,
+    };
+    without_origin.split_once('?').unwrap_or((without_origin, ""))
+}
+
+/// Render `template` against `request`
+pub fn render(template: &str, request: &MockRequest) -> Result<String> {
+    let nodes = parse(template)?;
+    let mut out = String::with_capacity(template.len());
+    render_nodes(&nodes, request, &Scope::default(), &mut out)?;
+    Ok(out)
+}
+
+#[derive(Debug, PartialEq)]
+enum Node {
+    Text(String),
+    Expr(String),
+    Each { expr: String, body: Vec<Node> },
+    If { expr: String, negate: bool, then: Vec<Node>, otherwise: Vec<Node> },
+}
+
+/// An open `#each`/`#if`/`#unless` block while parsing
+struct Block {
+    kind: &'static str,
+    expr: String,
+    then: Vec<Node>,
+    otherwise: Option<Vec<Node>>,
+}
+
+fn parse(template: &str) -> Result<Vec<Node>> {
+    let mut root = Vec::new();
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut rest = template;
+
+    fn current<'a>(root: &'a mut Vec<Node>, blocks: &'a mut [Block]) -> &'a mut Vec<Node> {
+        match blocks.last_mut() {
+            Some(block) => block.otherwise.as_mut().unwrap_or(&mut block.then),
+            None => root,
+        }
+    }
+
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            current(&mut root, &mut blocks).push(Node::Text(rest[..start].to_string()));
+        }
+        let end = rest[start..]
+            .find("}}")
+            .ok_or_else(|| anyhow!("Unclosed '{{{{' in template"))?;
+        let tag = rest[start + 2..start + end].trim();
+        rest = &rest[start + end + 2..];
+
+        if let Some(open) = tag.strip_prefix('#') {
+            let (kind, expr) = open.split_once(char::is_whitespace).unwrap_or((open, ""));
+            let kind = match kind {
+                "each" => "each",
+                "if" => "if",
+                "unless" => "unless",
+                other => return Err(anyhow!("Unknown block '#{}' in template", other)),
+            };
+            if expr.trim().is_empty() {
+                return Err(anyhow!("'{{{{#{}}}}}' needs an expression", kind));
+            }
+            blocks.push(Block { kind, expr: expr.trim().to_string(), then: Vec::new(), otherwise: None });
+        } else if tag == "else" {
+            match blocks.last_mut() {
+                Some(block) if block.kind != "each" && block.otherwise.is_none() => block.otherwise = Some(Vec::new()),
+                _ => return Err(anyhow!("'{{{{else}}}}' outside of an #if or #unless block")),
+            }
+        } else if let Some(close) = tag.strip_prefix('/') {
+            let block = blocks
+                .pop()
+                .ok_or_else(|| anyhow!("'{{{{/{}}}}}' without a matching block", close))?;
+            if block.kind != close.trim() {
+                return Err(anyhow!("'{{{{/{}}}}}' closes an open #{} block", close.trim(), block.kind));
+            }
+            let node = match block.kind {
+                "each" => Node::Each { expr: block.expr, body: block.then },
+                kind => Node::If {
+                    expr: block.expr,
+                    negate: kind == "unless",
+                    then: block.then,
+                    otherwise: block.otherwise.unwrap_or_default(),
+                },
+            };
+            current(&mut root, &mut blocks).push(node);
+        } else {
+            current(&mut root, &mut blocks).push(Node::Expr(tag.to_string()));
+        }
+    }
+    if !rest.is_empty() {
+        current(&mut root, &mut blocks).push(Node::Text(rest.to_string()));
+    }
+    if let Some(block) = blocks.last() {
+        return Err(anyhow!("Unclosed '{{{{#{}}}}}' block in template", block.kind));
+    }
+    Ok(root)
+}
+
+/// The item of the innermost `#each`
+#[derive(Default)]
+struct Scope<'a> {
+    this: Option<&'a str>,
+    index: Option<usize>,
+}
+
+fn render_nodes(nodes: &[Node], request: &MockRequest, scope: &Scope, out: &mut String) -> Result<()> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Expr(expr) => out.push_str(&evaluate(expr, request, scope)?),
+            Node::Each { expr, body } => {
+                for (index, item) in evaluate_list(expr, request, scope)?.iter().enumerate() {
+                    let inner = Scope { this: Some(item), index: Some(index) };
+                    render_nodes(body, request, &inner, out)?;
+                }
+            }
+            Node::If { expr, negate, then, otherwise } => {
+                let branch = if is_truthy(&evaluate(expr, request, scope)?) != *negate { then } else { otherwise };
+                render_nodes(branch, request, scope, out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_truthy(value: &str) -> bool {
+    !matches!(value, "" | "false" | "0")
+}
+
+/// Values an `#each` iterates: every XPath/JSONPath match, or the elements of
+/// a JSON array
+fn evaluate_list(expr: &str, request: &MockRequest, scope: &Scope) -> Result<Vec<String>> {
+    let args = tokenize(expr)?;
+    let items = match args.first().map(String::as_str) {
+        Some("xpath") => XPathEvaluator::evaluate_all(&source(&args, request, scope)?, &argument(&args, 1, request, scope)?),
+        Some("jsonPath") => JsonPathEvaluator::evaluate_all(&source(&args, request, scope)?, &argument(&args, 1, request, scope)?),
+        _ => vec![evaluate(expr, request, scope)?],
+    };
+    // "$.items" matches the array itself
+    if let [single] = items.as_slice() {
+        if let Ok(serde_json::Value::Array(elements)) = serde_json::from_str(single) {
+            return Ok(elements.iter().map(crate::utils::json_path::value_to_string).collect());
+        }
+        if single.is_empty() {
+            return Ok(Vec::new());
+        }
+    }
+    Ok(items)
+}
+
+fn evaluate(expr: &str, request: &MockRequest, scope: &Scope) -> Result<String> {
+    let args = tokenize(expr)?;
+    let arg = |index: usize| argument(&args, index, request, scope);
+    match args.first().map(String::as_str) {
+        None => Err(anyhow!("Empty '{{{{}}}}' in template")),
+        Some("xpath") => Ok(XPathEvaluator::evaluate(&source(&args, request, scope)?, &arg(1)?).unwrap_or_default()),
+        Some("jsonPath") => Ok(JsonPathEvaluator::evaluate(&source(&args, request, scope)?, &arg(1)?).unwrap_or_default()),
+        Some("eq") => Ok((arg(1)? == arg(2)?).to_string()),
+        Some("ne") => Ok((arg(1)? != arg(2)?).to_string()),
+        Some("contains") => Ok(arg(1)?.contains(&arg(2)?).to_string()),
+        Some("default") => {
+            for index in 1..args.len() {
+                let value = arg(index)?;
+                if !value.is_empty() {
+                    return Ok(value);
+                }
+            }
+            Ok(String::new())
+        }
+        Some(_) if args.len() == 1 => argument(&args, 0, request, scope)
+            .ok()
+            .or_else(|| builtin(expr))
+            .ok_or_else(|| anyhow!("Unknown template expression '{{{{{}}}}}'", expr)),
+        Some(other) => Err(anyhow!("Unknown template helper '{}' in '{{{{{}}}}}'", other, expr)),
+    }
+}
+
+/// `xpath`/`jsonPath` read the request body unless given a second argument
+fn source(args: &[String], request: &MockRequest, scope: &Scope) -> Result<String> {
+    if args.len() > 2 {
+        argument(args, 2, request, scope)
+    } else {
+        Ok(request.body.clone())
+    }
+}
+
+fn argument(args: &[String], index: usize, request: &MockRequest, scope: &Scope) -> Result<String> {
+    let arg = args
+        .get(index)
+        .ok_or_else(|| anyhow!("'{}' is missing argument {}", args[0], index))?;
+    if let Some(literal) = arg.strip_prefix('\u{0}') {
+        return Ok(literal.to_string());
+    }
+    if arg.parse::<f64>().is_ok() {
+        return Ok(arg.clone());
+    }
+    resolve(arg, request, scope).ok_or_else(|| anyhow!("Unknown template value '{}'", arg))
+}
+
+/// Look up a path like `request.headers.soapaction` or `this.id`
+fn resolve(path: &str, request: &MockRequest, scope: &Scope) -> Option<String> {
+    let lookup = |map: &HashMap<String, String>, key: &str| Some(map.get(key).cloned().unwrap_or_default());
+    match path {
+        "this" => return scope.this.map(str::to_string),
+        "@index" => return scope.index.map(|i| i.to_string()),
+        "request.method" => return Some(request.method.clone()),
+        "request.url" => return Some(request.url.clone()),
+        "request.path" => return Some(request.path.clone()),
+        "request.body" => return Some(request.body.clone()),
+        _ => {}
+    }
+    if let Some(field) = path.strip_prefix("this.") {
+        let item: serde_json::Value = serde_json::from_str(scope.this?).ok()?;
+        let value = field.split('.').try_fold(&item, |value, key| match key.parse::<usize>() {
+            Ok(i) => value.get(i),
+            Err(_) => value.get(key),
+        });
+        return Some(value.map(crate::utils::json_path::value_to_string).unwrap_or_default());
+    }
+    if let Some(name) = path.strip_prefix("request.headers.") {
+        return lookup(&request.headers, &name.to_lowercase());
+    }
+    if let Some(name) = path.strip_prefix("request.query.") {
+        return lookup(&request.query, name);
+    }
+    if let Some(name) = path.strip_prefix("request.params.") {
+        return lookup(&request.params, name);
+    }
+    if let Some(key) = path.strip_prefix("scenario.") {
+        return lookup(&request.scenario, key);
+    }
+    if let Some(index) = path.strip_prefix("request.pathSegments.") {
+        let index: usize = index.parse().ok()?;
+        let segment = request.path.split('/').filter(|s| !s.is_empty()).nth(index);
+        return Some(segment.unwrap_or_default().to_string());
+    }
+    None
+}
+
+/// Built-in functions shared with request wildcards (`uuid`, `now+1d`, ...)
+fn builtin(expr: &str) -> Option<String> {
+    let tag = format!("{{{{{}}}}}", expr);
+    let value = WildcardProcessor::process_functions(&tag);
+    (value != tag).then_some(value)
+}
+
+/// Split an expression on whitespace, keeping quoted strings together.
+/// Literals are marked with a leading NUL so they are never resolved as paths.
+fn tokenize(expr: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = expr.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' || c == '\'' {
+            chars.next();
+            let mut literal = String::from('\u{0}');
+            loop {
+                match chars.next() {
+                    Some(ch) if ch == c => break,
+                    Some(ch) => literal.push(ch),
+                    None => return Err(anyhow!("Unterminated string in '{{{{{}}}}}'", expr)),
+                }
+            }
+            tokens.push(literal);
+        } else {
+            let mut token = String::new();
+            while let Some(&ch) = chars.peek() {
+                if ch.is_whitespace() {
+                    break;
+                }
+                token.push(ch);
+                chars.next();
+            }
+            tokens.push(token);
+        }
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORDER: &str = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"><soap:Body>
+        <ns:PlaceOrder xmlns:ns="urn:shop"><ns:OrderId>A-42</ns:OrderId><ns:Sku>X1</ns:Sku><ns:Sku>Y2</ns:Sku></ns:PlaceOrder>
+    </soap:Body></soap:Envelope>"#;
+
+    fn request(body: &str) -> MockRequest {
+        let headers = HashMap::from([("SOAPAction".to_string(), "\"urn:PlaceOrder\"".to_string())]);
+        MockRequest::new("POST", "/shop/orders/17?verbose=true&tag=a%20b", &headers, body)
+    }
+
+    #[test]
+    fn test_request_values() {
+        let rendered = render(
+            "{{request.method}} {{request.path}} {{request.pathSegments.2}} {{request.query.tag}} {{request.headers.soapaction}} [{{request.query.missing}}]",
+            &request(""),
+        )
+        .unwrap();
+        assert_eq!(rendered, "POST /shop/orders/17 17 a b \"urn:PlaceOrder\" []");
+        assert_eq!(MockRequest::new("GET", "http://host:8080/a/b?x=1", &HashMap::new(), "").path, "/a/b");
+    }
+
+    #[test]
+    fn test_echoes_order_id_and_loops() {
+        let template = concat!(
+            "<OrderId>{{xpath \"//*[local-name()='OrderId']\"}}</OrderId>",
+            "{{#each xpath \"//*[local-name()='Sku']\"}}<Line n=\"{{@index}}\">{{this}}</Line>{{/each}}",
+        );
+        assert_eq!(
+            render(template, &request(ORDER)).unwrap(),
+            "<OrderId>A-42</OrderId><Line n=\"0\">X1</Line><Line n=\"1\">Y2</Line>"
+        );
+
+        let json = r#"{"items":[{"sku":"X1","qty":2},{"sku":"Y2","qty":1}]}"#;
+        let template = r#"{{#each jsonPath "$.items"}}{{this.sku}}x{{this.qty}};{{/each}}"#;
+        assert_eq!(render(template, &request(json)).unwrap(), "X1x2;Y2x1;");
+    }
+
+    #[test]
+    fn test_conditionals() {
+        let template = r#"{{#if eq request.query.verbose "true"}}full{{else}}short{{/if}}|{{#unless request.query.missing}}none{{/unless}}|{{#if contains request.body "Sku"}}{{default request.query.missing 'n/a'}}{{/if}}"#;
+        assert_eq!(render(template, &request(ORDER)).unwrap(), "full|none|n/a");
+    }
+
+    #[test]
+    fn test_builtins() {
+        let rendered = render("{{uuid}}|{{randomInt(5,5)}}|{{now+1d}}", &request("")).unwrap();
+        let parts: Vec<&str> = rendered.split('|').collect();
+        assert_eq!(parts[0].len(), 36);
+        assert_eq!(parts[1], "5");
+        assert!(chrono::DateTime::parse_from_rfc3339(parts[2]).is_ok());
+    }
+
+    #[test]
+    fn test_template_errors() {
+        let error = |template: &str| render(template, &request("")).unwrap_err().to_string();
+        assert_eq!(error("{{#if request.body}}x"), "Unclosed '{{#if}}' block in template");
+        assert_eq!(error("{{#each request.body}}x{{/if}}"), "'{{/if}}' closes an open #each block");
+        assert_eq!(error("{{orderId}}"), "Unknown template expression '{{orderId}}'");
+        assert_eq!(error("{{xpath}}"), "'xpath' is missing argument 1");
+        assert_eq!(error("a {{request.body"), "Unclosed '{{' in template");
+        assert_eq!(render("no tags", &request("")).unwrap(), "no tags");
+    }
+}
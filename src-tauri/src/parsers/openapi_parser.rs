@@ -2,6 +2,7 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenApiSpec {
@@ -10,6 +11,10 @@ pub struct OpenApiSpec {
     pub description: Option<String>,
     pub base_url: Option<String>,
     pub paths: Vec<OpenApiPath>,
+    /// Schemas referenced from parameter, request and response schemas, keyed
+    /// by `$ref` (e.g. `#/components/schemas/Pet`)
+    #[serde(default)]
+    pub schemas: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +27,38 @@ pub struct OpenApiPath {
     pub tags: Vec<String>,
     pub parameters: Vec<OpenApiParameter>,
     pub sample_body: Option<String>,
+    #[serde(default)]
+    pub request_body: Option<OpenApiRequestBody>,
+    #[serde(default)]
+    pub responses: Vec<OpenApiResponse>,
+}
+
+/// The declared request body of an operation (OAS3 `requestBody`, or a
+/// Swagger 2.0 `in: body` parameter + `consumes`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenApiRequestBody {
+    pub required: bool,
+    pub content: Vec<OpenApiMediaType>,
+}
+
+/// A declared response of an operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenApiResponse {
+    /// "200", a range such as "4XX", or "default"
+    pub status: String,
+    pub description: Option<String>,
+    /// Media types the response may use; empty when it has no body
+    pub content: Vec<OpenApiMediaType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenApiMediaType {
+    pub content_type: String,
+    /// JSON Schema of the body; `$ref`s resolve through `OpenApiSpec::schemas`
+    pub schema: Option<Value>,
+    /// `example`, or the first of `examples`
+    #[serde(default)]
+    pub example: Option<Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +68,16 @@ pub struct OpenApiParameter {
     pub required: bool,
     pub param_type: Option<String>,
     pub description: Option<String>,
+    /// JSON Schema of the value (Swagger 2.0: built from `type`/`format`/`enum`)
+    #[serde(default)]
+    pub schema: Option<Value>,
+}
+
+impl OpenApiSpec {
+    /// Generate a sample value for `schema`, resolving `$ref`s through `schemas`
+    pub fn sample_for_schema(&self, schema: &Value) -> Value {
+        OpenApiParser::generate_sample(schema, &|r| self.schemas.get(r).cloned(), 0)
+    }
 }
 
 pub struct OpenApiParser;
@@ -88,12 +135,27 @@ impl OpenApiParser {
         // Extract paths
         let paths = Self::extract_paths(&spec)?;
 
+        // Index every schema reachable through `$ref` from the operations
+        let mut schemas = BTreeMap::new();
+        for path in &paths {
+            let media_types = path
+                .responses
+                .iter()
+                .flat_map(|r| &r.content)
+                .chain(path.request_body.iter().flat_map(|b| &b.content));
+            let parameter_schemas = path.parameters.iter().filter_map(|p| p.schema.as_ref());
+            for schema in media_types.filter_map(|m| m.schema.as_ref()).chain(parameter_schemas) {
+                Self::collect_refs(schema, &spec, &mut schemas);
+            }
+        }
+
         Ok(OpenApiSpec {
             title,
             version,
             description,
             base_url,
             paths,
+            schemas,
         })
     }
 
@@ -127,6 +189,9 @@ impl OpenApiParser {
 
         let mut paths = Vec::new();
         let methods = ["get", "post", "put", "delete", "patch", "head", "options"];
+        // Swagger 2.0: media types come from `produces` and `consumes`
+        let global_produces = spec.get("produces");
+        let global_consumes = spec.get("consumes");
 
         for (path_key, path_item) in paths_obj {
             let path_obj = path_item.as_object().context("Invalid path item")?;
@@ -156,10 +221,21 @@ impl OpenApiParser {
                         })
                         .unwrap_or_default();
 
-                    let parameters = op_obj.get("parameters")
+                    // Path-level parameters apply unless the operation overrides them
+                    let mut parameters: Vec<OpenApiParameter> = op_obj.get("parameters")
                         .and_then(|v| v.as_array())
-                        .map(Self::extract_parameters)
+                        .map(|arr| Self::extract_parameters(arr, spec))
                         .unwrap_or_default();
+                    if let Some(shared) = path_obj.get("parameters").and_then(|v| v.as_array()) {
+                        for param in Self::extract_parameters(shared, spec) {
+                            if !parameters.iter().any(|p| p.name == param.name && p.location == param.location) {
+                                parameters.push(param);
+                            }
+                        }
+                    }
+
+                    let consumes = op_obj.get("consumes").or(global_consumes);
+                    let request_body = Self::extract_request_body(op_obj, consumes, spec);
 
                     // Generate sample body for methods that typically have a body
                     let sample_body = match *method {
@@ -167,6 +243,12 @@ impl OpenApiParser {
                         _ => None,
                     };
 
+                    let produces = op_obj.get("produces").or(global_produces);
+                    let responses = op_obj.get("responses")
+                        .and_then(|v| v.as_object())
+                        .map(|r| Self::extract_responses(r, produces, spec))
+                        .unwrap_or_default();
+
                     paths.push(OpenApiPath {
                         path: path_key.clone(),
                         method: method.to_uppercase(),
@@ -176,6 +258,8 @@ impl OpenApiParser {
                         tags,
                         parameters,
                         sample_body,
+                        request_body,
+                        responses,
                     });
                 }
             }
@@ -184,6 +268,139 @@ impl OpenApiParser {
         Ok(paths)
     }
 
+    /// Extract declared responses (OAS3 `content`, or Swagger 2.0 `schema` + `produces`)
+    fn extract_responses(
+        responses: &serde_json::Map<String, Value>,
+        produces: Option<&Value>,
+        spec: &Value,
+    ) -> Vec<OpenApiResponse> {
+        responses
+            .iter()
+            .filter(|(status, _)| !status.starts_with("x-"))
+            .map(|(status, response)| {
+                // Shared responses: `$ref: '#/components/responses/NotFound'`
+                let response = response
+                    .get("$ref")
+                    .and_then(|r| r.as_str())
+                    .and_then(|r| Self::resolve_ref(r, spec))
+                    .unwrap_or_else(|| response.clone());
+
+                let content = if let Some(content) = response.get("content") {
+                    Self::extract_content(content, spec)
+                } else if let Some(schema) = response.get("schema") {
+                    // Swagger 2.0 keys examples by media type
+                    let examples = response.get("examples");
+                    Self::media_type_names(produces)
+                        .into_iter()
+                        .map(|content_type| OpenApiMediaType {
+                            example: examples.and_then(|e| e.get(&content_type)).cloned(),
+                            content_type,
+                            schema: Some(schema.clone()),
+                        })
+                        .collect()
+                } else {
+                    vec![]
+                };
+
+                OpenApiResponse {
+                    status: status.clone(),
+                    description: response.get("description").and_then(|d| d.as_str()).map(|s| s.to_string()),
+                    content,
+                }
+            })
+            .collect()
+    }
+
+    /// Extract the request body (OAS3 `requestBody`, or Swagger 2.0 `in: body` / `in: formData`)
+    fn extract_request_body(
+        op_obj: &serde_json::Map<String, Value>,
+        consumes: Option<&Value>,
+        spec: &Value,
+    ) -> Option<OpenApiRequestBody> {
+        if let Some(body) = op_obj.get("requestBody") {
+            // Shared bodies: `$ref: '#/components/requestBodies/Pet'`
+            let body = body
+                .get("$ref")
+                .and_then(|r| r.as_str())
+                .and_then(|r| Self::resolve_ref(r, spec))
+                .unwrap_or_else(|| body.clone());
+            return Some(OpenApiRequestBody {
+                required: body.get("required").and_then(|v| v.as_bool()).unwrap_or(false),
+                content: body.get("content").map(|c| Self::extract_content(c, spec)).unwrap_or_default(),
+            });
+        }
+
+        let parameters = op_obj.get("parameters").and_then(|v| v.as_array())?;
+        let body_param = parameters.iter().find(|p| p.get("in").and_then(|v| v.as_str()) == Some("body"))?;
+        Some(OpenApiRequestBody {
+            required: body_param.get("required").and_then(|v| v.as_bool()).unwrap_or(false),
+            content: Self::media_type_names(consumes)
+                .into_iter()
+                .map(|content_type| OpenApiMediaType {
+                    content_type,
+                    schema: body_param.get("schema").cloned(),
+                    example: None,
+                })
+                .collect(),
+        })
+    }
+
+    /// OAS3 `content` map: media type -> schema and example
+    fn extract_content(content: &Value, spec: &Value) -> Vec<OpenApiMediaType> {
+        let Some(content) = content.as_object() else {
+            return vec![];
+        };
+        content
+            .iter()
+            .map(|(content_type, media)| {
+                let example = media.get("example").cloned().or_else(|| {
+                    // `examples` maps names to Example objects (possibly `$ref`s)
+                    let first = media.get("examples")?.as_object()?.values().next()?;
+                    let first = match first.get("$ref").and_then(|r| r.as_str()) {
+                        Some(ref_path) => Self::resolve_ref(ref_path, spec)?,
+                        None => first.clone(),
+                    };
+                    first.get("value").cloned()
+                });
+                OpenApiMediaType {
+                    content_type: content_type.clone(),
+                    schema: media.get("schema").cloned(),
+                    example,
+                }
+            })
+            .collect()
+    }
+
+    /// Swagger 2.0 `produces`/`consumes` list, defaulting to JSON
+    fn media_type_names(types: Option<&Value>) -> Vec<String> {
+        types
+            .and_then(|p| p.as_array())
+            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+            .filter(|types: &Vec<String>| !types.is_empty())
+            .unwrap_or_else(|| vec!["application/json".to_string()])
+    }
+
+    /// Add every `$ref` target reachable from `schema` to `refs`
+    fn collect_refs(schema: &Value, spec: &Value, refs: &mut BTreeMap<String, Value>) {
+        match schema {
+            Value::Object(map) => {
+                if let Some(ref_path) = map.get("$ref").and_then(|r| r.as_str()) {
+                    if !refs.contains_key(ref_path) {
+                        if let Some(resolved) = Self::resolve_ref(ref_path, spec) {
+                            refs.insert(ref_path.to_string(), resolved.clone());
+                            Self::collect_refs(&resolved, spec, refs);
+                        }
+                    }
+                }
+                for value in map.values() {
+                    Self::collect_refs(value, spec, refs);
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|v| Self::collect_refs(v, spec, refs)),
+            _ => {}
+        }
+    }
+
     /// Extract and generate a sample JSON body from an operation's requestBody (OAS3) or body parameter (Swagger 2)
     fn extract_sample_body(op_obj: &serde_json::Map<String, Value>, spec: &Value) -> Option<String> {
         // OpenAPI 3.x: requestBody.content['application/json'].schema
@@ -203,20 +420,21 @@ impl OpenApiParser {
                 .cloned()
         }?;
 
-        let sample = Self::generate_sample(&schema, spec, 0);
+        let sample = Self::generate_sample(&schema, &|r| Self::resolve_ref(r, spec), 0);
         serde_json::to_string_pretty(&sample).ok()
     }
 
-    /// Recursively generate a sample value from a JSON Schema node
-    fn generate_sample(schema: &Value, spec: &Value, depth: usize) -> Value {
+    /// Recursively generate a sample value from a JSON Schema node, resolving
+    /// `$ref`s with `resolve`
+    fn generate_sample(schema: &Value, resolve: &dyn Fn(&str) -> Option<Value>, depth: usize) -> Value {
         if depth > 5 {
             return Value::Null;
         }
 
         // Resolve $ref
         if let Some(ref_path) = schema.get("$ref").and_then(|v| v.as_str()) {
-            if let Some(resolved) = Self::resolve_ref(ref_path, spec) {
-                return Self::generate_sample(&resolved, spec, depth + 1);
+            if let Some(resolved) = resolve(ref_path) {
+                return Self::generate_sample(&resolved, resolve, depth + 1);
             }
             return Value::Null;
         }
@@ -234,7 +452,7 @@ impl OpenApiParser {
                 if let Some(all_of) = schema.get("allOf").and_then(|v| v.as_array()) {
                     let mut merged = serde_json::Map::new();
                     for sub in all_of {
-                        if let Value::Object(obj) = Self::generate_sample(sub, spec, depth + 1) {
+                        if let Value::Object(obj) = Self::generate_sample(sub, resolve, depth + 1) {
                             merged.extend(obj);
                         }
                     }
@@ -245,21 +463,21 @@ impl OpenApiParser {
                     .and_then(|v| v.as_array())
                 {
                     if let Some(first) = one_of.first() {
-                        return Self::generate_sample(first, spec, depth + 1);
+                        return Self::generate_sample(first, resolve, depth + 1);
                     }
                 }
 
                 let mut obj = serde_json::Map::new();
                 if let Some(props) = schema.get("properties").and_then(|v| v.as_object()) {
                     for (key, prop_schema) in props {
-                        obj.insert(key.clone(), Self::generate_sample(prop_schema, spec, depth + 1));
+                        obj.insert(key.clone(), Self::generate_sample(prop_schema, resolve, depth + 1));
                     }
                 }
                 Value::Object(obj)
             }
             "array" => {
                 let item_sample = schema.get("items")
-                    .map(|items| Self::generate_sample(items, spec, depth + 1))
+                    .map(|items| Self::generate_sample(items, resolve, depth + 1))
                     .unwrap_or(Value::Null);
                 Value::Array(vec![item_sample])
             }
@@ -309,10 +527,14 @@ impl OpenApiParser {
         Some(current)
     }
 
-    #[allow(clippy::ptr_arg)] // Called with &Vec from as_array(), closure handles coercion
-    fn extract_parameters(params_array: &Vec<Value>) -> Vec<OpenApiParameter> {
+    fn extract_parameters(params_array: &[Value], spec: &Value) -> Vec<OpenApiParameter> {
         params_array.iter()
             .filter_map(|param| {
+                // Shared parameters: `$ref: '#/components/parameters/Limit'`
+                let param = match param.get("$ref").and_then(|r| r.as_str()) {
+                    Some(ref_path) => Self::resolve_ref(ref_path, spec)?,
+                    None => param.clone(),
+                };
                 let param_obj = param.as_object()?;
                 
                 let name = param_obj.get("name")?.as_str()?.to_string();
@@ -326,6 +548,21 @@ impl OpenApiParser {
                 let description = param_obj.get("description")
                     .and_then(|v| v.as_str())
                     .map(|s| s.to_string());
+                // Swagger 2.0 declares non-body parameter schemas inline
+                let schema = param_obj.get("schema").cloned().or_else(|| {
+                    param_type.as_ref().map(|_| {
+                        param_obj
+                            .iter()
+                            .filter(|(k, _)| {
+                                matches!(k.as_str(), "type" | "format" | "enum" | "items" | "minimum" | "maximum" | "pattern")
+                            })
+                            .map(|(k, v)| (k.clone(), v.clone()))
+                            .collect()
+                    })
+                });
+                let param_type = param_type.or_else(|| {
+                    schema.as_ref()?.get("type")?.as_str().map(|s| s.to_string())
+                });
 
                 Some(OpenApiParameter {
                     name,
@@ -333,6 +570,7 @@ impl OpenApiParser {
                     required,
                     param_type,
                     description,
+                    schema,
                 })
             })
             .collect()
@@ -375,4 +613,117 @@ mod tests {
         assert_eq!(spec.paths[0].method, "GET");
         assert_eq!(spec.paths[0].path, "/users");
     }
+
+    #[test]
+    fn test_swagger2_responses_and_ref_table() {
+        let spec_json = r##"{
+            "swagger": "2.0",
+            "info": { "title": "Legacy", "version": "1" },
+            "produces": ["application/json", "application/xml"],
+            "paths": {
+                "/orders": {
+                    "get": {
+                        "operationId": "listOrders",
+                        "responses": {
+                            "200": {
+                                "description": "Orders",
+                                "schema": { "type": "array", "items": { "$ref": "#/definitions/Order" } }
+                            },
+                            "default": { "description": "Error" }
+                        }
+                    }
+                }
+            },
+            "definitions": {
+                "Order": { "type": "object", "properties": { "line": { "$ref": "#/definitions/Line" } } },
+                "Line": { "type": "object" }
+            }
+        }"##;
+
+        let spec = OpenApiParser::parse_json(spec_json).unwrap();
+        let responses = &spec.paths[0].responses;
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].status, "200");
+        assert_eq!(responses[0].description.as_deref(), Some("Orders"));
+        let content_types: Vec<&str> = responses[0].content.iter().map(|m| m.content_type.as_str()).collect();
+        assert_eq!(content_types, vec!["application/json", "application/xml"]);
+        assert!(responses[1].content.is_empty());
+
+        // Nested refs are indexed too
+        let refs: Vec<&str> = spec.schemas.keys().map(|k| k.as_str()).collect();
+        assert_eq!(refs, vec!["#/definitions/Line", "#/definitions/Order"]);
+    }
+
+    #[test]
+    fn test_request_bodies_examples_and_parameter_schemas() {
+        let spec_yaml = r##"
+openapi: 3.0.3
+info: { title: Orders, version: "1" }
+paths:
+  /orders/{id}:
+    parameters:
+      - { name: id, in: path, required: true, schema: { type: integer } }
+    put:
+      parameters:
+        - $ref: '#/components/parameters/Verbose'
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema: { $ref: '#/components/schemas/Order' }
+      responses:
+        '200':
+          description: Updated
+          content:
+            application/json:
+              schema: { $ref: '#/components/schemas/Order' }
+              examples:
+                shipped: { value: { id: 7, state: SHIPPED } }
+components:
+  parameters:
+    Verbose: { name: verbose, in: query, schema: { type: boolean } }
+  schemas:
+    Order:
+      type: object
+      properties:
+        id: { type: integer }
+        state: { type: string, enum: [OPEN, SHIPPED] }
+"##;
+        let spec = OpenApiParser::parse_yaml(spec_yaml).unwrap();
+        let op = &spec.paths[0];
+
+        let params: Vec<(&str, &str, Option<&str>)> = op
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.location.as_str(), p.param_type.as_deref()))
+            .collect();
+        assert_eq!(params, vec![("verbose", "query", Some("boolean")), ("id", "path", Some("integer"))]);
+
+        let body = op.request_body.as_ref().unwrap();
+        assert!(body.required);
+        assert_eq!(body.content[0].content_type, "application/json");
+        assert_eq!(op.responses[0].content[0].example, Some(serde_json::json!({"id": 7, "state": "SHIPPED"})));
+
+        let schema = body.content[0].schema.as_ref().unwrap();
+        assert_eq!(spec.sample_for_schema(schema), serde_json::json!({"id": 0, "state": "OPEN"}));
+
+        // Swagger 2.0: inline parameter types and body parameters
+        let swagger = r#"{
+            "swagger": "2.0",
+            "info": { "title": "Legacy", "version": "1" },
+            "paths": { "/orders": { "post": {
+                "parameters": [
+                    { "name": "limit", "in": "query", "type": "integer", "maximum": 50 },
+                    { "name": "order", "in": "body", "required": true, "schema": { "type": "object" } }
+                ],
+                "responses": { "201": { "description": "Created", "schema": { "type": "object" },
+                    "examples": { "application/json": { "id": 1 } } } }
+            } } }
+        }"#;
+        let spec = OpenApiParser::parse_json(swagger).unwrap();
+        let op = &spec.paths[0];
+        assert_eq!(op.parameters[0].schema, Some(serde_json::json!({"type": "integer", "maximum": 50})));
+        assert_eq!(op.request_body.as_ref().unwrap().content[0].content_type, "application/json");
+        assert_eq!(op.responses[0].content[0].example, Some(serde_json::json!({"id": 1})));
+    }
 }
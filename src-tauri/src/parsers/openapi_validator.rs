@@ -0,0 +1,426 @@
+// OpenAPI response validation
+//
+// Checks a REST response against the responses declared for its operation:
+// the status code must be declared (exactly, as a range such as `4XX`, or via
+// `default`), the Content-Type must be one the response lists, and a JSON body
+// must conform to the media type's schema.
+//
+// Requests are checked the same way against an operation's parameters and
+// request body (used by mocks generated from a spec).
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use super::openapi_parser::{OpenApiParameter, OpenApiPath, OpenApiRequestBody, OpenApiResponse, OpenApiSpec};
+use crate::utils::{JsonSchemaValidator, SchemaError};
+
+/// Find an operation by `operationId` or by "METHOD /path" (e.g. "GET /pets/{id}")
+pub fn find_operation<'a>(spec: &'a OpenApiSpec, operation: &str) -> Option<&'a OpenApiPath> {
+    let operation = operation.trim();
+    spec.paths
+        .iter()
+        .find(|p| p.operation_id.as_deref() == Some(operation))
+        .or_else(|| {
+            let (method, path) = operation.split_once(char::is_whitespace)?;
+            spec.paths
+                .iter()
+                .find(|p| p.method.eq_ignore_ascii_case(method) && p.path == path.trim())
+        })
+}
+
+/// Validate a response of `operation`, returning every violation found.
+///
+/// Fails only when the operation is not in the spec or declares no responses.
+pub fn validate_response(
+    spec: &OpenApiSpec,
+    operation: &str,
+    status: u16,
+    content_type: Option<&str>,
+    body: &str,
+) -> Result<Vec<SchemaError>> {
+    let op = find_operation(spec, operation)
+        .ok_or_else(|| anyhow!("Operation '{}' not found in OpenAPI spec '{}'", operation, spec.title))?;
+    if op.responses.is_empty() {
+        return Err(anyhow!("Operation '{}' declares no responses", operation));
+    }
+
+    let response = match match_status(&op.responses, status) {
+        Some(response) => response,
+        None => {
+            let declared: Vec<&str> = op.responses.iter().map(|r| r.status.as_str()).collect();
+            return Ok(vec![SchemaError {
+                path: "status".to_string(),
+                message: format!("{} is not a declared response (declared: {})", status, declared.join(", ")),
+            }]);
+        }
+    };
+
+    // Responses without content (e.g. 204) accept any body
+    if response.content.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let media_type = content_type.map(base_media_type).filter(|ct| !ct.is_empty());
+    let media = match &media_type {
+        Some(ct) => response
+            .content
+            .iter()
+            .find(|m| base_media_type(&m.content_type) == *ct)
+            .or_else(|| response.content.iter().find(|m| media_range_matches(&m.content_type, ct))),
+        // No Content-Type header: only an empty body is acceptable
+        None if body.trim().is_empty() => return Ok(vec![]),
+        None => None,
+    };
+    let media = match media {
+        Some(media) => media,
+        None => {
+            let allowed: Vec<&str> = response.content.iter().map(|m| m.content_type.as_str()).collect();
+            return Ok(vec![SchemaError {
+                path: "Content-Type".to_string(),
+                message: format!(
+                    "'{}' is not allowed for status {} (allowed: {})",
+                    media_type.as_deref().unwrap_or(""),
+                    response.status,
+                    allowed.join(", ")
+                ),
+            }]);
+        }
+    };
+
+    let schema = match &media.schema {
+        Some(schema) if is_json(media_type.as_deref().unwrap_or(&media.content_type)) => schema,
+        // Only JSON bodies are validated against schemas
+        _ => return Ok(vec![]),
+    };
+
+    let instance: Value = match serde_json::from_str(body) {
+        Ok(value) => value,
+        Err(e) => {
+            return Ok(vec![SchemaError {
+                path: "$".to_string(),
+                message: format!("body is not valid JSON: {}", e),
+            }])
+        }
+    };
+
+    Ok(JsonSchemaValidator::with_refs(&spec.schemas).validate(schema, &instance))
+}
+
+/// The parts of a REST request checked by `validate_request`
+pub struct RequestToValidate<'a> {
+    pub path_params: &'a HashMap<String, String>,
+    pub query: &'a HashMap<String, String>,
+    /// Header names are lower-case
+    pub headers: &'a HashMap<String, String>,
+    pub body: &'a str,
+}
+
+/// Validate a request against an operation's parameters and request body,
+/// returning every violation found. `$ref`s resolve through `schemas`.
+pub fn validate_request(
+    parameters: &[OpenApiParameter],
+    request_body: Option<&OpenApiRequestBody>,
+    schemas: &BTreeMap<String, Value>,
+    request: &RequestToValidate,
+) -> Vec<SchemaError> {
+    let validator = JsonSchemaValidator::with_refs(schemas);
+    let mut errors = Vec::new();
+
+    for param in parameters {
+        let value = match param.location.as_str() {
+            "path" => request.path_params.get(&param.name),
+            "query" => request.query.get(&param.name),
+            "header" => request.headers.get(&param.name.to_ascii_lowercase()),
+            // Cookies and Swagger 2.0 body/formData parameters aren't checked here
+            _ => continue,
+        };
+        let location = format!("{}.{}", param.location, param.name);
+        match (value, &param.schema) {
+            (None, _) if param.required || param.location == "path" => errors.push(SchemaError {
+                path: location,
+                message: format!("missing required {} parameter", param.location),
+            }),
+            (Some(value), Some(schema)) => {
+                let instance = coerce_parameter(value, schema);
+                errors.extend(validator.validate(schema, &instance).into_iter().map(|e| SchemaError {
+                    path: format!("{}{}", location, e.path.trim_start_matches('$')),
+                    message: e.message,
+                }));
+            }
+            _ => {}
+        }
+    }
+
+    let Some(request_body) = request_body else {
+        return errors;
+    };
+    if request.body.trim().is_empty() {
+        if request_body.required {
+            errors.push(SchemaError { path: "body".to_string(), message: "request body is required".to_string() });
+        }
+        return errors;
+    }
+    if request_body.content.is_empty() {
+        return errors;
+    }
+
+    let media_type = request.headers.get("content-type").map(|ct| base_media_type(ct)).unwrap_or_default();
+    let media = request_body
+        .content
+        .iter()
+        .find(|m| base_media_type(&m.content_type) == media_type)
+        .or_else(|| request_body.content.iter().find(|m| media_range_matches(&m.content_type, &media_type)));
+    let Some(media) = media else {
+        let accepted: Vec<&str> = request_body.content.iter().map(|m| m.content_type.as_str()).collect();
+        errors.push(SchemaError {
+            path: "Content-Type".to_string(),
+            message: format!("'{}' is not accepted (accepted: {})", media_type, accepted.join(", ")),
+        });
+        return errors;
+    };
+
+    if let Some(schema) = media.schema.as_ref().filter(|_| is_json(&media_type)) {
+        match serde_json::from_str::<Value>(request.body) {
+            Ok(instance) => errors.extend(validator.validate(schema, &instance)),
+            Err(e) => errors.push(SchemaError {
+                path: "$".to_string(),
+                message: format!("body is not valid JSON: {}", e),
+            }),
+        }
+    }
+    errors
+}
+
+/// Parameters arrive as strings; read them as the type their schema declares
+/// so e.g. `?limit=10` validates against `type: integer`
+fn coerce_parameter(value: &str, schema: &Value) -> Value {
+    match schema.get("type").and_then(|t| t.as_str()) {
+        Some("integer") | Some("number") => {
+            serde_json::from_str::<serde_json::Number>(value).map(Value::Number).unwrap_or_else(|_| Value::String(value.to_string()))
+        }
+        Some("boolean") => match value {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => Value::String(value.to_string()),
+        },
+        Some("array") => {
+            let items = schema.get("items").cloned().unwrap_or(Value::Null);
+            Value::Array(value.split(',').map(|item| coerce_parameter(item, &items)).collect())
+        }
+        _ => Value::String(value.to_string()),
+    }
+}
+
+/// Pick the declared response for a status: exact code, then range (`4XX`), then `default`
+fn match_status(responses: &[OpenApiResponse], status: u16) -> Option<&OpenApiResponse> {
+    let code = status.to_string();
+    let range = format!("{}XX", status / 100);
+    responses
+        .iter()
+        .find(|r| r.status == code)
+        .or_else(|| responses.iter().find(|r| r.status.eq_ignore_ascii_case(&range)))
+        .or_else(|| responses.iter().find(|r| r.status == "default"))
+}
+
+/// "application/json; charset=utf-8" -> "application/json"
+fn base_media_type(content_type: &str) -> String {
+    content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase()
+}
+
+/// Match a declared media range such as `*/*` or `application/*`
+fn media_range_matches(declared: &str, actual: &str) -> bool {
+    let declared = base_media_type(declared);
+    match declared.split_once('/') {
+        Some(("*", "*")) => true,
+        Some((kind, "*")) => actual.split_once('/').map(|(k, _)| k == kind).unwrap_or(false),
+        _ => false,
+    }
+}
+
+pub(crate) fn is_json(content_type: &str) -> bool {
+    let media_type = base_media_type(content_type);
+    media_type == "application/json" || media_type.ends_with("+json") || media_type == "*/*"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::OpenApiParser;
+
+    const PETSTORE: &str = r#"
+openapi: 3.0.3
+info:
+  title: Petstore
+  version: 1.0.0
+paths:
+  /pets/{id}:
+    get:
+      operationId: getPet
+      responses:
+        '200':
+          description: A pet
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/Pet'
+        '404':
+          $ref: '#/components/responses/NotFound'
+    delete:
+      responses:
+        '204':
+          description: Deleted
+components:
+  responses:
+    NotFound:
+      description: Not found
+      content:
+        application/problem+json:
+          schema:
+            $ref: '#/components/schemas/Problem'
+  schemas:
+    Pet:
+      type: object
+      required: [id, name]
+      properties:
+        id: { type: integer, format: int64 }
+        name: { type: string }
+        born: { type: string, format: date }
+        tags:
+          type: array
+          items: { $ref: '#/components/schemas/Tag' }
+    Tag:
+      type: object
+      required: [label]
+      properties:
+        label: { type: string }
+    Problem:
+      type: object
+      required: [title]
+      properties:
+        title: { type: string }
+"#;
+
+    fn spec() -> OpenApiSpec {
+        OpenApiParser::parse_auto(PETSTORE).unwrap()
+    }
+
+    fn messages(errors: Vec<SchemaError>) -> Vec<String> {
+        errors.into_iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn test_valid_responses() {
+        let spec = spec();
+        let ok = r#"{"id": 1, "name": "Rex", "born": "2020-05-01", "tags": [{"label": "good"}]}"#;
+        assert!(validate_response(&spec, "getPet", 200, Some("application/json; charset=utf-8"), ok).unwrap().is_empty());
+        assert!(validate_response(&spec, "get /pets/{id}", 200, Some("application/json"), ok).unwrap().is_empty());
+        assert!(validate_response(&spec, "getPet", 404, Some("application/problem+json"), r#"{"title":"Gone"}"#)
+            .unwrap()
+            .is_empty());
+        assert!(validate_response(&spec, "DELETE /pets/{id}", 204, None, "").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_violations_have_precise_paths() {
+        let spec = spec();
+        let body = r#"{"id": "1", "born": "May 2020", "tags": [{"label": "a"}, {}]}"#;
+        assert_eq!(
+            messages(validate_response(&spec, "getPet", 200, Some("application/json"), body).unwrap()),
+            vec![
+                "$: missing required property 'name'",
+                "$.born: 'May 2020' is not a valid date",
+                "$.id: expected integer, got string",
+                "$.tags[1]: missing required property 'label'",
+            ]
+        );
+
+        assert_eq!(
+            messages(validate_response(&spec, "getPet", 500, Some("application/json"), "{}").unwrap()),
+            vec!["status: 500 is not a declared response (declared: 200, 404)"]
+        );
+        assert_eq!(
+            messages(validate_response(&spec, "getPet", 200, Some("text/html"), "<html/>").unwrap()),
+            vec!["Content-Type: 'text/html' is not allowed for status 200 (allowed: application/json)"]
+        );
+        assert!(validate_response(&spec, "getPet", 200, Some("application/json"), "{oops")
+            .unwrap()[0]
+            .message
+            .starts_with("body is not valid JSON"));
+        assert!(validate_response(&spec, "listPets", 200, None, "").is_err());
+    }
+
+    #[test]
+    fn test_status_ranges_and_default() {
+        let responses = vec![
+            OpenApiResponse { status: "200".to_string(), description: None, content: vec![] },
+            OpenApiResponse { status: "4XX".to_string(), description: None, content: vec![] },
+            OpenApiResponse { status: "default".to_string(), description: None, content: vec![] },
+        ];
+        assert_eq!(match_status(&responses, 200).unwrap().status, "200");
+        assert_eq!(match_status(&responses, 418).unwrap().status, "4XX");
+        assert_eq!(match_status(&responses, 503).unwrap().status, "default");
+        assert!(media_range_matches("application/*", "application/xml"));
+        assert!(!media_range_matches("application/*", "text/plain"));
+    }
+
+    #[test]
+    fn test_validate_request() {
+        let spec = OpenApiParser::parse_auto(
+            r#"
+openapi: 3.0.3
+info: { title: Orders, version: "1" }
+paths:
+  /orders/{id}:
+    put:
+      parameters:
+        - { name: id, in: path, required: true, schema: { type: integer } }
+        - { name: limit, in: query, schema: { type: integer, maximum: 50 } }
+        - { name: X-Tenant, in: header, required: true, schema: { type: string } }
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              type: object
+              required: [state]
+              properties:
+                state: { type: string }
+      responses:
+        '200': { description: OK }
+"#,
+        )
+        .unwrap();
+        let op = &spec.paths[0];
+        let check = |path: &[(&str, &str)], query: &[(&str, &str)], headers: &[(&str, &str)], body: &str| {
+            let owned = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+                pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+            };
+            let request = RequestToValidate {
+                path_params: &owned(path),
+                query: &owned(query),
+                headers: &owned(headers),
+                body,
+            };
+            messages(validate_request(&op.parameters, op.request_body.as_ref(), &spec.schemas, &request))
+        };
+
+        let json = [("x-tenant", "acme"), ("content-type", "application/json")];
+        assert!(check(&[("id", "7")], &[("limit", "10")], &json, r#"{"state":"OPEN"}"#).is_empty());
+        assert_eq!(
+            check(&[("id", "seven")], &[("limit", "99")], &[("content-type", "application/json")], "{}"),
+            vec![
+                "path.id: expected integer, got string",
+                "query.limit: 99 is greater than the maximum of 50",
+                "header.X-Tenant: missing required header parameter",
+                "$: missing required property 'state'",
+            ]
+        );
+        assert_eq!(check(&[("id", "7")], &[], &json, ""), vec!["body: request body is required"]);
+        assert_eq!(
+            check(&[("id", "7")], &[], &[("x-tenant", "acme"), ("content-type", "text/plain")], "hi"),
+            vec!["Content-Type: 'text/plain' is not accepted (accepted: application/json)"]
+        );
+    }
+}
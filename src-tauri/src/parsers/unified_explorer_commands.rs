@@ -230,6 +230,7 @@ fn build_operation_json(op: &ServiceOperation) -> serde_json::Value {
         "targetNamespace": op.target_namespace,
         "originalEndpoint": op.original_endpoint,
         "fullSchema": op.full_schema.as_ref().map(|s| serde_json::to_value(s).unwrap_or(json!(null))).unwrap_or(json!(null)),
+        "outputSchema": op.output_schema.as_ref().map(|s| serde_json::to_value(s).unwrap_or(json!(null))).unwrap_or(json!(null)),
         "requests": json!([
             json!({
                 "name": format!("sample_{}", op.name),
@@ -262,6 +263,7 @@ fn generate_sample_xml(op: &ServiceOperation) -> String {
         target_namespace: Some(target_ns.clone()),
         original_endpoint: op.original_endpoint.clone(),
         full_schema: Some(schema.clone()),
+        output_schema: None,
         description: op.description.clone(),
         port_name: op.port_name.clone(),
     };
@@ -405,6 +407,9 @@ pub fn new_unified_request(params: serde_json::Value) -> Result<serde_json::Valu
                 full_schema: op.get("fullSchema").and_then(|v| {
                     serde_json::from_value(v.clone()).ok()
                 }),
+                output_schema: op.get("outputSchema").and_then(|v| {
+                    serde_json::from_value(v.clone()).ok()
+                }),
                 description: op.get("description").and_then(|v| v.as_str()).map(|s| s.to_string()),
                 port_name: op.get("portName").and_then(|v| v.as_str()).map(|s| s.to_string()),
             };
@@ -478,6 +483,7 @@ mod tests {
             port_name: None,
             original_endpoint: Some("http://example.com/service".to_string()),
             full_schema: None,
+            output_schema: None,
             action: Some("http://example.com/GetBalance".to_string()),
         };
 
@@ -506,6 +512,7 @@ mod tests {
             port_name: None,
             original_endpoint: Some("http://example.com/service".to_string()),
             full_schema: None,
+            output_schema: None,
             action: Some("http://example.com/Op1".to_string()),
         };
         let op2 = ServiceOperation {
@@ -517,6 +524,7 @@ mod tests {
             port_name: None,
             original_endpoint: Some("http://example.com/service".to_string()),
             full_schema: None,
+            output_schema: None,
             action: Some("http://example.com/Op2".to_string()),
         };
         let service = ApiService {
@@ -2,12 +2,18 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use once_cell::sync::Lazy;
-
-use crate::performance::runner::execute_request;
-use crate::performance::types::{PerformanceRun, PerformanceStats, PerformanceSuite};
+use tokio::task::JoinHandle;
+
+use crate::performance::distributed::{Coordinator, WorkerInfo};
+use crate::performance::load_model::{run_load, LoadHooks, LoadProfile};
+use crate::performance::metrics::{MetricsRecorder, TimeSeries};
+use crate::performance::comparison::{compare_runs, RegressionThresholds, RunComparison};
+use crate::performance::cron::CronSchedule;
+use crate::performance::scheduler::{self, PerformanceSchedule, RegressionCheck};
+use crate::performance::types::{PerformanceRun, PerformanceSuite};
 use crate::settings_manager;
 
 #[derive(Debug, Clone)]
@@ -28,18 +34,28 @@ static ABORT_FLAGS: Lazy<Arc<Mutex<HashMap<String, bool>>>> =
 pub struct CoordinatorStatusResponse {
     pub running: bool,
     pub port: u16,
-    pub workers: Vec<Value>,
+    pub workers: Vec<WorkerInfo>,
     pub expected_workers: u32,
 }
 
-static COORDINATOR_STATUS: Lazy<Arc<Mutex<CoordinatorStatusResponse>>> = Lazy::new(|| {
-    Arc::new(Mutex::new(CoordinatorStatusResponse {
-        running: false,
-        port: 0,
-        workers: Vec::new(),
-        expected_workers: 0,
-    }))
-});
+/// The running coordinator and the number of workers the user expects
+static COORDINATOR: Lazy<Mutex<Option<(Coordinator, u32)>>> = Lazy::new(|| Mutex::new(None));
+
+fn coordinator_status() -> Result<CoordinatorStatusResponse, String> {
+    let coordinator = COORDINATOR
+        .lock()
+        .map_err(|e| format!("Failed to lock coordinator status: {}", e))?;
+
+    Ok(match coordinator.as_ref() {
+        Some((coordinator, expected_workers)) => CoordinatorStatusResponse {
+            running: true,
+            port: coordinator.port(),
+            workers: coordinator.workers(),
+            expected_workers: *expected_workers,
+        },
+        None => CoordinatorStatusResponse::default(),
+    })
+}
 
 fn push_update(run_id: &str, update: Value) {
     let mut store = PERF_RUN_STORE.lock().unwrap();
@@ -48,6 +64,34 @@ fn push_update(run_id: &str, update: Value) {
     }
 }
 
+/// How long a finished time bucket stays open for results still in flight
+/// (e.g. on their way from a distributed worker)
+const METRICS_GRACE_MS: u64 = 1000;
+
+/// Most run times `preview_cron_schedule` returns
+const MAX_CRON_PREVIEW: usize = 100;
+
+fn push_time_series(run_id: &str, series: &TimeSeries) {
+    if !series.is_empty() {
+        push_update(
+            run_id,
+            serde_json::json!({
+                "type": "timeSeries",
+                "runId": run_id,
+                "timeSeries": series
+            }),
+        );
+    }
+}
+
+fn fail_run(run_id: &str, error: String) {
+    let mut store = PERF_RUN_STORE.lock().unwrap();
+    if let Some(data) = store.get_mut(run_id) {
+        data.error = Some(error);
+        data.done = true;
+    }
+}
+
 fn mark_done(run_id: &str) {
     let mut store = PERF_RUN_STORE.lock().unwrap();
     if let Some(data) = store.get_mut(run_id) {
@@ -71,6 +115,17 @@ fn now_ms() -> u64 {
         .as_millis() as u64
 }
 
+/// Scenario iterations a run will perform, warmups included, when the
+/// profile fixes it up front
+fn planned_iterations(profile: &LoadProfile, warmup_runs: u32) -> Option<u64> {
+    match profile {
+        LoadProfile::ConstantVus { vus, iterations, duration_ms: None } => {
+            Some(*vus as u64 * (iterations.unwrap_or(1) + warmup_runs) as u64)
+        }
+        _ => None,
+    }
+}
+
 // ── Request types ─────────────────────────────────────────────────────────────
 
 #[derive(Debug, Deserialize)]
@@ -83,6 +138,23 @@ pub struct RunPerformanceSuiteRequest {
     /// Optional suite payload from the frontend — used as fallback if the suite
     /// is not found in the persisted config (e.g. due to a save race condition).
     pub suite: Option<Value>,
+    /// Spread the run across the workers connected to the coordinator
+    #[serde(default)]
+    pub distributed: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparePerformanceRunsRequest {
+    /// Run to compare; either inline or an id from `performanceHistory`
+    pub current: Option<PerformanceRun>,
+    pub current_run_id: Option<String>,
+    /// Baseline, inline or by id; defaults to the latest completed run of
+    /// the same suite before `current`
+    pub baseline: Option<PerformanceRun>,
+    pub baseline_run_id: Option<String>,
+    #[serde(default)]
+    pub thresholds: RegressionThresholds,
 }
 
 fn default_true() -> bool {
@@ -112,42 +184,30 @@ pub struct PerformanceRunUpdatesResponse {
 pub async fn start_coordinator(
     port: u16,
     expected_workers: u32,
+    token: Option<String>,
 ) -> Result<CoordinatorStatusResponse, String> {
-    let mut status = COORDINATOR_STATUS
-        .lock()
-        .map_err(|e| format!("Failed to lock coordinator status: {}", e))?;
-
-    status.running = true;
-    status.port = port;
-    status.expected_workers = expected_workers;
-    status.workers.clear();
+    if let Some((running, _)) = COORDINATOR.lock().unwrap().take() {
+        running.stop();
+    }
 
-    log::warn!(
-        "[performance] start_coordinator is using placeholder backend status only"
-    );
+    let coordinator = Coordinator::start(port, token).await.map_err(|e| e.to_string())?;
+    *COORDINATOR.lock().unwrap() = Some((coordinator, expected_workers));
 
-    Ok(status.clone())
+    coordinator_status()
 }
 
 #[tauri::command]
 pub async fn stop_coordinator() -> Result<CoordinatorStatusResponse, String> {
-    let mut status = COORDINATOR_STATUS
-        .lock()
-        .map_err(|e| format!("Failed to lock coordinator status: {}", e))?;
-
-    status.running = false;
-    status.workers.clear();
+    if let Some((coordinator, _)) = COORDINATOR.lock().unwrap().take() {
+        coordinator.stop();
+    }
 
-    Ok(status.clone())
+    coordinator_status()
 }
 
 #[tauri::command]
 pub async fn get_coordinator_status() -> Result<CoordinatorStatusResponse, String> {
-    let status = COORDINATOR_STATUS
-        .lock()
-        .map_err(|e| format!("Failed to lock coordinator status: {}", e))?;
-
-    Ok(status.clone())
+    coordinator_status()
 }
 
 #[tauri::command]
@@ -174,19 +234,59 @@ pub async fn run_performance_suite(
                 })
                 .ok()
         })
-        .or_else(|| {
-            // Fallback: load from persisted config
-            let config = settings_manager::load_config_internal().ok()?;
-            let suites: Vec<PerformanceSuite> = config
-                .performance_suites
-                .unwrap_or_default()
-                .into_iter()
-                .filter_map(|v| serde_json::from_value(v).ok())
-                .collect();
-            suites.into_iter().find(|s| s.id == request.suite_id)
-        })
+        .or_else(|| find_saved_suite(&request.suite_id))
         .ok_or_else(|| format!("Performance suite not found: {}", request.suite_id))?;
 
+    let (run_id, _) = start_run(
+        suite,
+        request.environment.unwrap_or_default(),
+        request.distributed,
+        None,
+    )?;
+
+    Ok(RunPerformanceSuiteResponse {
+        run_id: Some(run_id),
+    })
+}
+
+/// Load a suite from the persisted config
+pub(crate) fn find_saved_suite(suite_id: &str) -> Option<PerformanceSuite> {
+    let config = settings_manager::load_config_internal().ok()?;
+    config
+        .performance_suites
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|v| serde_json::from_value::<PerformanceSuite>(v).ok())
+        .find(|s| s.id == suite_id)
+}
+
+/// Start a run in the background. Progress is published under the returned
+/// run id; the handle resolves to the finished run (`None` if it could not
+/// run at all).
+pub(crate) fn start_run(
+    suite: PerformanceSuite,
+    environment: HashMap<String, String>,
+    distributed: bool,
+    regression_check: Option<RegressionCheck>,
+) -> Result<(String, JoinHandle<Option<PerformanceRun>>), String> {
+    let profile = LoadProfile::for_suite(&suite);
+    profile.validate()?;
+
+    let coordinator = if distributed {
+        let coordinator = COORDINATOR
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|(coordinator, _)| coordinator.clone())
+            .ok_or("Coordinator is not running")?;
+        if coordinator.workers().is_empty() {
+            return Err("No workers connected to the coordinator".to_string());
+        }
+        Some(coordinator)
+    } else {
+        None
+    };
+
     let run_id = format!(
         "perf-{}-{}",
         now_ms(),
@@ -211,9 +311,9 @@ pub async fn run_performance_suite(
         .insert(run_id.clone(), false);
 
     let run_id_clone = run_id.clone();
-    let initial_vars = request.environment.unwrap_or_default();
+    let initial_vars = environment;
 
-    tokio::spawn(async move {
+    let handle = tokio::spawn(async move {
         let suite_id = suite.id.clone();
         let suite_name = suite.name.clone();
         let start_time = now_ms();
@@ -228,107 +328,80 @@ pub async fn run_performance_suite(
             }),
         );
 
-        let total_iterations = suite.iterations + suite.warmup_runs;
-        let mut all_results = Vec::new();
-        let mut variables = initial_vars;
-        let mut status = "completed".to_string();
+        let total = planned_iterations(&profile, suite.warmup_runs);
+        let metrics = Arc::new(Mutex::new(MetricsRecorder::default()));
+        let recorder = metrics.clone();
+        let progress_run_id = run_id_clone.clone();
+        let abort_run_id = run_id_clone.clone();
+        let hooks = LoadHooks {
+            should_stop: Arc::new(move || is_aborted(&abort_run_id)),
+            on_iteration: Arc::new(move |progress| {
+                push_update(
+                    &progress_run_id,
+                    serde_json::json!({
+                        "type": "iterationComplete",
+                        "runId": progress_run_id,
+                        "vu": progress.vu,
+                        "iteration": progress.iteration,
+                        "completed": progress.completed,
+                        "total": total
+                    }),
+                );
+            }),
+            on_result: Arc::new(move |result| recorder.lock().unwrap().record(result)),
+        };
 
-        'outer: for iteration in 0..total_iterations {
-            if is_aborted(&run_id_clone) {
-                status = "aborted".to_string();
-                break;
+        // Publish each second's metrics once it is over
+        let (series_metrics, series_run_id) = (metrics.clone(), run_id_clone.clone());
+        let series_publisher = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_millis(1000));
+            loop {
+                ticker.tick().await;
+                let closed = series_metrics
+                    .lock()
+                    .unwrap()
+                    .close_before(now_ms().saturating_sub(METRICS_GRACE_MS));
+                push_time_series(&series_run_id, &closed);
             }
-
-            let is_warmup = iteration < suite.warmup_runs;
-
-            let mut requests = suite.requests.clone();
-            requests.sort_by_key(|r| r.order);
-
-            if suite.concurrency <= 1 {
-                for req in &requests {
-                    if is_aborted(&run_id_clone) {
-                        status = "aborted".to_string();
-                        break 'outer;
-                    }
-
-                    let result = execute_request(req, iteration, &variables).await;
-
-                    if let Some(ref extracted) = result.extracted_values {
-                        for (k, v) in extracted {
-                            variables.insert(k.clone(), v.clone());
-                        }
-                    }
-
-                    if !is_warmup {
-                        all_results.push(result);
-                    }
-
-                    if suite.delay_between_requests > 0 {
-                        tokio::time::sleep(tokio::time::Duration::from_millis(
-                            suite.delay_between_requests,
-                        ))
-                        .await;
-                    }
-                }
-            } else {
-                let concurrency = suite.concurrency as usize;
-                for chunk in requests.chunks(concurrency) {
-                    if is_aborted(&run_id_clone) {
-                        status = "aborted".to_string();
-                        break 'outer;
-                    }
-
-                    let chunk_vars = variables.clone();
-                    let mut handles = Vec::new();
-                    for req in chunk {
-                        let req = req.clone();
-                        let vars = chunk_vars.clone();
-                        handles.push(tokio::spawn(async move {
-                            execute_request(&req, iteration, &vars).await
-                        }));
-                    }
-
-                    for handle in handles {
-                        match handle.await {
-                            Ok(result) => {
-                                if let Some(ref extracted) = result.extracted_values {
-                                    for (k, v) in extracted {
-                                        variables.entry(k.clone()).or_insert_with(|| v.clone());
-                                    }
-                                }
-                                if !is_warmup {
-                                    all_results.push(result);
-                                }
-                            }
-                            Err(e) => {
-                                log::warn!("[run_performance_suite] Task join error: {}", e);
-                            }
-                        }
-                    }
-
-                    if suite.delay_between_requests > 0 {
-                        tokio::time::sleep(tokio::time::Duration::from_millis(
-                            suite.delay_between_requests,
-                        ))
-                        .await;
-                    }
+        });
+
+        let load = match coordinator {
+            Some(coordinator) => match coordinator.run_suite(&suite, initial_vars, hooks).await {
+                Ok(load) => load,
+                Err(e) => {
+                    log::error!("[run_performance_suite] Run {} failed: {}", run_id_clone, e);
+                    series_publisher.abort();
+                    fail_run(&run_id_clone, e.to_string());
+                    ABORT_FLAGS.lock().unwrap().remove(&run_id_clone);
+                    return None;
                 }
-            }
-
-            push_update(
-                &run_id_clone,
-                serde_json::json!({
-                    "type": "iterationComplete",
-                    "runId": run_id_clone,
-                    "iteration": iteration,
-                    "total": total_iterations
-                }),
-            );
+            },
+            None => run_load(&suite, &profile, initial_vars, hooks).await,
+        };
+        series_publisher.abort();
+        let status = if load.aborted {
+            "aborted"
+        } else if !load.errors.is_empty() {
+            "failed"
+        } else {
+            "completed"
         }
+        .to_string();
+        let all_results = load.results;
 
         let end_time = now_ms();
-        let summary = PerformanceStats::calculate(&all_results);
-        let run = PerformanceRun {
+        let (summary, time_series) = {
+            let mut metrics = metrics.lock().unwrap();
+            push_time_series(&run_id_clone, &metrics.close_all());
+            if metrics.late_results() > 0 {
+                log::warn!(
+                    "[run_performance_suite] {} results arrived too late for the time series",
+                    metrics.late_results()
+                );
+            }
+            (metrics.summary(), metrics.time_series().clone())
+        };
+        let mut run = PerformanceRun {
             id: run_id_clone.clone(),
             suite_id,
             suite_name,
@@ -338,11 +411,17 @@ pub async fn run_performance_suite(
             results: all_results,
             summary,
             environment: None,
+            time_series: Some(time_series),
+            baseline_run_id: None,
+            regressions: Vec::new(),
         };
 
         if let Ok(mut config) = settings_manager::load_config_internal() {
+            let mut history: Vec<Value> = config.performance_history.unwrap_or_default();
+            if let Some(check) = &regression_check {
+                check.apply(&history, &mut run);
+            }
             if let Ok(run_value) = serde_json::to_value(&run) {
-                let mut history: Vec<Value> = config.performance_history.unwrap_or_default();
                 history.push(run_value);
                 const MAX_HISTORY: usize = 50;
                 if history.len() > MAX_HISTORY {
@@ -358,22 +437,27 @@ pub async fn run_performance_suite(
             serde_json::json!({
                 "type": "runCompleted",
                 "runId": run_id_clone,
-                "run": run
+                "run": run,
+                "droppedIterations": load.dropped_iterations,
+                "errors": load.errors
             }),
         );
 
-        mark_done(&run_id_clone);
+        if load.errors.is_empty() {
+            mark_done(&run_id_clone);
+        } else {
+            fail_run(&run_id_clone, load.errors.join("; "));
+        }
         ABORT_FLAGS.lock().unwrap().remove(&run_id_clone);
 
         log::info!(
             "[run_performance_suite] Run {} completed",
             run_id_clone
         );
+        Some(run)
     });
 
-    Ok(RunPerformanceSuiteResponse {
-        run_id: Some(run_id),
-    })
+    Ok((run_id, handle))
 }
 
 #[tauri::command]
@@ -416,3 +500,80 @@ pub async fn abort_performance_suite(run_id: String) -> Result<(), String> {
     }
     Ok(())
 }
+
+/// Stored schedules with `nextRun` computed for the local time zone
+#[tauri::command]
+pub async fn get_performance_schedules() -> Result<Vec<PerformanceSchedule>, String> {
+    let now = chrono::Local::now();
+    Ok(scheduler::load_schedules()
+        .into_iter()
+        .map(|mut schedule| {
+            schedule.next_run = schedule.next_run_after(&now).map(|t| t.timestamp_millis() as u64);
+            schedule
+        })
+        .collect())
+}
+
+/// Trigger a schedule immediately; the outcome is recorded on the schedule
+#[tauri::command]
+pub async fn run_performance_schedule_now(schedule_id: String) -> Result<(), String> {
+    let schedule = scheduler::load_schedules()
+        .into_iter()
+        .find(|s| s.id == schedule_id)
+        .ok_or_else(|| format!("Schedule not found: {}", schedule_id))?;
+    if scheduler::is_running(&schedule.id) {
+        return Err(format!("Schedule '{}' is already running", schedule.id));
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = scheduler::trigger(schedule).await {
+            log::error!("[scheduler] {}", e);
+        }
+    });
+    Ok(())
+}
+
+/// Validate a cron expression and return its next `count` run times (epoch
+/// ms), at most `MAX_CRON_PREVIEW`
+#[tauri::command]
+pub async fn preview_cron_schedule(cron_expression: String, count: Option<usize>) -> Result<Vec<u64>, String> {
+    let schedule = CronSchedule::parse(&cron_expression)?;
+    let mut times = Vec::new();
+    let count = count.unwrap_or(5).min(MAX_CRON_PREVIEW);
+    let mut after = chrono::Local::now();
+    while times.len() < count {
+        let Some(next) = schedule.next_after(&after) else { break };
+        times.push(next.timestamp_millis() as u64);
+        after = next;
+    }
+    Ok(times)
+}
+
+/// Compare two runs per request, flagging regressions beyond the thresholds
+#[tauri::command]
+pub async fn compare_performance_runs(request: ComparePerformanceRunsRequest) -> Result<RunComparison, String> {
+    let history: Vec<Value> = settings_manager::load_config_internal()?
+        .performance_history
+        .unwrap_or_default();
+    let from_history = |id: &str| {
+        history
+            .iter()
+            .filter_map(|v| serde_json::from_value::<PerformanceRun>(v.clone()).ok())
+            .find(|run| run.id == id)
+            .ok_or_else(|| format!("Performance run not found: {}", id))
+    };
+
+    let current = match (request.current, &request.current_run_id) {
+        (Some(run), _) => run,
+        (None, Some(id)) => from_history(id)?,
+        (None, None) => return Err("No run to compare".to_string()),
+    };
+    let baseline = match (request.baseline, &request.baseline_run_id) {
+        (Some(run), _) => run,
+        (None, Some(id)) => from_history(id)?,
+        (None, None) => scheduler::find_baseline(&history, &current, None)
+            .ok_or_else(|| format!("No baseline run for suite '{}'", current.suite_name))?,
+    };
+
+    Ok(compare_runs(&baseline, &current, &request.thresholds))
+}
@@ -0,0 +1,474 @@
+// Comparison of two performance runs
+//
+// Each request present in both runs (and the run as a whole) gets deltas for
+// p50/p95/p99, throughput and error rate. Latency changes are tested with a
+// two-sided Mann-Whitney U test (normal approximation with tie correction),
+// so a slower percentile only counts as a regression when the shift in the
+// whole distribution is statistically significant.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::performance::metrics::TimeBucket;
+use crate::performance::types::{percentile, PerformanceRun};
+
+/// Limits beyond which a change is a regression
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RegressionThresholds {
+    /// Allowed increase of p50/p95/p99, in percent
+    pub max_latency_increase: f64,
+    /// Allowed drop in throughput, in percent
+    pub max_throughput_drop: f64,
+    /// Allowed increase in error rate, in percentage points
+    pub max_error_rate_increase: f64,
+    /// Significance level for the Mann-Whitney test
+    pub alpha: f64,
+    /// Only flag latency increases that are statistically significant
+    pub require_significance: bool,
+}
+
+impl Default for RegressionThresholds {
+    fn default() -> Self {
+        Self {
+            max_latency_increase: 20.0,
+            max_throughput_drop: 20.0,
+            max_error_rate_increase: 1.0,
+            alpha: 0.05,
+            require_significance: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricDelta {
+    pub baseline: f64,
+    pub current: f64,
+    /// Relative change; `None` when the baseline is 0
+    pub change_percent: Option<f64>,
+}
+
+impl MetricDelta {
+    fn new(baseline: f64, current: f64) -> Self {
+        let change_percent = (baseline != 0.0).then(|| (current - baseline) / baseline * 100.0);
+        Self { baseline, current, change_percent }
+    }
+}
+
+/// Two-sided Mann-Whitney U test of current vs baseline response times
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MannWhitney {
+    /// U statistic of the current sample
+    pub u: f64,
+    /// Positive when current response times tend to be longer
+    pub z: f64,
+    pub p_value: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestComparison {
+    pub request_id: String,
+    pub request_name: String,
+    pub baseline_samples: usize,
+    pub current_samples: usize,
+    pub p50: MetricDelta,
+    pub p95: MetricDelta,
+    pub p99: MetricDelta,
+    /// Requests per second
+    pub throughput: MetricDelta,
+    /// Failed requests, in percent
+    pub error_rate: MetricDelta,
+    /// `None` when either run has no samples
+    pub mann_whitney: Option<MannWhitney>,
+    /// Latency shift is significant at `alpha`
+    pub significant: bool,
+    /// Metrics that crossed a threshold, e.g. "p95"
+    pub regressions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunComparison {
+    pub baseline_run_id: String,
+    pub current_run_id: String,
+    pub thresholds: RegressionThresholds,
+    /// The whole run, all requests together
+    pub overall: RequestComparison,
+    pub requests: Vec<RequestComparison>,
+    /// Requests only in the current run
+    pub added: Vec<String>,
+    /// Requests only in the baseline run
+    pub removed: Vec<String>,
+    /// No regressions, overall or per request
+    pub passed: bool,
+}
+
+#[derive(Debug, Default)]
+struct Sample {
+    name: String,
+    durations: Vec<f64>,
+    requests: u64,
+    errors: u64,
+    /// p50/p95/p99 over every request, when `durations` holds only part of them
+    percentiles: Option<[f64; 3]>,
+}
+
+impl Sample {
+    /// p50/p95/p99 of the response times
+    fn latency(&self) -> [f64; 3] {
+        self.percentiles
+            .unwrap_or_else(|| [50.0, 95.0, 99.0].map(|p| percentile(&self.durations, p)))
+    }
+}
+
+/// Request-weighted mean of the buckets' p50/p95/p99
+fn bucket_percentiles(buckets: &[TimeBucket]) -> Option<[f64; 3]> {
+    let requests: u64 = buckets.iter().map(|b| b.requests).sum();
+    if requests == 0 {
+        return None;
+    }
+    let weighted = |value: fn(&TimeBucket) -> f64| {
+        buckets.iter().map(|b| value(b) * b.requests as f64).sum::<f64>() / requests as f64
+    };
+    Some([weighted(|b| b.p50), weighted(|b| b.p95), weighted(|b| b.p99)])
+}
+
+/// Response times per request id plus the whole run (keyed by "")
+fn samples(run: &PerformanceRun) -> BTreeMap<String, Sample> {
+    let mut samples: BTreeMap<String, Sample> = BTreeMap::new();
+    for result in &run.results {
+        for key in [String::new(), result.request_id.clone()] {
+            let sample = samples.entry(key).or_default();
+            sample.name.clone_from(&result.request_name);
+            sample.durations.push(result.duration);
+            sample.requests += 1;
+            sample.errors += u64::from(!result.success);
+        }
+    }
+    samples.entry(String::new()).or_default().name = "Overall".to_string();
+
+    // Stored results may be capped; counts come from the time series when
+    // the run has one, and from the summary for the whole run otherwise.
+    // Percentiles of capped samples come from the same place: the summary's
+    // histogram covers every request, the time series every request per id.
+    let summary_percentiles = [run.summary.p50, run.summary.p95, run.summary.p99];
+    if let Some(series) = &run.time_series {
+        for (request_id, buckets) in std::iter::once((&String::new(), &series.overall)).chain(&series.requests) {
+            if let Some(sample) = samples.get_mut(request_id) {
+                sample.requests = buckets.iter().map(|b| b.requests).sum();
+                sample.errors = buckets.iter().map(|b| b.errors).sum();
+                if (sample.durations.len() as u64) < sample.requests {
+                    sample.percentiles = match request_id.is_empty() {
+                        true => Some(summary_percentiles),
+                        false => bucket_percentiles(buckets),
+                    };
+                }
+            }
+        }
+    } else if let Some(overall) = samples.get_mut("") {
+        overall.requests = run.summary.total_requests as u64;
+        overall.errors = run.summary.failure_count as u64;
+        if (overall.durations.len() as u64) < overall.requests {
+            overall.percentiles = Some(summary_percentiles);
+        }
+    }
+    for sample in samples.values_mut() {
+        sample.durations.sort_by(|a, b| a.total_cmp(b));
+    }
+    samples
+}
+
+fn duration_secs(run: &PerformanceRun) -> f64 {
+    (run.end_time.saturating_sub(run.start_time) as f64 / 1000.0).max(0.001)
+}
+
+fn compare_sample(
+    request_id: &str,
+    baseline: &Sample,
+    baseline_secs: f64,
+    current: &Sample,
+    current_secs: f64,
+    thresholds: &RegressionThresholds,
+) -> RequestComparison {
+    let (baseline_latency, current_latency) = (baseline.latency(), current.latency());
+    let latency = |i: usize| MetricDelta::new(baseline_latency[i], current_latency[i]);
+    let error_rate = |s: &Sample| if s.requests == 0 { 0.0 } else { s.errors as f64 / s.requests as f64 * 100.0 };
+
+    let mann_whitney = mann_whitney(&baseline.durations, &current.durations);
+    let slower = mann_whitney.as_ref().is_some_and(|m| m.z > 0.0 && m.p_value < thresholds.alpha);
+    let mut comparison = RequestComparison {
+        request_id: request_id.to_string(),
+        request_name: current.name.clone(),
+        baseline_samples: baseline.durations.len(),
+        current_samples: current.durations.len(),
+        p50: latency(0),
+        p95: latency(1),
+        p99: latency(2),
+        throughput: MetricDelta::new(baseline.requests as f64 / baseline_secs, current.requests as f64 / current_secs),
+        error_rate: MetricDelta::new(error_rate(baseline), error_rate(current)),
+        significant: mann_whitney.as_ref().is_some_and(|m| m.p_value < thresholds.alpha),
+        mann_whitney,
+        regressions: Vec::new(),
+    };
+
+    for (metric, delta) in [("p50", &comparison.p50), ("p95", &comparison.p95), ("p99", &comparison.p99)] {
+        let increased = delta.change_percent.is_some_and(|c| c > thresholds.max_latency_increase);
+        if increased && (slower || !thresholds.require_significance) {
+            comparison.regressions.push(metric.to_string());
+        }
+    }
+    if comparison.throughput.change_percent.is_some_and(|c| -c > thresholds.max_throughput_drop) {
+        comparison.regressions.push("throughput".to_string());
+    }
+    if comparison.error_rate.current - comparison.error_rate.baseline > thresholds.max_error_rate_increase {
+        comparison.regressions.push("errorRate".to_string());
+    }
+    comparison
+}
+
+/// Compare `current` against `baseline`
+pub fn compare_runs(baseline: &PerformanceRun, current: &PerformanceRun, thresholds: &RegressionThresholds) -> RunComparison {
+    let (baseline_samples, current_samples) = (samples(baseline), samples(current));
+    let (baseline_secs, current_secs) = (duration_secs(baseline), duration_secs(current));
+    let compare = |id: &str| {
+        compare_sample(id, &baseline_samples[id], baseline_secs, &current_samples[id], current_secs, thresholds)
+    };
+
+    let overall = compare("");
+    let requests: Vec<RequestComparison> = current_samples
+        .keys()
+        .filter(|id| !id.is_empty() && baseline_samples.contains_key(*id))
+        .map(|id| compare(id))
+        .collect();
+    let only_in = |a: &BTreeMap<String, Sample>, b: &BTreeMap<String, Sample>| -> Vec<String> {
+        a.iter().filter(|(id, _)| !b.contains_key(*id)).map(|(_, s)| s.name.clone()).collect()
+    };
+
+    RunComparison {
+        baseline_run_id: baseline.id.clone(),
+        current_run_id: current.id.clone(),
+        thresholds: thresholds.clone(),
+        passed: overall.regressions.is_empty() && requests.iter().all(|r| r.regressions.is_empty()),
+        overall,
+        requests,
+        added: only_in(&current_samples, &baseline_samples),
+        removed: only_in(&baseline_samples, &current_samples),
+    }
+}
+
+/// Mann-Whitney U test; `None` if either sample is empty
+pub fn mann_whitney(baseline: &[f64], current: &[f64]) -> Option<MannWhitney> {
+    let (n1, n2) = (baseline.len() as f64, current.len() as f64);
+    if baseline.is_empty() || current.is_empty() {
+        return None;
+    }
+
+    // Rank the pooled sample, averaging the ranks of ties
+    let mut pooled: Vec<(f64, bool)> = baseline.iter().map(|v| (*v, false)).chain(current.iter().map(|v| (*v, true))).collect();
+    pooled.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut current_rank_sum = 0.0;
+    let mut tie_term = 0.0;
+    let mut i = 0;
+    while i < pooled.len() {
+        let mut j = i;
+        while j + 1 < pooled.len() && pooled[j + 1].0 == pooled[i].0 {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        let ties = (j - i + 1) as f64;
+        tie_term += ties * ties * ties - ties;
+        current_rank_sum += rank * pooled[i..=j].iter().filter(|(_, is_current)| *is_current).count() as f64;
+        i = j + 1;
+    }
+
+    let n = n1 + n2;
+    let u = current_rank_sum - n2 * (n2 + 1.0) / 2.0;
+    let mean = n1 * n2 / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)).max(1.0));
+    if variance <= 0.0 {
+        // Every value identical
+        return Some(MannWhitney { u, z: 0.0, p_value: 1.0 });
+    }
+    // Continuity correction towards the mean
+    let diff = u - mean;
+    let z = (diff - 0.5 * diff.signum()) / variance.sqrt();
+    let z = if diff.abs() < 0.5 { 0.0 } else { z };
+    let p_value = (2.0 * (1.0 - normal_cdf(z.abs()))).clamp(0.0, 1.0);
+    Some(MannWhitney { u, z, p_value })
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// Abramowitz & Stegun 7.1.26 (|error| < 1.5e-7)
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let poly = t * (0.254_829_592 + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let value = 1.0 - poly * (-x * x).exp();
+    if x < 0.0 {
+        -value
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::performance::metrics::TimeSeries;
+    use crate::performance::types::{PerformanceResult, PerformanceStats};
+
+    fn run(id: &str, requests: &[(&str, Vec<f64>, usize)]) -> PerformanceRun {
+        let mut results = Vec::new();
+        for (request_id, durations, failures) in requests {
+            for (i, duration) in durations.iter().enumerate() {
+                results.push(PerformanceResult {
+                    request_id: request_id.to_string(),
+                    request_name: request_id.to_uppercase(),
+                    interface_name: None,
+                    operation_name: None,
+                    iteration: i as u32,
+                    vu: 1,
+                    duration: *duration,
+                    status: 200,
+                    success: i >= *failures,
+                    sla_breached: false,
+                    error: None,
+                    extracted_values: None,
+                    assertion_failures: 0,
+                    timestamp: 1_000 + i as u64,
+                });
+            }
+        }
+        PerformanceRun {
+            id: id.to_string(),
+            suite_id: "s1".to_string(),
+            suite_name: "Suite".to_string(),
+            start_time: 0,
+            end_time: 10_000,
+            status: "completed".to_string(),
+            summary: PerformanceStats::calculate(&results),
+            results,
+            environment: None,
+            time_series: None,
+            baseline_run_id: None,
+            regressions: vec![],
+        }
+    }
+
+    /// Deterministic spread of `n` values around `center`
+    fn spread(center: f64, n: usize) -> Vec<f64> {
+        (0..n).map(|i| center + ((i * 37) % 21) as f64 - 10.0).collect()
+    }
+
+    #[test]
+    fn test_mann_whitney_matches_reference() {
+        // scipy.stats.mannwhitneyu([1,2,3,4,5], [6,7,8,9,10], method="asymptotic"): U=25, p=0.0122
+        let result = mann_whitney(&[1.0, 2.0, 3.0, 4.0, 5.0], &[6.0, 7.0, 8.0, 9.0, 10.0]).unwrap();
+        assert_eq!(result.u, 25.0);
+        assert!(result.z > 0.0);
+        assert!((result.p_value - 0.0122).abs() < 0.0005, "{}", result.p_value);
+
+        // Same method with tied ranks: U=13, p=0.1720
+        let tied = mann_whitney(&[1.0, 2.0, 2.0, 3.0], &[2.0, 3.0, 3.0, 4.0]).unwrap();
+        assert_eq!(tied.u, 13.0);
+        assert!((tied.p_value - 0.1720).abs() < 0.0005, "{}", tied.p_value);
+
+        assert_eq!(mann_whitney(&[5.0; 4], &[5.0; 3]).unwrap().p_value, 1.0);
+        assert!(mann_whitney(&[], &[1.0]).is_none());
+    }
+
+    #[test]
+    fn test_significant_slowdown_is_a_regression() {
+        let baseline = run("base", &[("login", spread(100.0, 200), 0), ("search", spread(50.0, 200), 0)]);
+        let current = run("cur", &[("login", spread(140.0, 200), 0), ("search", spread(50.0, 200), 0), ("new", spread(10.0, 5), 0)]);
+
+        let comparison = compare_runs(&baseline, &current, &RegressionThresholds::default());
+        assert!(!comparison.passed);
+        assert_eq!(comparison.added, vec!["NEW"]);
+        assert!(comparison.removed.is_empty());
+
+        let login = comparison.requests.iter().find(|r| r.request_id == "login").unwrap();
+        assert!(login.significant);
+        assert_eq!(login.regressions, vec!["p50", "p95", "p99"]);
+        assert!((login.p50.change_percent.unwrap() - 40.0).abs() < 1.0);
+
+        let search = comparison.requests.iter().find(|r| r.request_id == "search").unwrap();
+        assert!(!search.significant);
+        assert!(search.regressions.is_empty());
+        assert_eq!(search.throughput.change_percent, Some(0.0));
+    }
+
+    #[test]
+    fn test_capped_results_take_percentiles_from_the_metrics() {
+        let bucket = |requests: u64, p50: f64| TimeBucket {
+            timestamp: 0,
+            requests,
+            errors: 0,
+            throughput: requests as f64,
+            error_rate: 0.0,
+            avg_response_time: p50,
+            p50,
+            p95: p50 * 2.0,
+            p99: p50 * 3.0,
+        };
+        let baseline = run("base", &[("a", spread(100.0, 50), 0)]);
+        // Only the first (fast) results were stored; the run slowed down later
+        let mut current = run("cur", &[("a", spread(100.0, 50), 0)]);
+        current.summary.total_requests = 200;
+        current.summary.p50 = 300.0;
+        current.summary.p95 = 600.0;
+        current.summary.p99 = 900.0;
+        let buckets = vec![bucket(50, 100.0), bucket(150, 400.0)];
+        current.time_series = Some(TimeSeries {
+            interval_ms: 1000,
+            overall: buckets.clone(),
+            requests: BTreeMap::from([("a".to_string(), buckets)]),
+        });
+
+        let comparison = compare_runs(&baseline, &current, &RegressionThresholds::default());
+        assert_eq!(comparison.overall.p50.current, 300.0);
+        assert_eq!(comparison.overall.p99.current, 900.0);
+        let a = &comparison.requests[0];
+        assert_eq!((a.p50.current, a.p95.current), (325.0, 650.0));
+        // Uncapped runs still use their stored results
+        let mut stored = spread(100.0, 50);
+        stored.sort_by(f64::total_cmp);
+        assert_eq!(a.p50.baseline, percentile(&stored, 50.0));
+    }
+
+    #[test]
+    fn test_thresholds_for_throughput_errors_and_significance() {
+        let baseline = run("base", &[("a", spread(100.0, 100), 0)]);
+        // Fewer requests, 5% errors, a slow tail on only a few samples
+        let mut durations = spread(100.0, 60);
+        durations[..2].copy_from_slice(&[900.0, 900.0]);
+        let current = run("cur", &[("a", durations, 3)]);
+
+        let comparison = compare_runs(&baseline, &current, &RegressionThresholds::default());
+        let a = &comparison.requests[0];
+        assert_eq!(a.throughput.change_percent, Some(-40.0));
+        assert_eq!(a.error_rate.current, 5.0);
+        // p99 jumped but the distributions are not significantly different
+        assert!(!a.significant);
+        assert_eq!(a.regressions, vec!["throughput", "errorRate"]);
+        assert_eq!(comparison.overall.regressions, vec!["throughput", "errorRate"]);
+
+        let lenient = RegressionThresholds {
+            max_throughput_drop: 50.0,
+            max_error_rate_increase: 10.0,
+            require_significance: false,
+            ..RegressionThresholds::default()
+        };
+        let comparison = compare_runs(&baseline, &current, &lenient);
+        assert_eq!(comparison.requests[0].regressions, vec!["p99"]);
+
+        let thresholds: RegressionThresholds = serde_json::from_str(r#"{"maxLatencyIncrease": 5}"#).unwrap();
+        assert_eq!(thresholds.max_latency_increase, 5.0);
+        assert_eq!(thresholds.alpha, 0.05);
+    }
+}
@@ -0,0 +1,228 @@
+// Cron expressions for performance schedules
+//
+// Standard 5-field syntax: minute hour day-of-month month day-of-week.
+// Fields accept `*`, numbers, ranges (`1-5`), lists (`1,15`), steps (`*/15`,
+// `0-30/10`) and month/day names (`JAN`, `MON`); day-of-week 0 and 7 are
+// both Sunday. The macros `@hourly`, `@daily`, `@weekly`, `@monthly` and
+// `@yearly` are also accepted. As in Vixie cron, when both day fields are
+// restricted a time matches if either of them does.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use std::str::FromStr;
+
+/// How far ahead `next_after` searches before giving up (e.g. "0 0 30 2 *")
+const SEARCH_YEARS: i32 = 5;
+
+const MONTHS: [&str; 12] = ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    months: u16,
+    weekdays: u8,
+    /// Day-of-month field was `*` (or `?`)
+    any_day: bool,
+    /// Day-of-week field was `*` (or `?`)
+    any_weekday: bool,
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(expression: &str) -> Result<Self, String> {
+        Self::parse(expression)
+    }
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expanded = match expression.trim().to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            "@weekly" => "0 0 * * 0".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@hourly" => "0 * * * *".to_string(),
+            _ => expression.trim().to_string(),
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "Invalid cron expression '{}': expected 5 fields (minute hour day month weekday), got {}",
+                expression.trim(),
+                fields.len()
+            ));
+        }
+
+        let invalid = |e: String| format!("Invalid cron expression '{}': {}", expression.trim(), e);
+        let weekdays = parse_field(fields[4], "weekday", 0, 7, &WEEKDAYS).map_err(invalid)?;
+        Ok(Self {
+            minutes: parse_field(fields[0], "minute", 0, 59, &[]).map_err(invalid)?,
+            hours: parse_field(fields[1], "hour", 0, 23, &[]).map_err(invalid)? as u32,
+            days: parse_field(fields[2], "day", 1, 31, &[]).map_err(invalid)? as u32,
+            months: parse_field(fields[3], "month", 1, 12, &MONTHS).map_err(invalid)? as u16,
+            // 7 is Sunday too
+            weekdays: ((weekdays | (weekdays >> 7)) & 0x7f) as u8,
+            any_day: is_wildcard(fields[2]),
+            any_weekday: is_wildcard(fields[4]),
+        })
+    }
+
+    /// Whether the minute containing `time` matches
+    pub fn matches(&self, time: &NaiveDateTime) -> bool {
+        self.minutes & (1 << time.minute()) != 0
+            && self.hours & (1 << time.hour()) != 0
+            && self.months & (1 << time.month()) != 0
+            && self.day_matches(&time.date())
+    }
+
+    fn day_matches(&self, date: &NaiveDate) -> bool {
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    /// First matching minute strictly after `after`, in `after`'s time zone.
+    /// Local times skipped by a DST change never match.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let timezone = after.timezone();
+        let start = after.naive_local();
+        let mut time = start.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start.year() + SEARCH_YEARS;
+
+        while time.year() <= limit {
+            if self.months & (1 << time.month()) == 0 {
+                let (year, month) = if time.month() == 12 { (time.year() + 1, 1) } else { (time.year(), time.month() + 1) };
+                time = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.day_matches(&time.date()) {
+                time = time.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if self.hours & (1 << time.hour()) == 0 {
+                time = time.with_minute(0)? + Duration::hours(1);
+            } else if self.minutes & (1 << time.minute()) == 0 {
+                time += Duration::minutes(1);
+            } else if let Some(next) = timezone.from_local_datetime(&time).earliest() {
+                return Some(next);
+            } else {
+                time += Duration::minutes(1);
+            }
+        }
+        None
+    }
+}
+
+fn is_wildcard(field: &str) -> bool {
+    field == "*" || field == "?"
+}
+
+/// Parse one field into a bitmask where bit `n` means value `n` matches
+fn parse_field(field: &str, name: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let value = |text: &str| -> Result<u32, String> {
+        let number = match names.iter().position(|n| n.eq_ignore_ascii_case(text)) {
+            // Month names are 1-based, weekday names 0-based
+            Some(index) => index as u32 + min,
+            None => text.parse::<u32>().map_err(|_| format!("invalid {} '{}'", name, text))?,
+        };
+        if number < min || number > max {
+            return Err(format!("{} {} out of range {}-{}", name, number, min, max));
+        }
+        Ok(number)
+    };
+
+    let mut mask = 0u64;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step = step.parse::<u32>().ok().filter(|s| *s > 0).ok_or_else(|| format!("invalid step '{}' in {}", step, name))?;
+                (range, step)
+            }
+            None => (item, 1),
+        };
+        let (start, end) = if is_wildcard(range) {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            let (start, end) = (value(start)?, value(end)?);
+            if start > end {
+                return Err(format!("invalid {} range '{}'", name, range));
+            }
+            (start, end)
+        } else {
+            let start = value(range)?;
+            // "5/15" means every 15 starting at 5
+            (start, if step > 1 { max } else { start })
+        };
+        for n in (start..=end).step_by(step as usize) {
+            mask |= 1 << n;
+        }
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Utc};
+
+    fn at(text: &str) -> DateTime<Utc> {
+        Utc.from_utc_datetime(&NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap())
+    }
+
+    fn next(expression: &str, after: &str) -> String {
+        CronSchedule::parse(expression)
+            .unwrap()
+            .next_after(&at(after))
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_next_after() {
+        assert_eq!(next("0 3 * * *", "2024-03-10 02:59"), "2024-03-10 03:00");
+        assert_eq!(next("0 3 * * *", "2024-03-10 03:00"), "2024-03-11 03:00");
+        assert_eq!(next("*/15 * * * *", "2024-03-10 10:07"), "2024-03-10 10:15");
+        assert_eq!(next("0-30/10 9-17 * * MON-FRI", "2024-03-08 17:45"), "2024-03-11 09:00");
+        assert_eq!(next("@monthly", "2024-12-15 08:00"), "2025-01-01 00:00");
+        assert_eq!(next("0 0 29 FEB *", "2024-03-01 00:00"), "2028-02-29 00:00");
+        assert_eq!(next("5/20 * * * *", "2024-01-01 00:06"), "2024-01-01 00:25");
+        assert_eq!(next("0 12 * * 7", "2024-03-10 13:00"), "2024-03-17 12:00");
+        assert_eq!(next("0 0 30 2 *", "2024-01-01 00:00"), "");
+    }
+
+    #[test]
+    fn test_day_fields_combine_like_cron() {
+        // Both restricted: the 13th OR any Friday
+        let schedule = CronSchedule::parse("0 0 13 * 5").unwrap();
+        assert_eq!(next("0 0 13 * 5", "2024-09-01 00:00"), "2024-09-06 00:00");
+        assert!(schedule.matches(&at("2024-09-13 00:00").naive_utc()));
+        // Only one restricted: both must match
+        assert_eq!(next("0 0 * 9 5", "2024-09-01 00:00"), "2024-09-06 00:00");
+        assert_eq!(next("0 0 13 * *", "2024-09-01 00:00"), "2024-09-13 00:00");
+    }
+
+    #[test]
+    fn test_time_zone_is_preserved() {
+        let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+        let after = offset.with_ymd_and_hms(2024, 6, 1, 2, 30, 0).unwrap();
+        let next = CronSchedule::parse("0 3 * * *").unwrap().next_after(&after).unwrap();
+        assert_eq!(next.to_rfc3339(), "2024-06-01T03:00:00+02:00");
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        let error = |expression: &str| CronSchedule::parse(expression).unwrap_err();
+        assert_eq!(
+            error("0 3 * *"),
+            "Invalid cron expression '0 3 * *': expected 5 fields (minute hour day month weekday), got 4"
+        );
+        assert_eq!(error("60 * * * *"), "Invalid cron expression '60 * * * *': minute 60 out of range 0-59");
+        assert_eq!(error("* * 0 * *"), "Invalid cron expression '* * 0 * *': day 0 out of range 1-31");
+        assert!(error("*/0 * * * *").contains("invalid step '0' in minute"));
+        assert!(error("* * * FOO *").contains("invalid month 'FOO'"));
+        assert!(error("* 5-1 * * *").contains("invalid hour range '5-1'"));
+        assert!("0 3 * * sun".parse::<CronSchedule>().is_ok());
+    }
+}
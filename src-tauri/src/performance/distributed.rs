@@ -0,0 +1,935 @@
+// Distributed performance runs
+//
+// A coordinator listens on a TCP port; headless workers (`apinox-cli worker`)
+// connect to it. Both sides exchange newline-delimited JSON messages. To run a
+// suite the coordinator splits its load profile across the connected workers
+// (weighted by their `maxConcurrent`), each worker drives its share with
+// `run_load`, and the coordinator merges every worker's results into a single
+// `LoadRun` with globally unique VU numbers.
+//
+// Assignments carry resolved variables, so a coordinator without a shared
+// token only listens on the loopback interface; with one it listens on every
+// interface and turns away workers whose hello does not carry the token.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, watch};
+
+use crate::performance::load_model::{
+    run_load, IterationProgress, LoadHooks, LoadProfile, LoadRun, LoadStage, ResultReservoir,
+};
+use crate::performance::types::{PerformanceResult, PerformanceSuite};
+
+/// How often workers report that they are alive
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long a worker may go without a heartbeat before a run gives up on it
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How often a coordinator run checks its stop hook
+const STOP_POLL: Duration = Duration::from_millis(100);
+
+/// Messages sent by workers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum WorkerMessage {
+    #[serde(rename_all = "camelCase")]
+    Hello {
+        worker_id: Option<String>,
+        max_concurrent: u32,
+        platform: String,
+        version: String,
+        /// Shared secret the coordinator was started with
+        #[serde(default)]
+        token: Option<String>,
+    },
+    Heartbeat,
+    /// A measured request completed
+    #[serde(rename_all = "camelCase")]
+    Result { run_id: String, result: Box<PerformanceResult> },
+    /// A VU finished a scenario iteration
+    #[serde(rename_all = "camelCase")]
+    Progress { run_id: String, vu: u32, iteration: u32 },
+    /// The worker's share of a run is over
+    #[serde(rename_all = "camelCase")]
+    Finished {
+        run_id: String,
+        aborted: bool,
+        dropped_iterations: u64,
+        error: Option<String>,
+    },
+}
+
+/// Messages sent by the coordinator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CoordinatorMessage {
+    #[serde(rename_all = "camelCase")]
+    Welcome { worker_id: String },
+    /// Run `suite` (whose load profile is the worker's share)
+    #[serde(rename_all = "camelCase")]
+    Assign {
+        run_id: String,
+        suite: PerformanceSuite,
+        variables: HashMap<String, String>,
+    },
+    /// Stop a run; an empty id stops every run
+    #[serde(rename_all = "camelCase")]
+    Abort { run_id: String },
+}
+
+/// A worker connected to the coordinator (matches the webview's `DistributedWorker`)
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerInfo {
+    pub id: String,
+    /// "idle" | "working"
+    pub status: String,
+    pub max_concurrent: u32,
+    pub platform: Option<String>,
+    pub version: Option<String>,
+    pub address: String,
+    pub connected_at: u64,
+    pub last_heartbeat: Option<u64>,
+    /// VUs of the current (or last) run assigned to this worker
+    pub assigned_vus: Option<u32>,
+    pub completed_iterations: u64,
+}
+
+/// What a run hears about its workers
+enum RunEvent {
+    Message(WorkerMessage),
+    Disconnected,
+}
+
+struct ConnectedWorker {
+    info: WorkerInfo,
+    outbox: mpsc::UnboundedSender<CoordinatorMessage>,
+}
+
+struct CoordinatorState {
+    port: u16,
+    token: Option<String>,
+    heartbeat_timeout: Duration,
+    workers: Mutex<HashMap<String, ConnectedWorker>>,
+    /// Event channel of each run in progress, keyed by run id
+    runs: Mutex<HashMap<String, mpsc::UnboundedSender<(String, RunEvent)>>>,
+    shutdown: watch::Sender<bool>,
+    next_worker: AtomicU64,
+}
+
+/// Accepts worker connections and runs suites across them. Cheap to clone.
+#[derive(Clone)]
+pub struct Coordinator {
+    state: Arc<CoordinatorState>,
+}
+
+impl Coordinator {
+    /// Listen on `port`; port 0 picks a free port. With a `token` the
+    /// coordinator listens on every interface and only accepts workers
+    /// presenting it, without one it only listens on 127.0.0.1.
+    pub async fn start(port: u16, token: Option<String>) -> Result<Self> {
+        Self::listen(port, token, HEARTBEAT_TIMEOUT).await
+    }
+
+    async fn listen(port: u16, token: Option<String>, heartbeat_timeout: Duration) -> Result<Self> {
+        let token = token.filter(|t| !t.is_empty());
+        let host = if token.is_some() { "0.0.0.0" } else { "127.0.0.1" };
+        let listener = TcpListener::bind((host, port))
+            .await
+            .map_err(|e| anyhow!("Failed to listen on port {}: {}", port, e))?;
+        let port = listener.local_addr()?.port();
+        let (shutdown, _) = watch::channel(false);
+        let coordinator = Coordinator {
+            state: Arc::new(CoordinatorState {
+                port,
+                token,
+                heartbeat_timeout,
+                workers: Mutex::new(HashMap::new()),
+                runs: Mutex::new(HashMap::new()),
+                shutdown,
+                next_worker: AtomicU64::new(1),
+            }),
+        };
+
+        let accepting = coordinator.clone();
+        tokio::spawn(async move { accepting.accept_loop(listener).await });
+        log::info!("[performance] Coordinator listening on {}:{}", host, port);
+        Ok(coordinator)
+    }
+
+    pub fn port(&self) -> u16 {
+        self.state.port
+    }
+
+    /// Connected workers, by id
+    pub fn workers(&self) -> Vec<WorkerInfo> {
+        let mut workers: Vec<WorkerInfo> =
+            self.state.workers.lock().unwrap().values().map(|w| w.info.clone()).collect();
+        workers.sort_by(|a, b| a.id.cmp(&b.id));
+        workers
+    }
+
+    /// Wait until at least `count` workers are connected
+    pub async fn wait_for_workers(&self, count: usize, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let connected = self.state.workers.lock().unwrap().len();
+            if connected >= count {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(anyhow!("Only {} of {} workers connected", connected, count));
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    /// Stop listening and disconnect every worker
+    pub fn stop(&self) {
+        let _ = self.state.shutdown.send(true);
+        self.state.workers.lock().unwrap().clear();
+        log::info!("[performance] Coordinator on port {} stopped", self.state.port);
+    }
+
+    async fn accept_loop(&self, listener: TcpListener) {
+        let mut shutdown = self.state.shutdown.subscribe();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, address)) => {
+                        let coordinator = self.clone();
+                        tokio::spawn(async move { coordinator.serve(stream, address).await });
+                    }
+                    Err(e) => log::warn!("[performance] Coordinator accept failed: {}", e),
+                },
+                _ = shutdown.changed() => break,
+            }
+        }
+    }
+
+    /// Talk to one worker until it disconnects or the coordinator stops
+    async fn serve(&self, stream: TcpStream, address: SocketAddr) {
+        let (reader, writer) = stream.into_split();
+        let (outbox, messages) = mpsc::unbounded_channel();
+        tokio::spawn(write_messages(writer, messages));
+
+        let mut lines = BufReader::new(reader).lines();
+        let mut shutdown = self.state.shutdown.subscribe();
+        let mut worker_id: Option<String> = None;
+
+        loop {
+            let line = tokio::select! {
+                line = lines.next_line() => line,
+                _ = shutdown.changed() => break,
+            };
+            let line = match line {
+                Ok(Some(line)) if line.trim().is_empty() => continue,
+                Ok(Some(line)) => line,
+                _ => break,
+            };
+            let message: WorkerMessage = match serde_json::from_str(&line) {
+                Ok(message) => message,
+                Err(e) => {
+                    log::warn!("[performance] Ignoring malformed worker message from {}: {}", address, e);
+                    continue;
+                }
+            };
+
+            match (&worker_id, message) {
+                (None, WorkerMessage::Hello { token, .. })
+                    if self.state.token.is_some() && token != self.state.token =>
+                {
+                    log::warn!("[performance] Rejected worker {}: invalid token", address);
+                    break;
+                }
+                (None, WorkerMessage::Hello { worker_id: requested, max_concurrent, platform, version, .. }) => {
+                    let id = self.register(requested, max_concurrent, platform, version, address, outbox.clone());
+                    let _ = outbox.send(CoordinatorMessage::Welcome { worker_id: id.clone() });
+                    worker_id = Some(id);
+                }
+                (None, _) => log::warn!("[performance] Worker {} sent a message before hello", address),
+                (Some(id), message) => self.handle(id, message),
+            }
+        }
+
+        if let Some(id) = worker_id {
+            self.state.workers.lock().unwrap().remove(&id);
+            self.broadcast(&id, || RunEvent::Disconnected);
+            log::info!("[performance] Worker '{}' disconnected", id);
+        }
+    }
+
+    fn register(
+        &self,
+        requested: Option<String>,
+        max_concurrent: u32,
+        platform: String,
+        version: String,
+        address: SocketAddr,
+        outbox: mpsc::UnboundedSender<CoordinatorMessage>,
+    ) -> String {
+        let mut workers = self.state.workers.lock().unwrap();
+        let base = requested
+            .filter(|id| !id.trim().is_empty())
+            .unwrap_or_else(|| format!("worker-{}", self.state.next_worker.fetch_add(1, Ordering::SeqCst)));
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while workers.contains_key(&id) {
+            id = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+
+        workers.insert(
+            id.clone(),
+            ConnectedWorker {
+                info: WorkerInfo {
+                    id: id.clone(),
+                    status: "idle".to_string(),
+                    max_concurrent,
+                    platform: Some(platform),
+                    version: Some(version),
+                    address: address.to_string(),
+                    connected_at: now_ms(),
+                    last_heartbeat: Some(now_ms()),
+                    assigned_vus: None,
+                    completed_iterations: 0,
+                },
+                outbox,
+            },
+        );
+        log::info!("[performance] Worker '{}' connected from {}", id, address);
+        id
+    }
+
+    fn handle(&self, worker_id: &str, message: WorkerMessage) {
+        {
+            let mut workers = self.state.workers.lock().unwrap();
+            if let Some(worker) = workers.get_mut(worker_id) {
+                match &message {
+                    WorkerMessage::Heartbeat => worker.info.last_heartbeat = Some(now_ms()),
+                    WorkerMessage::Progress { .. } => worker.info.completed_iterations += 1,
+                    WorkerMessage::Finished { .. } => worker.info.status = "idle".to_string(),
+                    WorkerMessage::Hello { .. } | WorkerMessage::Result { .. } => {}
+                }
+            }
+        }
+
+        let run_id = match &message {
+            WorkerMessage::Result { run_id, .. }
+            | WorkerMessage::Progress { run_id, .. }
+            | WorkerMessage::Finished { run_id, .. } => run_id.clone(),
+            _ => return,
+        };
+        if let Some(run) = self.state.runs.lock().unwrap().get(&run_id) {
+            let _ = run.send((worker_id.to_string(), RunEvent::Message(message)));
+        }
+    }
+
+    fn broadcast(&self, worker_id: &str, event: impl Fn() -> RunEvent) {
+        for run in self.state.runs.lock().unwrap().values() {
+            let _ = run.send((worker_id.to_string(), event()));
+        }
+    }
+
+    /// Run `suite` across the connected workers and merge their results.
+    ///
+    /// Fails only when the run cannot start; workers that disconnect, stop
+    /// sending heartbeats or report an error are listed in `LoadRun::errors`.
+    pub async fn run_suite(
+        &self,
+        suite: &PerformanceSuite,
+        variables: HashMap<String, String>,
+        hooks: LoadHooks,
+    ) -> Result<LoadRun> {
+        let profile = LoadProfile::for_suite(suite);
+        profile.validate().map_err(|e| anyhow!(e))?;
+
+        let run_id = format!("dist-{}", uuid::Uuid::new_v4());
+        let (events, mut inbox) = mpsc::unbounded_channel();
+        self.state.runs.lock().unwrap().insert(run_id.clone(), events);
+
+        // Worker -> first VU number of its share
+        let mut assigned: HashMap<String, u32> = HashMap::new();
+        {
+            let mut workers = self.state.workers.lock().unwrap();
+            let mut ids: Vec<String> = workers.keys().cloned().collect();
+            ids.sort();
+            let weights: Vec<u32> = ids.iter().map(|id| workers[id].info.max_concurrent.max(1)).collect();
+
+            let mut vu_offset = 0;
+            for (id, share) in ids.iter().zip(partition(&profile, &weights)) {
+                let Some(share) = share else { continue };
+                let worker = workers.get_mut(id).unwrap();
+                let vus = peak_vus(&share);
+                let mut part = suite.clone();
+                part.load_profile = Some(share);
+                let assignment = CoordinatorMessage::Assign {
+                    run_id: run_id.clone(),
+                    suite: part,
+                    variables: variables.clone(),
+                };
+                if worker.outbox.send(assignment).is_ok() {
+                    worker.info.status = "working".to_string();
+                    worker.info.assigned_vus = Some(vus);
+                    worker.info.completed_iterations = 0;
+                    assigned.insert(id.clone(), vu_offset);
+                    vu_offset += vus;
+                }
+            }
+        }
+        if assigned.is_empty() {
+            self.state.runs.lock().unwrap().remove(&run_id);
+            return Err(anyhow!("No workers connected to the coordinator"));
+        }
+        log::info!("[performance] Run {} assigned to {} workers", run_id, assigned.len());
+
+        let mut pending = assigned.clone();
+        let mut run = LoadRun { results: Vec::new(), aborted: false, dropped_iterations: 0, errors: Vec::new() };
+        let mut results = ResultReservoir::default();
+        let mut completed = 0;
+        let mut abort_sent = false;
+        let mut stop_poll = tokio::time::interval(STOP_POLL);
+
+        while !pending.is_empty() {
+            let event = tokio::select! {
+                event = inbox.recv() => event,
+                _ = stop_poll.tick() => {
+                    if !abort_sent && (hooks.should_stop)() {
+                        abort_sent = true;
+                        self.send_to(pending.keys(), CoordinatorMessage::Abort { run_id: run_id.clone() });
+                    }
+                    for worker_id in self.drop_silent_workers(pending.keys()) {
+                        run.errors.push(format!("Worker '{}' stopped sending heartbeats", worker_id));
+                        pending.remove(&worker_id);
+                    }
+                    continue;
+                }
+            };
+            let Some((worker_id, event)) = event else { break };
+            let Some(&vu_offset) = pending.get(&worker_id) else { continue };
+
+            match event {
+                RunEvent::Message(WorkerMessage::Result { mut result, .. }) => {
+                    result.vu += vu_offset;
+                    (hooks.on_result)(&result);
+                    results.push(*result);
+                }
+                RunEvent::Message(WorkerMessage::Progress { vu, iteration, .. }) => {
+                    completed += 1;
+                    (hooks.on_iteration)(&IterationProgress { vu: vu + vu_offset, iteration, completed });
+                }
+                RunEvent::Message(WorkerMessage::Finished { aborted, dropped_iterations, error, .. }) => {
+                    run.aborted |= aborted;
+                    run.dropped_iterations += dropped_iterations;
+                    if let Some(error) = error {
+                        run.errors.push(format!("Worker '{}': {}", worker_id, error));
+                    }
+                    pending.remove(&worker_id);
+                }
+                RunEvent::Disconnected => {
+                    run.errors.push(format!("Worker '{}' disconnected before finishing", worker_id));
+                    pending.remove(&worker_id);
+                }
+                RunEvent::Message(_) => {}
+            }
+        }
+
+        self.state.runs.lock().unwrap().remove(&run_id);
+        run.aborted |= abort_sent;
+        run.results = results.into_results();
+        Ok(run)
+    }
+
+    /// Disconnect the workers among `ids` whose last heartbeat is older than
+    /// `HEARTBEAT_TIMEOUT`, returning their ids
+    fn drop_silent_workers<'a>(&self, ids: impl Iterator<Item = &'a String>) -> Vec<String> {
+        let cutoff = now_ms().saturating_sub(self.state.heartbeat_timeout.as_millis() as u64);
+        let mut workers = self.state.workers.lock().unwrap();
+        let silent: Vec<String> = ids
+            .filter(|id| workers.get(*id).is_some_and(|w| w.info.last_heartbeat.unwrap_or(w.info.connected_at) < cutoff))
+            .cloned()
+            .collect();
+        for id in &silent {
+            if let Some(worker) = workers.remove(id) {
+                let _ = worker.outbox.send(CoordinatorMessage::Abort { run_id: String::new() });
+            }
+            log::warn!("[performance] Dropped worker '{}': no heartbeat for {:?}", id, self.state.heartbeat_timeout);
+        }
+        silent
+    }
+
+    fn send_to<'a>(&self, ids: impl Iterator<Item = &'a String>, message: CoordinatorMessage) {
+        let workers = self.state.workers.lock().unwrap();
+        for id in ids {
+            if let Some(worker) = workers.get(id) {
+                let _ = worker.outbox.send(message.clone());
+            }
+        }
+    }
+}
+
+/// Split a profile across workers in proportion to `weights`. Workers whose
+/// share would have no VUs get `None`.
+pub fn partition(profile: &LoadProfile, weights: &[u32]) -> Vec<Option<LoadProfile>> {
+    match profile {
+        LoadProfile::ConstantVus { vus, iterations, duration_ms } => split(*vus, weights)
+            .into_iter()
+            .map(|vus| {
+                (vus > 0).then_some(LoadProfile::ConstantVus { vus, iterations: *iterations, duration_ms: *duration_ms })
+            })
+            .collect(),
+        LoadProfile::Stages { stages } => {
+            let targets: Vec<Vec<u32>> = stages.iter().map(|s| split(s.target, weights)).collect();
+            (0..weights.len())
+                .map(|i| {
+                    let share: Vec<LoadStage> = stages
+                        .iter()
+                        .zip(&targets)
+                        .map(|(stage, targets)| LoadStage { duration_ms: stage.duration_ms, target: targets[i] })
+                        .collect();
+                    share.iter().any(|s| s.target > 0).then_some(LoadProfile::Stages { stages: share })
+                })
+                .collect()
+        }
+        LoadProfile::ArrivalRate { rate, duration_ms, max_vus } => {
+            let total: f64 = weights.iter().map(|w| *w as f64).sum();
+            split(*max_vus, weights)
+                .into_iter()
+                .zip(weights)
+                .map(|(max_vus, weight)| {
+                    let rate = rate * *weight as f64 / total;
+                    (rate > 0.0).then(|| LoadProfile::ArrivalRate { rate, duration_ms: *duration_ms, max_vus: max_vus.max(1) })
+                })
+                .collect()
+        }
+    }
+}
+
+/// Largest-remainder split of `total` in proportion to `weights`
+fn split(total: u32, weights: &[u32]) -> Vec<u32> {
+    let sum: u64 = weights.iter().map(|w| *w as u64).sum();
+    if sum == 0 {
+        return vec![0; weights.len()];
+    }
+    let exact: Vec<f64> = weights.iter().map(|w| total as f64 * *w as f64 / sum as f64).collect();
+    let mut shares: Vec<u32> = exact.iter().map(|e| e.floor() as u32).collect();
+    let mut order: Vec<usize> = (0..weights.len()).collect();
+    order.sort_by(|a, b| (exact[*b] - exact[*b].floor()).total_cmp(&(exact[*a] - exact[*a].floor())));
+    let remaining = total - shares.iter().sum::<u32>();
+    for i in order.into_iter().take(remaining as usize) {
+        shares[i] += 1;
+    }
+    shares
+}
+
+/// Most VUs a profile can have active at once
+fn peak_vus(profile: &LoadProfile) -> u32 {
+    match profile {
+        LoadProfile::ConstantVus { vus, .. } => *vus,
+        LoadProfile::Stages { stages } => stages.iter().map(|s| s.target).max().unwrap_or(0),
+        LoadProfile::ArrivalRate { max_vus, .. } => *max_vus,
+    }
+}
+
+/// Options for `run_worker`
+#[derive(Debug, Clone)]
+pub struct WorkerOptions {
+    /// Coordinator address, e.g. "10.0.0.5:8787"
+    pub coordinator: String,
+    /// Requested worker id; the coordinator assigns one when omitted
+    pub id: Option<String>,
+    /// Relative capacity, used to weight this worker's share of each run
+    pub max_concurrent: u32,
+    /// How long to keep retrying the initial connection
+    pub connect_timeout: Duration,
+    /// Shared secret the coordinator was started with
+    pub token: Option<String>,
+}
+
+/// Serve runs for a coordinator until it disconnects
+pub async fn run_worker(options: WorkerOptions) -> Result<()> {
+    let stream = connect(&options.coordinator, options.connect_timeout).await?;
+    let (reader, writer) = stream.into_split();
+    let (outbox, messages) = mpsc::unbounded_channel();
+    tokio::spawn(write_messages(writer, messages));
+
+    let _ = outbox.send(WorkerMessage::Hello {
+        worker_id: options.id.clone(),
+        max_concurrent: options.max_concurrent,
+        platform: std::env::consts::OS.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        token: options.token.clone(),
+    });
+    let heartbeat = outbox.clone();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(HEARTBEAT_INTERVAL);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            if heartbeat.send(WorkerMessage::Heartbeat).is_err() {
+                break;
+            }
+        }
+    });
+
+    let aborts: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>> = Arc::new(Mutex::new(HashMap::new()));
+    let mut welcomed = false;
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message: CoordinatorMessage = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                log::warn!("[performance] Ignoring malformed coordinator message: {}", e);
+                continue;
+            }
+        };
+
+        match message {
+            CoordinatorMessage::Welcome { worker_id } => {
+                welcomed = true;
+                log::info!("[performance] Connected to {} as '{}'", options.coordinator, worker_id);
+            }
+            CoordinatorMessage::Assign { run_id, suite, variables } => {
+                let stop = Arc::new(AtomicBool::new(false));
+                aborts.lock().unwrap().insert(run_id.clone(), stop.clone());
+                let (outbox, aborts) = (outbox.clone(), aborts.clone());
+                tokio::spawn(async move {
+                    let finished = run_share(&run_id, &suite, variables, stop, outbox.clone()).await;
+                    aborts.lock().unwrap().remove(&run_id);
+                    let _ = outbox.send(finished);
+                });
+            }
+            CoordinatorMessage::Abort { run_id } => {
+                for (id, stop) in aborts.lock().unwrap().iter() {
+                    if run_id.is_empty() || *id == run_id {
+                        stop.store(true, Ordering::SeqCst);
+                    }
+                }
+            }
+        }
+    }
+
+    // Coordinator gone: stop whatever is still running
+    for stop in aborts.lock().unwrap().values() {
+        stop.store(true, Ordering::SeqCst);
+    }
+    if !welcomed {
+        return Err(anyhow!("Coordinator {} closed the connection; check the worker token", options.coordinator));
+    }
+    Ok(())
+}
+
+/// Run this worker's share of a run, streaming its results and finished iterations
+async fn run_share(
+    run_id: &str,
+    suite: &PerformanceSuite,
+    variables: HashMap<String, String>,
+    stop: Arc<AtomicBool>,
+    outbox: mpsc::UnboundedSender<WorkerMessage>,
+) -> WorkerMessage {
+    let profile = LoadProfile::for_suite(suite);
+    if let Err(error) = profile.validate() {
+        return WorkerMessage::Finished {
+            run_id: run_id.to_string(),
+            aborted: false,
+            dropped_iterations: 0,
+            error: Some(error),
+        };
+    }
+
+    let (progress_run_id, progress) = (run_id.to_string(), outbox.clone());
+    let result_run_id = run_id.to_string();
+    let hooks = LoadHooks {
+        should_stop: Arc::new(move || stop.load(Ordering::SeqCst)),
+        on_iteration: Arc::new(move |iteration| {
+            let _ = progress.send(WorkerMessage::Progress {
+                run_id: progress_run_id.clone(),
+                vu: iteration.vu,
+                iteration: iteration.iteration,
+            });
+        }),
+        // Results stream as they complete so the coordinator sees every one,
+        // not just those the worker keeps
+        on_result: Arc::new(move |result| {
+            let _ = outbox.send(WorkerMessage::Result { run_id: result_run_id.clone(), result: Box::new(result.clone()) });
+        }),
+    };
+    let run = run_load(suite, &profile, variables, hooks).await;
+    WorkerMessage::Finished {
+        run_id: run_id.to_string(),
+        aborted: run.aborted,
+        dropped_iterations: run.dropped_iterations,
+        error: None,
+    }
+}
+
+async fn connect(address: &str, timeout: Duration) -> Result<TcpStream> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match TcpStream::connect(address).await {
+            Ok(stream) => return Ok(stream),
+            Err(e) if Instant::now() >= deadline => {
+                return Err(anyhow!("Failed to connect to coordinator {}: {}", address, e))
+            }
+            Err(_) => tokio::time::sleep(Duration::from_millis(500)).await,
+        }
+    }
+}
+
+/// Write each message as one JSON line until the channel closes
+async fn write_messages<T: Serialize>(mut writer: OwnedWriteHalf, mut messages: mpsc::UnboundedReceiver<T>) {
+    while let Some(message) = messages.recv().await {
+        let Ok(mut line) = serde_json::to_vec(&message) else { continue };
+        line.push(b'\n');
+        if writer.write_all(&line).await.is_err() {
+            break;
+        }
+    }
+    let _ = writer.shutdown().await;
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+
+    /// Server answering every request with 200 after a short pause
+    async fn ok_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut data = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    while !String::from_utf8_lossy(&data).contains("\r\n\r\n") {
+                        match stream.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => data.extend_from_slice(&buf[..n]),
+                        }
+                    }
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    let _ = stream
+                        .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                        .await;
+                });
+            }
+        });
+        base
+    }
+
+    fn suite(base: &str, profile: LoadProfile) -> PerformanceSuite {
+        let mut suite: PerformanceSuite = serde_json::from_value(serde_json::json!({
+            "id": "s1",
+            "name": "Ping",
+            "requests": [
+                { "id": "r1", "name": "Ping", "method": "GET", "order": 1, "endpoint": format!("{}/ping/{{{{_vu}}}}", base) },
+                { "id": "r2", "name": "Pong", "method": "GET", "order": 2, "endpoint": format!("{}/pong", base) }
+            ]
+        }))
+        .unwrap();
+        suite.load_profile = Some(profile);
+        suite
+    }
+
+    fn spawn_worker(port: u16, id: &str, max_concurrent: u32) -> tokio::task::JoinHandle<Result<()>> {
+        spawn_worker_with_token(port, id, max_concurrent, None)
+    }
+
+    fn spawn_worker_with_token(
+        port: u16,
+        id: &str,
+        max_concurrent: u32,
+        token: Option<&str>,
+    ) -> tokio::task::JoinHandle<Result<()>> {
+        tokio::spawn(run_worker(WorkerOptions {
+            coordinator: format!("127.0.0.1:{}", port),
+            id: Some(id.to_string()),
+            max_concurrent,
+            connect_timeout: Duration::from_secs(5),
+            token: token.map(str::to_string),
+        }))
+    }
+
+    #[test]
+    fn test_partition_by_weight() {
+        assert_eq!(split(10, &[1, 1, 1]), vec![4, 3, 3]);
+        assert_eq!(split(10, &[2, 1, 1]), vec![5, 3, 2]);
+        assert_eq!(split(1, &[1, 1]), vec![1, 0]);
+
+        let shares = partition(&LoadProfile::ConstantVus { vus: 3, iterations: Some(5), duration_ms: None }, &[1, 1, 1, 1]);
+        assert_eq!(shares.iter().filter(|s| s.is_some()).count(), 3);
+        assert_eq!(shares[0], Some(LoadProfile::ConstantVus { vus: 1, iterations: Some(5), duration_ms: None }));
+        assert_eq!(shares[3], None);
+
+        let stages = LoadProfile::Stages {
+            stages: vec![LoadStage { duration_ms: 1000, target: 4 }, LoadStage { duration_ms: 1000, target: 0 }],
+        };
+        assert_eq!(
+            partition(&stages, &[1, 1])[1],
+            Some(LoadProfile::Stages {
+                stages: vec![LoadStage { duration_ms: 1000, target: 2 }, LoadStage { duration_ms: 1000, target: 0 }],
+            })
+        );
+
+        let rate = partition(&LoadProfile::ArrivalRate { rate: 30.0, duration_ms: 1000, max_vus: 10 }, &[2, 1]);
+        assert_eq!(rate[0], Some(LoadProfile::ArrivalRate { rate: 20.0, duration_ms: 1000, max_vus: 7 }));
+        assert_eq!(rate[1], Some(LoadProfile::ArrivalRate { rate: 10.0, duration_ms: 1000, max_vus: 3 }));
+    }
+
+    #[tokio::test]
+    async fn test_workers_share_a_run_and_results_are_merged() {
+        let base = ok_server().await;
+        let coordinator = Coordinator::start(0, None).await.unwrap();
+        let port = coordinator.port();
+        let workers = vec![spawn_worker(port, "w1", 1), spawn_worker(port, "w2", 2), spawn_worker(port, "w3", 1)];
+        coordinator.wait_for_workers(3, Duration::from_secs(5)).await.unwrap();
+
+        let connected = coordinator.workers();
+        let ids: Vec<&str> = connected.iter().map(|w| w.id.as_str()).collect();
+        assert_eq!(ids, vec!["w1", "w2", "w3"]);
+        assert!(connected.iter().all(|w| w.status == "idle" && w.platform.is_some()));
+
+        let progress = Arc::new(AtomicU64::new(0));
+        let seen = progress.clone();
+        let hooks = LoadHooks {
+            on_iteration: Arc::new(move |_| {
+                seen.fetch_add(1, Ordering::SeqCst);
+            }),
+            ..LoadHooks::default()
+        };
+        let profile = LoadProfile::ConstantVus { vus: 8, iterations: Some(3), duration_ms: None };
+        let run = coordinator.run_suite(&suite(&base, profile), HashMap::new(), hooks).await.unwrap();
+
+        assert!(run.errors.is_empty(), "{:?}", run.errors);
+        assert!(!run.aborted);
+        assert_eq!(run.results.len(), 8 * 3 * 2);
+        assert_eq!(progress.load(Ordering::SeqCst), 8 * 3);
+        let mut vus: Vec<u32> = run.results.iter().map(|r| r.vu).collect();
+        vus.sort();
+        vus.dedup();
+        assert_eq!(vus, (1..=8).collect::<Vec<_>>());
+        assert!(run.results.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+
+        // The worker with twice the capacity ran twice the VUs
+        let assigned: HashMap<String, Option<u32>> =
+            coordinator.workers().into_iter().map(|w| (w.id, w.assigned_vus)).collect();
+        assert_eq!(assigned["w2"], Some(4));
+        assert_eq!(assigned["w1"], Some(2));
+        assert_eq!(assigned["w3"], Some(2));
+
+        coordinator.stop();
+        for worker in workers {
+            tokio::time::timeout(Duration::from_secs(5), worker).await.unwrap().unwrap().unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_abort_and_missing_workers() {
+        let base = ok_server().await;
+        let coordinator = Coordinator::start(0, None).await.unwrap();
+        let profile = LoadProfile::ConstantVus { vus: 2, iterations: None, duration_ms: Some(60_000) };
+        let suite = suite(&base, profile);
+
+        let error = coordinator.run_suite(&suite, HashMap::new(), LoadHooks::default()).await.unwrap_err();
+        assert_eq!(error.to_string(), "No workers connected to the coordinator");
+
+        let _workers = [spawn_worker(coordinator.port(), "a", 1), spawn_worker(coordinator.port(), "b", 1)];
+        coordinator.wait_for_workers(2, Duration::from_secs(5)).await.unwrap();
+
+        let started = Instant::now();
+        let hooks = LoadHooks {
+            should_stop: Arc::new(move || started.elapsed() > Duration::from_millis(300)),
+            ..LoadHooks::default()
+        };
+        let run = coordinator.run_suite(&suite, HashMap::new(), hooks).await.unwrap();
+
+        assert!(run.aborted);
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(!run.results.is_empty());
+        assert!(run.errors.is_empty());
+        coordinator.stop();
+    }
+
+    #[tokio::test]
+    async fn test_workers_without_the_token_are_rejected() {
+        let coordinator = Coordinator::start(0, Some("s3cret".to_string())).await.unwrap();
+        let port = coordinator.port();
+
+        for token in [None, Some("wrong")] {
+            let worker = spawn_worker_with_token(port, "intruder", 1, token);
+            let error = tokio::time::timeout(Duration::from_secs(5), worker).await.unwrap().unwrap().unwrap_err();
+            assert!(error.to_string().contains("check the worker token"), "{}", error);
+        }
+        assert!(coordinator.workers().is_empty());
+
+        let _worker = spawn_worker_with_token(port, "trusted", 1, Some("s3cret"));
+        coordinator.wait_for_workers(1, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(coordinator.workers()[0].id, "trusted");
+        coordinator.stop();
+    }
+
+    #[tokio::test]
+    async fn test_silent_workers_are_dropped_from_the_run() {
+        let coordinator = Coordinator::listen(0, None, Duration::from_millis(300)).await.unwrap();
+
+        // Says hello, then never heartbeats or finishes
+        let mut stream = TcpStream::connect(("127.0.0.1", coordinator.port())).await.unwrap();
+        stream
+            .write_all(b"{\"type\":\"hello\",\"workerId\":\"stuck\",\"maxConcurrent\":1,\"platform\":\"linux\",\"version\":\"0\"}\n")
+            .await
+            .unwrap();
+        coordinator.wait_for_workers(1, Duration::from_secs(5)).await.unwrap();
+
+        let profile = LoadProfile::ConstantVus { vus: 1, iterations: Some(1), duration_ms: None };
+        let run = tokio::time::timeout(
+            Duration::from_secs(5),
+            coordinator.run_suite(&suite("http://127.0.0.1:1", profile), HashMap::new(), LoadHooks::default()),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(run.errors, vec!["Worker 'stuck' stopped sending heartbeats".to_string()]);
+        assert!(coordinator.workers().is_empty());
+        coordinator.stop();
+    }
+
+    #[test]
+    fn test_messages_are_tagged_json() {
+        let hello = serde_json::to_value(WorkerMessage::Hello {
+            worker_id: None,
+            max_concurrent: 4,
+            platform: "linux".to_string(),
+            version: "1.0.0".to_string(),
+            token: None,
+        })
+        .unwrap();
+        assert_eq!(hello["type"], "hello");
+        assert_eq!(hello["maxConcurrent"], 4);
+
+        let abort: CoordinatorMessage = serde_json::from_str(r#"{"type":"abort","runId":""}"#).unwrap();
+        assert!(matches!(abort, CoordinatorMessage::Abort { run_id } if run_id.is_empty()));
+        assert_eq!(serde_json::to_string(&WorkerMessage::Heartbeat).unwrap(), r#"{"type":"heartbeat"}"#);
+    }
+}
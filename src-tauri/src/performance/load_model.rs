@@ -0,0 +1,629 @@
+// Load model for performance suites
+//
+// Virtual users (VUs) each run the suite's full ordered scenario with their own
+// variable context, seeded from the run's environment plus `_vu` and
+// `_iteration`. A `LoadProfile` decides how many VUs are active over time:
+// a constant pool, a ramp through stages, or a constant arrival rate.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+use crate::performance::runner::execute_request;
+use crate::performance::types::{PerformanceRequest, PerformanceResult, PerformanceSuite};
+
+/// How often the stage controller adjusts the number of VUs
+const STAGE_TICK: Duration = Duration::from_millis(100);
+
+/// Highest arrival rate (iterations per second) a profile may ask for
+const MAX_ARRIVAL_RATE: f64 = 10_000.0;
+
+/// Raw results kept per run; longer runs keep a uniform sample of this size,
+/// while every result still reaches `LoadHooks::on_result`
+pub const MAX_STORED_RESULTS: usize = 10_000;
+
+/// How virtual users are driven during a run
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LoadProfile {
+    /// A fixed pool of VUs, each repeating the scenario `iterations` times
+    /// and/or until `durationMs` has elapsed (once when neither is set)
+    #[serde(rename_all = "camelCase")]
+    ConstantVus {
+        vus: u32,
+        #[serde(default)]
+        iterations: Option<u32>,
+        #[serde(default)]
+        duration_ms: Option<u64>,
+    },
+    /// VU count moves linearly from the previous target (0 at the start) to
+    /// each stage's target over the stage's duration
+    #[serde(rename_all = "camelCase")]
+    Stages { stages: Vec<LoadStage> },
+    /// Start `rate` scenario iterations per second regardless of response
+    /// times; iterations that would exceed `maxVus` in flight are dropped
+    #[serde(rename_all = "camelCase")]
+    ArrivalRate {
+        rate: f64,
+        duration_ms: u64,
+        #[serde(default = "default_max_vus")]
+        max_vus: u32,
+    },
+}
+
+fn default_max_vus() -> u32 {
+    100
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadStage {
+    pub duration_ms: u64,
+    pub target: u32,
+}
+
+impl LoadProfile {
+    /// The suite's profile; suites without one run `concurrency` VUs for
+    /// `iterations` each
+    pub fn for_suite(suite: &PerformanceSuite) -> Self {
+        suite.load_profile.clone().unwrap_or(LoadProfile::ConstantVus {
+            vus: suite.concurrency.max(1),
+            iterations: Some(suite.iterations),
+            duration_ms: None,
+        })
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            LoadProfile::ConstantVus { vus: 0, .. } => Err("Load profile needs at least 1 VU".to_string()),
+            LoadProfile::ConstantVus { duration_ms: Some(0), .. } => {
+                Err("Load profile duration must be positive".to_string())
+            }
+            LoadProfile::Stages { stages } if stages.is_empty() => {
+                Err("Load profile needs at least one stage".to_string())
+            }
+            LoadProfile::Stages { stages } => match stages.iter().position(|s| s.duration_ms == 0) {
+                Some(i) => Err(format!("Stage {} has no duration", i + 1)),
+                None => Ok(()),
+            },
+            LoadProfile::ArrivalRate { rate, .. } if !(rate.is_finite() && *rate > 0.0) => {
+                Err("Arrival rate must be positive".to_string())
+            }
+            LoadProfile::ArrivalRate { rate, .. } if *rate > MAX_ARRIVAL_RATE => {
+                Err(format!("Arrival rate must be at most {} per second", MAX_ARRIVAL_RATE))
+            }
+            LoadProfile::ArrivalRate { duration_ms: 0, .. } => {
+                Err("Load profile duration must be positive".to_string())
+            }
+            LoadProfile::ArrivalRate { max_vus: 0, .. } => Err("Load profile needs at least 1 VU".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Target VU count `elapsed_ms` into a staged run, or `None` once every
+/// stage has finished
+pub fn stage_target(stages: &[LoadStage], elapsed_ms: u64) -> Option<u32> {
+    let mut from = 0u32;
+    let mut stage_start = 0u64;
+    for stage in stages {
+        let stage_end = stage_start + stage.duration_ms;
+        if elapsed_ms < stage_end {
+            let progress = (elapsed_ms - stage_start) as f64 / stage.duration_ms as f64;
+            let target = from as f64 + (stage.target as f64 - from as f64) * progress;
+            return Some(target.round() as u32);
+        }
+        from = stage.target;
+        stage_start = stage_end;
+    }
+    None
+}
+
+/// Progress of a VU, reported after each scenario iteration
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IterationProgress {
+    pub vu: u32,
+    pub iteration: u32,
+    /// Scenario iterations completed so far by all VUs, warmups included
+    pub completed: u64,
+}
+
+/// Callbacks into a running load test
+#[derive(Clone)]
+pub struct LoadHooks {
+    /// Polled between requests; the run winds down once it returns `true`
+    pub should_stop: Arc<dyn Fn() -> bool + Send + Sync>,
+    pub on_iteration: Arc<dyn Fn(&IterationProgress) + Send + Sync>,
+    /// Called with every measured result, including those left out of the
+    /// stored sample
+    pub on_result: Arc<dyn Fn(&PerformanceResult) + Send + Sync>,
+}
+
+impl Default for LoadHooks {
+    fn default() -> Self {
+        Self {
+            should_stop: Arc::new(|| false),
+            on_iteration: Arc::new(|_| {}),
+            on_result: Arc::new(|_| {}),
+        }
+    }
+}
+
+/// Outcome of `run_load`
+#[derive(Debug, Clone)]
+pub struct LoadRun {
+    /// Results of measured (non-warmup) iterations by timestamp; past
+    /// `MAX_STORED_RESULTS`, a uniform sample of them
+    pub results: Vec<PerformanceResult>,
+    /// `should_stop` ended the run early
+    pub aborted: bool,
+    /// Arrival-rate iterations skipped because `maxVus` were busy
+    pub dropped_iterations: u64,
+    /// Problems that cut part of the run short (e.g. a distributed worker
+    /// disconnecting)
+    pub errors: Vec<String>,
+}
+
+/// Uniform random sample of a run's results (reservoir sampling), so a run
+/// with more than `capacity` results keeps a sample of the whole run rather
+/// than of its first moments
+pub struct ResultReservoir {
+    capacity: usize,
+    results: Vec<PerformanceResult>,
+    seen: u64,
+    rng: StdRng,
+}
+
+impl Default for ResultReservoir {
+    fn default() -> Self {
+        Self::new(MAX_STORED_RESULTS)
+    }
+}
+
+impl ResultReservoir {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, results: Vec::new(), seen: 0, rng: StdRng::from_entropy() }
+    }
+
+    pub fn push(&mut self, result: PerformanceResult) {
+        self.seen += 1;
+        if self.results.len() < self.capacity {
+            self.results.push(result);
+        } else {
+            let slot = self.rng.gen_range(0..self.seen) as usize;
+            if slot < self.capacity {
+                self.results[slot] = result;
+            }
+        }
+    }
+
+    /// The sampled results, by timestamp
+    pub fn into_results(mut self) -> Vec<PerformanceResult> {
+        self.results.sort_by_key(|r| r.timestamp);
+        self.results
+    }
+}
+
+/// State shared by every VU of a run
+struct Scenario {
+    requests: Vec<PerformanceRequest>,
+    think_time: Duration,
+    /// Leading iterations of each VU whose results are discarded
+    warmup: u32,
+    variables: HashMap<String, String>,
+    hooks: LoadHooks,
+    results: Mutex<ResultReservoir>,
+    completed: AtomicU64,
+}
+
+impl Scenario {
+    fn stopped(&self) -> bool {
+        (self.hooks.should_stop)()
+    }
+
+    /// Run the ordered requests once. Returns `false` if stopped part way.
+    async fn run_iteration(&self, vu: u32, iteration: u32, variables: &mut HashMap<String, String>) -> bool {
+        variables.insert("_vu".to_string(), vu.to_string());
+        variables.insert("_iteration".to_string(), iteration.to_string());
+
+        for (i, request) in self.requests.iter().enumerate() {
+            if self.stopped() {
+                return false;
+            }
+            if i > 0 && !self.think_time.is_zero() {
+                tokio::time::sleep(self.think_time).await;
+            }
+
+            let mut result = execute_request(request, iteration, variables).await;
+            result.vu = vu;
+            if let Some(extracted) = &result.extracted_values {
+                variables.extend(extracted.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+            if iteration >= self.warmup {
+                (self.hooks.on_result)(&result);
+                self.results.lock().unwrap().push(result);
+            }
+        }
+
+        let completed = self.completed.fetch_add(1, Ordering::SeqCst) + 1;
+        (self.hooks.on_iteration)(&IterationProgress { vu, iteration, completed });
+        true
+    }
+
+    /// Repeat the scenario until `iterations` (warmups included) are done,
+    /// the deadline passes, the VU is retired or the run is stopped
+    async fn run_vu(&self, vu: u32, iterations: Option<u32>, deadline: Option<Instant>, retired: &AtomicBool) {
+        let mut variables = self.variables.clone();
+        let mut iteration = 0;
+        while iterations.map_or(true, |n| iteration < n)
+            && deadline.map_or(true, |d| Instant::now() < d)
+            && !retired.load(Ordering::SeqCst)
+            && self.run_iteration(vu, iteration, &mut variables).await
+        {
+            iteration += 1;
+        }
+    }
+}
+
+/// Run a suite's scenario under `profile`. `variables` seed every VU's context.
+pub async fn run_load(
+    suite: &PerformanceSuite,
+    profile: &LoadProfile,
+    variables: HashMap<String, String>,
+    hooks: LoadHooks,
+) -> LoadRun {
+    let mut requests = suite.requests.clone();
+    requests.sort_by_key(|r| r.order);
+    let scenario = Arc::new(Scenario {
+        requests,
+        think_time: Duration::from_millis(suite.delay_between_requests),
+        warmup: suite.warmup_runs,
+        variables,
+        hooks,
+        results: Mutex::new(ResultReservoir::default()),
+        completed: AtomicU64::new(0),
+    });
+
+    let dropped_iterations = match profile {
+        LoadProfile::ConstantVus { vus, iterations, duration_ms } => {
+            let iterations = match (iterations, duration_ms) {
+                (None, None) => Some(1),
+                (iterations, _) => *iterations,
+            };
+            let iterations = iterations.map(|n| n + suite.warmup_runs);
+            let deadline = duration_ms.map(|ms| Instant::now() + Duration::from_millis(ms));
+            let mut pool = JoinSet::new();
+            for vu in 1..=*vus {
+                let scenario = scenario.clone();
+                pool.spawn(async move {
+                    scenario.run_vu(vu, iterations, deadline, &AtomicBool::new(false)).await
+                });
+            }
+            while pool.join_next().await.is_some() {}
+            0
+        }
+        LoadProfile::Stages { stages } => {
+            run_stages(&scenario, stages).await;
+            0
+        }
+        LoadProfile::ArrivalRate { rate, duration_ms, max_vus } => {
+            run_arrivals(&scenario, *rate, Duration::from_millis(*duration_ms), *max_vus).await
+        }
+    };
+
+    let aborted = scenario.stopped();
+    let results = std::mem::take(&mut *scenario.results.lock().unwrap()).into_results();
+    LoadRun { results, aborted, dropped_iterations, errors: Vec::new() }
+}
+
+/// Start and retire VUs to follow the stage targets. Retired VUs finish
+/// their current iteration; the newest VUs are retired first.
+async fn run_stages(scenario: &Arc<Scenario>, stages: &[LoadStage]) {
+    let start = Instant::now();
+    let end = start + Duration::from_millis(stages.iter().map(|s| s.duration_ms).sum());
+    let mut active: Vec<Arc<AtomicBool>> = Vec::new();
+    let mut pool = JoinSet::new();
+    let mut next_vu = 1;
+
+    while !scenario.stopped() {
+        let Some(target) = stage_target(stages, start.elapsed().as_millis() as u64) else {
+            break;
+        };
+        while active.len() < target as usize {
+            let retired = Arc::new(AtomicBool::new(false));
+            active.push(retired.clone());
+            let scenario = scenario.clone();
+            let vu = next_vu;
+            next_vu += 1;
+            pool.spawn(async move { scenario.run_vu(vu, None, Some(end), &retired).await });
+        }
+        while active.len() > target as usize {
+            if let Some(retired) = active.pop() {
+                retired.store(true, Ordering::SeqCst);
+            }
+        }
+        tokio::time::sleep(STAGE_TICK.min(end.saturating_duration_since(Instant::now()))).await;
+    }
+
+    while pool.join_next().await.is_some() {}
+}
+
+/// Start one iteration per `1 / rate` seconds, each with a fresh context.
+/// Returns the number of iterations dropped because `max_vus` were busy.
+async fn run_arrivals(scenario: &Arc<Scenario>, rate: f64, duration: Duration, max_vus: u32) -> u64 {
+    let start = Instant::now();
+    // Free VU numbers: an iteration takes one and hands it back when done, so
+    // iterations in flight never share a VU
+    let free_vus = Arc::new(Mutex::new((1..=max_vus).rev().collect::<Vec<u32>>()));
+    // `interval` panics on a zero period
+    let period = Duration::from_secs_f64(1.0 / rate).max(Duration::from_nanos(1));
+    let mut ticker = tokio::time::interval(period);
+    let mut pool = JoinSet::new();
+    let mut dropped = 0;
+    let mut iteration = 0;
+
+    loop {
+        ticker.tick().await;
+        if start.elapsed() >= duration || scenario.stopped() {
+            break;
+        }
+        let vu = free_vus.lock().unwrap().pop();
+        match vu {
+            Some(vu) => {
+                let (scenario, free_vus) = (scenario.clone(), free_vus.clone());
+                pool.spawn(async move {
+                    let mut variables = scenario.variables.clone();
+                    scenario.run_iteration(vu, iteration, &mut variables).await;
+                    free_vus.lock().unwrap().push(vu);
+                });
+            }
+            None => dropped += 1,
+        }
+        iteration += 1;
+    }
+
+    while pool.join_next().await.is_some() {}
+    if dropped > 0 {
+        log::warn!("[performance] Dropped {} iterations: all {} VUs were busy", dropped, max_vus);
+    }
+    dropped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Server that answers `/login/<vu>` with a token and `/use/...` with 200
+    /// after `delay_ms`, recording paths and the peak number of requests in flight
+    async fn slow_server(delay_ms: u64) -> (String, Arc<Mutex<Vec<String>>>, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let paths = Arc::new(Mutex::new(Vec::new()));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (seen, peak_out) = (paths.clone(), peak.clone());
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let (paths, in_flight, peak) = (paths.clone(), in_flight.clone(), peak.clone());
+                tokio::spawn(async move {
+                    let mut data = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    while !String::from_utf8_lossy(&data).contains("\r\n\r\n") {
+                        let n = stream.read(&mut buf).await.unwrap();
+                        if n == 0 {
+                            return;
+                        }
+                        data.extend_from_slice(&buf[..n]);
+                    }
+                    let text = String::from_utf8_lossy(&data).to_string();
+                    let path = text.split_whitespace().nth(1).unwrap_or("").to_string();
+
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                    let payload = match path.strip_prefix("/login/") {
+                        Some(vu) => format!(r#"{{"token":"tok-{}"}}"#, vu),
+                        None => "{}".to_string(),
+                    };
+                    paths.lock().unwrap().push(path);
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        payload.len(),
+                        payload
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        (base, seen, peak_out)
+    }
+
+    fn suite(base: &str) -> PerformanceSuite {
+        serde_json::from_value(serde_json::json!({
+            "id": "s1",
+            "name": "Login flow",
+            "requests": [
+                { "id": "r2", "name": "Use", "method": "GET", "order": 2, "endpoint": format!("{}/use/{{{{_vu}}}}/{{{{token}}}}", base) },
+                {
+                    "id": "r1", "name": "Login", "method": "GET", "order": 1,
+                    "endpoint": format!("{}/login/{{{{_vu}}}}", base),
+                    "extractors": [{ "variable": "token", "type": "JSONPath", "path": "$.token" }]
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_constant_vus_sustain_concurrency_with_own_context() {
+        let (base, paths, peak) = slow_server(40).await;
+        let mut suite = suite(&base);
+        suite.warmup_runs = 1;
+        let profile = LoadProfile::ConstantVus { vus: 4, iterations: Some(2), duration_ms: None };
+
+        let run = run_load(&suite, &profile, HashMap::new(), LoadHooks::default()).await;
+
+        // 4 VUs x (1 warmup + 2 measured) iterations x 2 requests
+        assert_eq!(paths.lock().unwrap().len(), 24);
+        assert_eq!(run.results.len(), 16);
+        assert!(!run.aborted);
+        assert_eq!(peak.load(Ordering::SeqCst), 4);
+        assert!(run.results.iter().all(|r| r.success && r.iteration >= 1 && (1..=4).contains(&r.vu)));
+
+        // Each VU uses the token it extracted itself
+        for path in paths.lock().unwrap().iter().filter(|p| p.starts_with("/use/")) {
+            let parts: Vec<&str> = path.split('/').collect();
+            assert_eq!(parts[3], format!("tok-{}", parts[2]), "{}", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stop_hook_and_progress() {
+        let (base, _paths, _peak) = slow_server(5).await;
+        let suite = suite(&base);
+        let completed = Arc::new(AtomicU64::new(0));
+        let seen = completed.clone();
+        let streamed = Arc::new(AtomicU64::new(0));
+        let hooks = LoadHooks {
+            should_stop: Arc::new(move || seen.load(Ordering::SeqCst) >= 3),
+            on_iteration: {
+                let completed = completed.clone();
+                Arc::new(move |p| completed.store(p.completed, Ordering::SeqCst))
+            },
+            on_result: {
+                let streamed = streamed.clone();
+                Arc::new(move |_| {
+                    streamed.fetch_add(1, Ordering::SeqCst);
+                })
+            },
+        };
+        let profile = LoadProfile::ConstantVus { vus: 1, iterations: None, duration_ms: Some(60_000) };
+
+        let started = Instant::now();
+        let run = run_load(&suite, &profile, HashMap::new(), hooks).await;
+
+        assert!(run.aborted);
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(completed.load(Ordering::SeqCst), 3);
+        assert_eq!(run.results.len(), 6);
+        assert_eq!(streamed.load(Ordering::SeqCst), 6);
+    }
+
+    #[test]
+    fn test_reservoir_samples_the_whole_run() {
+        let mut reservoir = ResultReservoir::new(100);
+        for timestamp in 0..10_000u64 {
+            reservoir.push(PerformanceResult {
+                request_id: "r1".to_string(),
+                request_name: "Login".to_string(),
+                interface_name: None,
+                operation_name: None,
+                iteration: 0,
+                vu: 1,
+                duration: 1.0,
+                status: 200,
+                success: true,
+                sla_breached: false,
+                error: None,
+                extracted_values: None,
+                assertion_failures: 0,
+                timestamp,
+            });
+        }
+        let results = reservoir.into_results();
+        assert_eq!(results.len(), 100);
+        assert!(results.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+        // Not just the first 100: the sample reaches across the run
+        let late = results.iter().filter(|r| r.timestamp >= 5_000).count();
+        assert!((25..=75).contains(&late), "{} of 100 from the second half", late);
+    }
+
+    #[tokio::test]
+    async fn test_arrival_rate_and_stages() {
+        let (base, _paths, peak) = slow_server(20).await;
+        let suite = suite(&base);
+
+        let arrivals = LoadProfile::ArrivalRate { rate: 40.0, duration_ms: 500, max_vus: 50 };
+        let run = run_load(&suite, &arrivals, HashMap::new(), LoadHooks::default()).await;
+        let iterations = run.results.len() / 2;
+        assert!((15..=21).contains(&iterations), "{} iterations", iterations);
+        assert_eq!(run.dropped_iterations, 0);
+
+        let throttled = LoadProfile::ArrivalRate { rate: 100.0, duration_ms: 300, max_vus: 1 };
+        let run = run_load(&suite, &throttled, HashMap::new(), LoadHooks::default()).await;
+        assert!(run.dropped_iterations > 0);
+
+        // A VU runs one iteration at a time, even when iterations are dropped
+        let crowded = LoadProfile::ArrivalRate { rate: 60.0, duration_ms: 500, max_vus: 3 };
+        let run = run_load(&suite, &crowded, HashMap::new(), LoadHooks::default()).await;
+        let mut spans: HashMap<(u32, u32), (f64, f64)> = HashMap::new();
+        for r in &run.results {
+            let span = spans.entry((r.vu, r.iteration)).or_insert((f64::MAX, 0.0));
+            span.0 = span.0.min(r.timestamp as f64);
+            span.1 = span.1.max(r.timestamp as f64 + r.duration);
+        }
+        let mut spans: Vec<(u32, f64, f64)> = spans.into_iter().map(|((vu, _), (from, to))| (vu, from, to)).collect();
+        spans.sort_by(|a, b| (a.0, a.1).partial_cmp(&(b.0, b.1)).unwrap());
+        assert!(spans.iter().all(|s| (1..=3).contains(&s.0)));
+        for pair in spans.windows(2).filter(|p| p[0].0 == p[1].0) {
+            assert!(pair[1].1 + 2.0 >= pair[0].2, "VU {} overlaps: {:?}", pair[0].0, pair);
+        }
+
+        peak.store(0, Ordering::SeqCst);
+        let ramp = LoadProfile::Stages {
+            stages: vec![LoadStage { duration_ms: 300, target: 3 }, LoadStage { duration_ms: 300, target: 0 }],
+        };
+        let run = run_load(&suite, &ramp, HashMap::new(), LoadHooks::default()).await;
+        assert!(!run.results.is_empty());
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        assert!(run.results.iter().all(|r| (1..=3).contains(&r.vu)));
+    }
+
+    #[test]
+    fn test_stage_targets_and_validation() {
+        let stages = vec![
+            LoadStage { duration_ms: 1000, target: 10 },
+            LoadStage { duration_ms: 2000, target: 10 },
+            LoadStage { duration_ms: 1000, target: 0 },
+        ];
+        assert_eq!(stage_target(&stages, 0), Some(0));
+        assert_eq!(stage_target(&stages, 500), Some(5));
+        assert_eq!(stage_target(&stages, 1500), Some(10));
+        assert_eq!(stage_target(&stages, 3750), Some(3));
+        assert_eq!(stage_target(&stages, 4000), None);
+
+        assert!(LoadProfile::Stages { stages }.validate().is_ok());
+        assert!(LoadProfile::Stages { stages: vec![] }.validate().is_err());
+        assert_eq!(
+            LoadProfile::ConstantVus { vus: 0, iterations: None, duration_ms: None }.validate().unwrap_err(),
+            "Load profile needs at least 1 VU"
+        );
+        assert!(LoadProfile::ArrivalRate { rate: 0.0, duration_ms: 1000, max_vus: 1 }.validate().is_err());
+        assert!(LoadProfile::ArrivalRate { rate: 1e12, duration_ms: 1000, max_vus: 1 }.validate().is_err());
+
+        let profile: LoadProfile =
+            serde_json::from_value(serde_json::json!({ "type": "arrivalRate", "rate": 5, "durationMs": 1000 })).unwrap();
+        assert_eq!(profile, LoadProfile::ArrivalRate { rate: 5.0, duration_ms: 1000, max_vus: 100 });
+
+        let legacy: PerformanceSuite =
+            serde_json::from_value(serde_json::json!({ "id": "s", "name": "S", "iterations": 3, "concurrency": 2 })).unwrap();
+        assert_eq!(
+            LoadProfile::for_suite(&legacy),
+            LoadProfile::ConstantVus { vus: 2, iterations: Some(3), duration_ms: None }
+        );
+    }
+}
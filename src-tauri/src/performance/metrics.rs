@@ -0,0 +1,405 @@
+// Streaming metrics for performance runs
+//
+// Response times are recorded into HDR-style histograms (log-linear buckets
+// with ~0.1% relative precision) instead of sorting every result, so a run's
+// summary and per-second time series cost memory proportional to the range
+// of response times rather than the number of requests.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::performance::types::{PerformanceResult, PerformanceStats};
+
+/// Values below this are recorded exactly; above it every power of two is
+/// split into `SUB_BUCKETS / 2` linear steps
+const SUB_BUCKETS: u64 = 2048;
+const SUB_BUCKET_BITS: u32 = 11;
+
+/// Width of a time-series bucket
+pub const DEFAULT_INTERVAL_MS: u64 = 1000;
+
+/// HDR-style histogram of response times, recorded in microseconds
+#[derive(Debug, Clone, Default)]
+pub struct Histogram {
+    counts: BTreeMap<u32, u64>,
+    total: u64,
+    sum_ms: f64,
+    min_ms: f64,
+    max_ms: f64,
+}
+
+impl Histogram {
+    pub fn record(&mut self, duration_ms: f64) {
+        let duration_ms = duration_ms.max(0.0);
+        let micros = (duration_ms * 1000.0).round() as u64;
+        *self.counts.entry(bucket_index(micros)).or_insert(0) += 1;
+        if self.total == 0 || duration_ms < self.min_ms {
+            self.min_ms = duration_ms;
+        }
+        if duration_ms > self.max_ms {
+            self.max_ms = duration_ms;
+        }
+        self.total += 1;
+        self.sum_ms += duration_ms;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.total
+    }
+
+    pub fn min(&self) -> f64 {
+        self.min_ms
+    }
+
+    pub fn max(&self) -> f64 {
+        self.max_ms
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.sum_ms / self.total as f64
+        }
+    }
+
+    /// Response time (ms) at percentile `p` (0-100), within the histogram's
+    /// precision and clamped to the exact min/max
+    pub fn percentile(&self, p: f64) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        let rank = ((p.clamp(0.0, 100.0) / 100.0) * self.total as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (index, count) in &self.counts {
+            seen += count;
+            if seen >= rank {
+                let (low, high) = bucket_range(*index);
+                let micros = (low + high) as f64 / 2.0;
+                return (micros / 1000.0).clamp(self.min_ms, self.max_ms);
+            }
+        }
+        self.max_ms
+    }
+}
+
+/// Bucket holding `micros`: exact below `SUB_BUCKETS`, then `SUB_BUCKETS / 2`
+/// buckets per power of two
+fn bucket_index(micros: u64) -> u32 {
+    if micros < SUB_BUCKETS {
+        return micros as u32;
+    }
+    let shift = (63 - micros.leading_zeros()) - (SUB_BUCKET_BITS - 1);
+    let half = SUB_BUCKETS / 2;
+    (SUB_BUCKETS + (shift as u64 - 1) * half + ((micros >> shift) - half)) as u32
+}
+
+/// Lowest and highest microsecond values of a bucket
+fn bucket_range(index: u32) -> (u64, u64) {
+    let index = index as u64;
+    if index < SUB_BUCKETS {
+        return (index, index);
+    }
+    let half = SUB_BUCKETS / 2;
+    let shift = (index - SUB_BUCKETS) / half + 1;
+    let low = ((index - SUB_BUCKETS) % half + half) << shift;
+    (low, low + (1 << shift) - 1)
+}
+
+/// Metrics of one time bucket
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeBucket {
+    /// Start of the bucket (epoch ms)
+    pub timestamp: u64,
+    pub requests: u64,
+    pub errors: u64,
+    /// Requests per second
+    pub throughput: f64,
+    pub error_rate: f64,
+    pub avg_response_time: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+}
+
+/// Per-bucket metrics for a run, overall and per request id
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeSeries {
+    pub interval_ms: u64,
+    pub overall: Vec<TimeBucket>,
+    pub requests: BTreeMap<String, Vec<TimeBucket>>,
+}
+
+impl TimeSeries {
+    pub fn is_empty(&self) -> bool {
+        self.overall.is_empty()
+    }
+
+    fn append(&mut self, other: TimeSeries) {
+        self.overall.extend(other.overall);
+        for (request_id, buckets) in other.requests {
+            self.requests.entry(request_id).or_default().extend(buckets);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct BucketAccumulator {
+    histogram: Histogram,
+    errors: u64,
+}
+
+impl BucketAccumulator {
+    fn record(&mut self, result: &PerformanceResult) {
+        self.histogram.record(result.duration);
+        if !result.success {
+            self.errors += 1;
+        }
+    }
+
+    fn finish(&self, timestamp: u64, interval_ms: u64) -> TimeBucket {
+        let requests = self.histogram.count();
+        TimeBucket {
+            timestamp,
+            requests,
+            errors: self.errors,
+            throughput: requests as f64 * 1000.0 / interval_ms as f64,
+            error_rate: if requests == 0 { 0.0 } else { self.errors as f64 / requests as f64 },
+            avg_response_time: self.histogram.mean(),
+            p50: self.histogram.percentile(50.0),
+            p95: self.histogram.percentile(95.0),
+            p99: self.histogram.percentile(99.0),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct OpenBucket {
+    overall: BucketAccumulator,
+    requests: HashMap<String, BucketAccumulator>,
+}
+
+/// Aggregates results as they complete into a run summary and a time series.
+///
+/// Results are bucketed by completion time. Buckets are summarised when
+/// closed; a result arriving for an already closed bucket still counts in
+/// the summary but not in the time series.
+#[derive(Debug)]
+pub struct MetricsRecorder {
+    interval_ms: u64,
+    overall: Histogram,
+    success_count: u64,
+    sla_breach_count: u64,
+    assertion_failure_count: u64,
+    first_timestamp: Option<u64>,
+    last_timestamp: u64,
+    open: BTreeMap<u64, OpenBucket>,
+    /// Start of the first bucket still open
+    closed_until: u64,
+    series: TimeSeries,
+    late_results: u64,
+}
+
+impl Default for MetricsRecorder {
+    fn default() -> Self {
+        Self::new(DEFAULT_INTERVAL_MS)
+    }
+}
+
+impl MetricsRecorder {
+    pub fn new(interval_ms: u64) -> Self {
+        let interval_ms = interval_ms.max(1);
+        Self {
+            interval_ms,
+            overall: Histogram::default(),
+            success_count: 0,
+            sla_breach_count: 0,
+            assertion_failure_count: 0,
+            first_timestamp: None,
+            last_timestamp: 0,
+            open: BTreeMap::new(),
+            closed_until: 0,
+            series: TimeSeries { interval_ms, ..TimeSeries::default() },
+            late_results: 0,
+        }
+    }
+
+    pub fn record(&mut self, result: &PerformanceResult) {
+        self.overall.record(result.duration);
+        if result.success {
+            self.success_count += 1;
+        }
+        if result.sla_breached {
+            self.sla_breach_count += 1;
+        }
+        if result.assertion_failures > 0 {
+            self.assertion_failure_count += 1;
+        }
+        self.first_timestamp = Some(self.first_timestamp.map_or(result.timestamp, |t| t.min(result.timestamp)));
+        self.last_timestamp = self.last_timestamp.max(result.timestamp);
+
+        let completed = result.timestamp + result.duration.max(0.0).round() as u64;
+        let bucket = completed - completed % self.interval_ms;
+        if bucket < self.closed_until {
+            self.late_results += 1;
+            return;
+        }
+        let open = self.open.entry(bucket).or_default();
+        open.overall.record(result);
+        open.requests.entry(result.request_id.clone()).or_default().record(result);
+    }
+
+    /// Close every bucket that ends by `until_ms` and return their metrics;
+    /// closed buckets are also kept for `time_series`
+    pub fn close_before(&mut self, until_ms: u64) -> TimeSeries {
+        let until_ms = until_ms - until_ms % self.interval_ms;
+        let still_open = self.open.split_off(&until_ms);
+        let closing = std::mem::replace(&mut self.open, still_open);
+        self.closed_until = self.closed_until.max(until_ms);
+
+        let mut closed = TimeSeries { interval_ms: self.interval_ms, ..TimeSeries::default() };
+        for (timestamp, bucket) in closing {
+            closed.overall.push(bucket.overall.finish(timestamp, self.interval_ms));
+            for (request_id, accumulator) in bucket.requests {
+                closed
+                    .requests
+                    .entry(request_id)
+                    .or_default()
+                    .push(accumulator.finish(timestamp, self.interval_ms));
+            }
+        }
+        self.series.append(closed.clone());
+        closed
+    }
+
+    /// Close the remaining buckets, returning them like `close_before`
+    pub fn close_all(&mut self) -> TimeSeries {
+        match self.open.keys().next_back() {
+            Some(last) => self.close_before(last + self.interval_ms),
+            None => TimeSeries { interval_ms: self.interval_ms, ..TimeSeries::default() },
+        }
+    }
+
+    /// Every bucket closed so far
+    pub fn time_series(&self) -> &TimeSeries {
+        &self.series
+    }
+
+    /// Results that arrived after their bucket was closed
+    pub fn late_results(&self) -> u64 {
+        self.late_results
+    }
+
+    pub fn summary(&self) -> PerformanceStats {
+        let total = self.overall.count() as usize;
+        let success_count = self.success_count as usize;
+        PerformanceStats {
+            total_requests: total,
+            success_count,
+            failure_count: total - success_count,
+            success_rate: if total == 0 { 0.0 } else { success_count as f64 / total as f64 },
+            avg_response_time: self.overall.mean(),
+            min_response_time: self.overall.min(),
+            max_response_time: self.overall.max(),
+            p50: self.overall.percentile(50.0),
+            p95: self.overall.percentile(95.0),
+            p99: self.overall.percentile(99.0),
+            sla_breach_count: self.sla_breach_count as usize,
+            assertion_failure_count: self.assertion_failure_count as usize,
+            total_duration: self
+                .first_timestamp
+                .map_or(0, |first| self.last_timestamp - first) as f64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(request_id: &str, timestamp: u64, duration: f64, success: bool) -> PerformanceResult {
+        PerformanceResult {
+            request_id: request_id.to_string(),
+            request_name: request_id.to_uppercase(),
+            interface_name: None,
+            operation_name: None,
+            iteration: 0,
+            vu: 1,
+            duration,
+            status: if success { 200 } else { 500 },
+            success,
+            sla_breached: duration > 100.0,
+            error: None,
+            extracted_values: None,
+            assertion_failures: 0,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_bucket_ranges_cover_values() {
+        for micros in [0, 1, 2047, 2048, 2049, 4095, 4096, 123_456, 3_600_000_000] {
+            let (low, high) = bucket_range(bucket_index(micros));
+            assert!(low <= micros && micros <= high, "{} not in {}..={}", micros, low, high);
+            assert!((high - low) as f64 <= micros as f64 / 1000.0 + 1.0);
+        }
+        assert_eq!(bucket_index(2048), 2048);
+        assert_eq!(bucket_index(4096), 2048 + 1024);
+    }
+
+    #[test]
+    fn test_histogram_percentiles_within_precision() {
+        let mut histogram = Histogram::default();
+        for ms in 1..=10_000 {
+            histogram.record(ms as f64 / 10.0);
+        }
+        assert_eq!(histogram.count(), 10_000);
+        assert_eq!(histogram.min(), 0.1);
+        assert_eq!(histogram.max(), 1000.0);
+        assert!((histogram.mean() - 500.05).abs() < 1e-9);
+        for (p, expected) in [(50.0, 500.0), (95.0, 950.0), (99.0, 990.0), (100.0, 1000.0)] {
+            let actual = histogram.percentile(p);
+            assert!((actual - expected).abs() / expected < 0.001, "p{} = {}", p, actual);
+        }
+        assert_eq!(Histogram::default().percentile(99.0), 0.0);
+    }
+
+    #[test]
+    fn test_time_series_buckets_per_second_and_request() {
+        let mut recorder = MetricsRecorder::default();
+        recorder.record(&result("a", 10_000, 50.0, true));
+        recorder.record(&result("b", 10_100, 150.0, false));
+        recorder.record(&result("a", 10_990, 20.0, true)); // completes in the next second
+        recorder.record(&result("a", 12_500, 30.0, true));
+
+        let first = recorder.close_before(11_000);
+        assert_eq!(first.overall.len(), 1);
+        assert_eq!(first.overall[0].timestamp, 10_000);
+        assert_eq!(first.overall[0].requests, 2);
+        assert_eq!(first.overall[0].errors, 1);
+        assert_eq!(first.overall[0].error_rate, 0.5);
+        assert_eq!(first.overall[0].throughput, 2.0);
+        assert_eq!(first.requests["b"][0].p99, 150.0);
+
+        // Too late for the closed bucket: counted in the summary only
+        recorder.record(&result("b", 10_200, 10.0, true));
+        assert_eq!(recorder.late_results(), 1);
+
+        let rest = recorder.close_all();
+        let timestamps: Vec<u64> = rest.overall.iter().map(|b| b.timestamp).collect();
+        assert_eq!(timestamps, vec![11_000, 12_000]);
+        assert_eq!(recorder.time_series().overall.len(), 3);
+        assert_eq!(recorder.time_series().requests["a"].len(), 3);
+        assert!(recorder.close_all().is_empty());
+
+        let summary = recorder.summary();
+        assert_eq!(summary.total_requests, 5);
+        assert_eq!(summary.failure_count, 1);
+        assert_eq!(summary.sla_breach_count, 1);
+        assert_eq!(summary.min_response_time, 10.0);
+        assert_eq!(summary.max_response_time, 150.0);
+        assert_eq!(summary.total_duration, 2500.0);
+    }
+}
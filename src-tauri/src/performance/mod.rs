@@ -1,8 +1,16 @@
 pub mod commands;
+pub mod comparison;
+pub mod cron;
+pub mod distributed;
+pub mod load_model;
+pub mod metrics;
 pub mod runner;
+pub mod scheduler;
 pub mod types;
 
+pub use load_model::{LoadProfile, LoadStage};
 pub use commands::{
-    abort_performance_suite, get_coordinator_status, get_performance_run_updates,
+    abort_performance_suite, compare_performance_runs, get_coordinator_status, get_performance_run_updates,
+    get_performance_schedules, preview_cron_schedule, run_performance_schedule_now,
     run_performance_suite, start_coordinator, stop_coordinator,
 };
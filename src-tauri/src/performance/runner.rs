@@ -2,8 +2,12 @@ use std::collections::HashMap;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use crate::http::client::{HttpClient, HttpRequest};
-use crate::performance::types::{PerformanceRequest, PerformanceResult};
-use crate::utils::{substitute_variables, CONTENT_TYPE_XML};
+use crate::performance::types::{PerformanceRequest, PerformanceResult, RequestExtractor};
+use crate::testing::frontend_runner::run_assertions;
+use crate::testing::frontend_types::FrontendAssertion;
+use crate::testing::script_engine::ScriptResponse;
+use crate::testing::variable_extractor::{ExtractorType, ResponseSnapshot, VariableExtractor};
+use crate::utils::{substitute_variables, XPathOptions, CONTENT_TYPE_XML};
 
 /// Execute a single performance request and return a `PerformanceResult`.
 pub async fn execute_request(
@@ -24,7 +28,8 @@ pub async fn execute_request(
     let mut success = false;
     let mut error: Option<String> = None;
     let mut response_body = String::new();
-    let mut extracted_values: HashMap<String, String> = HashMap::new();
+    let mut response_headers: HashMap<String, String> = HashMap::new();
+    let mut header_list: Vec<(String, String)> = Vec::new();
 
     match HttpClient::new() {
         Ok(client) => {
@@ -60,6 +65,8 @@ pub async fn execute_request(
                 }
             }
             response_body = resp.body;
+            response_headers = resp.headers;
+            header_list = resp.header_list;
         }
         Err(e) => {
             error = Some(format!("Failed to create HTTP client: {}", e));
@@ -68,11 +75,27 @@ pub async fn execute_request(
 
     let duration = start.elapsed().as_secs_f64() * 1000.0;
 
-    for extractor in &req.extractors {
-        if extractor.extractor_type.to_lowercase() == "xpath" {
-            if let Some(value) = extract_xpath(&response_body, &extractor.path) {
-                extracted_values.insert(extractor.variable.clone(), value);
-            }
+    let snapshot = ResponseSnapshot::new(&response_body)
+        .with_status(status)
+        // Every value of repeated headers such as Set-Cookie stays extractable
+        .with_headers(header_list.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+        .with_elapsed_ms(duration as u64);
+    let extracted_values = extract_values(&req.extractors, &snapshot);
+
+    // A request that never got a response has nothing to assert on
+    let mut assertion_failures = 0;
+    if status != 0 && !req.assertions.is_empty() {
+        let response = ScriptResponse {
+            body: response_body,
+            status: Some(status),
+            headers: response_headers,
+            duration_ms: duration as u64,
+        };
+        let (failures, message) = check_assertions(&req.assertions, &response, variables).await;
+        assertion_failures = failures;
+        if let Some(message) = message {
+            success = false;
+            error.get_or_insert(message);
         }
     }
 
@@ -84,6 +107,7 @@ pub async fn execute_request(
         interface_name: None,
         operation_name: None,
         iteration,
+        vu: 0,
         duration,
         status,
         success,
@@ -94,21 +118,180 @@ pub async fn execute_request(
         } else {
             Some(extracted_values)
         },
+        assertion_failures,
         timestamp,
     }
 }
 
-/// Extract a value from XML using the last element name in an XPath-like path.
-fn extract_xpath(xml: &str, path: &str) -> Option<String> {
-    let element = path.split('/').rfind(|s| !s.is_empty())?;
-    let element = element.split(':').next_back().unwrap_or(element);
+/// Run the request's extractors, falling back to each one's default value
+fn extract_values(extractors: &[RequestExtractor], response: &ResponseSnapshot) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    for extractor in extractors {
+        let value = ExtractorType::from_config(&extractor.extractor_type, extractor.source.as_deref(), &extractor.path)
+            .and_then(|e| VariableExtractor::extract_from(&e, response));
+        let value = match value {
+            Ok(value) => Some(value),
+            Err(e) => {
+                log::debug!("[Performance] Extractor '{}' found no value: {}", extractor.variable, e);
+                None
+            }
+        };
+        if let Some(value) = value.or_else(|| extractor.default_value.clone()) {
+            values.insert(extractor.variable.clone(), value);
+        }
+    }
+    values
+}
+
+/// Run the request's assertions; returns the failure count and, when any
+/// failed, a message listing them. Script assertions run on the blocking
+/// pool, so a slow script never stalls the virtual users sharing a worker.
+async fn check_assertions(
+    assertions: &[FrontendAssertion],
+    response: &ScriptResponse,
+    variables: &HashMap<String, String>,
+) -> (u32, Option<String>) {
+    let failed: Vec<String> = run_assertions(assertions, response, variables, &XPathOptions::default())
+        .await
+        .into_iter()
+        .filter(|r| r.status == "FAIL")
+        .map(|r| format!("{}: {}", r.name, r.message.as_deref().unwrap_or("failed")))
+        .collect();
+    if failed.is_empty() {
+        (0, None)
+    } else {
+        (failed.len() as u32, Some(format!("Assertion failed: {}", failed.join("; "))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::frontend_types::AssertionConfiguration;
+
+    fn extractor(variable: &str, kind: &str, source: Option<&str>, path: &str) -> RequestExtractor {
+        RequestExtractor {
+            variable: variable.to_string(),
+            extractor_type: kind.to_string(),
+            source: source.map(str::to_string),
+            path: path.to_string(),
+            default_value: None,
+        }
+    }
+
+    #[test]
+    fn test_extract_values_uses_full_extractor_set() {
+        let body = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"><soap:Body>
+            <ns:Order xmlns:ns="urn:shop"><ns:Id>7</ns:Id><ns:Lines><ns:Id>99</ns:Id></ns:Lines></ns:Order>
+        </soap:Body></soap:Envelope>"#;
+        let snapshot = ResponseSnapshot::new(body)
+            .with_status(201)
+            .with_headers([("Location", "/orders/7")]);
+        let mut missing = extractor("missing", "JSONPath", None, "$.id");
+        missing.default_value = Some("none".to_string());
+
+        let values = extract_values(
+            &[
+                extractor("orderId", "XPath", None, "//*[local-name()='Order']/*[local-name()='Id']"),
+                extractor("lineId", "Regex", None, r"<ns:Lines><ns:Id>(\d+)"),
+                extractor("location", "XPath", Some("header"), "Location"),
+                extractor("status", "StatusCode", None, ""),
+                missing,
+                extractor("skipped", "JSONPath", None, "$.id"),
+            ],
+            &snapshot,
+        );
+        assert_eq!(values["orderId"], "7");
+        assert_eq!(values["lineId"], "99");
+        assert_eq!(values["location"], "/orders/7");
+        assert_eq!(values["status"], "201");
+        assert_eq!(values["missing"], "none");
+        assert!(!values.contains_key("skipped"));
+    }
+
+    #[tokio::test]
+    async fn test_check_assertions_reports_failures() {
+        let assertion = |kind: &str, configuration: AssertionConfiguration| FrontendAssertion {
+            assertion_type: kind.to_string(),
+            name: None,
+            configuration: Some(configuration),
+        };
+        let response = ScriptResponse {
+            body: r#"{"status":"ok"}"#.to_string(),
+            status: Some(200),
+            headers: HashMap::new(),
+            duration_ms: 120,
+        };
+        let contains = assertion("Simple Contains", AssertionConfiguration { token: Some("ok".into()), ..Default::default() });
+        let sla = assertion("Response SLA", AssertionConfiguration { sla: Some("100".into()), ..Default::default() });
+        let status = assertion("HTTP Status", AssertionConfiguration { expected_status: Some("201".into()), ..Default::default() });
 
-    let open_tag = format!("<{}", element);
-    let close_tag = format!("</{}>", element);
+        assert_eq!(check_assertions(std::slice::from_ref(&contains), &response, &HashMap::new()).await, (0, None));
+        let (failures, message) = check_assertions(&[contains, sla, status], &response, &HashMap::new()).await;
+        assert_eq!(failures, 2);
+        assert_eq!(
+            message.unwrap(),
+            "Assertion failed: Response SLA: Response time 120ms exceeded SLA of 100ms; HTTP Status: Status 200 not in expected [201]"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extractors_see_every_set_cookie_header() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/login", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf).await.unwrap();
+            let response = "HTTP/1.1 200 OK\r\nSet-Cookie: session=abc; Path=/\r\nSet-Cookie: csrf=xyz\r\n\
+                            Content-Length: 0\r\nConnection: close\r\n\r\n";
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
 
-    let start = xml.find(&open_tag)?;
-    let content_start = xml[start..].find('>')? + start + 1;
-    let end = xml[content_start..].find(&close_tag)? + content_start;
+        let req = PerformanceRequest {
+            id: "login".to_string(),
+            name: "Login".to_string(),
+            endpoint,
+            method: "GET".to_string(),
+            soap_action: None,
+            request_body: String::new(),
+            headers: HashMap::new(),
+            extractors: vec![
+                extractor("session", "Cookie", None, "session"),
+                extractor("csrf", "Cookie", None, "csrf"),
+            ],
+            assertions: vec![],
+            sla_threshold: None,
+            order: 0,
+        };
+        let result = execute_request(&req, 0, &HashMap::new()).await;
+        let values = result.extracted_values.unwrap();
+        assert_eq!(values["session"], "abc");
+        assert_eq!(values["csrf"], "xyz");
+    }
+
+    #[tokio::test]
+    async fn test_check_assertions_runs_scripts() {
+        let script = |source: &str| FrontendAssertion {
+            assertion_type: "Script".to_string(),
+            name: Some("Order state".to_string()),
+            configuration: Some(AssertionConfiguration { script: Some(source.into()), ..Default::default() }),
+        };
+        let response = ScriptResponse {
+            body: r#"{"state":"OPEN"}"#.to_string(),
+            status: Some(200),
+            headers: HashMap::new(),
+            duration_ms: 5,
+        };
+        let variables = HashMap::from([("expected".to_string(), "OPEN".to_string())]);
 
-    Some(xml[content_start..end].trim().to_string())
+        let pass = script("return JSON.parse(response.body).state === vars.expected");
+        assert_eq!(check_assertions(&[pass], &response, &variables).await, (0, None));
+        let fail = script("assert(JSON.parse(response.body).state === 'SHIPPED', 'not shipped')");
+        let (failures, message) = check_assertions(&[fail], &response, &variables).await;
+        assert_eq!(failures, 1);
+        assert_eq!(message.unwrap(), "Assertion failed: Order state: AssertionError: not shipped");
+    }
 }
@@ -0,0 +1,415 @@
+// Scheduled runs
+//
+// While the app is running, enabled `performanceSchedules` are checked every
+// few seconds and a schedule whose cron expression matched a minute since the
+// previous check is triggered; runs missed while the app was closed are not
+// caught up. Performance schedules run their suite like
+// `run_performance_suite` (the run is stored in `performanceHistory`) and are
+// compared with a baseline run; test-suite schedules run a project's suite
+// headlessly, as `apinox-cli run` would.
+
+use chrono::{DateTime, Local, TimeZone};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::cli::{self, RunOptions};
+use crate::performance::commands::{find_saved_suite, start_run};
+use crate::performance::cron::CronSchedule;
+use crate::performance::types::{PerformanceRun, PerformanceStats, Regression};
+use crate::settings_manager;
+
+/// How often schedules are checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Allowed relative worsening of a metric before it counts as a regression
+pub const DEFAULT_REGRESSION_THRESHOLD: f64 = 20.0;
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Ids of schedules currently running; a schedule never overlaps itself
+static RUNNING: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScheduleTarget {
+    #[default]
+    Performance,
+    TestSuite,
+}
+
+/// Entry of `ApinoxConfig.performance_schedules`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceSchedule {
+    pub id: String,
+    /// Performance suite id, or test suite name/id for `testSuite` schedules
+    pub suite_id: String,
+    #[serde(default)]
+    pub suite_name: String,
+    pub cron_expression: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub target: ScheduleTarget,
+    /// Project directory holding the test suite (`testSuite` schedules)
+    #[serde(default)]
+    pub project_path: Option<String>,
+    /// Environment whose variables the run resolves
+    #[serde(default)]
+    pub environment: Option<String>,
+    /// Run to compare against; defaults to the suite's latest completed run
+    #[serde(default)]
+    pub baseline_run_id: Option<String>,
+    /// Percent a metric may worsen before it is flagged (default 20)
+    #[serde(default)]
+    pub regression_threshold: Option<f64>,
+    #[serde(default)]
+    pub last_run: Option<u64>,
+    #[serde(default)]
+    pub last_run_status: Option<String>,
+    #[serde(default)]
+    pub last_run_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub last_regressions: Vec<Regression>,
+    #[serde(default)]
+    pub next_run: Option<u64>,
+    #[serde(default)]
+    pub created_at: u64,
+}
+
+impl PerformanceSchedule {
+    /// Next run after `after`, if enabled and the expression is valid
+    pub fn next_run_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        if !self.enabled {
+            return None;
+        }
+        CronSchedule::parse(&self.cron_expression).ok()?.next_after(after)
+    }
+
+    /// Whether a scheduled minute fell in `(since, now]`
+    pub fn is_due<Tz: TimeZone>(&self, since: &DateTime<Tz>, now: &DateTime<Tz>) -> bool {
+        self.next_run_after(since).is_some_and(|next| next <= *now)
+    }
+}
+
+/// Compares a finished run with a baseline from the history
+#[derive(Debug, Clone)]
+pub struct RegressionCheck {
+    pub baseline_run_id: Option<String>,
+    pub threshold_percent: f64,
+}
+
+impl RegressionCheck {
+    /// Fill in `run.baseline_run_id` and `run.regressions`
+    pub fn apply(&self, history: &[Value], run: &mut PerformanceRun) {
+        let Some(baseline) = find_baseline(history, run, self.baseline_run_id.as_deref()) else {
+            log::info!("[scheduler] No baseline for suite '{}' yet", run.suite_name);
+            return;
+        };
+        run.regressions = detect_regressions(&baseline.summary, &run.summary, self.threshold_percent);
+        for regression in &run.regressions {
+            log::warn!(
+                "[scheduler] Suite '{}' regressed: {} {:.1} -> {:.1} ({:+.1}%) against run {}",
+                run.suite_name,
+                regression.metric,
+                regression.baseline,
+                regression.current,
+                regression.change_percent,
+                baseline.id
+            );
+        }
+        run.baseline_run_id = Some(baseline.id);
+    }
+}
+
+/// The requested run, or the latest completed run of the same suite
+pub(crate) fn find_baseline(history: &[Value], run: &PerformanceRun, baseline_run_id: Option<&str>) -> Option<PerformanceRun> {
+    let mut runs = history
+        .iter()
+        .rev()
+        .filter_map(|v| serde_json::from_value::<PerformanceRun>(v.clone()).ok())
+        .filter(|r| r.id != run.id);
+    match baseline_run_id {
+        Some(id) => runs.find(|r| r.id == id),
+        None => runs.find(|r| r.suite_id == run.suite_id && r.status == "completed" && r.summary.total_requests > 0),
+    }
+}
+
+/// Summary metrics of `current` that are worse than `baseline` by more than
+/// `threshold_percent`
+pub fn detect_regressions(baseline: &PerformanceStats, current: &PerformanceStats, threshold_percent: f64) -> Vec<Regression> {
+    // (metric, baseline, current, lower is better)
+    let metrics = [
+        ("avgResponseTime", baseline.avg_response_time, current.avg_response_time, true),
+        ("p50", baseline.p50, current.p50, true),
+        ("p95", baseline.p95, current.p95, true),
+        ("p99", baseline.p99, current.p99, true),
+        ("successRate", baseline.success_rate, current.success_rate, false),
+    ];
+    metrics
+        .into_iter()
+        .filter(|(_, baseline, _, _)| *baseline > 0.0)
+        .filter_map(|(metric, baseline, current, lower_is_better)| {
+            let change = (current - baseline) / baseline * 100.0;
+            let change_percent = if lower_is_better { change } else { -change };
+            (change_percent > threshold_percent).then(|| Regression {
+                metric: metric.to_string(),
+                baseline,
+                current,
+                change_percent,
+            })
+        })
+        .collect()
+}
+
+/// Start checking schedules in the background (once per process)
+pub fn start() {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tauri::async_runtime::spawn(async {
+        let mut last_check = Local::now();
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let now = Local::now();
+            for schedule in load_schedules() {
+                if schedule.is_due(&last_check, &now) {
+                    log::info!("[scheduler] Triggering schedule '{}' ({})", schedule.id, schedule.cron_expression);
+                    tokio::spawn(async move {
+                        if let Err(e) = trigger(schedule).await {
+                            log::error!("[scheduler] {}", e);
+                        }
+                    });
+                }
+            }
+            last_check = now;
+        }
+    });
+    log::info!("[scheduler] Started");
+}
+
+pub fn load_schedules() -> Vec<PerformanceSchedule> {
+    settings_manager::load_config_internal()
+        .ok()
+        .and_then(|config| config.performance_schedules)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|v| serde_json::from_value(v).ok())
+        .collect()
+}
+
+pub fn is_running(schedule_id: &str) -> bool {
+    RUNNING.lock().unwrap().contains(schedule_id)
+}
+
+struct ScheduleOutcome {
+    status: String,
+    run_id: Option<String>,
+    regressions: Vec<Regression>,
+}
+
+/// Run a schedule now and record the outcome on it
+pub async fn trigger(schedule: PerformanceSchedule) -> Result<(), String> {
+    if !RUNNING.lock().unwrap().insert(schedule.id.clone()) {
+        return Err(format!("Schedule '{}' is already running", schedule.id));
+    }
+    let started = Local::now();
+    let outcome = execute(&schedule).await;
+    RUNNING.lock().unwrap().remove(&schedule.id);
+
+    let failed = ScheduleOutcome { status: "failed".to_string(), run_id: None, regressions: Vec::new() };
+    record_outcome(&schedule.id, started.timestamp_millis() as u64, outcome.as_ref().unwrap_or(&failed))?;
+    outcome
+        .map(|_| ())
+        .map_err(|e| format!("Schedule '{}' failed: {}", schedule.id, e))
+}
+
+async fn execute(schedule: &PerformanceSchedule) -> Result<ScheduleOutcome, String> {
+    match schedule.target {
+        ScheduleTarget::Performance => {
+            let suite = find_saved_suite(&schedule.suite_id)
+                .ok_or_else(|| format!("Performance suite not found: {}", schedule.suite_id))?;
+            let check = RegressionCheck {
+                baseline_run_id: schedule.baseline_run_id.clone(),
+                threshold_percent: schedule.regression_threshold.unwrap_or(DEFAULT_REGRESSION_THRESHOLD),
+            };
+            let variables = resolve_variables(schedule.environment.as_deref()).await?;
+            let (run_id, handle) = start_run(suite, variables, false, Some(check))?;
+            let run = handle
+                .await
+                .ok()
+                .flatten()
+                .ok_or_else(|| format!("Run {} did not complete", run_id))?;
+            Ok(ScheduleOutcome { status: run.status, run_id: Some(run_id), regressions: run.regressions })
+        }
+        ScheduleTarget::TestSuite => {
+            let project_dir = schedule
+                .project_path
+                .clone()
+                .ok_or("Test suite schedules need a projectPath")?;
+            let options = RunOptions {
+                project_dir,
+                suites: vec![schedule.suite_id.clone()],
+                environment: schedule.environment.clone(),
+                ..RunOptions::default()
+            };
+            let suites = cli::run_project(&options).await?;
+            let passed = suites.iter().all(cli::SuiteRunResult::passed);
+            Ok(ScheduleOutcome {
+                status: if passed { "completed" } else { "failed" }.to_string(),
+                run_id: None,
+                regressions: Vec::new(),
+            })
+        }
+    }
+}
+
+async fn resolve_variables(environment: Option<&str>) -> Result<HashMap<String, String>, String> {
+    let mut variables = settings_manager::get_global_variables().await.unwrap_or_default();
+    if let Some(name) = environment {
+        variables.extend(settings_manager::get_resolved_environment(name.to_string()).await?);
+    }
+    Ok(variables)
+}
+
+/// Write the outcome onto the stored schedule, keeping fields this module
+/// does not know about
+fn record_outcome(schedule_id: &str, started: u64, outcome: &ScheduleOutcome) -> Result<(), String> {
+    let mut config = settings_manager::load_config_internal()?;
+    let mut schedules = config.performance_schedules.unwrap_or_default();
+    let entry = schedules
+        .iter_mut()
+        .find(|s| s.get("id").and_then(Value::as_str) == Some(schedule_id))
+        .and_then(Value::as_object_mut);
+
+    if let Some(entry) = entry {
+        let next_run = serde_json::from_value::<PerformanceSchedule>(Value::Object(entry.clone()))
+            .ok()
+            .and_then(|s| s.next_run_after(&Local::now()))
+            .map(|t| t.timestamp_millis());
+        entry.insert("lastRun".to_string(), started.into());
+        entry.insert("lastRunStatus".to_string(), outcome.status.clone().into());
+        entry.insert("lastRunId".to_string(), outcome.run_id.clone().into());
+        entry.insert("nextRun".to_string(), next_run.into());
+        if outcome.regressions.is_empty() {
+            entry.remove("lastRegressions");
+        } else {
+            entry.insert(
+                "lastRegressions".to_string(),
+                serde_json::to_value(&outcome.regressions).map_err(|e| e.to_string())?,
+            );
+        }
+    }
+
+    config.performance_schedules = Some(schedules);
+    settings_manager::save_config_internal(&config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn stats(p95: f64, success_rate: f64) -> PerformanceStats {
+        let mut stats = PerformanceStats::calculate(&[]);
+        stats.total_requests = 100;
+        stats.avg_response_time = 50.0;
+        stats.p50 = 40.0;
+        stats.p95 = p95;
+        stats.p99 = 0.0;
+        stats.success_rate = success_rate;
+        stats
+    }
+
+    fn run(id: &str, suite_id: &str, status: &str, p95: f64) -> PerformanceRun {
+        PerformanceRun {
+            id: id.to_string(),
+            suite_id: suite_id.to_string(),
+            suite_name: "Checkout".to_string(),
+            start_time: 0,
+            end_time: 0,
+            status: status.to_string(),
+            results: vec![],
+            summary: stats(p95, 1.0),
+            environment: None,
+            time_series: None,
+            baseline_run_id: None,
+            regressions: vec![],
+        }
+    }
+
+    #[test]
+    fn test_schedule_from_frontend_shape() {
+        let schedule: PerformanceSchedule = serde_json::from_value(serde_json::json!({
+            "id": "sch1",
+            "suiteId": "s1",
+            "suiteName": "Checkout",
+            "cronExpression": "*/5 * * * *",
+            "enabled": true,
+            "createdAt": 1700000000000u64
+        }))
+        .unwrap();
+        assert_eq!(schedule.target, ScheduleTarget::Performance);
+
+        let since = Utc.with_ymd_and_hms(2024, 1, 1, 10, 4, 50).unwrap();
+        assert!(schedule.is_due(&since, &Utc.with_ymd_and_hms(2024, 1, 1, 10, 5, 5).unwrap()));
+        assert!(!schedule.is_due(&since, &Utc.with_ymd_and_hms(2024, 1, 1, 10, 4, 59).unwrap()));
+
+        let disabled = PerformanceSchedule { enabled: false, ..schedule.clone() };
+        assert!(!disabled.is_due(&since, &Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap()));
+        let invalid = PerformanceSchedule { cron_expression: "whenever".to_string(), ..schedule };
+        assert!(invalid.next_run_after(&since).is_none());
+    }
+
+    #[test]
+    fn test_detect_regressions() {
+        let regressions = detect_regressions(&stats(100.0, 1.0), &stats(130.0, 0.7), 20.0);
+        let flagged: Vec<(&str, f64)> = regressions.iter().map(|r| (r.metric.as_str(), r.change_percent.round())).collect();
+        // p99 has no baseline to compare with
+        assert_eq!(flagged, vec![("p95", 30.0), ("successRate", 30.0)]);
+
+        assert!(detect_regressions(&stats(100.0, 1.0), &stats(115.0, 0.9), 20.0).is_empty());
+        assert!(detect_regressions(&stats(100.0, 1.0), &stats(50.0, 1.0), 20.0).is_empty());
+    }
+
+    #[test]
+    fn test_baseline_selection() {
+        let history: Vec<Value> = [
+            run("r1", "s1", "completed", 100.0),
+            run("r2", "s2", "completed", 500.0),
+            run("r3", "s1", "aborted", 900.0),
+            run("r4", "s1", "completed", 110.0),
+        ]
+        .iter()
+        .map(|r| serde_json::to_value(r).unwrap())
+        .collect();
+        let check = |baseline_run_id: Option<&str>| RegressionCheck {
+            baseline_run_id: baseline_run_id.map(str::to_string),
+            threshold_percent: 20.0,
+        };
+
+        // Latest completed run of the same suite
+        let mut current = run("r5", "s1", "completed", 140.0);
+        check(None).apply(&history, &mut current);
+        assert_eq!(current.baseline_run_id.as_deref(), Some("r4"));
+        assert_eq!(current.regressions.len(), 1);
+        assert!((current.regressions[0].change_percent - 27.27).abs() < 0.01);
+
+        // Pinned baseline
+        let mut current = run("r5", "s1", "completed", 140.0);
+        check(Some("r2")).apply(&history, &mut current);
+        assert_eq!(current.baseline_run_id.as_deref(), Some("r2"));
+        assert!(current.regressions.is_empty());
+
+        let mut first = run("r1", "s9", "completed", 100.0);
+        check(None).apply(&history, &mut first);
+        assert_eq!(first.baseline_run_id, None);
+    }
+}
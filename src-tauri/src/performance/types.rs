@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::performance::load_model::LoadProfile;
+use crate::performance::metrics::{MetricsRecorder, TimeSeries};
+use crate::testing::frontend_types::FrontendAssertion;
+
 /// Deserialize a JSON null or missing value as an empty Vec.
 fn null_as_empty_vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
 where
@@ -35,6 +39,9 @@ pub struct PerformanceSuite {
     pub warmup_runs: u32,
     #[serde(default = "default_concurrency")]
     pub concurrency: u32,
+    /// How VUs are driven; without one, `concurrency` VUs run `iterations` each
+    #[serde(default)]
+    pub load_profile: Option<LoadProfile>,
 }
 
 fn default_iterations() -> u32 {
@@ -59,6 +66,9 @@ pub struct PerformanceRequest {
     pub headers: HashMap<String, String>,
     #[serde(default, deserialize_with = "null_as_empty_vec")]
     pub extractors: Vec<RequestExtractor>,
+    /// Checked against every response; a failing assertion fails the request
+    #[serde(default, deserialize_with = "null_as_empty_vec")]
+    pub assertions: Vec<FrontendAssertion>,
     pub sla_threshold: Option<f64>,
     #[serde(default)]
     pub order: u32,
@@ -74,7 +84,13 @@ pub struct RequestExtractor {
     pub variable: String,
     #[serde(rename = "type")]
     pub extractor_type: String,
+    /// "body" | "header" | "status"
+    #[serde(default)]
+    pub source: Option<String>,
     pub path: String,
+    /// Used when the extractor finds nothing
+    #[serde(default)]
+    pub default_value: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,12 +101,18 @@ pub struct PerformanceResult {
     pub interface_name: Option<String>,
     pub operation_name: Option<String>,
     pub iteration: u32,
+    /// Virtual user that sent the request (1-based)
+    #[serde(default)]
+    pub vu: u32,
     pub duration: f64,
     pub status: u16,
     pub success: bool,
     pub sla_breached: bool,
     pub error: Option<String>,
     pub extracted_values: Option<HashMap<String, String>>,
+    /// Number of the request's assertions that failed
+    #[serde(default)]
+    pub assertion_failures: u32,
     pub timestamp: u64,
 }
 
@@ -108,6 +130,9 @@ pub struct PerformanceStats {
     pub p95: f64,
     pub p99: f64,
     pub sla_breach_count: usize,
+    /// Requests that failed because of an assertion (included in `failure_count`)
+    #[serde(default)]
+    pub assertion_failure_count: usize,
     pub total_duration: f64,
 }
 
@@ -123,56 +148,36 @@ pub struct PerformanceRun {
     pub results: Vec<PerformanceResult>,
     pub summary: PerformanceStats,
     pub environment: Option<String>,
+    /// Per-second metrics; absent for runs recorded before time series existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_series: Option<TimeSeries>,
+    /// Run that `regressions` were measured against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline_run_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regressions: Vec<Regression>,
+}
+
+/// A summary metric that got worse than the baseline by more than the
+/// allowed threshold
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Regression {
+    /// `PerformanceStats` field name, e.g. "p95"
+    pub metric: String,
+    pub baseline: f64,
+    pub current: f64,
+    /// Relative change, positive when worse
+    pub change_percent: f64,
 }
 
 impl PerformanceStats {
     pub fn calculate(results: &[PerformanceResult]) -> Self {
-        if results.is_empty() {
-            return Self {
-                total_requests: 0,
-                success_count: 0,
-                failure_count: 0,
-                success_rate: 0.0,
-                avg_response_time: 0.0,
-                min_response_time: 0.0,
-                max_response_time: 0.0,
-                p50: 0.0,
-                p95: 0.0,
-                p99: 0.0,
-                sla_breach_count: 0,
-                total_duration: 0.0,
-            };
-        }
-
-        let mut durations: Vec<f64> = results.iter().map(|r| r.duration).collect();
-        durations.sort_by(|a, b| a.partial_cmp(b).unwrap());
-
-        let success_count = results.iter().filter(|r| r.success).count();
-        let sla_breach_count = results.iter().filter(|r| r.sla_breached).count();
-        let total = results.len();
-
-        let avg = durations.iter().sum::<f64>() / total as f64;
-        let total_duration = results
-            .last()
-            .map(|r| r.timestamp)
-            .unwrap_or(0)
-            .saturating_sub(results.first().map(|r| r.timestamp).unwrap_or(0))
-            as f64;
-
-        Self {
-            total_requests: total,
-            success_count,
-            failure_count: total - success_count,
-            success_rate: success_count as f64 / total as f64,
-            avg_response_time: avg,
-            min_response_time: durations[0],
-            max_response_time: *durations.last().unwrap(),
-            p50: percentile(&durations, 50.0),
-            p95: percentile(&durations, 95.0),
-            p99: percentile(&durations, 99.0),
-            sla_breach_count,
-            total_duration,
+        let mut recorder = MetricsRecorder::default();
+        for result in results {
+            recorder.record(result);
         }
+        recorder.summary()
     }
 }
 
@@ -59,6 +59,8 @@ struct OperationMeta {
     original_endpoint: Option<String>,
     #[serde(default, skip_serializing_if = "JsonValue::is_null", rename = "fullSchema")]
     full_schema: JsonValue,
+    #[serde(default, skip_serializing_if = "JsonValue::is_null", rename = "outputSchema")]
+    output_schema: JsonValue,
     #[serde(skip_serializing_if = "Option::is_none", rename = "displayName")]
     display_name: Option<String>,
 }
@@ -330,6 +332,7 @@ fn save_operation(op: &JsonValue, iface_dir: &Path) -> Result<(), String> {
         target_namespace: op["targetNamespace"].as_str().map(|s| s.to_string()),
         original_endpoint: op["originalEndpoint"].as_str().map(|s| s.to_string()),
         full_schema: op["fullSchema"].clone(),
+        output_schema: op["outputSchema"].clone(),
         display_name: op["displayName"].as_str().map(|s| s.to_string()),
     };
     let meta_val = serde_json::to_value(&meta)
@@ -474,6 +477,22 @@ fn save_test_case(tc: &JsonValue, suite_dir: &Path) -> Result<(), String> {
     Ok(())
 }
 
+/// Resolve a project reference: a project directory, or the name of a
+/// directory in the projects folder
+pub(crate) fn resolve_project_dir(reference: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(reference);
+    if path.is_dir() {
+        return Ok(path);
+    }
+
+    let named = projects_dir()?.join(reference);
+    if named.is_dir() {
+        return Ok(named);
+    }
+
+    Err(format!("Project directory does not exist: {}", reference))
+}
+
 /// Load a project from disk
 #[tauri::command]
 pub async fn load_project(dir_path: String) -> Result<serde_json::Value, String> {
@@ -550,6 +569,8 @@ pub(crate) async fn load_project_internal(dir_path: &str) -> Result<serde_json::
         }
     }
     
+    fill_step_target_namespaces(&interfaces, &mut test_suites);
+
     // Build project object
     let project = serde_json::json!({
         "name": props.name,
@@ -628,6 +649,7 @@ fn load_operation(op_dir: &Path) -> Result<JsonValue, String> {
             requests.push(req);
         }
     }
+    inherit_target_namespace(&mut requests, &meta_val["targetNamespace"]);
 
     meta_val["requests"] = JsonValue::Array(requests);
 
@@ -653,6 +675,60 @@ fn load_request(op_dir: &Path, base_name: &str) -> Result<JsonValue, String> {
     Ok(meta_val)
 }
 
+/// Give requests without a `targetNamespace` their operation's, so XPath
+/// assertions can use `tns:` without binding it
+fn inherit_target_namespace(requests: &mut [JsonValue], namespace: &JsonValue) {
+    if !namespace.is_string() {
+        return;
+    }
+    for request in requests {
+        if request.get("targetNamespace").map_or(true, JsonValue::is_null) {
+            request["targetNamespace"] = namespace.clone();
+        }
+    }
+}
+
+/// Give test step requests without a `targetNamespace` the one of the project
+/// request they were copied from (by `requestId` or id). Steps that can't be
+/// traced back get the project's namespace when all its operations share one.
+fn fill_step_target_namespaces(interfaces: &[JsonValue], test_suites: &mut [JsonValue]) {
+    let operations = || {
+        interfaces
+            .iter()
+            .flat_map(|i| i["operations"].as_array().into_iter().flatten())
+    };
+    let mut by_request = std::collections::HashMap::new();
+    for op in operations() {
+        for request in op["requests"].as_array().into_iter().flatten() {
+            if let (Some(id), Some(namespace)) = (request["id"].as_str(), request["targetNamespace"].as_str()) {
+                by_request.insert(id.to_string(), namespace.to_string());
+            }
+        }
+    }
+    let namespaces: std::collections::HashSet<&str> =
+        operations().filter_map(|op| op["targetNamespace"].as_str()).collect();
+    let only_namespace = (namespaces.len() == 1).then(|| namespaces.into_iter().next()).flatten();
+
+    let steps = test_suites
+        .iter_mut()
+        .flat_map(|s| s.get_mut("testCases").and_then(JsonValue::as_array_mut).into_iter().flatten())
+        .flat_map(|c| c.get_mut("steps").and_then(JsonValue::as_array_mut).into_iter().flatten());
+    for step in steps {
+        let Some(config) = step.get_mut("config") else { continue };
+        let linked = [&config["requestId"], &config["request"]["id"]]
+            .into_iter()
+            .filter_map(|id| id.as_str())
+            .find_map(|id| by_request.get(id).map(String::as_str));
+        let Some(request) = config.get_mut("request").filter(|r| r.is_object()) else { continue };
+        if !request.get("targetNamespace").map_or(true, JsonValue::is_null) {
+            continue;
+        }
+        if let Some(namespace) = linked.or(only_namespace) {
+            request["targetNamespace"] = JsonValue::String(namespace.to_string());
+        }
+    }
+}
+
 /// Load a test suite from its directory
 fn load_test_suite(suite_dir: &Path) -> Result<JsonValue, String> {
     let meta_path = suite_dir.join("suite.json");
@@ -851,6 +927,7 @@ fn save_unified_operation(op: &serde_json::Value, project_dir: &Path) -> Result<
         "targetNamespace": op["targetNamespace"],
         "originalEndpoint": op["originalEndpoint"],
         "fullSchema": op["fullSchema"],
+        "outputSchema": op["outputSchema"],
         "displayName": op["displayName"],
     }))?;
 
@@ -1022,6 +1099,7 @@ fn load_unified_operation(op_dir: &Path) -> Result<serde_json::Value, String> {
     let op_data: serde_json::Value = serde_json::from_str(&op_json)
         .map_err(|e| format!("Failed to parse operation.json: {}", e))?;
     let name = op_data["name"].as_str().ok_or("Missing operation name")?;
+    inherit_target_namespace(&mut requests, &op_data["targetNamespace"]);
 
     Ok(serde_json::json!({
         "name": name,
@@ -1030,6 +1108,7 @@ fn load_unified_operation(op_dir: &Path) -> Result<serde_json::Value, String> {
         "targetNamespace": op_data["targetNamespace"],
         "originalEndpoint": op_data["originalEndpoint"],
         "fullSchema": op_data["fullSchema"],
+        "outputSchema": op_data["outputSchema"],
         "displayName": op_data["displayName"],
         "requests": requests,
     }))
@@ -1069,4 +1148,64 @@ mod tests {
         assert!(resolved.ends_with("CountryInfoServiceSoap"));
         assert!(resolved.is_absolute());
     }
+
+    #[tokio::test]
+    async fn loaded_requests_bind_tns_to_their_operation_namespace() {
+        use crate::testing::frontend_runner::{run_test_case, RunContext};
+        use crate::testing::frontend_types::FrontendTestCase;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Answers every request with a namespaced SOAP response
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/auth", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf).await;
+                let body = r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><LoginResponse xmlns="urn:auth"><Token>abc</Token></LoginResponse></s:Body></s:Envelope>"#;
+                let response = format!("HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body);
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        let dir = std::env::temp_dir().join(format!("apinox-tns-{}", uuid::Uuid::new_v4()));
+        let op_dir = dir.join("interfaces/Auth/Login");
+        let case_dir = dir.join("tests/Smoke/Login");
+        fs::create_dir_all(&op_dir).unwrap();
+        fs::create_dir_all(&case_dir).unwrap();
+        let write = |path: PathBuf, value: JsonValue| fs::write(path, value.to_string()).unwrap();
+        write(dir.join("properties.json"), serde_json::json!({ "name": "Auth", "format": "folder" }));
+        write(dir.join("interfaces/Auth/interface.json"), serde_json::json!({ "name": "Auth", "type": "wsdl" }));
+        write(op_dir.join("operation.json"), serde_json::json!({ "name": "Login", "targetNamespace": "urn:auth" }));
+        write(op_dir.join("Sample.json"), serde_json::json!({ "name": "Sample", "id": "req-1" }));
+        fs::write(op_dir.join("Sample.xml"), "<Login/>").unwrap();
+        write(dir.join("tests/Smoke/suite.json"), serde_json::json!({ "name": "Smoke" }));
+        write(case_dir.join("case.json"), serde_json::json!({ "id": "tc-1", "name": "Login" }));
+        write(case_dir.join("001.json"), serde_json::json!({
+            "id": "step-1",
+            "name": "Login",
+            "type": "request",
+            "config": { "request": {
+                "name": "Login",
+                "request": "<Login/>",
+                "endpoint": endpoint,
+                "assertions": [{
+                    "type": "XPath Match",
+                    "configuration": { "xpath": "//tns:Token", "expectedContent": "abc" }
+                }]
+            } }
+        }));
+
+        let project = load_project_internal(&dir.to_string_lossy()).await.unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        let request = &project["interfaces"][0]["operations"][0]["requests"][0];
+        assert_eq!(request["targetNamespace"], "urn:auth");
+        let case = &project["testSuites"][0]["testCases"][0];
+        assert_eq!(case["steps"][0]["config"]["request"]["targetNamespace"], "urn:auth");
+
+        let test_case: FrontendTestCase = serde_json::from_value(case.clone()).unwrap();
+        let result = run_test_case(&test_case, None, &mut RunContext::default()).await;
+        let step = &result.steps[0].result;
+        assert!(result.passed, "{:?} {:?}", step.error, step.assertion_results);
+    }
 }
@@ -1,5 +1,4 @@
 use std::collections::HashMap;
-use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
@@ -20,9 +19,10 @@ use uuid::Uuid;
 use crate::breakpoint::service::SharedBreakpointService;
 use crate::certificates::manager::CertManager;
 use crate::certificates::sni_resolver::SniResolver;
+use crate::mock::faults;
 use crate::mock::server::find_matching_rule;
 use crate::mock::state::SharedMockState;
-use crate::proxy_models::{PausedTraffic, ProxyConfig, TrafficEvent};
+use crate::proxy_models::{MockRule, PausedTraffic, ProxyConfig, TrafficEvent};
 use crate::utils::{emit_traffic_event, match_pattern, CONTENT_TYPE_PLAIN};
 use crate::replacer::service::SharedReplacerService;
 
@@ -81,9 +81,10 @@ pub async fn run_proxy(
                 let mock_state = mock_state.clone();
                 let breakpoints = breakpoints.clone();
                 async move {
-                    Ok::<_, Infallible>(
+                    faults::deliver(
                         handle_request(req, config, replacer, app, tls_acceptor, mock_state, breakpoints).await,
                     )
+                    .await
                 }
             });
 
@@ -157,9 +158,10 @@ async fn handle_connect(
                             let breakpoints = breakpoints.clone();
                             async move {
                                 let req = rewrite_to_https(inner_req, &hostname);
-                                Ok::<_, Infallible>(
+                                faults::deliver(
                                     handle_http(req, config, replacer, app, mock_state, breakpoints).await,
                                 )
+                                .await
                             }
                         });
 
@@ -274,12 +276,17 @@ async fn handle_http(
                     r.hit_count += 1;
                 }
             }
+            let fault_plan = plan_faults(&mock_state, &config, Some(rule)).await;
 
             let status = rule.status_code;
             let content_type = rule.content_type.clone().unwrap_or_else(|| CONTENT_TYPE_PLAIN.to_string());
             let resp_body = rule.response_body.clone();
             let mut resp_headers: HashMap<String, String> = rule.response_headers.clone().unwrap_or_default();
             resp_headers.entry("content-type".to_string()).or_insert(content_type);
+            let (status, resp_headers, resp_body) = fault_plan
+                .as_ref()
+                .and_then(|p| p.replacement(&req_headers))
+                .unwrap_or((status, resp_headers, resp_body));
 
             let duration_ms = start.elapsed().as_millis() as u64;
             let now = Utc::now();
@@ -306,8 +313,9 @@ async fn handle_http(
             }
             let body_bytes = Bytes::from(resp_body);
             hb = hb.header("content-length", body_bytes.len());
-            return hb.body(Full::new(body_bytes))
+            let response = hb.body(Full::new(body_bytes))
                 .unwrap_or_else(|_| error_response(StatusCode::INTERNAL_SERVER_ERROR, "Build error"));
+            return faults::with_plan(response, fault_plan);
         }
         log::debug!("[Proxy] No mock rule matched — forwarding");
     }
@@ -506,6 +514,12 @@ async fn handle_http(
         }
     };
 
+    let fault_plan = plan_faults(&mock_state, &config, None).await;
+    let (status, resp_headers, resp_body) = fault_plan
+        .as_ref()
+        .and_then(|p| p.replacement(&req_headers))
+        .unwrap_or((status, resp_headers, resp_body));
+
     let duration_ms = start.elapsed().as_millis() as u64;
     let now = Utc::now();
 
@@ -545,10 +559,25 @@ async fn handle_http(
     let body_bytes = Bytes::from(resp_body);
     hb = hb.header("content-length", body_bytes.len());
 
-    hb.body(Full::new(body_bytes)).unwrap_or_else(|e| {
+    let response = hb.body(Full::new(body_bytes)).unwrap_or_else(|e| {
         log::error!("[Proxy] Failed to build response: {}", e);
         error_response(StatusCode::INTERNAL_SERVER_ERROR, "Response build error")
-    })
+    });
+    faults::with_plan(response, fault_plan)
+}
+
+/// Plan faults for a response: the matched mock rule's own profile, else the
+/// proxy's. Random state lives with the mock state so rule sequences are
+/// shared with the mock server.
+async fn plan_faults(
+    mock_state: &SharedMockState,
+    config: &ProxyConfig,
+    rule: Option<&MockRule>,
+) -> Option<faults::FaultPlan> {
+    if config.fault_profile.is_none() && rule.map_or(true, |r| r.fault_profile.is_none()) {
+        return None;
+    }
+    mock_state.lock().await.faults.plan_for(rule, "proxy", config.fault_profile.as_ref())
 }
 
 /// Resolve the URL to forward to.
@@ -1,7 +1,11 @@
 /// Shared data models for APIprox.
 /// These mirror the TypeScript definitions that were in shared/src/models.ts.
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::mock::faults::FaultProfile;
+use crate::parsers::openapi_parser::{OpenApiParameter, OpenApiRequestBody};
 
 // ---------------------------------------------------------------------------
 // Proxy / Traffic
@@ -37,6 +41,9 @@ pub struct ProxyConfig {
     pub target_url: String,
     /// "proxy" | "mock" | "both"
     pub mode: String,
+    /// Faults injected into every response the proxy sends
+    #[serde(default)]
+    pub fault_profile: Option<FaultProfile>,
 }
 
 impl Default for ProxyConfig {
@@ -46,6 +53,7 @@ impl Default for ProxyConfig {
             port: 8888,
             target_url: String::new(),
             mode: "proxy".to_string(),
+            fault_profile: None,
         }
     }
 }
@@ -58,6 +66,7 @@ impl Default for ProxyConfig {
 #[serde(rename_all = "camelCase")]
 pub struct MockMatchCondition {
     /// "url" | "operation" | "soapAction" | "header" | "contains" | "xpath" | "templateName"
+    /// | "method" | "path" (an OpenAPI path template such as `/orders/{id}`)
     pub r#type: String,
     pub pattern: String,
     #[serde(default)]
@@ -66,6 +75,19 @@ pub struct MockMatchCondition {
     pub header_name: Option<String>,
 }
 
+/// The parts of an OpenAPI operation a mock checks requests against
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockRequestValidation {
+    #[serde(default)]
+    pub parameters: Vec<OpenApiParameter>,
+    #[serde(default)]
+    pub request_body: Option<OpenApiRequestBody>,
+    /// `$ref` targets of the parameter and body schemas
+    #[serde(default)]
+    pub schemas: BTreeMap<String, Value>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MockRule {
@@ -81,6 +103,32 @@ pub struct MockRule {
     pub content_type: Option<String>,
     pub response_headers: Option<HashMap<String, String>>,
     pub delay_ms: Option<u64>,
+    /// Render the body, header values and `status_template` with `mock::template`
+    #[serde(default)]
+    pub templated: bool,
+    /// Templated status code, e.g. `{{#if request.query.id}}200{{else}}404{{/if}}`;
+    /// falls back to `status_code` when empty or not a number
+    #[serde(default)]
+    pub status_template: Option<String>,
+    /// Scenario (state machine) this rule belongs to
+    #[serde(default)]
+    pub scenario: Option<String>,
+    /// Only match while the scenario is in this state (initially "Started")
+    #[serde(default)]
+    pub required_state: Option<String>,
+    /// Move the scenario to this state after matching
+    #[serde(default)]
+    pub new_state: Option<String>,
+    /// Values stored in the scenario after matching; rendered as templates and
+    /// readable by later responses as `{{scenario.<key>}}`
+    #[serde(default)]
+    pub scenario_values: Option<HashMap<String, String>>,
+    /// Answer requests that violate the operation with a 400 problem-details body
+    #[serde(default)]
+    pub request_validation: Option<MockRequestValidation>,
+    /// Faults injected into this rule's responses, instead of the global profile
+    #[serde(default)]
+    pub fault_profile: Option<FaultProfile>,
     #[serde(default)]
     pub hit_count: u64,
     pub recorded_at: Option<i64>,
@@ -115,6 +163,10 @@ pub struct MockConfig {
     pub route_through_proxy: bool,
     #[serde(default)]
     pub record_mode: bool,
+    /// Faults injected into every response of rules without their own profile
+    /// and of passthrough requests
+    #[serde(default)]
+    pub fault_profile: Option<FaultProfile>,
 }
 
 impl Default for MockConfig {
@@ -127,6 +179,7 @@ impl Default for MockConfig {
             passthrough_enabled: true,
             route_through_proxy: false,
             record_mode: false,
+            fault_profile: None,
         }
     }
 }
@@ -0,0 +1,22 @@
+/// Tauri Commands for Test Reports
+use std::path::PathBuf;
+
+use super::{write_report, ReportFormat, TestReport};
+
+/// Write a report to disk in the requested format ("junit", "tap" or "html").
+/// Returns the path that was written.
+#[tauri::command]
+pub async fn export_test_report(
+    report: TestReport,
+    format: String,
+    file_path: String,
+) -> Result<String, String> {
+    let format: ReportFormat = format.parse().map_err(|e: anyhow::Error| e.to_string())?;
+    let path = PathBuf::from(&file_path);
+
+    write_report(&report, format, &path)
+        .map_err(|e| format!("Failed to write report to {}: {}", file_path, e))?;
+
+    log::info!("[Reports] Wrote {:?} report to {}", format, file_path);
+    Ok(file_path)
+}
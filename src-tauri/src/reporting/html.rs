@@ -0,0 +1,155 @@
+/// Self-contained HTML report writer
+///
+/// Everything (styles included) is inlined so the file can be archived as a
+/// build artifact and opened without network access. Each step expands to
+/// show its assertions and the request/response bodies.
+use super::{escape_markup, ReportCase, ReportStatus, ReportStep, TestReport};
+
+const STYLE: &str = "\
+body{font-family:-apple-system,Segoe UI,Roboto,sans-serif;margin:24px;color:#1f2328;background:#fff}
+h1{font-size:22px;margin:0 0 4px}
+.meta{color:#656d76;margin-bottom:16px}
+.summary span{display:inline-block;margin-right:16px;font-weight:600}
+.pass{color:#1a7f37}.fail{color:#cf222e}.skip{color:#9a6700}
+h2{font-size:18px;border-bottom:1px solid #d0d7de;padding-bottom:4px;margin-top:28px}
+details.case{border:1px solid #d0d7de;border-radius:6px;margin:8px 0;padding:6px 10px}
+details.case.failed{border-color:#cf222e}
+details.step{margin:6px 0 6px 16px}
+summary{cursor:pointer}
+.badge{display:inline-block;min-width:38px;text-align:center;border-radius:4px;color:#fff;font-size:12px;padding:1px 4px;margin-right:6px}
+.badge.pass{background:#1a7f37}.badge.fail{background:#cf222e}.badge.skip{background:#9a6700}
+.duration{color:#656d76;font-size:12px;margin-left:6px}
+.error{color:#cf222e;margin:4px 0}
+table{border-collapse:collapse;margin:6px 0}
+td,th{border:1px solid #d0d7de;padding:3px 8px;text-align:left;font-size:13px}
+pre{background:#f6f8fa;border:1px solid #d0d7de;border-radius:4px;padding:8px;overflow:auto;max-height:400px;font-size:12px;white-space:pre-wrap;word-break:break-all}
+";
+
+pub fn render(report: &TestReport) -> String {
+    let total = report.total();
+    let failures = report.failures();
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{} - Test Report</title>\n", escape_markup(&report.name)));
+    html.push_str(&format!("<style>\n{}</style>\n</head>\n<body>\n", STYLE));
+
+    html.push_str(&format!("<h1>{}</h1>\n", escape_markup(&report.name)));
+    html.push_str(&format!(
+        "<div class=\"meta\">{} &middot; {} ms</div>\n",
+        escape_markup(&report.timestamp),
+        report.duration_ms()
+    ));
+    html.push_str(&format!(
+        "<div class=\"summary\"><span>{} test case(s)</span><span class=\"pass\">{} passed</span><span class=\"fail\">{} failed</span></div>\n",
+        total,
+        total - failures,
+        failures
+    ));
+
+    for suite in &report.suites {
+        html.push_str(&format!(
+            "<h2>{} <span class=\"duration\">{} / {} passed</span></h2>\n",
+            escape_markup(&suite.name),
+            suite.cases.len() - suite.failures(),
+            suite.cases.len()
+        ));
+        for case in &suite.cases {
+            render_case(&mut html, case);
+        }
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn render_case(html: &mut String, case: &ReportCase) {
+    // Failed cases start expanded so the cause is visible immediately
+    html.push_str(&format!(
+        "<details class=\"case{}\"{}>\n<summary>{}{}<span class=\"duration\">{} ms</span></summary>\n",
+        if case.passed { "" } else { " failed" },
+        if case.passed { "" } else { " open" },
+        badge(if case.passed { ReportStatus::Passed } else { ReportStatus::Failed }),
+        escape_markup(&case.name),
+        case.duration_ms
+    ));
+
+    if let Some(failure) = &case.failure {
+        html.push_str(&format!("<div class=\"error\">{}</div>\n", escape_markup(failure)));
+    }
+
+    for step in &case.steps {
+        render_step(html, step);
+    }
+
+    html.push_str("</details>\n");
+}
+
+fn render_step(html: &mut String, step: &ReportStep) {
+    let status = step.status_code.map(|c| format!(" &middot; HTTP {}", c)).unwrap_or_default();
+    html.push_str(&format!(
+        "<details class=\"step\"{}>\n<summary>{}{}<span class=\"duration\">{} ms{}</span></summary>\n",
+        if step.passed { "" } else { " open" },
+        badge(if step.passed { ReportStatus::Passed } else { ReportStatus::Failed }),
+        escape_markup(&step.name),
+        step.duration_ms,
+        status
+    ));
+
+    if let Some(error) = &step.error {
+        html.push_str(&format!("<div class=\"error\">{}</div>\n", escape_markup(error)));
+    }
+
+    if !step.assertions.is_empty() {
+        html.push_str("<table>\n<tr><th>Status</th><th>Assertion</th><th>Message</th></tr>\n");
+        for a in &step.assertions {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                badge(a.status),
+                escape_markup(&a.name),
+                escape_markup(a.message.as_deref().unwrap_or(""))
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    if let Some(body) = step.request_body.as_deref().filter(|b| !b.is_empty()) {
+        html.push_str(&format!("<div>Request</div>\n<pre>{}</pre>\n", escape_markup(body)));
+    }
+    if let Some(body) = step.response_body.as_deref().filter(|b| !b.is_empty()) {
+        html.push_str(&format!("<div>Response</div>\n<pre>{}</pre>\n", escape_markup(body)));
+    }
+
+    html.push_str("</details>\n");
+}
+
+fn badge(status: ReportStatus) -> String {
+    let class = match status {
+        ReportStatus::Passed => "pass",
+        ReportStatus::Failed => "fail",
+        ReportStatus::Skipped => "skip",
+    };
+    format!("<span class=\"badge {}\">{}</span>", class, status.label())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporting::tests::sample_report;
+
+    #[test]
+    fn test_render_html() {
+        let html = render(&sample_report());
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<style>"));
+        assert!(!html.contains("<link") && !html.contains("<script"));
+        assert!(html.contains("<span class=\"pass\">1 passed</span><span class=\"fail\">1 failed</span>"));
+        // Names and bodies are escaped
+        assert!(html.contains("Create &lt;Order&gt; &amp; Pay"));
+        assert!(html.contains("<pre>&lt;GetOrder&gt;&lt;id&gt;1&lt;/id&gt;&lt;/GetOrder&gt;</pre>"));
+        assert!(html.contains("Status 500 not in expected [200]"));
+        // Only the failing case and step start expanded
+        assert_eq!(html.matches("<details class=\"case failed\" open>").count(), 1);
+    }
+}
@@ -0,0 +1,130 @@
+/// JUnit XML writer
+///
+/// Produces the de-facto `<testsuites>` schema understood by Jenkins, GitLab,
+/// Azure DevOps and GitHub test reporters. Steps are listed in `<system-out>`
+/// so the individual request results are visible next to the failure.
+use super::{escape_markup, seconds, ReportCase, ReportSuite, TestReport};
+
+pub fn render(report: &TestReport) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" time=\"{}\" timestamp=\"{}\">\n",
+        escape_markup(&report.name),
+        report.total(),
+        report.failures(),
+        seconds(report.duration_ms()),
+        escape_markup(&report.timestamp),
+    ));
+
+    for (index, suite) in report.suites.iter().enumerate() {
+        render_suite(&mut xml, suite, index, &report.timestamp);
+    }
+
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn render_suite(xml: &mut String, suite: &ReportSuite, index: usize, timestamp: &str) {
+    xml.push_str(&format!(
+        "  <testsuite id=\"{}\" name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\" time=\"{}\" timestamp=\"{}\">\n",
+        index,
+        escape_markup(&suite.name),
+        suite.cases.len(),
+        suite.failures(),
+        seconds(suite.duration_ms()),
+        escape_markup(timestamp),
+    ));
+
+    for case in &suite.cases {
+        render_case(xml, case, &suite.name);
+    }
+
+    xml.push_str("  </testsuite>\n");
+}
+
+fn render_case(xml: &mut String, case: &ReportCase, classname: &str) {
+    xml.push_str(&format!(
+        "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\">\n",
+        escape_markup(&case.name),
+        escape_markup(classname),
+        seconds(case.duration_ms),
+    ));
+
+    if !case.passed {
+        let message = case.failure.as_deref().unwrap_or("Test case failed");
+        xml.push_str(&format!(
+            "      <failure message=\"{}\" type=\"AssertionFailure\">{}</failure>\n",
+            escape_markup(message),
+            escape_markup(&failure_details(case)),
+        ));
+    }
+
+    if !case.steps.is_empty() {
+        let log: Vec<String> = case
+            .steps
+            .iter()
+            .map(|s| {
+                let status = s.status_code.map(|c| format!(" HTTP {}", c)).unwrap_or_default();
+                format!(
+                    "[{}] {} ({}ms){}",
+                    if s.passed { "PASS" } else { "FAIL" },
+                    s.name,
+                    s.duration_ms,
+                    status
+                )
+            })
+            .collect();
+        xml.push_str(&format!(
+            "      <system-out>{}</system-out>\n",
+            escape_markup(&log.join("\n"))
+        ));
+    }
+
+    xml.push_str("    </testcase>\n");
+}
+
+/// Failing steps with their assertion messages and response body
+fn failure_details(case: &ReportCase) -> String {
+    let mut lines = Vec::new();
+    for step in case.steps.iter().filter(|s| !s.passed) {
+        lines.push(format!("Step: {}", step.name));
+        if let Some(error) = &step.error {
+            lines.push(format!("Error: {}", error));
+        }
+        for assertion in &step.assertions {
+            lines.push(format!(
+                "  [{}] {}{}",
+                assertion.status.label(),
+                assertion.name,
+                assertion.message.as_deref().map(|m| format!(": {}", m)).unwrap_or_default()
+            ));
+        }
+        if let Some(body) = &step.response_body {
+            lines.push(format!("Response:\n{}", body));
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporting::tests::sample_report;
+
+    #[test]
+    fn test_render_junit() {
+        let xml = render(&sample_report());
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+        assert!(xml.contains("<testsuites name=\"Orders\" tests=\"2\" failures=\"1\" errors=\"0\" time=\"1.620\""));
+        assert!(xml.contains("<testsuite id=\"0\" name=\"Order Suite\" tests=\"2\" failures=\"1\""));
+        assert!(xml.contains("<testcase name=\"Get Order\" classname=\"Order Suite\" time=\"0.120\">"));
+        assert!(xml.contains("<testcase name=\"Create &lt;Order&gt; &amp; Pay\""));
+        assert!(xml.contains("<failure message=\"CreateOrder: HTTP Status: Status 500 not in expected [200]\""));
+        assert!(xml.contains("Response:\n&lt;Fault/&gt;</failure>"));
+        assert_eq!(xml.matches("<failure").count(), 1);
+
+        // Must be well-formed
+        assert!(sxd_document::parser::parse(&xml).is_ok());
+    }
+}
@@ -0,0 +1,459 @@
+/// Test Reports
+///
+/// Converts test case, test step and workflow results into a common report
+/// model and renders it as JUnit XML, TAP or a self-contained HTML page so
+/// results can be published by build servers.
+pub mod commands;
+pub mod html;
+pub mod junit;
+pub mod tap;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::testing::commands::TestCaseRunResult;
+use crate::testing::frontend_types::{StepExecutionResult, StepRunRecord, TestCaseExecutionResult};
+use crate::testing::test_runner::{TestCaseResult, TestStepResult};
+use crate::workflow::{WorkflowResult, WorkflowStepResult};
+
+pub use commands::export_test_report;
+
+/// Top-level report: a named collection of suites
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TestReport {
+    pub name: String,
+    /// RFC 3339 timestamp of when the run started
+    pub timestamp: String,
+    #[serde(default)]
+    pub suites: Vec<ReportSuite>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportSuite {
+    pub name: String,
+    #[serde(default)]
+    pub cases: Vec<ReportCase>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportCase {
+    pub name: String,
+    pub passed: bool,
+    pub duration_ms: u64,
+    /// Summary of why the case failed (first failing step)
+    pub failure: Option<String>,
+    #[serde(default)]
+    pub steps: Vec<ReportStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportStep {
+    pub name: String,
+    pub passed: bool,
+    pub duration_ms: u64,
+    pub status_code: Option<u16>,
+    pub request_body: Option<String>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub assertions: Vec<ReportAssertion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportAssertion {
+    pub name: String,
+    pub status: ReportStatus,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+impl ReportStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReportStatus::Passed => "PASS",
+            ReportStatus::Failed => "FAIL",
+            ReportStatus::Skipped => "SKIP",
+        }
+    }
+}
+
+/// Output format for a report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Junit,
+    Tap,
+    Html,
+}
+
+impl FromStr for ReportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "junit" | "xml" => Ok(ReportFormat::Junit),
+            "tap" => Ok(ReportFormat::Tap),
+            "html" => Ok(ReportFormat::Html),
+            other => Err(anyhow!("Unknown report format '{}' (expected junit, tap or html)", other)),
+        }
+    }
+}
+
+impl ReportFormat {
+    pub fn render(&self, report: &TestReport) -> String {
+        match self {
+            ReportFormat::Junit => junit::render(report),
+            ReportFormat::Tap => tap::render(report),
+            ReportFormat::Html => html::render(report),
+        }
+    }
+}
+
+/// Render `report` in `format` and write it to `path`, creating parent folders.
+pub fn write_report(report: &TestReport, format: ReportFormat, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, format.render(report))?;
+    Ok(())
+}
+
+impl TestReport {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            suites: Vec::new(),
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.suites.iter().map(|s| s.cases.len()).sum()
+    }
+
+    pub fn failures(&self) -> usize {
+        self.suites.iter().map(ReportSuite::failures).sum()
+    }
+
+    pub fn duration_ms(&self) -> u64 {
+        self.suites.iter().map(ReportSuite::duration_ms).sum()
+    }
+}
+
+impl ReportSuite {
+    pub fn new(name: impl Into<String>, cases: Vec<ReportCase>) -> Self {
+        Self { name: name.into(), cases }
+    }
+
+    pub fn failures(&self) -> usize {
+        self.cases.iter().filter(|c| !c.passed).count()
+    }
+
+    pub fn duration_ms(&self) -> u64 {
+        self.cases.iter().map(|c| c.duration_ms).sum()
+    }
+}
+
+impl ReportCase {
+    fn from_steps(name: &str, passed: bool, duration_ms: u64, steps: Vec<ReportStep>) -> Self {
+        let failure = if passed {
+            None
+        } else {
+            Some(
+                steps
+                    .iter()
+                    .find(|s| !s.passed)
+                    .map(|s| format!("{}: {}", s.name, s.error.as_deref().unwrap_or("failed")))
+                    .unwrap_or_else(|| "Test case failed".to_string()),
+            )
+        };
+
+        Self {
+            name: name.to_string(),
+            passed,
+            duration_ms,
+            failure,
+            steps,
+        }
+    }
+}
+
+impl From<&TestCaseResult> for ReportCase {
+    fn from(result: &TestCaseResult) -> Self {
+        let steps = result.step_results.iter().map(ReportStep::from).collect();
+        ReportCase::from_steps(&result.test_name, result.passed, result.total_duration_ms, steps)
+    }
+}
+
+impl From<&TestCaseRunResult> for ReportCase {
+    fn from(result: &TestCaseRunResult) -> Self {
+        let steps = result.step_results.iter().map(ReportStep::from).collect();
+        let name = match &result.data_row {
+            Some(row) => row.label(&result.test_case_name),
+            None => result.test_case_name.clone(),
+        };
+        ReportCase::from_steps(&name, result.passed, result.duration_ms, steps)
+    }
+}
+
+impl From<&TestCaseExecutionResult> for ReportCase {
+    fn from(result: &TestCaseExecutionResult) -> Self {
+        let steps = result.steps.iter().map(ReportStep::from).collect();
+        let name = match &result.data_row {
+            Some(row) => row.label(&result.name),
+            None => result.name.clone(),
+        };
+        ReportCase::from_steps(&name, result.passed, result.duration_ms, steps)
+    }
+}
+
+impl From<&TestStepResult> for ReportStep {
+    fn from(result: &TestStepResult) -> Self {
+        let assertions = result
+            .assertion_results
+            .iter()
+            .map(|a| ReportAssertion {
+                name: a.assertion_type.clone(),
+                status: if a.passed { ReportStatus::Passed } else { ReportStatus::Failed },
+                message: Some(a.message.clone()),
+            })
+            .collect();
+
+        Self {
+            name: result.step_name.clone(),
+            passed: result.passed,
+            duration_ms: result.duration_ms,
+            status_code: result.status_code,
+            request_body: None,
+            response_body: result.response_body.clone(),
+            error: result.error.clone(),
+            assertions,
+        }
+    }
+}
+
+impl ReportStep {
+    /// Build a step from a frontend runner result, which carries no name itself.
+    pub fn from_execution(name: &str, result: &StepExecutionResult) -> Self {
+        let assertions = result
+            .assertion_results
+            .iter()
+            .map(|a| ReportAssertion {
+                name: a.name.clone(),
+                status: match a.status.as_str() {
+                    "PASS" => ReportStatus::Passed,
+                    "FAIL" => ReportStatus::Failed,
+                    _ => ReportStatus::Skipped,
+                },
+                message: a.message.clone(),
+            })
+            .collect();
+
+        Self {
+            name: name.to_string(),
+            passed: result.passed,
+            duration_ms: result.duration_ms,
+            status_code: result.status_code,
+            request_body: result.request_body.clone(),
+            response_body: result.response_body.clone(),
+            error: result.error.clone(),
+            assertions,
+        }
+    }
+}
+
+impl From<&StepRunRecord> for ReportStep {
+    fn from(record: &StepRunRecord) -> Self {
+        ReportStep::from_execution(&record.step_name, &record.result)
+    }
+}
+
+impl From<&WorkflowResult> for ReportSuite {
+    /// Each top-level workflow step becomes a case; nested steps are flattened
+    /// into that case's steps with a `parent / child` path as the name.
+    fn from(result: &WorkflowResult) -> Self {
+        let cases = result
+            .step_results
+            .iter()
+            .map(|step| {
+                let mut steps = Vec::new();
+                flatten_workflow_step(step, None, &mut steps);
+                ReportCase::from_steps(&step.step_name, step.passed, step.duration_ms, steps)
+            })
+            .collect();
+
+        ReportSuite::new(result.workflow_name.clone(), cases)
+    }
+}
+
+fn flatten_workflow_step(step: &WorkflowStepResult, parent: Option<&str>, out: &mut Vec<ReportStep>) {
+    let name = match parent {
+        Some(parent) => format!("{} / {}", parent, step.step_name),
+        None => step.step_name.clone(),
+    };
+
+    out.push(ReportStep {
+        name: name.clone(),
+        passed: step.passed,
+        duration_ms: step.duration_ms,
+        status_code: None,
+        request_body: None,
+        response_body: None,
+        error: step.error.clone(),
+        assertions: vec![],
+    });
+
+    for child in step.child_results.iter().flatten() {
+        flatten_workflow_step(child, Some(&name), out);
+    }
+}
+
+/// Escape text for XML/HTML element content and attribute values.
+pub(crate) fn escape_markup(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Control characters other than tab/newline/CR are not allowed in XML 1.0
+            c if (c as u32) < 0x20 && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Format milliseconds as seconds with millisecond precision (`1.234`).
+pub(crate) fn seconds(ms: u64) -> String {
+    format!("{}.{:03}", ms / 1000, ms % 1000)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// One passing and one failing case, shared by the writer tests
+    pub(crate) fn sample_report() -> TestReport {
+        let passing = ReportCase::from_steps(
+            "Get Order",
+            true,
+            120,
+            vec![ReportStep {
+                name: "GetOrder".to_string(),
+                passed: true,
+                duration_ms: 120,
+                status_code: Some(200),
+                request_body: Some("<GetOrder><id>1</id></GetOrder>".to_string()),
+                response_body: Some("<Order id=\"1\"/>".to_string()),
+                error: None,
+                assertions: vec![ReportAssertion {
+                    name: "HTTP Status".to_string(),
+                    status: ReportStatus::Passed,
+                    message: None,
+                }],
+            }],
+        );
+        let failing = ReportCase::from_steps(
+            "Create <Order> & Pay",
+            false,
+            1500,
+            vec![ReportStep {
+                name: "CreateOrder".to_string(),
+                passed: false,
+                duration_ms: 1500,
+                status_code: Some(500),
+                request_body: None,
+                response_body: Some("<Fault/>".to_string()),
+                error: Some("HTTP Status: Status 500 not in expected [200]".to_string()),
+                assertions: vec![ReportAssertion {
+                    name: "HTTP Status".to_string(),
+                    status: ReportStatus::Failed,
+                    message: Some("Status 500 not in expected [200]".to_string()),
+                }],
+            }],
+        );
+
+        TestReport {
+            name: "Orders".to_string(),
+            timestamp: "2024-01-01T00:00:00+00:00".to_string(),
+            suites: vec![ReportSuite::new("Order Suite", vec![passing, failing])],
+        }
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("JUnit".parse::<ReportFormat>().unwrap(), ReportFormat::Junit);
+        assert_eq!("tap".parse::<ReportFormat>().unwrap(), ReportFormat::Tap);
+        assert_eq!("html".parse::<ReportFormat>().unwrap(), ReportFormat::Html);
+        assert!("pdf".parse::<ReportFormat>().is_err());
+    }
+
+    #[test]
+    fn test_case_failure_summary_uses_first_failing_step() {
+        let report = sample_report();
+        assert_eq!(report.total(), 2);
+        assert_eq!(report.failures(), 1);
+        assert_eq!(
+            report.suites[0].cases[1].failure.as_deref(),
+            Some("CreateOrder: HTTP Status: Status 500 not in expected [200]")
+        );
+    }
+
+    #[test]
+    fn test_workflow_result_flattens_children() {
+        let workflow = WorkflowResult {
+            workflow_name: "Checkout".to_string(),
+            passed: false,
+            aborted: false,
+            total_duration_ms: 30,
+            step_results: vec![WorkflowStepResult {
+                step_name: "Loop".to_string(),
+                node_type: "loop".to_string(),
+                passed: false,
+                duration_ms: 30,
+                error: None,
+                child_results: Some(vec![WorkflowStepResult {
+                    step_name: "Pay".to_string(),
+                    node_type: "sequential".to_string(),
+                    passed: false,
+                    duration_ms: 10,
+                    error: Some("declined".to_string()),
+                    child_results: None,
+                }]),
+            }],
+            variables: HashMap::new(),
+        };
+
+        let suite = ReportSuite::from(&workflow);
+        assert_eq!(suite.name, "Checkout");
+        assert_eq!(suite.cases.len(), 1);
+        let steps = &suite.cases[0].steps;
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[1].name, "Loop / Pay");
+        assert_eq!(suite.cases[0].failure.as_deref(), Some("Loop: failed"));
+    }
+
+    #[test]
+    fn test_escape_markup() {
+        assert_eq!(escape_markup("a<b>&\"c'\u{1}"), "a&lt;b&gt;&amp;&quot;c&apos;");
+        assert_eq!(seconds(1234), "1.234");
+        assert_eq!(seconds(5), "0.005");
+    }
+}
@@ -0,0 +1,110 @@
+/// TAP (Test Anything Protocol) version 13 writer
+///
+/// Each test case is one test point named `suite / case`. Failures carry a
+/// YAML diagnostic block with the failing steps and assertion messages.
+use super::{ReportCase, TestReport};
+
+pub fn render(report: &TestReport) -> String {
+    let mut out = String::from("TAP version 13\n");
+    out.push_str(&format!("1..{}\n", report.total()));
+
+    let mut number = 0;
+    for suite in &report.suites {
+        for case in &suite.cases {
+            number += 1;
+            out.push_str(&format!(
+                "{} {} - {} / {}\n",
+                if case.passed { "ok" } else { "not ok" },
+                number,
+                sanitize_description(&suite.name),
+                sanitize_description(&case.name),
+            ));
+
+            if !case.passed {
+                render_diagnostics(&mut out, case);
+            }
+        }
+    }
+
+    out
+}
+
+fn render_diagnostics(out: &mut String, case: &ReportCase) {
+    out.push_str("  ---\n");
+    out.push_str(&format!(
+        "  message: {}\n",
+        yaml_string(case.failure.as_deref().unwrap_or("Test case failed"))
+    ));
+    out.push_str(&format!("  duration_ms: {}\n", case.duration_ms));
+
+    let failed: Vec<_> = case.steps.iter().filter(|s| !s.passed).collect();
+    if !failed.is_empty() {
+        out.push_str("  steps:\n");
+        for step in failed {
+            out.push_str(&format!("    - name: {}\n", yaml_string(&step.name)));
+            if let Some(code) = step.status_code {
+                out.push_str(&format!("      status: {}\n", code));
+            }
+            if let Some(error) = &step.error {
+                out.push_str(&format!("      error: {}\n", yaml_string(error)));
+            }
+            let failed_assertions: Vec<_> = step
+                .assertions
+                .iter()
+                .filter(|a| a.status == super::ReportStatus::Failed)
+                .collect();
+            if !failed_assertions.is_empty() {
+                out.push_str("      assertions:\n");
+                for a in failed_assertions {
+                    out.push_str(&format!(
+                        "        - {}\n",
+                        yaml_string(&format!(
+                            "{}: {}",
+                            a.name,
+                            a.message.as_deref().unwrap_or("failed")
+                        ))
+                    ));
+                }
+            }
+        }
+    }
+
+    out.push_str("  ...\n");
+}
+
+/// `#` starts a directive in TAP and newlines end the test line
+fn sanitize_description(text: &str) -> String {
+    text.replace('#', "\\#").replace(['\n', '\r'], " ")
+}
+
+/// Double-quoted YAML scalar (JSON strings are valid YAML)
+fn yaml_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporting::tests::sample_report;
+
+    #[test]
+    fn test_render_tap() {
+        let tap = render(&sample_report());
+        let lines: Vec<&str> = tap.lines().collect();
+
+        assert_eq!(lines[0], "TAP version 13");
+        assert_eq!(lines[1], "1..2");
+        assert_eq!(lines[2], "ok 1 - Order Suite / Get Order");
+        assert_eq!(lines[3], "not ok 2 - Order Suite / Create <Order> & Pay");
+        assert_eq!(lines[4], "  ---");
+        assert!(tap.contains("  message: \"CreateOrder: HTTP Status: Status 500 not in expected [200]\"\n"));
+        assert!(tap.contains("      status: 500\n"));
+        assert!(tap.contains("        - \"HTTP Status: Status 500 not in expected [200]\"\n"));
+        assert!(tap.ends_with("  ...\n"));
+    }
+
+    #[test]
+    fn test_sanitize_description() {
+        assert_eq!(sanitize_description("Case #1\nline"), "Case \\#1 line");
+    }
+}
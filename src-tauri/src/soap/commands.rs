@@ -76,6 +76,7 @@ mod tests {
             input: Some(json!("GetUserRequest")),
             output: json!("GetUserResponse"),
             full_schema: None,
+            output_schema: None,
             description: None,
             port_name: None,
         };
@@ -159,7 +159,7 @@ impl EnvelopeBuilder {
             }
         } else {
             // Simple type - generate value
-            let value = self.get_or_generate_value(&current_path, &node.node_type);
+            let value = self.get_or_generate_value(&current_path, node);
             if !value.is_empty() {
                 xml.push_str(&format!("{}  {}\n", indent, value));
             }
@@ -172,14 +172,19 @@ impl EnvelopeBuilder {
     }
     
     /// Get a user-provided value or generate a sample value
-    fn get_or_generate_value(&self, path: &str, xsd_type: &str) -> String {
+    fn get_or_generate_value(&self, path: &str, node: &SchemaNode) -> String {
         // Check if user provided a value
         if let Some(value) = self.values.get(path) {
             return xml_escape(value);
         }
         
+        // Enumerations only accept one of their values
+        if let Some(option) = node.options.as_ref().and_then(|o| o.first()) {
+            return xml_escape(option);
+        }
+        
         // Generate sample value based on XSD type
-        generate_sample_value(xsd_type)
+        generate_sample_value(&node.node_type)
     }
 }
 
@@ -302,6 +307,7 @@ mod tests {
             input: Some(json!("GetUserRequest")),
             output: json!("GetUserResponse"),
             full_schema: None,
+            output_schema: None,
             description: None,
             port_name: None,
         };
@@ -340,6 +346,7 @@ mod tests {
             input: Some(json!("ListOfContinentsByName")),
             output: json!("ListOfContinentsByNameResponse"),
             full_schema: Some(schema_node),
+            output_schema: None,
             description: None,
             port_name: None,
         };
@@ -364,6 +364,7 @@ fn parse_test_step(events: &[SoapEvent], pos: &mut usize, attrs: &HashMap<String
     let mut request_config: Option<Value> = None;
     let mut delay_ms: u64 = 0;
     let mut script_content = String::new();
+    let mut transfers: Vec<Value> = Vec::new();
 
     while *pos < events.len() {
         let (local, child_attrs) = match &events[*pos] {
@@ -478,6 +479,32 @@ fn parse_test_step(events: &[SoapEvent], pos: &mut usize, attrs: &HashMap<String
                             }
                         }
                     }
+                    "transfer" => {
+                        // One <con:transfers> element per property transfer
+                        while *pos < events.len() {
+                            let transfer_local = match &events[*pos] {
+                                SoapEvent::Start { local, .. } => {
+                                    let l = local.clone();
+                                    *pos += 1;
+                                    l
+                                }
+                                SoapEvent::End => {
+                                    *pos += 1;
+                                    break;
+                                }
+                                _ => {
+                                    *pos += 1;
+                                    continue;
+                                }
+                            };
+
+                            if transfer_local == "transfers" {
+                                transfers.push(parse_transfer(events, pos));
+                            } else {
+                                skip_element(events, pos);
+                            }
+                        }
+                    }
                     _ => skip_element(events, pos),
                 }
             }
@@ -514,7 +541,7 @@ fn parse_test_step(events: &[SoapEvent], pos: &mut usize, attrs: &HashMap<String
             "id": new_id(),
             "name": name,
             "type": "transfer",
-            "config": {},
+            "config": { "transfers": transfers },
         }),
         _ => json!({
             "id": new_id(),
@@ -525,6 +552,85 @@ fn parse_test_step(events: &[SoapEvent], pos: &mut usize, attrs: &HashMap<String
     }
 }
 
+/// Parse one `<con:transfers>` element into a PropertyTransfer Value.
+///
+/// Steps named `#TestCase#`, `#TestSuite#`, `#Project#` or `#Global#` are
+/// SoapUI property holders; their properties map to context variables.
+///
+/// We've already consumed the `Start` event.
+fn parse_transfer(events: &[SoapEvent], pos: &mut usize) -> Value {
+    let mut fields: HashMap<String, String> = HashMap::new();
+
+    while *pos < events.len() {
+        let local = match &events[*pos] {
+            SoapEvent::Start { local, .. } => {
+                let l = local.clone();
+                *pos += 1;
+                l
+            }
+            SoapEvent::End => {
+                *pos += 1;
+                break;
+            }
+            _ => {
+                *pos += 1;
+                continue;
+            }
+        };
+        let text = read_text(events, pos);
+        fields.insert(local, text);
+    }
+
+    let is_property_holder = |step: &str| {
+        matches!(step, "#TestCase#" | "#TestSuite#" | "#Project#" | "#Global#")
+    };
+    let field = |key: &str| fields.get(key).cloned().unwrap_or_default();
+
+    let mut transfer = serde_json::Map::new();
+    transfer.insert("name".into(), json!(field("name")));
+
+    let source_step = field("sourceStep");
+    if is_property_holder(&source_step) {
+        transfer.insert("sourceVariable".into(), json!(field("sourceType")));
+    } else {
+        transfer.insert("sourceStepId".into(), json!(source_step));
+        transfer.insert("sourceProperty".into(), json!(transfer_property(&field("sourceType"))));
+    }
+    if !field("sourcePath").is_empty() {
+        transfer.insert("sourcePath".into(), json!(field("sourcePath")));
+    }
+    let language = match field("type").as_str() {
+        "JSONPATH" => Some("JSONPath"),
+        "XQUERY" | "XPATH" => Some("XPath"),
+        _ => None,
+    };
+    if let Some(language) = language {
+        transfer.insert("pathLanguage".into(), json!(language));
+    }
+
+    let target_step = field("targetStep");
+    if is_property_holder(&target_step) {
+        transfer.insert("targetVariable".into(), json!(field("targetType")));
+    } else {
+        transfer.insert("targetStepId".into(), json!(target_step));
+        transfer.insert("targetProperty".into(), json!(transfer_property(&field("targetType"))));
+    }
+    if !field("targetPath").is_empty() {
+        transfer.insert("targetPath".into(), json!(field("targetPath")));
+    }
+
+    Value::Object(transfer)
+}
+
+/// Map a SoapUI step property name onto the transfer property names we run.
+fn transfer_property(soapui_type: &str) -> &str {
+    match soapui_type {
+        "ResponseAsXml" | "RawResponse" => "Response",
+        "RawRequest" => "Request",
+        other => other,
+    }
+}
+
 /// Parse one `<con:testCase>` element.
 ///
 /// We've already consumed the `Start` event.
@@ -990,3 +1096,72 @@ pub async fn import_soapui_xml(file_path: &str) -> Result<Vec<Value>, String> {
         )),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_step(xml: &str) -> Value {
+        let events = collect_events(xml);
+        let mut pos = 0usize;
+        match &events[pos] {
+            SoapEvent::Start { attrs, .. } => {
+                let attrs = attrs.clone();
+                pos += 1;
+                parse_test_step(&events, &mut pos, &attrs)
+            }
+            _ => panic!("expected a testStep element"),
+        }
+    }
+
+    #[test]
+    fn test_parse_transfer_step() {
+        let step = parse_step(
+            r#"<con:testStep xmlns:con="http://eviware.com/soapui/config" type="transfer" name="Pass session">
+                <con:settings/>
+                <con:config>
+                    <con:transfers setNullOnMissingSource="true">
+                        <con:name>session</con:name>
+                        <con:sourceType>Response</con:sourceType>
+                        <con:sourceStep>Login</con:sourceStep>
+                        <con:sourcePath><![CDATA[declare namespace ns='urn:auth'; //ns:session]]></con:sourcePath>
+                        <con:targetType>Request</con:targetType>
+                        <con:targetStep>GetOrders</con:targetStep>
+                        <con:targetPath>//session</con:targetPath>
+                        <con:type>XPATH</con:type>
+                    </con:transfers>
+                    <con:transfers>
+                        <con:name>order id</con:name>
+                        <con:sourceType>ResponseAsXml</con:sourceType>
+                        <con:sourceStep>GetOrders</con:sourceStep>
+                        <con:sourcePath>$.orders[0].id</con:sourcePath>
+                        <con:targetType>orderId</con:targetType>
+                        <con:targetStep>#TestCase#</con:targetStep>
+                        <con:type>JSONPATH</con:type>
+                    </con:transfers>
+                </con:config>
+            </con:testStep>"#,
+        );
+
+        assert_eq!(step["type"], "transfer");
+        let transfers = step["config"]["transfers"].as_array().unwrap();
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(
+            transfers[0],
+            json!({
+                "name": "session",
+                "sourceStepId": "Login",
+                "sourceProperty": "Response",
+                "sourcePath": "declare namespace ns='urn:auth'; //ns:session",
+                "pathLanguage": "XPath",
+                "targetStepId": "GetOrders",
+                "targetProperty": "Request",
+                "targetPath": "//session",
+            })
+        );
+        assert_eq!(transfers[1]["sourceProperty"], "Response");
+        assert_eq!(transfers[1]["pathLanguage"], "JSONPath");
+        assert_eq!(transfers[1]["targetVariable"], "orderId");
+        assert!(transfers[1].get("targetStepId").is_none());
+    }
+}
@@ -109,6 +109,14 @@ mod tests {
             content_type: None,
             response_headers: None,
             delay_ms: Some(100),
+            templated: false,
+            status_template: None,
+            scenario: None,
+            required_state: None,
+            new_state: None,
+            scenario_values: None,
+            request_validation: None,
+            fault_profile: None,
             hit_count: 0,
             recorded_at: None,
             recorded_from: None,
@@ -230,6 +238,14 @@ mod tests {
             content_type: None,
             response_headers: None,
             delay_ms: None,
+            templated: false,
+            status_template: None,
+            scenario: None,
+            required_state: None,
+            new_state: None,
+            scenario_values: None,
+            request_validation: None,
+            fault_profile: None,
             hit_count: i as u64,
             recorded_at: None,
             recorded_from: None,
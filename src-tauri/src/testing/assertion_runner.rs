@@ -4,9 +4,10 @@
 use anyhow::{Result, anyhow};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use sxd_document::parser;
-use sxd_xpath::{evaluate_xpath, Value};
 use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use crate::utils::{json_path, JsonPath, XPathEvaluator, XPathOptions};
+use apinox_wsdl_parser::{SchemaValidator, WsdlParser};
 
 /// Assertion type
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -16,6 +17,9 @@ pub enum AssertionType {
     XPath {
         xpath: String,
         expected: String,
+        /// Extra prefix → namespace URI bindings
+        #[serde(default)]
+        namespaces: HashMap<String, String>,
     },
     /// JSONPath assertion on JSON response
     #[serde(rename = "jsonpath")]
@@ -44,6 +48,13 @@ pub enum AssertionType {
     ResponseTime {
         max_ms: u64,
     },
+    /// SOAP body validates against the operation's output element in the WSDL
+    #[serde(rename = "schemacompliance")]
+    SchemaCompliance {
+        /// Path to the WSDL file, or the WSDL XML itself
+        wsdl: String,
+        operation: String,
+    },
 }
 
 /// Assertion result
@@ -89,10 +100,26 @@ pub fn run_assertion(
     response_body: &str,
     status_code: u16,
     response_time_ms: Option<u64>,
+) -> Result<AssertionResult> {
+    run_assertion_with(assertion, response_body, status_code, response_time_ms, &XPathOptions::default())
+}
+
+/// `run_assertion` with the request's XPath namespace options (e.g. its WSDL
+/// target namespace)
+pub fn run_assertion_with(
+    assertion: &AssertionType,
+    response_body: &str,
+    status_code: u16,
+    response_time_ms: Option<u64>,
+    xpath_options: &XPathOptions,
 ) -> Result<AssertionResult> {
     match assertion {
-        AssertionType::XPath { xpath, expected } => {
-            run_xpath_assertion(response_body, xpath, expected)
+        AssertionType::XPath { xpath, expected, namespaces } => {
+            let mut options = xpath_options.clone();
+            for (prefix, uri) in namespaces {
+                options = options.with_namespace(prefix, uri);
+            }
+            run_xpath_assertion(response_body, xpath, expected, &options)
         }
         AssertionType::JsonPath { path, expected } => {
             run_jsonpath_assertion(response_body, path, expected)
@@ -112,33 +139,15 @@ pub fn run_assertion(
         AssertionType::ResponseTime { max_ms } => {
             run_response_time_assertion(response_time_ms, *max_ms)
         }
+        AssertionType::SchemaCompliance { wsdl, operation } => {
+            run_schema_compliance_assertion(response_body, wsdl, operation)
+        }
     }
 }
 
-fn run_xpath_assertion(xml: &str, xpath: &str, expected: &str) -> Result<AssertionResult> {
-    let package = parser::parse(xml)
-        .map_err(|e| anyhow!("Failed to parse XML: {}", e))?;
-    let document = package.as_document();
-    
-    let value = evaluate_xpath(&document, xpath)
-        .map_err(|e| anyhow!("XPath evaluation failed: {}", e))?;
-    
-    let actual = match value {
-        Value::String(s) => s,
-        Value::Number(n) => n.to_string(),
-        Value::Boolean(b) => b.to_string(),
-        Value::Nodeset(nodes) => {
-            if nodes.size() == 0 {
-                String::new()
-            } else {
-                nodes.document_order()
-                    .iter()
-                    .map(|n| n.string_value())
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            }
-        }
-    };
+fn run_xpath_assertion(xml: &str, xpath: &str, expected: &str, options: &XPathOptions) -> Result<AssertionResult> {
+    // No match (or an expression that fails to evaluate) compares as empty
+    let actual = XPathEvaluator::evaluate_with(xml, xpath, options).unwrap_or_default();
     
     if actual == expected {
         Ok(AssertionResult::success(
@@ -158,9 +167,11 @@ fn run_xpath_assertion(xml: &str, xpath: &str, expected: &str) -> Result<Asserti
 fn run_jsonpath_assertion(json: &str, path: &str, expected: &str) -> Result<AssertionResult> {
     let value: JsonValue = serde_json::from_str(json)
         .map_err(|e| anyhow!("Failed to parse JSON: {}", e))?;
-    
-    // Simple JSONPath implementation (supports basic paths like $.user.name)
-    let actual = extract_json_path(&value, path)?;
+    let query = JsonPath::parse(path)?;
+
+    // Several matches are compared as a JSON array, e.g. `["a","b"]`
+    let actual = json_path::render_matches(&query.query(&value))
+        .ok_or_else(|| anyhow!("JSONPath '{}' returned no results", path))?;
     
     if actual == expected {
         Ok(AssertionResult::success(
@@ -177,26 +188,28 @@ fn run_jsonpath_assertion(json: &str, path: &str, expected: &str) -> Result<Asse
     }
 }
 
-fn extract_json_path(value: &JsonValue, path: &str) -> Result<String> {
-    let parts: Vec<&str> = path.trim_start_matches('$').trim_start_matches('.').split('.').collect();
-    let mut current = value;
-    
-    for part in parts {
-        if part.is_empty() {
-            continue;
-        }
-        
-        current = current.get(part)
-            .ok_or_else(|| anyhow!("Path '{}' not found", part))?;
+fn run_schema_compliance_assertion(xml: &str, wsdl: &str, operation: &str) -> Result<AssertionResult> {
+    let wsdl_xml = if wsdl.trim_start().starts_with('<') {
+        wsdl.to_string()
+    } else {
+        std::fs::read_to_string(wsdl).map_err(|e| anyhow!("Failed to read WSDL '{}': {}", wsdl, e))?
+    };
+    let schema = WsdlParser::response_schema(&wsdl_xml, operation)?;
+    let violations = SchemaValidator::validate(&schema, xml)?;
+
+    if violations.is_empty() {
+        Ok(AssertionResult::success(
+            "schemacompliance".to_string(),
+            format!("Response complies with the schema of '{}'", operation),
+        ))
+    } else {
+        Ok(AssertionResult::failure(
+            "schemacompliance".to_string(),
+            format!("Response has {} schema violation(s)", violations.len()),
+            Some(violations.iter().map(|v| v.to_string()).collect::<Vec<_>>().join("\n")),
+            Some(schema.element.name),
+        ))
     }
-    
-    Ok(match current {
-        JsonValue::String(s) => s.clone(),
-        JsonValue::Number(n) => n.to_string(),
-        JsonValue::Bool(b) => b.to_string(),
-        JsonValue::Null => "null".to_string(),
-        _ => current.to_string(),
-    })
 }
 
 fn run_regex_assertion(text: &str, pattern: &str, expected: Option<&str>) -> Result<AssertionResult> {
@@ -325,12 +338,35 @@ mod tests {
         let assertion = AssertionType::XPath {
             xpath: "/root/user/name".to_string(),
             expected: "John".to_string(),
+            namespaces: HashMap::new(),
         };
         
         let result = run_assertion(&assertion, xml, 200, None).unwrap();
         assert!(result.passed);
     }
     
+    #[test]
+    fn test_xpath_assertion_binds_namespaces() {
+        let xml = r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><m:LoginResponse xmlns:m="urn:auth"><m:Token>abc</m:Token></m:LoginResponse></s:Body></s:Envelope>"#;
+        let xpath = |path: &str, namespaces: HashMap<String, String>| AssertionType::XPath {
+            xpath: path.to_string(),
+            expected: "abc".to_string(),
+            namespaces,
+        };
+
+        // Prefixes bound by the assertion, and `tns` from the target namespace
+        let bound = xpath("//a:Token", HashMap::from([("a".to_string(), "urn:auth".to_string())]));
+        assert!(run_assertion(&bound, xml, 200, None).unwrap().passed);
+        let options = XPathOptions::default().with_target_namespace(Some("urn:auth".to_string()));
+        assert!(run_assertion_with(&xpath("//tns:Token", HashMap::new()), xml, 200, None, &options).unwrap().passed);
+        let function = AssertionType::XPath {
+            xpath: "ends-with(//tns:Token, 'bc')".to_string(),
+            expected: "true".to_string(),
+            namespaces: HashMap::new(),
+        };
+        assert!(run_assertion_with(&function, xml, 200, None, &options).unwrap().passed);
+    }
+
     #[test]
     fn test_jsonpath_assertion() {
         let json = r#"{"user":{"name":"John"}}"#;
@@ -342,6 +378,59 @@ mod tests {
         let result = run_assertion(&assertion, json, 200, None).unwrap();
         assert!(result.passed);
     }
+
+    #[test]
+    fn test_jsonpath_assertion_with_filter() {
+        let json = r#"{"orders":[{"id":"A1","total":20},{"id":"B2","total":45},{"id":"C3","total":70}]}"#;
+        let assert_path = |path: &str, expected: &str| {
+            let assertion = AssertionType::JsonPath {
+                path: path.to_string(),
+                expected: expected.to_string(),
+            };
+            run_assertion(&assertion, json, 200, None)
+        };
+
+        assert!(assert_path("$.orders[?@.total > 40 && @.total < 60].id", "B2").unwrap().passed);
+        assert!(assert_path("$.orders[1:].id", r#"["B2","C3"]"#).unwrap().passed);
+
+        let failed = assert_path("$.orders[0].total", "21").unwrap();
+        assert!(!failed.passed);
+        assert_eq!(failed.actual.as_deref(), Some("20"));
+    }
+    
+    #[test]
+    fn test_schema_compliance_assertion() {
+        let wsdl = r#"<definitions xmlns:tns="urn:t" xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <types><xsd:schema targetNamespace="urn:t">
+    <xsd:element name="PingResponse"><xsd:complexType><xsd:sequence>
+      <xsd:element name="status"><xsd:simpleType><xsd:restriction base="xsd:string">
+        <xsd:enumeration value="UP"/><xsd:enumeration value="DOWN"/>
+      </xsd:restriction></xsd:simpleType></xsd:element>
+      <xsd:element name="latency" type="xsd:int"/>
+    </xsd:sequence></xsd:complexType></xsd:element>
+  </xsd:schema></types>
+  <message name="PingOut"><part name="parameters" element="tns:PingResponse"/></message>
+  <portType name="Health"><operation name="Ping"><output message="tns:PingOut"/></operation></portType>
+</definitions>"#;
+        let assertion = AssertionType::SchemaCompliance {
+            wsdl: wsdl.to_string(),
+            operation: "Ping".to_string(),
+        };
+        let envelope = |body: &str| {
+            format!(r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body>{}</s:Body></s:Envelope>"#, body)
+        };
+
+        let ok = run_assertion(&assertion, &envelope("<PingResponse><status>UP</status><latency>3</latency></PingResponse>"), 200, None).unwrap();
+        assert!(ok.passed);
+
+        let bad = run_assertion(&assertion, &envelope("<PingResponse><status>SIDEWAYS</status></PingResponse>"), 200, None).unwrap();
+        assert!(!bad.passed);
+        assert_eq!(bad.message, "Response has 2 schema violation(s)");
+        assert_eq!(
+            bad.actual.as_deref(),
+            Some("/PingResponse/status: Value 'SIDEWAYS' is not one of [UP, DOWN]\n/PingResponse: Missing required element 'latency'")
+        );
+    }
     
     #[test]
     fn test_contains_assertion() {
@@ -2,9 +2,11 @@
 /// 
 /// Frontend-facing commands for test execution
 use serde::{Deserialize, Serialize};
-use crate::testing::frontend_types::FrontendTestCase;
+use crate::testing::data_source::{self, DataRow};
+use crate::testing::frontend_types::{FrontendTestCase, TestCaseExecutionResult};
 use crate::testing::{TestRunner, TestCase, TestStepResult};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use once_cell::sync::Lazy;
 
@@ -18,6 +20,10 @@ pub struct RunTestCaseRequest {
     #[serde(default)]
     pub stream: bool,
     pub fallback_endpoint: Option<String>,
+    /// Directory of the project the case belongs to; relative data source
+    /// paths resolve against it
+    #[serde(default)]
+    pub project_dir: Option<String>,
 }
 
 /// Response for a streaming run — returns a run ID for polling.
@@ -27,6 +33,9 @@ pub struct RunTestCaseResponse {
     pub run_id: Option<String>,
     pub passed: Option<bool>,
     pub duration_ms: Option<u64>,
+    /// Per-run results of a synchronous run, one per data row
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub results: Option<Vec<TestCaseExecutionResult>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -43,6 +52,10 @@ pub struct RunTestSuiteRequest {
     pub variables: Option<HashMap<String, String>>,
     #[serde(default)]
     pub stream: bool,
+    /// Directory of the project the suite belongs to; relative data source
+    /// paths resolve against it
+    #[serde(default)]
+    pub project_dir: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -58,6 +71,9 @@ pub struct TestCaseRunResult {
     pub passed: bool,
     pub step_results: Vec<TestStepResult>,
     pub duration_ms: u64,
+    /// The data source row this run used, for data-driven cases
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_row: Option<DataRow>,
 }
 
 #[derive(Debug, Clone)]
@@ -76,10 +92,19 @@ static TEST_RUN_STORE: Lazy<Arc<Mutex<HashMap<String, TestRunData>>>> =
 /// When `stream = true` (the default from the webview), the command spawns a
 /// background task and immediately returns a `run_id` that the frontend uses to
 /// poll `get_test_run_updates` for per-step progress events.
+///
+/// Cases with a data source run once per row, each run starting from the
+/// request's variables plus the row's columns; every run's `testCaseStart`
+/// and `testCaseComplete` events carry its `dataRow`.
 #[tauri::command]
 pub async fn run_test_case(request: RunTestCaseRequest) -> Result<RunTestCaseResponse, String> {
     log::info!("[run_test_case] Starting: '{}' (stream={})", request.test_case.name, request.stream);
 
+    // Data-driven cases run once per row; a missing data file fails the run up front
+    let project_dir = request.project_dir.map(PathBuf::from);
+    let rows = data_source::iterations(request.test_case.data_source.as_ref(), project_dir.as_deref())
+        .map_err(|e| format!("Test case {} failed: {:#}", request.test_case.name, e))?;
+
     if request.stream {
         // --- Streaming mode ---
         let run_id = format!(
@@ -99,77 +124,16 @@ pub async fn run_test_case(request: RunTestCaseRequest) -> Result<RunTestCaseRes
         let run_id_clone = run_id.clone();
         let test_case = request.test_case;
         let fallback_endpoint = request.fallback_endpoint;
-        let initial_vars: HashMap<String, serde_json::Value> = request
-            .variables
-            .unwrap_or_default()
-            .into_iter()
-            .map(|(k, v)| (k, serde_json::Value::String(v)))
-            .collect();
+        let initial_vars = request.variables.unwrap_or_default();
 
         tokio::spawn(async move {
-            use crate::testing::frontend_runner::run_step;
-
-            let case_id = test_case.id.clone();
-
-            // Emit testCaseStart
-            push_update(&run_id_clone, serde_json::json!({
-                "type": "testCaseStart",
-                "id": case_id
-            }));
-
-            let mut context = initial_vars;
             let mut all_passed = true;
-
-            for step in &test_case.steps {
-                let step_id = step.id.clone();
-
-                // Emit stepStart
-                push_update(&run_id_clone, serde_json::json!({
-                    "type": "stepStart",
-                    "caseId": case_id,
-                    "stepId": step_id
-                }));
-
-                match run_step(step, fallback_endpoint.as_deref(), &mut context).await {
-                    Ok(result) => {
-                        // Merge extracted variables into context for next steps
-                        for (k, v) in &result.extracted_variables {
-                            context.insert(k.clone(), serde_json::Value::String(v.clone()));
-                        }
-
-                        let update_type = if result.passed { "stepPass" } else { "stepFail" };
-                        if !result.passed {
-                            all_passed = false;
-                        }
-
-                        push_update(&run_id_clone, serde_json::json!({
-                            "type": update_type,
-                            "caseId": case_id,
-                            "stepId": step_id,
-                            "response": {
-                                "rawResponse": result.response_body,
-                                "duration": result.duration_ms,
-                                "statusCode": result.status_code
-                            },
-                            "assertionResults": result.assertion_results,
-                            "error": result.error
-                        }));
-
-                        if !result.passed {
-                            break; // Stop on first failure
-                        }
-                    }
-                    Err(e) => {
-                        all_passed = false;
-                        push_update(&run_id_clone, serde_json::json!({
-                            "type": "stepFail",
-                            "caseId": case_id,
-                            "stepId": step_id,
-                            "error": e.to_string()
-                        }));
-                        break;
-                    }
+            for row in rows {
+                let mut variables = initial_vars.clone();
+                if let Some(row) = &row {
+                    variables.extend(row.values.clone());
                 }
+                all_passed &= stream_test_case_row(&run_id_clone, &test_case, fallback_endpoint.as_deref(), variables, row).await;
             }
 
             log::info!("[run_test_case] '{}' {} (streamed)",
@@ -182,38 +146,33 @@ pub async fn run_test_case(request: RunTestCaseRequest) -> Result<RunTestCaseRes
             run_id: Some(run_id),
             passed: None,
             duration_ms: None,
+            results: None,
         })
     } else {
         // --- Synchronous mode (not used by webview but kept for completeness) ---
-        use crate::testing::frontend_runner::run_step;
-
-        let start = std::time::Instant::now();
-        let mut context: HashMap<String, serde_json::Value> = request
-            .variables
-            .unwrap_or_default()
-            .into_iter()
-            .map(|(k, v)| (k, serde_json::Value::String(v)))
-            .collect();
+        use crate::testing::frontend_runner::{run_test_case, RunContext};
 
-        let mut all_passed = true;
-        for step in &request.test_case.steps {
-            match run_step(step, request.fallback_endpoint.as_deref(), &mut context).await {
-                Ok(result) => {
-                    for (k, v) in &result.extracted_variables {
-                        context.insert(k.clone(), serde_json::Value::String(v.clone()));
-                    }
-                    if !result.passed {
-                        all_passed = false;
-                        break;
-                    }
-                }
-                Err(e) => {
-                    return Err(format!("Step failed: {}", e));
-                }
+        let initial_vars = request.variables.unwrap_or_default();
+        let mut results = Vec::new();
+        for row in rows {
+            let mut variables = initial_vars.clone();
+            if let Some(row) = &row {
+                variables.extend(row.values.clone());
             }
+            let mut context = RunContext::new(variables);
+
+            let mut result = run_test_case(
+                &request.test_case,
+                request.fallback_endpoint.as_deref(),
+                &mut context,
+            )
+            .await;
+            result.data_row = row;
+            results.push(result);
         }
 
-        let duration_ms = start.elapsed().as_millis() as u64;
+        let all_passed = results.iter().all(|r| r.passed);
+        let duration_ms = results.iter().map(|r| r.duration_ms).sum();
         log::info!("[run_test_case] '{}' {} ({}ms)",
             request.test_case.name,
             if all_passed { "PASSED" } else { "FAILED" },
@@ -223,10 +182,99 @@ pub async fn run_test_case(request: RunTestCaseRequest) -> Result<RunTestCaseRes
             run_id: None,
             passed: Some(all_passed),
             duration_ms: Some(duration_ms),
+            results: Some(results),
         })
     }
 }
 
+/// Stream one run of a test case (one data row, or the only run of a case
+/// without a data source), stopping at the first failing step. Returns
+/// whether every step passed.
+async fn stream_test_case_row(
+    run_id: &str,
+    test_case: &FrontendTestCase,
+    fallback_endpoint: Option<&str>,
+    variables: HashMap<String, String>,
+    row: Option<DataRow>,
+) -> bool {
+    use crate::testing::frontend_runner::{run_step, RunContext};
+
+    let case_id = test_case.id.clone();
+
+    // Emit testCaseStart
+    push_update(run_id, serde_json::json!({
+        "type": "testCaseStart",
+        "id": case_id,
+        "dataRow": row
+    }));
+
+    let mut context = RunContext::new(variables);
+    context.register_steps(&test_case.steps);
+    let mut all_passed = true;
+
+    for step in &test_case.steps {
+        let step_id = step.id.clone();
+
+        // Emit stepStart
+        push_update(run_id, serde_json::json!({
+            "type": "stepStart",
+            "caseId": case_id,
+            "stepId": step_id
+        }));
+
+        match run_step(step, fallback_endpoint, &mut context).await {
+            Ok(result) => {
+                // Merge extracted variables into context for next steps
+                context.record(step, &result);
+
+                let update_type = if result.passed { "stepPass" } else { "stepFail" };
+                if !result.passed {
+                    all_passed = false;
+                }
+
+                push_update(run_id, serde_json::json!({
+                    "type": update_type,
+                    "caseId": case_id,
+                    "stepId": step_id,
+                    "response": {
+                        "rawResponse": result.response_body,
+                        "duration": result.duration_ms,
+                        "statusCode": result.status_code
+                    },
+                    "assertionResults": result.assertion_results,
+                    "logs": result.logs,
+                    "transferResults": result.transfer_results,
+                    "error": result.error
+                }));
+
+                if !result.passed {
+                    break; // Stop on first failure
+                }
+            }
+            Err(e) => {
+                all_passed = false;
+                push_update(run_id, serde_json::json!({
+                    "type": "stepFail",
+                    "caseId": case_id,
+                    "stepId": step_id,
+                    "error": e.to_string()
+                }));
+                break;
+            }
+        }
+    }
+
+    // Emit testCaseComplete, one per data row
+    push_update(run_id, serde_json::json!({
+        "type": "testCaseComplete",
+        "id": case_id,
+        "passed": all_passed,
+        "dataRow": row
+    }));
+
+    all_passed
+}
+
 fn push_update(run_id: &str, update: serde_json::Value) {
     if let Ok(mut store) = TEST_RUN_STORE.lock() {
         if let Some(run) = store.get_mut(run_id) {
@@ -273,6 +321,7 @@ pub async fn run_test_suite(request: RunTestSuiteRequest) -> Result<RunTestSuite
         let run_id_clone = run_id.clone();
         let suite = request.test_suite;
         let initial_vars = request.variables.clone();
+        let project_dir = request.project_dir.map(PathBuf::from);
         
         // Spawn background task
         tokio::spawn(async move {
@@ -286,32 +335,31 @@ pub async fn run_test_suite(request: RunTestSuiteRequest) -> Result<RunTestSuite
             }
             
             for test_case in &suite.test_cases {
-                let start = std::time::Instant::now();
-                
-                match runner.run_test_case(test_case).await {
-                    Ok(result) => {
-                        let duration_ms = start.elapsed().as_millis() as u64;
-                        
-                        let update = serde_json::json!({
-                            "type": "testCaseComplete",
-                            "testCaseName": test_case.name,
-                            "passed": result.passed,
-                            "stepResults": result.step_results,
-                            "durationMs": duration_ms,
-                        });
-                        
-                        if let Ok(mut store) = TEST_RUN_STORE.lock() {
-                            if let Some(run) = store.get_mut(&run_id_clone) {
-                                run.updates.push(update);
+                match run_suite_case(&mut runner, test_case, project_dir.as_deref()).await {
+                    Ok(results) => {
+                        for result in results {
+                            let update = serde_json::json!({
+                                "type": "testCaseComplete",
+                                "testCaseName": result.test_case_name,
+                                "passed": result.passed,
+                                "stepResults": result.step_results,
+                                "durationMs": result.duration_ms,
+                                "dataRow": result.data_row,
+                            });
+
+                            if let Ok(mut store) = TEST_RUN_STORE.lock() {
+                                if let Some(run) = store.get_mut(&run_id_clone) {
+                                    run.updates.push(update);
+                                }
                             }
                         }
                     },
                     Err(e) => {
-                        log::error!("Test case {} failed: {}", test_case.name, e);
-                        
+                        log::error!("{}", e);
+
                         if let Ok(mut store) = TEST_RUN_STORE.lock() {
                             if let Some(run) = store.get_mut(&run_id_clone) {
-                                run.error = Some(format!("Test case {} failed: {}", test_case.name, e));
+                                run.error = Some(e);
                                 run.done = true;
                             }
                         }
@@ -348,30 +396,17 @@ pub async fn run_test_suite(request: RunTestSuiteRequest) -> Result<RunTestSuite
             }
         }
         
+        let project_dir = request.project_dir.map(PathBuf::from);
         for test_case in &request.test_suite.test_cases {
-            let start = std::time::Instant::now();
-            
-            match runner.run_test_case(test_case).await {
-                Ok(result) => {
-                    let duration_ms = start.elapsed().as_millis() as u64;
-                    let passed = result.passed;
-                    
-                    results.push(TestCaseRunResult {
-                        test_case_name: test_case.name.clone(),
-                        passed,
-                        step_results: result.step_results,
-                        duration_ms,
-                    });
-                    
-                    if !passed {
-                        all_passed = false;
-                    }
-                },
-                Err(e) => {
-                    log::error!("Test case {} failed: {}", test_case.name, e);
-                    return Err(format!("Test case {} failed: {}", test_case.name, e));
-                }
+            let case_results = run_suite_case(&mut runner, test_case, project_dir.as_deref()).await.map_err(|e| {
+                log::error!("{}", e);
+                e
+            })?;
+
+            if case_results.iter().any(|r| !r.passed) {
+                all_passed = false;
             }
+            results.extend(case_results);
         }
         
         log::info!("Test suite completed: {} ({})", 
@@ -387,6 +422,46 @@ pub async fn run_test_suite(request: RunTestSuiteRequest) -> Result<RunTestSuite
     }
 }
 
+/// Run one test case of a suite, once per data source row when it has one.
+///
+/// Each row starts from the runner's variables plus the row's columns; the
+/// variables are restored afterwards so rows do not leak into each other.
+/// Relative data source paths resolve against `project_dir`.
+async fn run_suite_case(
+    runner: &mut TestRunner,
+    test_case: &TestCase,
+    project_dir: Option<&Path>,
+) -> Result<Vec<TestCaseRunResult>, String> {
+    let rows = data_source::iterations(test_case.data_source.as_ref(), project_dir)
+        .map_err(|e| format!("Test case {} failed: {:#}", test_case.name, e))?;
+
+    let mut results = Vec::new();
+    for row in rows {
+        let saved = row.as_ref().map(|row| {
+            let saved = runner.variables.clone();
+            runner.variables.extend(row.values.clone());
+            saved
+        });
+
+        let start = std::time::Instant::now();
+        let outcome = runner.run_test_case(test_case).await;
+        if let Some(saved) = saved {
+            runner.variables = saved;
+        }
+
+        let result = outcome.map_err(|e| format!("Test case {} failed: {}", test_case.name, e))?;
+        results.push(TestCaseRunResult {
+            test_case_name: test_case.name.clone(),
+            passed: result.passed,
+            step_results: result.step_results,
+            duration_ms: start.elapsed().as_millis() as u64,
+            data_row: row,
+        });
+    }
+
+    Ok(results)
+}
+
 /// Get test run updates (for streaming mode)
 #[tauri::command]
 pub async fn get_test_run_updates(
@@ -432,3 +507,96 @@ pub struct TestRunUpdatesResponse {
     pub done: bool,
     pub error: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::data_source::DataSource;
+
+    #[tokio::test]
+    async fn test_suite_case_runs_once_per_data_row() {
+        // The data file is given relative to the project directory
+        let project_dir = std::env::temp_dir().join(format!("apinox-suite-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(project_dir.join("data")).unwrap();
+        std::fs::write(project_dir.join("data/users.csv"), "user,id\nalice,1\nbob,2\n").unwrap();
+
+        let test_case = TestCase {
+            name: "Lookup".to_string(),
+            description: None,
+            steps: vec![],
+            data_source: Some(DataSource {
+                path: "data/users.csv".to_string(),
+                format: None,
+            }),
+        };
+        let mut runner = TestRunner::new();
+        runner.set_variable("user".to_string(), "initial".to_string());
+
+        let results = run_suite_case(&mut runner, &test_case, Some(&project_dir)).await.unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.passed));
+        let row = results[1].data_row.as_ref().unwrap();
+        assert_eq!(row.index, 1);
+        assert_eq!(row.values.get("user").map(String::as_str), Some("bob"));
+        // Row values do not leak into the runner's variables
+        assert_eq!(runner.get_variable("user").map(String::as_str), Some("initial"));
+        assert!(runner.get_variable("id").is_none());
+
+        std::fs::remove_dir_all(&project_dir).unwrap();
+
+        let missing = TestCase {
+            data_source: Some(DataSource { path: "does-not-exist.csv".to_string(), format: None }),
+            ..test_case
+        };
+        let err = run_suite_case(&mut runner, &missing, None).await.unwrap_err();
+        assert!(err.contains("Failed to read data source"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_case_runs_once_per_data_row() {
+        let project_dir = std::env::temp_dir().join(format!("apinox-case-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&project_dir).unwrap();
+        std::fs::write(project_dir.join("users.json"), r#"[{"user":"alice"},{"user":"bob"}]"#).unwrap();
+
+        let test_case = FrontendTestCase {
+            id: "tc1".to_string(),
+            name: "Lookup".to_string(),
+            steps: vec![],
+            data_source: Some(DataSource { path: "users.json".to_string(), format: None }),
+        };
+        let request = |stream: bool| RunTestCaseRequest {
+            test_case: test_case.clone(),
+            variables: None,
+            stream,
+            fallback_endpoint: None,
+            project_dir: Some(project_dir.to_string_lossy().to_string()),
+        };
+
+        let response = run_test_case(request(false)).await.unwrap();
+        assert_eq!(response.passed, Some(true));
+        let rows: Vec<_> = response.results.unwrap().into_iter().map(|r| r.data_row.unwrap()).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].values["user"], "bob");
+
+        let run_id = run_test_case(request(true)).await.unwrap().run_id.unwrap();
+        let mut updates = Vec::new();
+        for _ in 0..100 {
+            let batch = get_test_run_updates(run_id.clone(), Some(updates.len())).await.unwrap();
+            updates.extend(batch.updates);
+            if batch.done {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let completed: Vec<_> = updates.iter().filter(|u| u["type"] == "testCaseComplete").collect();
+        assert_eq!(completed.len(), 2);
+        assert_eq!(completed[0]["dataRow"]["values"]["user"], "alice");
+        assert_eq!(completed[1]["dataRow"]["index"], 1);
+
+        std::fs::remove_dir_all(&project_dir).unwrap();
+        let mut missing = request(false);
+        missing.project_dir = None;
+        assert!(run_test_case(missing).await.is_err());
+    }
+}
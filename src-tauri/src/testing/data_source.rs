@@ -0,0 +1,268 @@
+/// Data Sources
+///
+/// Loads rows for data-driven test cases. A test case that references a
+/// `DataSource` runs once per row, with the row's columns added to the
+/// variable context (`{{column}}`).
+///
+/// Supported files:
+/// - CSV / TSV — the first row holds the column names (spreadsheets can be
+///   exported to either)
+/// - JSON — an array of objects; non-string values are stored as JSON text
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// File format of a data source
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataSourceFormat {
+    Csv,
+    Tsv,
+    Json,
+}
+
+/// Data source referenced by a test case
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataSource {
+    /// Path to the data file; relative paths resolve against the project directory
+    pub path: String,
+    /// Inferred from the file extension when omitted
+    #[serde(default)]
+    pub format: Option<DataSourceFormat>,
+}
+
+/// One row of a data source
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataRow {
+    /// Zero-based position of the row in the data source
+    pub index: usize,
+    pub values: HashMap<String, String>,
+}
+
+impl DataSource {
+    /// Read and parse every row of the data source.
+    pub fn load(&self, base_dir: Option<&Path>) -> Result<Vec<DataRow>> {
+        let path = self.resolve_path(base_dir);
+        let format = self
+            .format
+            .or_else(|| format_from_extension(&path))
+            .ok_or_else(|| anyhow!("Cannot tell the format of data source '{}'; set csv, tsv or json", self.path))?;
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read data source '{}'", path.display()))?;
+
+        let rows = match format {
+            DataSourceFormat::Csv => parse_delimited(&content, ','),
+            DataSourceFormat::Tsv => parse_delimited(&content, '\t'),
+            DataSourceFormat::Json => parse_json(&content),
+        }
+        .with_context(|| format!("Invalid data source '{}'", path.display()))?;
+
+        if rows.is_empty() {
+            return Err(anyhow!("Data source '{}' has no rows", path.display()));
+        }
+        Ok(rows)
+    }
+
+    fn resolve_path(&self, base_dir: Option<&Path>) -> PathBuf {
+        let path = PathBuf::from(&self.path);
+        match base_dir {
+            Some(base) if path.is_relative() => base.join(path),
+            _ => path,
+        }
+    }
+}
+
+impl DataRow {
+    /// Name used to report this row's run of a test case, e.g. "Login [row 2]"
+    pub fn label(&self, case_name: &str) -> String {
+        format!("{} [row {}]", case_name, self.index + 1)
+    }
+}
+
+/// The iterations of a test case: one per row when it has a data source,
+/// otherwise a single run without row values.
+pub fn iterations(source: Option<&DataSource>, base_dir: Option<&Path>) -> Result<Vec<Option<DataRow>>> {
+    match source {
+        Some(source) => Ok(source.load(base_dir)?.into_iter().map(Some).collect()),
+        None => Ok(vec![None]),
+    }
+}
+
+fn format_from_extension(path: &Path) -> Option<DataSourceFormat> {
+    match path.extension()?.to_str()?.to_lowercase().as_str() {
+        "csv" => Some(DataSourceFormat::Csv),
+        "tsv" | "tab" => Some(DataSourceFormat::Tsv),
+        "json" => Some(DataSourceFormat::Json),
+        _ => None,
+    }
+}
+
+/// Parse delimited text with a header row. Fields may be quoted with `"`;
+/// quoted fields can contain the delimiter, newlines and `""` escapes.
+pub fn parse_delimited(content: &str, delimiter: char) -> Result<Vec<DataRow>> {
+    let content = content.trim_start_matches('\u{feff}');
+    let mut records = split_records(content, delimiter)?.into_iter();
+
+    let headers: Vec<String> = match records.next() {
+        Some(header) => header.into_iter().map(|h| h.trim().to_string()).collect(),
+        None => return Ok(vec![]),
+    };
+
+    Ok(records
+        .enumerate()
+        .map(|(index, fields)| DataRow {
+            index,
+            values: headers
+                .iter()
+                .zip(fields.into_iter().chain(std::iter::repeat(String::new())))
+                .filter(|(h, _)| !h.is_empty())
+                .map(|(h, v)| (h.clone(), v))
+                .collect(),
+        })
+        .collect())
+}
+
+fn split_records(content: &str, delimiter: char) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.push(std::mem::take(&mut field));
+                // Skip blank lines
+                if !(record.len() == 1 && record[0].is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+            }
+            c if c == delimiter => record.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(anyhow!("Unterminated quoted field"));
+    }
+    record.push(field);
+    if !(record.len() == 1 && record[0].is_empty()) {
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Parse a JSON array of objects.
+pub fn parse_json(content: &str) -> Result<Vec<DataRow>> {
+    let value: Value = serde_json::from_str(content)?;
+    let items = value
+        .as_array()
+        .ok_or_else(|| anyhow!("Expected a JSON array of objects"))?;
+
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let object = item
+                .as_object()
+                .ok_or_else(|| anyhow!("Row {} is not a JSON object", index + 1))?;
+            let values = object
+                .iter()
+                .map(|(k, v)| {
+                    let v = match v {
+                        Value::String(s) => s.clone(),
+                        Value::Null => String::new(),
+                        other => other.to_string(),
+                    };
+                    (k.clone(), v)
+                })
+                .collect();
+            Ok(DataRow { index, values })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_parse_csv_with_quotes() {
+        let csv = "\u{feff}user,password,note\r\nalice,s3cret,plain\r\n\r\nbob,\"pa,ss\",\"said \"\"hi\"\"\nover two lines\"\ncarol,x\n";
+        let rows = parse_delimited(csv, ',').unwrap();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].values, row(&[("user", "alice"), ("password", "s3cret"), ("note", "plain")]));
+        assert_eq!(rows[1].index, 1);
+        assert_eq!(rows[1].values["password"], "pa,ss");
+        assert_eq!(rows[1].values["note"], "said \"hi\"\nover two lines");
+        // Short rows are padded with empty values
+        assert_eq!(rows[2].values, row(&[("user", "carol"), ("password", "x"), ("note", "")]));
+        assert_eq!(rows[2].label("Login"), "Login [row 3]");
+
+        assert!(parse_delimited("a,b\n\"open,1\n", ',').is_err());
+    }
+
+    #[test]
+    fn test_parse_json_rows() {
+        let rows = parse_json(r#"[{"user":"alice","age":30,"admin":true,"tags":["a"],"missing":null}]"#).unwrap();
+        assert_eq!(
+            rows[0].values,
+            row(&[("user", "alice"), ("age", "30"), ("admin", "true"), ("tags", "[\"a\"]"), ("missing", "")])
+        );
+
+        assert!(parse_json(r#"{"user":"alice"}"#).is_err());
+        assert!(parse_json(r#"[1, 2]"#).is_err());
+    }
+
+    #[test]
+    fn test_load_resolves_relative_paths_and_formats() {
+        let dir = std::env::temp_dir().join(format!("apinox-data-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("users.tsv"), "user\tid\nalice\t1\nbob\t2\n").unwrap();
+        std::fs::write(dir.join("empty.csv"), "user,id\n").unwrap();
+        std::fs::write(dir.join("users.txt"), "[{\"user\":\"alice\"}]").unwrap();
+
+        let source = DataSource { path: "users.tsv".to_string(), format: None };
+        let rows = source.load(Some(&dir)).unwrap();
+        assert_eq!(rows[1].values, row(&[("user", "bob"), ("id", "2")]));
+
+        let empty = DataSource { path: "empty.csv".to_string(), format: None };
+        assert!(empty.load(Some(&dir)).unwrap_err().to_string().contains("has no rows"));
+
+        let unknown = DataSource { path: "users.txt".to_string(), format: None };
+        assert!(unknown.load(Some(&dir)).is_err());
+        let explicit = DataSource { path: "users.txt".to_string(), format: Some(DataSourceFormat::Json) };
+        assert_eq!(explicit.load(Some(&dir)).unwrap().len(), 1);
+
+        assert_eq!(iterations(None, None).unwrap(), vec![None]);
+        assert_eq!(iterations(Some(&source), Some(&dir)).unwrap().len(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -6,22 +6,166 @@ use anyhow::Result;
 use reqwest::Client;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use crate::utils::{RegexExtractor, XPathEvaluator, substitute_variables, CONTENT_TYPE_XML};
+use crate::parsers::{openapi_validator, OpenApiParser};
+use crate::utils::{JsonPathEvaluator, SchemaError, XPathEvaluator, XPathOptions, substitute_variables, CONTENT_TYPE_XML};
 
+use super::assertion_runner::{self, AssertionType};
 use super::frontend_types::{
     AssertionResult, FrontendAssertion, FrontendExtractor, FrontendRequest,
-    FrontendTestStep, StepExecutionResult,
+    FrontendTestCase, FrontendTestStep, StepExecutionResult, StepRunRecord,
+    TestCaseExecutionResult,
 };
+use super::property_transfer;
+use super::script_engine::{self, ScriptResponse, DEFAULT_SCRIPT_TIMEOUT_MS};
+use super::variable_extractor::{ExtractorType, ResponseSnapshot, VariableExtractor};
+
+/// State carried from one step of a test case run to the next.
+#[derive(Debug, Clone, Default)]
+pub struct RunContext {
+    /// Variables available for substitution (initial values plus extracted ones)
+    pub variables: HashMap<String, Value>,
+    /// The most recent step result that produced a response
+    pub last_response: Option<StepExecutionResult>,
+    /// Results of finished steps, keyed by both step id and step name
+    pub step_results: HashMap<String, StepExecutionResult>,
+    /// Original request bodies of the case's request steps, keyed by step id
+    pub step_requests: HashMap<String, String>,
+    /// Step ids keyed by step name, so transfers can refer to either
+    pub step_ids: HashMap<String, String>,
+    /// Changes made to later request steps by transfer steps, keyed by step id
+    pub request_overrides: HashMap<String, RequestOverride>,
+}
+
+/// Request changes written by transfer steps, applied when the target runs.
+#[derive(Debug, Clone, Default)]
+pub struct RequestOverride {
+    pub body: Option<String>,
+    pub headers: HashMap<String, String>,
+    pub endpoint: Option<String>,
+}
+
+impl RunContext {
+    pub fn new(variables: HashMap<String, String>) -> Self {
+        Self {
+            variables: variables
+                .into_iter()
+                .map(|(k, v)| (k, Value::String(v)))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Remember the request bodies of a case's steps so transfers can target them.
+    pub fn register_steps(&mut self, steps: &[FrontendTestStep]) {
+        for step in steps {
+            self.step_ids.insert(step.name.clone(), step.id.clone());
+            if let Some(body) = step.config.request.as_ref().and_then(|r| r.request.clone()) {
+                self.step_requests.insert(step.id.clone(), body);
+            }
+        }
+    }
+
+    /// Merge a finished step's extracted variables and remember its result.
+    pub fn record(&mut self, step: &FrontendTestStep, result: &StepExecutionResult) {
+        for (k, v) in &result.extracted_variables {
+            self.variables.insert(k.clone(), Value::String(v.clone()));
+        }
+        if result.response_body.is_some() {
+            self.last_response = Some(result.clone());
+        }
+        self.step_results.insert(step.id.clone(), result.clone());
+        self.step_results.insert(step.name.clone(), result.clone());
+    }
+
+    /// The request body a step will send: a transferred body if one was
+    /// written, otherwise the step's original request.
+    pub fn request_body_for(&self, step: &str) -> Option<String> {
+        let id = self.step_id(step);
+        self.request_overrides
+            .get(id)
+            .and_then(|o| o.body.clone())
+            .or_else(|| self.step_requests.get(id).cloned())
+    }
+
+    /// The override for a step (by id or name), created if missing.
+    pub fn request_override_mut(&mut self, step: &str) -> &mut RequestOverride {
+        let id = self.step_id(step).to_string();
+        self.request_overrides.entry(id).or_default()
+    }
+
+    fn step_id<'a>(&'a self, step: &'a str) -> &'a str {
+        self.step_ids.get(step).map(String::as_str).unwrap_or(step)
+    }
+
+    /// Variables as plain strings, for template substitution and scripts
+    pub fn string_variables(&self) -> HashMap<String, String> {
+        self.variables
+            .iter()
+            .map(|(k, v)| (k.clone(), match v { Value::String(s) => s.clone(), other => other.to_string() }))
+            .collect()
+    }
+}
+
+/// Execute every step of a test case in order, stopping at the first failure.
+///
+/// Each step's result is recorded in `context` so later steps (and the
+/// caller) can see extracted variables and the last response.
+pub async fn run_test_case(
+    test_case: &FrontendTestCase,
+    fallback_endpoint: Option<&str>,
+    context: &mut RunContext,
+) -> TestCaseExecutionResult {
+    let start = Instant::now();
+    let mut steps = Vec::new();
+    let mut passed = true;
+    context.register_steps(&test_case.steps);
+
+    for step in &test_case.steps {
+        let result = match run_step(step, fallback_endpoint, context).await {
+            Ok(result) => result,
+            Err(e) => StepExecutionResult {
+                passed: false,
+                error: Some(e.to_string()),
+                ..Default::default()
+            },
+        };
+
+        context.record(step, &result);
+
+        let step_passed = result.passed;
+        steps.push(StepRunRecord {
+            step_id: step.id.clone(),
+            step_name: step.name.clone(),
+            step_type: step.step_type.clone(),
+            result,
+        });
+
+        if !step_passed {
+            passed = false;
+            break; // Stop on first failure
+        }
+    }
+
+    TestCaseExecutionResult {
+        id: test_case.id.clone(),
+        name: test_case.name.clone(),
+        passed,
+        duration_ms: start.elapsed().as_millis() as u64,
+        steps,
+        data_row: None,
+    }
+}
 
 /// Execute a single test step and return its result.
 ///
-/// `context` holds variables extracted by previous steps (for substitution).
+/// `context` holds the variables and last response from previous steps; the
+/// caller records this step's result into it with `RunContext::record`.
 pub async fn run_step(
     step: &FrontendTestStep,
     fallback_endpoint: Option<&str>,
-    context: &mut HashMap<String, Value>,
+    context: &mut RunContext,
 ) -> Result<StepExecutionResult> {
     let start = Instant::now();
 
@@ -30,52 +174,114 @@ pub async fn run_step(
             let req = step.config.request.as_ref().ok_or_else(|| {
                 anyhow::anyhow!("Step '{}' has type 'request' but no request config", step.name)
             })?;
-            run_request_step(req, fallback_endpoint, context, start).await
+            let overrides = context.request_overrides.get(&step.id).cloned();
+            run_request_step(req, overrides.as_ref(), fallback_endpoint, context, start).await
         }
         "delay" => {
             let ms = step.config.delay_ms.unwrap_or(0);
             tokio::time::sleep(tokio::time::Duration::from_millis(ms)).await;
             Ok(StepExecutionResult {
                 passed: true,
-                response_body: None,
-                status_code: None,
                 duration_ms: start.elapsed().as_millis() as u64,
-                assertion_results: vec![],
-                extracted_variables: HashMap::new(),
-                error: None,
+                ..Default::default()
+            })
+        }
+        "script" => {
+            let source = step.config.script_content.clone().unwrap_or_default();
+            let timeout_ms = step.config.timeout_ms.unwrap_or(DEFAULT_SCRIPT_TIMEOUT_MS);
+            run_script_step(source, timeout_ms, context, start).await
+        }
+        "transfer" => {
+            let transfers = property_transfer::transfers_for_step(&step.config);
+            let transfer_results = property_transfer::run_transfers(&transfers, context);
+
+            let failures: Vec<String> = transfer_results
+                .iter()
+                .filter(|r| !r.passed)
+                .map(|r| format!("{}: {}", r.name, r.message.as_deref().unwrap_or("failed")))
+                .collect();
+            // Values written to variables are reported like extracted ones
+            let extracted_variables = transfers
+                .iter()
+                .zip(&transfer_results)
+                .filter_map(|(t, r)| Some((t.target_variable.clone()?, r.value.clone()?)))
+                .collect();
+
+            Ok(StepExecutionResult {
+                passed: failures.is_empty(),
+                duration_ms: start.elapsed().as_millis() as u64,
+                extracted_variables,
+                error: (!failures.is_empty()).then(|| failures.join("; ")),
+                transfer_results,
+                ..Default::default()
             })
         }
         other => {
-            // script, transfer, workflow — not yet implemented in Rust, skip with pass
+            // workflow — not yet implemented in Rust, skip with pass
             log::warn!("[FrontendRunner] Step type '{}' not yet implemented, skipping", other);
             Ok(StepExecutionResult {
                 passed: true,
-                response_body: None,
-                status_code: None,
-                duration_ms: 0,
-                assertion_results: vec![],
-                extracted_variables: HashMap::new(),
-                error: None,
+                ..Default::default()
             })
         }
     }
 }
 
+/// Run a script step on a blocking thread. Variables the script sets are
+/// returned as extracted variables so they flow into later steps.
+async fn run_script_step(
+    source: String,
+    timeout_ms: u64,
+    context: &RunContext,
+    start: Instant,
+) -> Result<StepExecutionResult> {
+    let variables = context.string_variables();
+    let response = context.last_response.as_ref().map(script_response);
+
+    let vars_for_script = variables.clone();
+    let outcome = tokio::task::spawn_blocking(move || {
+        script_engine::run_script(&source, &vars_for_script, response.as_ref(), Duration::from_millis(timeout_ms))
+    })
+    .await?;
+
+    for line in &outcome.logs {
+        log::info!("[FrontendRunner] script: {}", line);
+    }
+
+    Ok(StepExecutionResult {
+        passed: outcome.passed,
+        duration_ms: start.elapsed().as_millis() as u64,
+        extracted_variables: outcome.changed_variables(&variables),
+        error: outcome.error,
+        logs: outcome.logs,
+        ..Default::default()
+    })
+}
+
+pub(crate) fn script_response(result: &StepExecutionResult) -> ScriptResponse {
+    ScriptResponse {
+        body: result.response_body.clone().unwrap_or_default(),
+        status: result.status_code,
+        headers: result.response_headers.clone(),
+        duration_ms: result.duration_ms,
+    }
+}
+
+/// Send a request step. `overrides` holds values written into this step by
+/// earlier transfer steps and takes precedence over the step's own config.
 async fn run_request_step(
     req: &FrontendRequest,
+    overrides: Option<&RequestOverride>,
     fallback_endpoint: Option<&str>,
-    context: &HashMap<String, Value>,
+    context: &RunContext,
     start: Instant,
 ) -> Result<StepExecutionResult> {
     // Convert context values to strings for template substitution
-    let str_context: HashMap<String, String> = context
-        .iter()
-        .map(|(k, v)| (k.clone(), match v { Value::String(s) => s.clone(), other => other.to_string() }))
-        .collect();
+    let str_context = context.string_variables();
 
-    let endpoint = req
-        .endpoint
-        .as_deref()
+    let endpoint = overrides
+        .and_then(|o| o.endpoint.as_deref())
+        .or(req.endpoint.as_deref())
         .filter(|s| !s.is_empty())
         .or(fallback_endpoint)
         .unwrap_or("")
@@ -86,18 +292,15 @@ async fn run_request_step(
     if endpoint.is_empty() {
         return Ok(StepExecutionResult {
             passed: false,
-            response_body: None,
-            status_code: None,
             duration_ms: start.elapsed().as_millis() as u64,
-            assertion_results: vec![],
-            extracted_variables: HashMap::new(),
             error: Some("No endpoint specified".to_string()),
+            ..Default::default()
         });
     }
 
-    let body = req
-        .request
-        .as_deref()
+    let body = overrides
+        .and_then(|o| o.body.as_deref())
+        .or(req.request.as_deref())
         .unwrap_or("")
         .to_string();
     let body = substitute_variables(&body, &str_context);
@@ -118,22 +321,24 @@ async fn run_request_step(
         _ => client.post(&endpoint),
     };
 
-    // Apply extra headers from the request config, skipping empty/invalid names
+    // Apply extra headers from the request config and transfer steps, skipping empty/invalid names
     // and letting Content-Type be overridden by any explicit header entry.
     let mut content_type_overridden = false;
-    if let Some(headers) = &req.headers {
-        for (k, v) in headers {
-            let k = k.trim();
-            if k.is_empty() {
-                continue; // skip blank header names (common when user leaves a row empty)
-            }
-            if k.eq_ignore_ascii_case("content-type") {
-                // Override the default Content-Type we set above
-                builder = builder.header("Content-Type", v.as_str());
-                content_type_overridden = true;
-            } else {
-                builder = builder.header(k, v.as_str());
-            }
+    let mut headers = req.headers.clone().unwrap_or_default();
+    if let Some(o) = overrides {
+        headers.extend(o.headers.clone());
+    }
+    for (k, v) in &headers {
+        let k = k.trim();
+        if k.is_empty() {
+            continue; // skip blank header names (common when user leaves a row empty)
+        }
+        if k.eq_ignore_ascii_case("content-type") {
+            // Override the default Content-Type we set above
+            builder = builder.header("Content-Type", v.as_str());
+            content_type_overridden = true;
+        } else {
+            builder = builder.header(k, v.as_str());
         }
     }
 
@@ -153,22 +358,22 @@ async fn run_request_step(
             let duration_ms = start.elapsed().as_millis() as u64;
             return Ok(StepExecutionResult {
                 passed: false,
-                response_body: None,
-                status_code: None,
+                request_body: Some(body),
                 duration_ms,
-                assertion_results: vec![],
-                extracted_variables: HashMap::new(),
                 error: Some(format!("HTTP request failed: {}", e)),
+                ..Default::default()
             });
         }
     };
 
     let status_code = response.status().as_u16();
-    let response_headers: HashMap<String, String> = response
+    // Keep repeated headers (Set-Cookie) for extractors; the result map keeps the last
+    let header_list: Vec<(String, String)> = response
         .headers()
         .iter()
         .filter_map(|(k, v)| v.to_str().ok().map(|vs| (k.to_string(), vs.to_string())))
         .collect();
+    let response_headers: HashMap<String, String> = header_list.iter().cloned().collect();
     let response_body = response.text().await.unwrap_or_default();
     let duration_ms = start.elapsed().as_millis() as u64;
 
@@ -177,17 +382,25 @@ async fn run_request_step(
     // Run assertions
     let assertion_results = run_assertions(
         req.assertions.as_deref().unwrap_or(&[]),
-        &response_body,
-        status_code,
-        duration_ms,
-    );
+        &ScriptResponse {
+            body: response_body.clone(),
+            status: Some(status_code),
+            headers: response_headers.clone(),
+            duration_ms,
+        },
+        &str_context,
+        &XPathOptions::default().with_target_namespace(req.target_namespace.clone()),
+    )
+    .await;
     let has_failures = assertion_results.iter().any(|r| r.status == "FAIL");
 
     // Extract variables
     let extracted_variables = run_extractors(
         req.extractors.as_deref().unwrap_or(&[]),
-        &response_body,
-        &response_headers,
+        &ResponseSnapshot::new(&response_body)
+            .with_status(status_code)
+            .with_headers(header_list.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .with_elapsed_ms(duration_ms),
     );
 
     let passed = !has_failures;
@@ -212,31 +425,81 @@ async fn run_request_step(
 
     Ok(StepExecutionResult {
         passed,
+        request_body: Some(body),
         response_body: Some(response_body),
         status_code: Some(status_code),
         duration_ms,
         assertion_results,
         extracted_variables,
         error,
+        response_headers,
+        ..Default::default()
     })
 }
 
 /// Run all assertions against the response.
-fn run_assertions(
+///
+/// `variables` is only used by Script assertions, `xpath_options` (the
+/// request's target namespace) by XPath Match.
+pub(crate) async fn run_assertions(
     assertions: &[FrontendAssertion],
-    body: &str,
-    status_code: u16,
-    duration_ms: u64,
+    response: &ScriptResponse,
+    variables: &HashMap<String, String>,
+    xpath_options: &XPathOptions,
 ) -> Vec<AssertionResult> {
-    assertions.iter().map(|a| run_assertion(a, body, status_code, duration_ms)).collect()
+    let mut results = Vec::with_capacity(assertions.len());
+    for assertion in assertions {
+        let result = if assertion.assertion_type == "Script" {
+            run_script_assertion(assertion, response, variables).await
+        } else {
+            run_assertion(assertion, response, xpath_options)
+        };
+        results.push(result);
+    }
+    results
+}
+
+/// Run a Script assertion on a blocking thread, like script steps
+async fn run_script_assertion(
+    assertion: &FrontendAssertion,
+    response: &ScriptResponse,
+    variables: &HashMap<String, String>,
+) -> AssertionResult {
+    let name = assertion.name.clone().unwrap_or_else(|| assertion.assertion_type.clone());
+    let script = assertion
+        .configuration
+        .as_ref()
+        .and_then(|c| c.script.clone())
+        .unwrap_or_default();
+    let (response, variables) = (response.clone(), variables.clone());
+    let outcome = tokio::task::spawn_blocking(move || {
+        script_engine::run_script(&script, &variables, Some(&response), Duration::from_millis(DEFAULT_SCRIPT_TIMEOUT_MS))
+    })
+    .await;
+    match outcome {
+        Ok(outcome) => AssertionResult {
+            name,
+            status: if outcome.passed { "PASS" } else { "FAIL" }.to_string(),
+            message: outcome.error,
+        },
+        Err(e) => AssertionResult {
+            name,
+            status: "FAIL".to_string(),
+            message: Some(format!("Script assertion failed to run: {}", e)),
+        },
+    }
 }
 
+/// Run a non-script assertion; Script assertions go through
+/// `run_script_assertion`
 fn run_assertion(
     assertion: &FrontendAssertion,
-    body: &str,
-    status_code: u16,
-    duration_ms: u64,
+    response: &ScriptResponse,
+    xpath_options: &XPathOptions,
 ) -> AssertionResult {
+    let body = response.body.as_str();
+    let status_code = response.status.unwrap_or(0);
+    let duration_ms = response.duration_ms;
     let name = assertion.name.clone().unwrap_or_else(|| assertion.assertion_type.clone());
     let config = assertion.configuration.as_ref();
 
@@ -309,7 +572,13 @@ fn run_assertion(
         "XPath Match" => {
             let xpath_expr = config.and_then(|c| c.xpath.as_deref()).unwrap_or("");
             let expected = config.and_then(|c| c.expected_content.as_deref()).unwrap_or("");
-            match XPathEvaluator::evaluate(body, xpath_expr) {
+            let mut options = xpath_options
+                .clone()
+                .ignoring_namespaces(config.and_then(|c| c.ignore_namespaces).unwrap_or(false));
+            for (prefix, uri) in config.and_then(|c| c.namespaces.as_ref()).into_iter().flatten() {
+                options = options.with_namespace(prefix, uri);
+            }
+            match XPathEvaluator::evaluate_with(body, xpath_expr, &options) {
                 Some(actual) => {
                     let ok = actual.trim() == expected.trim();
                     AssertionResult {
@@ -329,6 +598,70 @@ fn run_assertion(
                 },
             }
         }
+        "JSONPath Match" => {
+            let path = config.and_then(|c| c.json_path.as_deref()).unwrap_or("");
+            let expected = config.and_then(|c| c.expected_content.as_deref()).unwrap_or("");
+            match JsonPathEvaluator::evaluate(body, path) {
+                Some(actual) => {
+                    let ok = actual.trim() == expected.trim();
+                    AssertionResult {
+                        name,
+                        status: if ok { "PASS" } else { "FAIL" }.to_string(),
+                        message: if !ok {
+                            Some(format!("JSONPath '{}': got '{}', expected '{}'", path, actual, expected))
+                        } else {
+                            None
+                        },
+                    }
+                }
+                None => AssertionResult {
+                    name,
+                    status: "FAIL".to_string(),
+                    message: Some(format!("JSONPath '{}' returned no results or failed to evaluate", path)),
+                },
+            }
+        }
+        "OpenAPI Compliance" => {
+            let operation = config.and_then(|c| c.open_api_operation.as_deref()).unwrap_or("");
+            match check_openapi_compliance(config.and_then(|c| c.open_api_spec.as_deref()), operation, response) {
+                Ok(violations) => AssertionResult {
+                    name,
+                    status: if violations.is_empty() { "PASS" } else { "FAIL" }.to_string(),
+                    message: if !violations.is_empty() {
+                        let lines: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+                        Some(format!("Response does not match '{}':\n{}", operation, lines.join("\n")))
+                    } else {
+                        None
+                    },
+                },
+                Err(e) => AssertionResult {
+                    name,
+                    status: "FAIL".to_string(),
+                    message: Some(e),
+                },
+            }
+        }
+        "Schema Compliance" => {
+            let wsdl = config.and_then(|c| c.wsdl.clone()).unwrap_or_default();
+            let operation = config.and_then(|c| c.wsdl_operation.clone()).unwrap_or_default();
+            let schema = AssertionType::SchemaCompliance { wsdl, operation };
+            match assertion_runner::run_assertion(&schema, body, status_code, Some(duration_ms)) {
+                Ok(result) => AssertionResult {
+                    name,
+                    status: if result.passed { "PASS" } else { "FAIL" }.to_string(),
+                    message: if !result.passed {
+                        Some(format!("{}:\n{}", result.message, result.actual.unwrap_or_default()))
+                    } else {
+                        None
+                    },
+                },
+                Err(e) => AssertionResult {
+                    name,
+                    status: "FAIL".to_string(),
+                    message: Some(e.to_string()),
+                },
+            }
+        }
         "SOAP Fault" => {
             let expect_fault = config.and_then(|c| c.expect_fault).unwrap_or(false);
             let is_fault = body.contains("<faultcode>") || body.contains("<soap:Fault>")
@@ -348,12 +681,6 @@ fn run_assertion(
                 },
             }
         }
-        // Script assertions can't run in Rust — skip
-        "Script" => AssertionResult {
-            name,
-            status: "SKIP".to_string(),
-            message: Some("Script assertions not supported in Rust backend".to_string()),
-        },
         other => AssertionResult {
             name,
             status: "SKIP".to_string(),
@@ -362,31 +689,58 @@ fn run_assertion(
     }
 }
 
+/// Validate a response against the operation's declared responses.
+///
+/// `spec` is a path to the spec file, or the spec content itself.
+fn check_openapi_compliance(
+    spec: Option<&str>,
+    operation: &str,
+    response: &ScriptResponse,
+) -> Result<Vec<SchemaError>, String> {
+    let spec = spec.map(str::trim).filter(|s| !s.is_empty())
+        .ok_or_else(|| "No OpenAPI spec configured".to_string())?;
+    let content = if spec.starts_with('{') || spec.contains('\n') {
+        spec.to_string()
+    } else {
+        std::fs::read_to_string(spec).map_err(|e| format!("Failed to read OpenAPI spec '{}': {}", spec, e))?
+    };
+    let spec = OpenApiParser::parse_auto(&content).map_err(|e| format!("Invalid OpenAPI spec: {}", e))?;
+
+    let content_type = response
+        .headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+        .map(|(_, v)| v.as_str());
+    openapi_validator::validate_response(
+        &spec,
+        operation,
+        response.status.unwrap_or(0),
+        content_type,
+        &response.body,
+    )
+    .map_err(|e| e.to_string())
+}
+
 /// Run all extractors and return the extracted variable map.
 fn run_extractors(
     extractors: &[FrontendExtractor],
-    body: &str,
-    headers: &HashMap<String, String>,
+    response: &ResponseSnapshot,
 ) -> HashMap<String, String> {
     let mut vars = HashMap::new();
     for ext in extractors {
-        let value = match ext.extractor_type.as_deref().unwrap_or("XPath") {
-            "XPath" => {
-                let path = ext.path.as_deref().unwrap_or("");
-                XPathEvaluator::evaluate(body, path)
-            }
-            "Regex" => {
-                let path = ext.path.as_deref().unwrap_or("");
-                RegexExtractor::extract(body, path)
-            }
-            "Header" => {
-                let name = ext.path.as_deref().unwrap_or("");
-                // Case-insensitive header lookup
-                headers.iter()
-                    .find(|(k, _)| k.eq_ignore_ascii_case(name))
-                    .map(|(_, v)| v.clone())
+        let value = ExtractorType::from_config(
+            ext.extractor_type.as_deref().unwrap_or("XPath"),
+            ext.source.as_deref(),
+            ext.path.as_deref().unwrap_or(""),
+        )
+        .and_then(|extractor| VariableExtractor::extract_from(&extractor, response));
+
+        let value = match value {
+            Ok(v) => Some(v),
+            Err(e) => {
+                log::debug!("[FrontendRunner] Extractor '{}' found no value: {}", ext.variable, e);
+                None
             }
-            _ => None,
         };
         if let Some(v) = value.or_else(|| ext.default_value.clone()) {
             vars.insert(ext.variable.clone(), v);
@@ -394,3 +748,274 @@ fn run_extractors(
     }
     vars
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::frontend_types::FrontendStepConfig;
+
+    fn script_step(id: &str, script: &str) -> FrontendTestStep {
+        FrontendTestStep {
+            id: id.to_string(),
+            name: id.to_string(),
+            step_type: "script".to_string(),
+            config: FrontendStepConfig {
+                script_content: Some(script.to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_script_steps_share_variables() {
+        let case = FrontendTestCase {
+            id: "tc1".to_string(),
+            name: "Scripts".to_string(),
+            steps: vec![
+                script_step("s1", "log('setting'); vars.orderId = 'A-' + vars.prefix;"),
+                script_step("s2", "assert.equal(vars.orderId, 'A-7');"),
+            ],
+            data_source: None,
+        };
+        let mut context = RunContext::new(HashMap::from([("prefix".to_string(), "7".to_string())]));
+
+        let result = run_test_case(&case, None, &mut context).await;
+
+        assert!(result.passed, "{:?}", result.steps);
+        assert_eq!(result.steps[0].result.logs, vec!["setting"]);
+        assert_eq!(context.variables.get("orderId"), Some(&Value::String("A-7".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_failing_script_stops_case() {
+        let case = FrontendTestCase {
+            id: "tc2".to_string(),
+            name: "Failing".to_string(),
+            steps: vec![
+                script_step("s1", "assert(response !== null, 'no previous response');"),
+                script_step("s2", "vars.reached = 'yes';"),
+            ],
+            data_source: None,
+        };
+        let mut context = RunContext::default();
+
+        let result = run_test_case(&case, None, &mut context).await;
+
+        assert!(!result.passed);
+        assert_eq!(result.steps.len(), 1);
+        assert_eq!(
+            result.steps[0].result.error.as_deref(),
+            Some("AssertionError: no previous response")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_script_assertion() {
+        let response = ScriptResponse {
+            body: "<Order><Status>OK</Status></Order>".to_string(),
+            status: Some(200),
+            headers: HashMap::new(),
+            duration_ms: 5,
+        };
+        let assertion = |script: &str| FrontendAssertion {
+            assertion_type: "Script".to_string(),
+            name: None,
+            configuration: Some(crate::testing::frontend_types::AssertionConfiguration {
+                script: Some(script.to_string()),
+                ..Default::default()
+            }),
+        };
+
+        let pass = run_script_assertion(&assertion("return response.body.indexOf('OK') > -1"), &response, &HashMap::new()).await;
+        assert_eq!(pass.status, "PASS");
+
+        let fail = run_script_assertion(&assertion("assert(response.status === 500, 'expected 500')"), &response, &HashMap::new()).await;
+        assert_eq!(fail.status, "FAIL");
+        assert_eq!(fail.message.as_deref(), Some("AssertionError: expected 500"));
+    }
+
+    #[test]
+    fn test_xpath_match_namespace_options() {
+        let response = ScriptResponse {
+            body: r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><GetOrderResponse xmlns="urn:orders"><Status>SHIPPED</Status></GetOrderResponse></s:Body></s:Envelope>"#.to_string(),
+            status: Some(200),
+            headers: HashMap::new(),
+            duration_ms: 5,
+        };
+        let assertion = |xpath: &str, ignore: bool| FrontendAssertion {
+            assertion_type: "XPath Match".to_string(),
+            name: None,
+            configuration: Some(crate::testing::frontend_types::AssertionConfiguration {
+                xpath: Some(xpath.to_string()),
+                expected_content: Some("shipped".to_string()),
+                ignore_namespaces: Some(ignore),
+                ..Default::default()
+            }),
+        };
+        let options = XPathOptions::default().with_target_namespace(Some("urn:orders".to_string()));
+        let check = |xpath: &str, ignore: bool| {
+            run_assertion(&assertion(xpath, ignore), &response, &options).status
+        };
+
+        assert_eq!(check("lower-case(//s:Body/tns:GetOrderResponse/tns:Status)", false), "PASS");
+        assert_eq!(check("lower-case(//GetOrderResponse/Status)", false), "FAIL");
+        assert_eq!(check("lower-case(//GetOrderResponse/Status)", true), "PASS");
+    }
+
+    #[test]
+    fn test_jsonpath_match_assertion() {
+        let response = ScriptResponse {
+            body: r#"{"items":[{"id":1,"status":"OK"},{"id":2,"status":"FAILED"}]}"#.to_string(),
+            status: Some(200),
+            headers: HashMap::new(),
+            duration_ms: 5,
+        };
+        let assertion = |path: &str, expected: &str| FrontendAssertion {
+            assertion_type: "JSONPath Match".to_string(),
+            name: None,
+            configuration: Some(crate::testing::frontend_types::AssertionConfiguration {
+                json_path: Some(path.to_string()),
+                expected_content: Some(expected.to_string()),
+                ..Default::default()
+            }),
+        };
+
+        let pass = run_assertion(&assertion("$.items[?@.status == 'FAILED'].id", "2"), &response, &XPathOptions::default());
+        assert_eq!(pass.status, "PASS");
+
+        let fail = run_assertion(&assertion("$.items[0].id", "2"), &response, &XPathOptions::default());
+        assert_eq!(fail.status, "FAIL");
+        assert_eq!(fail.message.as_deref(), Some("JSONPath '$.items[0].id': got '1', expected '2'"));
+
+        let missing = run_assertion(&assertion("$.items[9].id", "2"), &response, &XPathOptions::default());
+        assert_eq!(missing.status, "FAIL");
+    }
+
+    #[test]
+    fn test_openapi_compliance_assertion() {
+        let spec = r##"{
+            "openapi": "3.0.0",
+            "info": { "title": "Orders", "version": "1" },
+            "paths": { "/orders/{id}": { "get": {
+                "operationId": "getOrder",
+                "responses": { "200": { "description": "Order", "content": { "application/json": {
+                    "schema": { "$ref": "#/components/schemas/Order" }
+                } } } }
+            } } },
+            "components": { "schemas": { "Order": {
+                "type": "object",
+                "required": ["id"],
+                "properties": { "id": { "type": "integer" }, "placed": { "type": "string", "format": "date-time" } }
+            } } }
+        }"##;
+        let response = |body: &str, status: u16| ScriptResponse {
+            body: body.to_string(),
+            status: Some(status),
+            headers: HashMap::from([("content-type".to_string(), "application/json".to_string())]),
+            duration_ms: 5,
+        };
+        let assertion = FrontendAssertion {
+            assertion_type: "OpenAPI Compliance".to_string(),
+            name: None,
+            configuration: Some(crate::testing::frontend_types::AssertionConfiguration {
+                open_api_spec: Some(spec.to_string()),
+                open_api_operation: Some("GET /orders/{id}".to_string()),
+                ..Default::default()
+            }),
+        };
+        let check = |body: &str, status: u16| {
+            run_assertion(&assertion, &response(body, status), &XPathOptions::default())
+        };
+
+        assert_eq!(check(r#"{"id": 7, "placed": "2024-01-15T10:30:00Z"}"#, 200).status, "PASS");
+
+        let fail = check(r#"{"placed": "yesterday"}"#, 200);
+        assert_eq!(fail.status, "FAIL");
+        assert_eq!(
+            fail.message.as_deref(),
+            Some("Response does not match 'GET /orders/{id}':\n$: missing required property 'id'\n$.placed: 'yesterday' is not a valid date-time")
+        );
+
+        let undeclared = check("{}", 404);
+        assert_eq!(undeclared.message.as_deref(), Some("Response does not match 'GET /orders/{id}':\nstatus: 404 is not a declared response (declared: 200)"));
+    }
+
+    #[test]
+    fn test_schema_compliance_assertion() {
+        let wsdl = r#"<definitions xmlns:tns="urn:t" xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns="http://schemas.xmlsoap.org/wsdl/">
+  <types><xsd:schema targetNamespace="urn:t">
+    <xsd:element name="PingResponse"><xsd:complexType><xsd:sequence>
+      <xsd:element name="latency" type="xsd:int"/>
+    </xsd:sequence></xsd:complexType></xsd:element>
+  </xsd:schema></types>
+  <message name="PingOut"><part name="parameters" element="tns:PingResponse"/></message>
+  <portType name="Health"><operation name="Ping"><output message="tns:PingOut"/></operation></portType>
+</definitions>"#;
+        let assertion = FrontendAssertion {
+            assertion_type: "Schema Compliance".to_string(),
+            name: None,
+            configuration: Some(crate::testing::frontend_types::AssertionConfiguration {
+                wsdl: Some(wsdl.to_string()),
+                wsdl_operation: Some("Ping".to_string()),
+                ..Default::default()
+            }),
+        };
+        let check = |body: &str| {
+            let response = ScriptResponse {
+                body: format!(r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body>{}</s:Body></s:Envelope>"#, body),
+                status: Some(200),
+                headers: HashMap::new(),
+                duration_ms: 5,
+            };
+            run_assertion(&assertion, &response, &XPathOptions::default())
+        };
+
+        assert_eq!(check("<PingResponse><latency>3</latency></PingResponse>").status, "PASS");
+
+        let fail = check("<PingResponse/>");
+        assert_eq!(fail.status, "FAIL");
+        assert_eq!(
+            fail.message.as_deref(),
+            Some("Response has 1 schema violation(s):\n/PingResponse: Missing required element 'latency'")
+        );
+    }
+
+    #[test]
+    fn test_extractors_read_response_metadata() {
+        let extractor = |variable: &str, kind: &str, source: &str, path: &str| FrontendExtractor {
+            id: variable.to_string(),
+            variable: variable.to_string(),
+            extractor_type: Some(kind.to_string()),
+            source: Some(source.to_string()),
+            path: Some(path.to_string()),
+            default_value: None,
+        };
+        let headers = [("Location", "/orders/7"), ("Set-Cookie", "sid=s-1; Path=/")];
+        let response = ResponseSnapshot::new(r#"{"order":{"id":7}}"#)
+            .with_status(201)
+            .with_headers(headers)
+            .with_elapsed_ms(12);
+
+        let vars = run_extractors(
+            &[
+                extractor("location", "Header", "header", "location"),
+                extractor("legacyHeader", "XPath", "header", "Location"),
+                extractor("status", "StatusCode", "body", ""),
+                extractor("session", "Cookie", "body", "sid"),
+                extractor("orderId", "JSONPath", "body", "$.order.id"),
+                FrontendExtractor {
+                    default_value: Some("none".to_string()),
+                    ..extractor("missing", "Header", "header", "X-Missing")
+                },
+            ],
+            &response,
+        );
+
+        assert_eq!(vars["location"], "/orders/7");
+        assert_eq!(vars["legacyHeader"], "/orders/7");
+        assert_eq!(vars["status"], "201");
+        assert_eq!(vars["session"], "s-1");
+        assert_eq!(vars["orderId"], "7");
+        assert_eq!(vars["missing"], "none");
+    }
+}
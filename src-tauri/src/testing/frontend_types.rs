@@ -6,6 +6,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::data_source::{DataRow, DataSource};
+
 /// Matches TypeScript `TestCase`
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -13,6 +15,9 @@ pub struct FrontendTestCase {
     pub id: String,
     pub name: String,
     pub steps: Vec<FrontendTestStep>,
+    /// Run the case once per row of this data source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_source: Option<DataSource>,
 }
 
 /// Matches TypeScript `TestStep`
@@ -37,6 +42,45 @@ pub struct FrontendStepConfig {
     pub delay_ms: Option<u64>,
     /// For 'script' steps
     pub script_content: Option<String>,
+    /// For 'script' steps: abort the script after this many milliseconds
+    pub timeout_ms: Option<u64>,
+    /// For 'transfer' steps with a single transfer
+    pub source_step_id: Option<String>,
+    /// "Response" | "Headers" | "Status"
+    pub source_property: Option<String>,
+    pub source_path: Option<String>,
+    pub target_step_id: Option<String>,
+    /// "Request" | "Header" | "Endpoint"
+    pub target_property: Option<String>,
+    pub target_path: Option<String>,
+    /// For 'transfer' steps with several transfers (e.g. imported from SoapUI)
+    pub transfers: Option<Vec<PropertyTransfer>>,
+}
+
+/// A single property transfer: read a value from a previous step (or a
+/// variable) and write it to a variable or into a later step's request.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyTransfer {
+    pub name: Option<String>,
+    /// Step id or name to read from; defaults to the last response
+    pub source_step_id: Option<String>,
+    /// "Response" (default) | "Request" | "Headers" | "Status"
+    pub source_property: Option<String>,
+    /// Read from this variable instead of a step
+    pub source_variable: Option<String>,
+    /// XPath, JSONPath or regex applied to the source; empty takes it whole
+    pub source_path: Option<String>,
+    /// "XPath" | "JSONPath" | "Regex"; inferred from the path when omitted
+    pub path_language: Option<String>,
+    /// Store the value in this variable
+    pub target_variable: Option<String>,
+    /// Step id or name whose request receives the value
+    pub target_step_id: Option<String>,
+    /// "Request" (default) | "Header" | "Endpoint"
+    pub target_property: Option<String>,
+    /// XPath into the target request body, or the header name
+    pub target_path: Option<String>,
 }
 
 /// Matches TypeScript `ApiRequest`
@@ -52,6 +96,8 @@ pub struct FrontendRequest {
     pub headers: Option<HashMap<String, String>>,
     pub assertions: Option<Vec<FrontendAssertion>>,
     pub extractors: Option<Vec<FrontendExtractor>>,
+    /// WSDL target namespace of the operation, bound to `tns` in XPath assertions
+    pub target_namespace: Option<String>,
 }
 
 /// Matches TypeScript `Assertion`
@@ -75,7 +121,22 @@ pub struct AssertionConfiguration {
     pub sla: Option<String>,
     /// For XPath Match
     pub xpath: Option<String>,
+    /// XPath Match: compare local names only
+    pub ignore_namespaces: Option<bool>,
+    /// XPath Match: extra prefix → namespace URI bindings
+    pub namespaces: Option<HashMap<String, String>>,
+    /// For JSONPath Match
+    pub json_path: Option<String>,
+    /// Expected value for XPath / JSONPath Match
     pub expected_content: Option<String>,
+    /// For OpenAPI Compliance: spec file path, or the spec itself (JSON/YAML)
+    pub open_api_spec: Option<String>,
+    /// For OpenAPI Compliance: operationId or "METHOD /path"
+    pub open_api_operation: Option<String>,
+    /// For Schema Compliance: WSDL file path, or the WSDL itself
+    pub wsdl: Option<String>,
+    /// For Schema Compliance: operation whose output element is checked
+    pub wsdl_operation: Option<String>,
     /// For SOAP Fault
     pub expect_fault: Option<bool>,
     pub fault_code: Option<String>,
@@ -91,10 +152,11 @@ pub struct AssertionConfiguration {
 pub struct FrontendExtractor {
     pub id: String,
     pub variable: String,
-    /// "XPath" | "JSONPath" | "Regex" | "Header"
+    /// "XPath" | "JSONPath" | "Regex" | "Header" | "StatusCode" | "Cookie" |
+    /// "ElapsedTime" | "SoapFault"
     #[serde(rename = "type")]
     pub extractor_type: Option<String>,
-    /// "body" | "header"
+    /// "body" | "header" | "status"
     pub source: Option<String>,
     pub path: Option<String>,
     pub default_value: Option<String>,
@@ -110,14 +172,61 @@ pub struct AssertionResult {
 }
 
 /// Result of executing a single step
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct StepExecutionResult {
     pub passed: bool,
+    /// The request body actually sent (after variable substitution)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<String>,
     pub response_body: Option<String>,
     pub status_code: Option<u16>,
     pub duration_ms: u64,
     pub assertion_results: Vec<AssertionResult>,
     pub extracted_variables: HashMap<String, String>,
     pub error: Option<String>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub response_headers: HashMap<String, String>,
+    /// Output written by script steps via `log(...)`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<String>,
+    /// Outcome of each transfer in a 'transfer' step
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub transfer_results: Vec<TransferResult>,
+}
+
+/// Result of a single property transfer
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferResult {
+    pub name: String,
+    pub passed: bool,
+    /// The value that was transferred
+    pub value: Option<String>,
+    pub message: Option<String>,
+}
+
+/// A step result tagged with the step it belongs to
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepRunRecord {
+    pub step_id: String,
+    pub step_name: String,
+    pub step_type: String,
+    #[serde(flatten)]
+    pub result: StepExecutionResult,
+}
+
+/// Result of executing a whole test case
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCaseExecutionResult {
+    pub id: String,
+    pub name: String,
+    pub passed: bool,
+    pub duration_ms: u64,
+    pub steps: Vec<StepRunRecord>,
+    /// The data source row this run used, for data-driven cases
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_row: Option<DataRow>,
 }
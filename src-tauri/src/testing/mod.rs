@@ -7,9 +7,12 @@ pub mod variable_extractor;
 pub mod commands;
 pub mod frontend_types;
 pub mod frontend_runner;
+pub mod script_engine;
+pub mod property_transfer;
+pub mod data_source;
 
 pub use assertion_runner::{AssertionType, AssertionResult, run_assertion};
-pub use variable_extractor::{ExtractorType, VariableExtractor, extract_variable};
+pub use variable_extractor::{ExtractorType, ResponseSnapshot, VariableExtractor, extract_variable};
 pub use test_runner::{TestRunner, TestCase, TestStep, TestStepType, TestStepResult};
 
 // Re-export commands
@@ -0,0 +1,375 @@
+/// Property Transfer
+///
+/// Executes 'transfer' steps. Each transfer reads a value from a previous
+/// step (response body, request body, headers or status) or a variable,
+/// optionally narrows it with XPath, JSONPath or a regex, and writes it to a
+/// variable or into a later request step (body XPath, header or endpoint).
+use serde_json::Value;
+
+use crate::utils::{RegexExtractor, XPathEvaluator};
+
+use super::frontend_runner::RunContext;
+use super::frontend_types::{FrontendStepConfig, PropertyTransfer, TransferResult};
+use super::variable_extractor::{ExtractorType, VariableExtractor};
+
+/// Collect the transfers configured on a step: the `transfers` list, or the
+/// single transfer described by the flat `source*`/`target*` fields.
+pub fn transfers_for_step(config: &FrontendStepConfig) -> Vec<PropertyTransfer> {
+    if let Some(transfers) = config.transfers.as_ref().filter(|t| !t.is_empty()) {
+        return transfers.clone();
+    }
+
+    if config.source_step_id.is_none() && config.source_path.is_none() && config.target_step_id.is_none() {
+        return vec![];
+    }
+
+    vec![PropertyTransfer {
+        name: None,
+        source_step_id: config.source_step_id.clone(),
+        source_property: config.source_property.clone(),
+        source_variable: None,
+        source_path: config.source_path.clone(),
+        path_language: None,
+        target_variable: None,
+        target_step_id: config.target_step_id.clone(),
+        target_property: config.target_property.clone(),
+        target_path: config.target_path.clone(),
+    }]
+}
+
+/// Run transfers in order. Later transfers see the effects of earlier ones.
+pub fn run_transfers(transfers: &[PropertyTransfer], context: &mut RunContext) -> Vec<TransferResult> {
+    transfers
+        .iter()
+        .enumerate()
+        .map(|(i, transfer)| {
+            let name = transfer
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("Transfer {}", i + 1));
+
+            match read_source(transfer, context).and_then(|value| {
+                write_target(transfer, &value, context)?;
+                Ok(value)
+            }) {
+                Ok(value) => TransferResult {
+                    name,
+                    passed: true,
+                    value: Some(value),
+                    message: None,
+                },
+                Err(message) => TransferResult {
+                    name,
+                    passed: false,
+                    value: None,
+                    message: Some(message),
+                },
+            }
+        })
+        .collect()
+}
+
+fn read_source(transfer: &PropertyTransfer, context: &RunContext) -> Result<String, String> {
+    let source = if let Some(variable) = &transfer.source_variable {
+        context
+            .variables
+            .get(variable)
+            .map(|v| match v {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .ok_or_else(|| format!("Variable '{}' is not set", variable))?
+    } else {
+        let result = match transfer.source_step_id.as_deref().filter(|s| !s.is_empty()) {
+            Some(step) => context
+                .step_results
+                .get(step)
+                .ok_or_else(|| format!("Source step '{}' has not run", step))?,
+            None => context
+                .last_response
+                .as_ref()
+                .ok_or("No previous response to transfer from")?,
+        };
+
+        match transfer.source_property.as_deref().unwrap_or("Response") {
+            "Response" => result.response_body.clone().unwrap_or_default(),
+            "Request" => result.request_body.clone().unwrap_or_default(),
+            "Status" => result
+                .status_code
+                .map(|c| c.to_string())
+                .ok_or("Source step has no status code")?,
+            "Headers" => {
+                // A path on Headers names the header to read
+                return match transfer.source_path.as_deref().filter(|p| !p.is_empty()) {
+                    Some(header) => result
+                        .response_headers
+                        .iter()
+                        .find(|(k, _)| k.eq_ignore_ascii_case(header))
+                        .map(|(_, v)| v.clone())
+                        .ok_or_else(|| format!("Header '{}' not found", header)),
+                    None => {
+                        let mut lines: Vec<String> = result
+                            .response_headers
+                            .iter()
+                            .map(|(k, v)| format!("{}: {}", k, v))
+                            .collect();
+                        lines.sort();
+                        Ok(lines.join("\n"))
+                    }
+                };
+            }
+            other => return Err(format!("Unknown source property '{}'", other)),
+        }
+    };
+
+    match transfer.source_path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => apply_path(&source, path, transfer.path_language.as_deref()),
+        None => Ok(source),
+    }
+}
+
+/// Narrow `source` with the path, inferring the language when not given:
+/// `$…` is JSONPath, anything else XPath. Regex must be explicit.
+fn apply_path(source: &str, path: &str, language: Option<&str>) -> Result<String, String> {
+    let language = language.map(str::to_lowercase).unwrap_or_else(|| {
+        if path.starts_with('$') { "jsonpath" } else { "xpath" }.to_string()
+    });
+
+    match language.as_str() {
+        "xpath" => XPathEvaluator::evaluate(source, path)
+            .ok_or_else(|| format!("XPath '{}' returned no results", path)),
+        "jsonpath" => VariableExtractor::extract(&ExtractorType::JsonPath { path: path.to_string() }, source)
+            .map_err(|e| e.to_string()),
+        "regex" => RegexExtractor::extract(source, path)
+            .ok_or_else(|| format!("Regex '{}' did not match", path)),
+        other => Err(format!("Unknown path language '{}'", other)),
+    }
+}
+
+fn write_target(transfer: &PropertyTransfer, value: &str, context: &mut RunContext) -> Result<(), String> {
+    if let Some(variable) = transfer.target_variable.as_deref().filter(|v| !v.is_empty()) {
+        context
+            .variables
+            .insert(variable.to_string(), Value::String(value.to_string()));
+        return Ok(());
+    }
+
+    let target = transfer
+        .target_step_id
+        .as_deref()
+        .filter(|t| !t.is_empty())
+        .ok_or("Transfer has no target variable or target step")?;
+    let target_path = transfer.target_path.as_deref().map(str::trim).filter(|p| !p.is_empty());
+
+    match transfer.target_property.as_deref().unwrap_or("Request") {
+        "Request" => {
+            let body = match target_path {
+                Some(xpath) => {
+                    let current = context
+                        .request_body_for(target)
+                        .ok_or_else(|| format!("Target step '{}' has no request", target))?;
+                    let (updated, changed) = XPathEvaluator::set_values(&current, xpath, value)
+                        .ok_or_else(|| format!("Target XPath '{}' could not be applied to the request of '{}'", xpath, target))?;
+                    if changed == 0 {
+                        return Err(format!("Target XPath '{}' matched nothing in the request of '{}'", xpath, target));
+                    }
+                    updated
+                }
+                None => value.to_string(),
+            };
+            context.request_override_mut(target).body = Some(body);
+        }
+        "Header" => {
+            let header = target_path.ok_or("Header transfers need a target path naming the header")?;
+            context
+                .request_override_mut(target)
+                .headers
+                .insert(header.to_string(), value.to_string());
+        }
+        "Endpoint" => {
+            context.request_override_mut(target).endpoint = Some(value.to_string());
+        }
+        other => return Err(format!("Unknown target property '{}'", other)),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::frontend_types::{FrontendRequest, FrontendTestStep, StepExecutionResult};
+    use std::collections::HashMap;
+
+    const LOGIN_RESPONSE: &str = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"><soap:Body><LoginResponse><session>S-42</session></LoginResponse></soap:Body></soap:Envelope>"#;
+
+    fn context_with_login() -> RunContext {
+        let mut context = RunContext::default();
+        context.register_steps(&[FrontendTestStep {
+            id: "step-2".to_string(),
+            name: "GetOrders".to_string(),
+            step_type: "request".to_string(),
+            config: FrontendStepConfig {
+                request: Some(FrontendRequest {
+                    request: Some("<GetOrders><session>?</session><limit>10</limit></GetOrders>".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        }]);
+
+        let login = FrontendTestStep {
+            id: "step-1".to_string(),
+            name: "Login".to_string(),
+            step_type: "request".to_string(),
+            config: FrontendStepConfig::default(),
+        };
+        context.record(
+            &login,
+            &StepExecutionResult {
+                passed: true,
+                response_body: Some(LOGIN_RESPONSE.to_string()),
+                status_code: Some(200),
+                response_headers: HashMap::from([("X-Request-Id".to_string(), "r-1".to_string())]),
+                ..Default::default()
+            },
+        );
+        context
+    }
+
+    fn transfer() -> PropertyTransfer {
+        PropertyTransfer {
+            source_step_id: Some("Login".to_string()),
+            source_path: Some("//session".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_transfer_to_variable() {
+        let mut context = context_with_login();
+        let results = run_transfers(
+            &[PropertyTransfer {
+                target_variable: Some("sessionId".to_string()),
+                ..transfer()
+            }],
+            &mut context,
+        );
+
+        assert_eq!(results[0], TransferResult {
+            name: "Transfer 1".to_string(),
+            passed: true,
+            value: Some("S-42".to_string()),
+            message: None,
+        });
+        assert_eq!(context.variables.get("sessionId"), Some(&Value::String("S-42".to_string())));
+    }
+
+    #[test]
+    fn test_transfer_into_target_request_xpath() {
+        let mut context = context_with_login();
+        let results = run_transfers(
+            &[PropertyTransfer {
+                target_step_id: Some("step-2".to_string()),
+                target_property: Some("Request".to_string()),
+                target_path: Some("//session".to_string()),
+                ..transfer()
+            }],
+            &mut context,
+        );
+
+        assert!(results[0].passed, "{:?}", results[0].message);
+        let body = context.request_body_for("GetOrders").unwrap();
+        assert_eq!(XPathEvaluator::evaluate(&body, "//session").as_deref(), Some("S-42"));
+        assert_eq!(XPathEvaluator::evaluate(&body, "//limit").as_deref(), Some("10"));
+    }
+
+    #[test]
+    fn test_header_status_and_regex_sources() {
+        let mut context = context_with_login();
+        let results = run_transfers(
+            &[
+                PropertyTransfer {
+                    name: Some("request id".to_string()),
+                    source_step_id: Some("step-1".to_string()),
+                    source_property: Some("Headers".to_string()),
+                    source_path: Some("x-request-id".to_string()),
+                    target_step_id: Some("GetOrders".to_string()),
+                    target_property: Some("Header".to_string()),
+                    target_path: Some("X-Correlation-Id".to_string()),
+                    ..Default::default()
+                },
+                PropertyTransfer {
+                    source_property: Some("Status".to_string()),
+                    target_variable: Some("loginStatus".to_string()),
+                    ..Default::default()
+                },
+                PropertyTransfer {
+                    source_path: Some(r"S-(\d+)".to_string()),
+                    path_language: Some("Regex".to_string()),
+                    target_variable: Some("sessionNumber".to_string()),
+                    ..Default::default()
+                },
+            ],
+            &mut context,
+        );
+
+        assert!(results.iter().all(|r| r.passed), "{:?}", results);
+        assert_eq!(results[0].name, "request id");
+        let overrides = context.request_overrides.get("step-2").unwrap();
+        assert_eq!(overrides.headers.get("X-Correlation-Id").map(String::as_str), Some("r-1"));
+        assert_eq!(context.variables.get("loginStatus"), Some(&Value::String("200".to_string())));
+        assert_eq!(context.variables.get("sessionNumber"), Some(&Value::String("42".to_string())));
+    }
+
+    #[test]
+    fn test_failed_transfers_report_reason() {
+        let mut context = context_with_login();
+        let results = run_transfers(
+            &[
+                PropertyTransfer {
+                    source_step_id: Some("Missing".to_string()),
+                    target_variable: Some("x".to_string()),
+                    ..Default::default()
+                },
+                PropertyTransfer {
+                    source_path: Some("//nothing".to_string()),
+                    target_variable: Some("x".to_string()),
+                    ..transfer()
+                },
+                PropertyTransfer {
+                    target_step_id: Some("GetOrders".to_string()),
+                    target_path: Some("//nothing".to_string()),
+                    ..transfer()
+                },
+                transfer(),
+            ],
+            &mut context,
+        );
+
+        let messages: Vec<_> = results.iter().map(|r| r.message.clone().unwrap_or_default()).collect();
+        assert_eq!(messages[0], "Source step 'Missing' has not run");
+        assert_eq!(messages[1], "XPath '//nothing' returned no results");
+        assert_eq!(messages[2], "Target XPath '//nothing' matched nothing in the request of 'GetOrders'");
+        assert_eq!(messages[3], "Transfer has no target variable or target step");
+        assert!(results.iter().all(|r| !r.passed));
+    }
+
+    #[test]
+    fn test_single_transfer_from_flat_config() {
+        let config = FrontendStepConfig {
+            source_step_id: Some("step-1".to_string()),
+            source_property: Some("Response".to_string()),
+            source_path: Some("//session".to_string()),
+            target_step_id: Some("step-2".to_string()),
+            target_property: Some("Request".to_string()),
+            target_path: Some("//session".to_string()),
+            ..Default::default()
+        };
+
+        let transfers = transfers_for_step(&config);
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].target_step_id.as_deref(), Some("step-2"));
+        assert!(transfers_for_step(&FrontendStepConfig::default()).is_empty());
+    }
+}
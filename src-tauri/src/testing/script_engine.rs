@@ -0,0 +1,324 @@
+/// Script Engine
+///
+/// Runs JavaScript for `script` test steps and Script assertions in an
+/// embedded QuickJS runtime. The runtime has no file system, network or
+/// module access, a memory cap, and an interrupt handler that aborts the
+/// script once its timeout elapses.
+///
+/// Scripts see these globals:
+/// - `vars` — the context variables as a plain object (assignments are kept)
+/// - `context.getVariable(name)` / `context.setVariable(name, value)`
+/// - `response` — `{ body, status, headers, durationMs, json() }` for the
+///   previous response, or `null` when there is none
+/// - `log(...args)` — collected into `ScriptOutcome::logs`
+/// - `assert(condition, message)`, `assert.equal(actual, expected, message)`
+///
+/// A script fails when it throws (including a failed `assert`), times out, or
+/// returns `false`.
+use rquickjs::{Context, Runtime};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Default time a script may run before it is aborted
+pub const DEFAULT_SCRIPT_TIMEOUT_MS: u64 = 5_000;
+
+/// Upper bound on the memory a single script runtime may allocate
+const MEMORY_LIMIT_BYTES: usize = 64 * 1024 * 1024;
+
+/// Response data exposed to scripts as `response`
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptResponse {
+    pub body: String,
+    pub status: Option<u16>,
+    pub headers: HashMap<String, String>,
+    pub duration_ms: u64,
+}
+
+/// Outcome of running a script
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScriptOutcome {
+    pub passed: bool,
+    /// Messages written with `log(...)`
+    pub logs: Vec<String>,
+    /// The value of `vars` when the script finished
+    pub variables: HashMap<String, String>,
+    pub error: Option<String>,
+    pub timed_out: bool,
+}
+
+impl ScriptOutcome {
+    /// Variables that were added or changed compared to `before`
+    pub fn changed_variables(&self, before: &HashMap<String, String>) -> HashMap<String, String> {
+        self.variables
+            .iter()
+            .filter(|(k, v)| before.get(*k) != Some(*v))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+
+    fn failed(error: String) -> Self {
+        Self {
+            passed: false,
+            error: Some(error),
+            ..Default::default()
+        }
+    }
+}
+
+/// State returned from the JavaScript side as JSON
+#[derive(Debug, Deserialize)]
+struct ScriptState {
+    #[serde(default)]
+    logs: Vec<String>,
+    #[serde(default)]
+    vars: HashMap<String, String>,
+    error: Option<String>,
+    #[serde(default)]
+    returned_false: bool,
+}
+
+/// Sets up the globals, runs the user script via `new Function` (so syntax
+/// errors are caught like any other exception and `return` is allowed) and
+/// records the outcome on `__apinox`.
+const PRELUDE: &str = r#"
+var __apinox = { logs: [], error: null, returnedFalse: false };
+function __apinoxFormat(v) {
+    if (typeof v === 'string') return v;
+    try { var s = JSON.stringify(v); return s === undefined ? String(v) : s; } catch (e) { return String(v); }
+}
+function log() {
+    __apinox.logs.push(Array.prototype.map.call(arguments, __apinoxFormat).join(' '));
+}
+function AssertionError(message) {
+    this.name = 'AssertionError';
+    this.message = message;
+}
+AssertionError.prototype = Object.create(Error.prototype);
+function assert(condition, message) {
+    if (!condition) throw new AssertionError(message || 'Assertion failed');
+}
+assert.equal = function (actual, expected, message) {
+    if (actual != expected) {
+        throw new AssertionError(message || ('Expected ' + __apinoxFormat(expected) + ' but got ' + __apinoxFormat(actual)));
+    }
+};
+var context = {
+    getVariable: function (name) { return vars[name]; },
+    setVariable: function (name, value) { vars[name] = value; }
+};
+if (response) {
+    response.json = function () { return JSON.parse(response.body); };
+}
+"#;
+
+const RUN: &str = r#"
+try {
+    var __ret = new Function(__apinoxSource).call(globalThis);
+    if (__ret === false) __apinox.returnedFalse = true;
+} catch (e) {
+    __apinox.error = (e && e.name && e.message !== undefined) ? (e.name + ': ' + e.message) : String(e);
+}
+"#;
+
+const COLLECT: &str = r#"
+(function () {
+    var out = {};
+    for (var k in vars) {
+        if (vars[k] !== undefined && vars[k] !== null) out[k] = typeof vars[k] === 'string' ? vars[k] : __apinoxFormat(vars[k]);
+    }
+    return JSON.stringify({ logs: __apinox.logs, vars: out, error: __apinox.error, returned_false: __apinox.returnedFalse });
+})()
+"#;
+
+/// Run `source` with the given variables and previous response.
+///
+/// This blocks the calling thread for at most `timeout`; async callers should
+/// use `tokio::task::spawn_blocking`.
+pub fn run_script(
+    source: &str,
+    variables: &HashMap<String, String>,
+    response: Option<&ScriptResponse>,
+    timeout: Duration,
+) -> ScriptOutcome {
+    let runtime = match Runtime::new() {
+        Ok(rt) => rt,
+        Err(e) => return ScriptOutcome::failed(format!("Failed to start script engine: {}", e)),
+    };
+    runtime.set_memory_limit(MEMORY_LIMIT_BYTES);
+
+    // The deadline is cleared after the user script so the state can still be
+    // collected from an interrupted run.
+    let deadline = Rc::new(Cell::new(Some(Instant::now() + timeout)));
+    let handler_deadline = deadline.clone();
+    runtime.set_interrupt_handler(Some(Box::new(move || {
+        handler_deadline.get().map(|d| Instant::now() >= d).unwrap_or(false)
+    })));
+
+    let context = match Context::full(&runtime) {
+        Ok(ctx) => ctx,
+        Err(e) => return ScriptOutcome::failed(format!("Failed to create script context: {}", e)),
+    };
+
+    let globals = format!(
+        "var vars = {};\nvar response = {};\nvar __apinoxSource = {};\n",
+        serde_json::to_string(variables).unwrap_or_else(|_| "{}".to_string()),
+        response
+            .and_then(|r| serde_json::to_string(r).ok())
+            .unwrap_or_else(|| "null".to_string()),
+        serde_json::to_string(source).unwrap_or_else(|_| "\"\"".to_string()),
+    );
+
+    context.with(|ctx| {
+        if let Err(e) = ctx.eval::<(), _>(format!("{}{}", globals, PRELUDE)) {
+            return ScriptOutcome::failed(format!("Failed to initialise script globals: {}", e));
+        }
+
+        let run_result = ctx.eval::<(), _>(RUN);
+        let timed_out = run_result.is_err() && deadline.get().map(|d| Instant::now() >= d).unwrap_or(false);
+        deadline.set(None);
+
+        let state = ctx
+            .eval::<String, _>(COLLECT)
+            .ok()
+            .and_then(|json| serde_json::from_str::<ScriptState>(&json).ok());
+
+        let Some(state) = state else {
+            return ScriptOutcome::failed(match run_result {
+                Err(e) => format!("Script failed: {}", e),
+                Ok(()) => "Script failed: could not read script state".to_string(),
+            });
+        };
+
+        let error = if timed_out {
+            Some(format!("Script timed out after {}ms", timeout.as_millis()))
+        } else if let Err(e) = run_result {
+            Some(format!("Script failed: {}", e))
+        } else if let Some(error) = state.error {
+            Some(error)
+        } else if state.returned_false {
+            Some("Script returned false".to_string())
+        } else {
+            None
+        };
+
+        ScriptOutcome {
+            passed: error.is_none(),
+            logs: state.logs,
+            variables: state.vars,
+            error,
+            timed_out,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn run(source: &str) -> ScriptOutcome {
+        run_script(source, &HashMap::new(), None, Duration::from_millis(DEFAULT_SCRIPT_TIMEOUT_MS))
+    }
+
+    #[test]
+    fn test_logs_and_variables() {
+        let before = vars(&[("orderId", "42"), ("unchanged", "x")]);
+        let outcome = run_script(
+            "log('order', vars.orderId, {a: 1});\nvars.total = 10 * 2;\ncontext.setVariable('status', 'ok');",
+            &before,
+            None,
+            Duration::from_secs(5),
+        );
+
+        assert!(outcome.passed, "{:?}", outcome.error);
+        assert_eq!(outcome.logs, vec!["order 42 {\"a\":1}"]);
+        let changed = outcome.changed_variables(&before);
+        assert_eq!(changed.len(), 2);
+        assert_eq!(changed.get("total").map(String::as_str), Some("20"));
+        assert_eq!(changed.get("status").map(String::as_str), Some("ok"));
+    }
+
+    #[test]
+    fn test_response_is_exposed() {
+        let response = ScriptResponse {
+            body: r#"{"order":{"id":7,"items":[1,2,3]}}"#.to_string(),
+            status: Some(201),
+            headers: vars(&[("Content-Type", "application/json")]),
+            duration_ms: 12,
+        };
+        let outcome = run_script(
+            "assert.equal(response.status, 201);\n\
+             assert(response.headers['Content-Type'] === 'application/json');\n\
+             var data = response.json();\n\
+             assert(data.order.items.length === 3, 'three items');\n\
+             vars.orderId = data.order.id;",
+            &HashMap::new(),
+            Some(&response),
+            Duration::from_secs(5),
+        );
+
+        assert!(outcome.passed, "{:?}", outcome.error);
+        assert_eq!(outcome.variables.get("orderId").map(String::as_str), Some("7"));
+    }
+
+    #[test]
+    fn test_failed_assert_reports_message() {
+        let outcome = run("log('before'); assert(1 === 2, 'numbers differ'); log('after');");
+        assert!(!outcome.passed);
+        assert_eq!(outcome.error.as_deref(), Some("AssertionError: numbers differ"));
+        assert_eq!(outcome.logs, vec!["before"]);
+
+        let outcome = run("assert.equal(response, 'x')");
+        assert_eq!(outcome.error.as_deref(), Some("AssertionError: Expected x but got null"));
+    }
+
+    #[test]
+    fn test_thrown_and_syntax_errors() {
+        let outcome = run("throw new Error('boom')");
+        assert_eq!(outcome.error.as_deref(), Some("Error: boom"));
+
+        let outcome = run("this is not javascript");
+        assert!(!outcome.passed);
+        assert!(outcome.error.unwrap().starts_with("SyntaxError"));
+
+        let outcome = run("throw 'plain string'");
+        assert_eq!(outcome.error.as_deref(), Some("plain string"));
+    }
+
+    #[test]
+    fn test_return_false_fails() {
+        assert!(!run("return false;").passed);
+        assert!(run("return true;").passed);
+        assert!(run("").passed);
+    }
+
+    #[test]
+    fn test_timeout_aborts_infinite_loop() {
+        let start = Instant::now();
+        let outcome = run_script(
+            "log('spinning'); while (true) {}",
+            &HashMap::new(),
+            None,
+            Duration::from_millis(100),
+        );
+
+        assert!(outcome.timed_out);
+        assert!(!outcome.passed);
+        assert_eq!(outcome.error.as_deref(), Some("Script timed out after 100ms"));
+        assert_eq!(outcome.logs, vec!["spinning"]);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_no_host_access() {
+        let outcome = run("assert(typeof require === 'undefined'); assert(typeof std === 'undefined'); assert(typeof os === 'undefined');");
+        assert!(outcome.passed, "{:?}", outcome.error);
+    }
+}
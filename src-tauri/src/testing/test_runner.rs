@@ -6,9 +6,11 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
-use super::assertion_runner::{AssertionType, AssertionResult, run_assertion};
-use super::variable_extractor::{ExtractorType, extract_variable};
+use super::data_source::DataSource;
+use super::assertion_runner::{AssertionType, AssertionResult, run_assertion_with};
+use super::variable_extractor::{ExtractorType, ResponseSnapshot, VariableExtractor};
 use crate::soap::{SoapClient, SoapVersion, WsSecurityConfig};
+use crate::utils::XPathOptions;
 use crate::parsers::wsdl::types::ServiceOperation;
 
 /// Test step type
@@ -81,6 +83,9 @@ pub struct TestCase {
     pub name: String,
     pub description: Option<String>,
     pub steps: Vec<TestStep>,
+    /// Run the case once per row of this data source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_source: Option<DataSource>,
 }
 
 /// Test case result
@@ -263,9 +268,16 @@ impl TestRunner {
         // Run assertions
         let mut assertion_results = Vec::new();
         let mut all_assertions_passed = true;
+        let xpath_options = XPathOptions::default().with_target_namespace(operation.target_namespace.clone());
         
         for assertion in &step.assertions {
-            let result = run_assertion(assertion, &response_body, response.status_code, Some(duration_ms))?;
+            let result = run_assertion_with(
+                assertion,
+                &response_body,
+                response.status_code,
+                Some(duration_ms),
+                &xpath_options,
+            )?;
             if !result.passed {
                 all_assertions_passed = false;
             }
@@ -273,9 +285,14 @@ impl TestRunner {
         }
         
         // Extract variables
+        let snapshot = ResponseSnapshot::new(&response_body)
+            .with_envelope(&response.raw_xml)
+            .with_status(response.status_code)
+            .with_headers(response.headers.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .with_elapsed_ms(duration_ms);
         let mut extracted_variables = HashMap::new();
         for extraction in &step.extractions {
-            if let Ok(value) = extract_variable(&extraction.extractor, &response_body) {
+            if let Ok(value) = VariableExtractor::extract_from(&extraction.extractor, &snapshot) {
                 extracted_variables.insert(extraction.name.clone(), value);
             }
         }
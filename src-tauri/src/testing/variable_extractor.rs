@@ -1,10 +1,15 @@
 /// Variable Extractor
 /// 
 /// Extracts values from responses using XPath, JSONPath, regex, etc.
+///
+/// Shared by `TestRunner`, the frontend runner and the performance runner so
+/// request chaining behaves the same everywhere. Body extractors work on the
+/// response text alone; header, status, cookie, elapsed-time and SOAP fault
+/// extractors need a `ResponseSnapshot`.
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use crate::utils::{RegexExtractor, XPathEvaluator};
+use crate::utils::{json_path, JsonPath, RegexExtractor, XPathEvaluator};
 
 /// Variable extraction method
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -25,6 +30,100 @@ pub enum ExtractorType {
     },
     /// Extract entire response
     Full,
+    /// Value of a response header (case-insensitive)
+    Header {
+        name: String,
+    },
+    /// HTTP status code
+    StatusCode,
+    /// Value of a cookie set by the response (`Set-Cookie`)
+    Cookie {
+        name: String,
+    },
+    /// Response time in milliseconds
+    ElapsedTime,
+    /// A SOAP 1.1 or 1.2 fault field: "code", "string", "actor" or "detail"
+    SoapFault {
+        field: String,
+    },
+}
+
+impl ExtractorType {
+    /// Build an extractor from the loose `type`/`source`/`path` triple used by
+    /// request and performance extractors in the webview ("XPath", "Header",
+    /// source "header", ...).
+    pub fn from_config(kind: &str, source: Option<&str>, path: &str) -> Result<Self> {
+        let path = path.to_string();
+        match source.map(str::to_lowercase).as_deref() {
+            Some("header") => return Ok(ExtractorType::Header { name: path }),
+            Some("status") => return Ok(ExtractorType::StatusCode),
+            _ => {}
+        }
+
+        Ok(match kind.to_lowercase().as_str() {
+            "xpath" => ExtractorType::XPath { xpath: path },
+            "jsonpath" => ExtractorType::JsonPath { path },
+            "regex" => ExtractorType::Regex { pattern: path },
+            "full" | "body" => ExtractorType::Full,
+            "header" => ExtractorType::Header { name: path },
+            "status" | "statuscode" => ExtractorType::StatusCode,
+            "cookie" => ExtractorType::Cookie { name: path },
+            "time" | "elapsedtime" | "responsetime" => ExtractorType::ElapsedTime,
+            "soapfault" | "fault" => ExtractorType::SoapFault { field: path },
+            other => return Err(anyhow!("Unknown extractor type '{}'", other)),
+        })
+    }
+}
+
+/// The parts of a response that extractors can read
+#[derive(Debug, Clone, Default)]
+pub struct ResponseSnapshot<'a> {
+    pub body: &'a str,
+    /// Full SOAP envelope, when `body` only holds the unwrapped payload
+    pub envelope: Option<&'a str>,
+    pub status: Option<u16>,
+    /// Response headers in received order; names may repeat (`Set-Cookie`)
+    pub headers: Vec<(&'a str, &'a str)>,
+    pub elapsed_ms: Option<u64>,
+}
+
+impl<'a> ResponseSnapshot<'a> {
+    pub fn new(body: &'a str) -> Self {
+        Self {
+            body,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    pub fn with_headers<I>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        self.headers = headers.into_iter().collect();
+        self
+    }
+
+    pub fn with_elapsed_ms(mut self, elapsed_ms: u64) -> Self {
+        self.elapsed_ms = Some(elapsed_ms);
+        self
+    }
+
+    pub fn with_envelope(mut self, envelope: &'a str) -> Self {
+        self.envelope = Some(envelope);
+        self
+    }
+
+    fn header(&self, name: &str) -> Option<&'a str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| *v)
+    }
 }
 
 /// Variable extractor
@@ -33,11 +132,32 @@ pub struct VariableExtractor;
 impl VariableExtractor {
     /// Extract a value from response
     pub fn extract(extractor: &ExtractorType, response: &str) -> Result<String> {
+        Self::extract_from(extractor, &ResponseSnapshot::new(response))
+    }
+
+    /// Extract a value from any part of a response
+    pub fn extract_from(extractor: &ExtractorType, response: &ResponseSnapshot) -> Result<String> {
         match extractor {
-            ExtractorType::XPath { xpath } => Self::extract_xpath(response, xpath),
-            ExtractorType::JsonPath { path } => Self::extract_jsonpath(response, path),
-            ExtractorType::Regex { pattern } => Self::extract_regex(response, pattern),
-            ExtractorType::Full => Ok(response.to_string()),
+            ExtractorType::XPath { xpath } => Self::extract_xpath(response.body, xpath),
+            ExtractorType::JsonPath { path } => Self::extract_jsonpath(response.body, path),
+            ExtractorType::Regex { pattern } => Self::extract_regex(response.body, pattern),
+            ExtractorType::Full => Ok(response.body.to_string()),
+            ExtractorType::Header { name } => response
+                .header(name)
+                .map(str::to_string)
+                .ok_or_else(|| anyhow!("Header '{}' not found", name)),
+            ExtractorType::StatusCode => response
+                .status
+                .map(|s| s.to_string())
+                .ok_or_else(|| anyhow!("Response has no status code")),
+            ExtractorType::Cookie { name } => Self::extract_cookie(response, name),
+            ExtractorType::ElapsedTime => response
+                .elapsed_ms
+                .map(|ms| ms.to_string())
+                .ok_or_else(|| anyhow!("Response has no elapsed time")),
+            ExtractorType::SoapFault { field } => {
+                Self::extract_soap_fault(response.envelope.unwrap_or(response.body), field)
+            }
         }
     }
     
@@ -49,33 +169,61 @@ impl VariableExtractor {
     fn extract_jsonpath(json: &str, path: &str) -> Result<String> {
         let value: JsonValue = serde_json::from_str(json)
             .map_err(|e| anyhow!("Failed to parse JSON: {}", e))?;
-        
-        // Simple JSONPath implementation
-        let parts: Vec<&str> = path.trim_start_matches('$').trim_start_matches('.').split('.').collect();
-        let mut current = &value;
-        
-        for part in parts {
-            if part.is_empty() {
-                continue;
-            }
-            
-            current = current.get(part)
-                .ok_or_else(|| anyhow!("Path '{}' not found", part))?;
-        }
-        
-        Ok(match current {
-            JsonValue::String(s) => s.clone(),
-            JsonValue::Number(n) => n.to_string(),
-            JsonValue::Bool(b) => b.to_string(),
-            JsonValue::Null => "null".to_string(),
-            _ => current.to_string(),
-        })
+        let query = JsonPath::parse(path)?;
+
+        // Several matches (wildcards, filters, `..`) are returned as a JSON array
+        json_path::render_matches(&query.query(&value))
+            .ok_or_else(|| anyhow!("JSONPath '{}' returned no results", path))
     }
     
     fn extract_regex(text: &str, pattern: &str) -> Result<String> {
         RegexExtractor::extract(text, pattern)
             .ok_or_else(|| anyhow!("Regex '{}' did not match", pattern))
     }
+
+    /// Find `name=value` in the response's `Set-Cookie` headers
+    fn extract_cookie(response: &ResponseSnapshot, name: &str) -> Result<String> {
+        response
+            .headers
+            .iter()
+            .filter(|(k, _)| k.eq_ignore_ascii_case("set-cookie"))
+            .filter_map(|(_, v)| v.split(';').next()?.split_once('='))
+            .find(|(cookie, _)| cookie.trim() == name)
+            .map(|(_, value)| value.trim().trim_matches('"').to_string())
+            .ok_or_else(|| anyhow!("Cookie '{}' not set by response", name))
+    }
+
+    /// Read a fault field by local name so SOAP 1.1 (`faultcode`, `faultstring`,
+    /// `faultactor`, `detail`) and 1.2 (`Code/Value`, `Reason/Text`, `Role`,
+    /// `Detail`) faults both work.
+    fn extract_soap_fault(xml: &str, field: &str) -> Result<String> {
+        let fault = "//*[local-name()='Fault']";
+        let xpaths: Vec<String> = match field.to_lowercase().as_str() {
+            "code" | "faultcode" => vec![
+                format!("{}/*[local-name()='faultcode']", fault),
+                format!("{}/*[local-name()='Code']/*[local-name()='Value']", fault),
+            ],
+            "" | "string" | "faultstring" | "reason" => vec![
+                format!("{}/*[local-name()='faultstring']", fault),
+                format!("{}/*[local-name()='Reason']/*[local-name()='Text']", fault),
+            ],
+            "actor" | "faultactor" | "role" => vec![
+                format!("{}/*[local-name()='faultactor']", fault),
+                format!("{}/*[local-name()='Role']", fault),
+            ],
+            "detail" => vec![
+                format!("{}/*[local-name()='detail']", fault),
+                format!("{}/*[local-name()='Detail']", fault),
+            ],
+            other => return Err(anyhow!("Unknown SOAP fault field '{}'", other)),
+        };
+
+        xpaths
+            .iter()
+            .find_map(|xpath| XPathEvaluator::evaluate(xml, xpath))
+            .map(|v| v.trim().to_string())
+            .ok_or_else(|| anyhow!("Response has no SOAP fault {}", field))
+    }
 }
 
 /// Convenience function for extracting variables
@@ -108,6 +256,19 @@ mod tests {
         let value = extract_variable(&extractor, json).unwrap();
         assert_eq!(value, "67890");
     }
+
+    #[test]
+    fn test_extract_jsonpath_indexes_and_filters() {
+        let json = r#"{"items":[{"id":1,"status":"OK"},{"id":2,"status":"FAILED"},{"id":3,"status":"OK"}]}"#;
+        let extract = |path: &str| extract_variable(&ExtractorType::JsonPath { path: path.to_string() }, json);
+
+        assert_eq!(extract("$.items[0].id").unwrap(), "1");
+        assert_eq!(extract("$.items[-1].status").unwrap(), "OK");
+        assert_eq!(extract("$.items[?@.status == 'FAILED'].id").unwrap(), "2");
+        assert_eq!(extract("$..id").unwrap(), "[1,2,3]");
+        assert!(extract("$.items[5].id").unwrap_err().to_string().contains("returned no results"));
+        assert!(extract("$.items[").is_err());
+    }
     
     #[test]
     fn test_extract_regex() {
@@ -128,4 +289,74 @@ mod tests {
         let value = extract_variable(&extractor, text).unwrap();
         assert_eq!(value, "Complete response");
     }
+
+    fn snapshot<'a>(body: &'a str, headers: &[(&'a str, &'a str)]) -> ResponseSnapshot<'a> {
+        ResponseSnapshot::new(body)
+            .with_status(201)
+            .with_headers(headers.iter().copied())
+            .with_elapsed_ms(87)
+    }
+
+    #[test]
+    fn test_extract_response_metadata() {
+        let headers = [
+            ("Content-Type", "application/json"),
+            ("Set-Cookie", "theme=dark; Path=/"),
+            ("set-cookie", "JSESSIONID=\"abc123\"; HttpOnly"),
+            ("X-Request-Id", "r-9"),
+        ];
+        let response = snapshot("{}", &headers);
+        let extract = |e: ExtractorType| VariableExtractor::extract_from(&e, &response);
+
+        assert_eq!(extract(ExtractorType::Header { name: "x-request-id".to_string() }).unwrap(), "r-9");
+        assert_eq!(extract(ExtractorType::StatusCode).unwrap(), "201");
+        assert_eq!(extract(ExtractorType::ElapsedTime).unwrap(), "87");
+        assert_eq!(extract(ExtractorType::Cookie { name: "JSESSIONID".to_string() }).unwrap(), "abc123");
+        assert!(extract(ExtractorType::Cookie { name: "missing".to_string() }).is_err());
+        assert!(extract(ExtractorType::Header { name: "Missing".to_string() }).is_err());
+
+        // Body-only extraction has no metadata
+        assert!(extract_variable(&ExtractorType::StatusCode, "{}").is_err());
+    }
+
+    #[test]
+    fn test_extract_soap_fault_fields() {
+        let soap11 = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"><soap:Body><soap:Fault><faultcode>soap:Client</faultcode><faultstring>Invalid credentials</faultstring><detail><code>AUTH-1</code></detail></soap:Fault></soap:Body></soap:Envelope>"#;
+        let soap12 = r#"<env:Envelope xmlns:env="http://www.w3.org/2003/05/soap-envelope"><env:Body><env:Fault><env:Code><env:Value>env:Sender</env:Value></env:Code><env:Reason><env:Text xml:lang="en">Bad request</env:Text></env:Reason></env:Fault></env:Body></env:Envelope>"#;
+        let fault = |xml: &str, field: &str| {
+            let response = ResponseSnapshot::new("<unwrapped/>").with_envelope(xml);
+            VariableExtractor::extract_from(&ExtractorType::SoapFault { field: field.to_string() }, &response)
+        };
+
+        assert_eq!(fault(soap11, "code").unwrap(), "soap:Client");
+        assert_eq!(fault(soap11, "string").unwrap(), "Invalid credentials");
+        assert_eq!(fault(soap11, "detail").unwrap(), "AUTH-1");
+        assert_eq!(fault(soap12, "code").unwrap(), "env:Sender");
+        assert_eq!(fault(soap12, "reason").unwrap(), "Bad request");
+        assert!(fault(soap12, "actor").is_err());
+        assert!(fault("<ok/>", "code").is_err());
+    }
+
+    #[test]
+    fn test_extractor_from_config() {
+        assert_eq!(
+            ExtractorType::from_config("XPath", Some("body"), "//id").unwrap(),
+            ExtractorType::XPath { xpath: "//id".to_string() }
+        );
+        assert_eq!(
+            ExtractorType::from_config("Regex", Some("header"), "Location").unwrap(),
+            ExtractorType::Header { name: "Location".to_string() }
+        );
+        assert_eq!(ExtractorType::from_config("XPath", Some("status"), "").unwrap(), ExtractorType::StatusCode);
+        assert_eq!(
+            ExtractorType::from_config("Cookie", None, "sid").unwrap(),
+            ExtractorType::Cookie { name: "sid".to_string() }
+        );
+        assert!(ExtractorType::from_config("Groovy", None, "").is_err());
+
+        let parsed: ExtractorType = serde_json::from_str(r#"{"type":"soapfault","field":"code"}"#).unwrap();
+        assert_eq!(parsed, ExtractorType::SoapFault { field: "code".to_string() });
+        let parsed: ExtractorType = serde_json::from_str(r#"{"type":"statuscode"}"#).unwrap();
+        assert_eq!(parsed, ExtractorType::StatusCode);
+    }
 }
@@ -0,0 +1,1064 @@
+// JSONPath (RFC 9535) for JSON responses.
+//
+// Supports the full query syntax: child and descendant segments (`.name`,
+// `['name']`, `..name`), wildcards, indexes, slices (`[1:5:2]`), unions
+// (`[0,'a']`) and filters (`[?@.status == 'OK' && @.qty > 1]`) with the
+// standard functions `length`, `count`, `match`, `search` and `value`.
+//
+// For compatibility with the old dotted-path matcher, a path without a
+// leading `$` is treated as relative to the root (`user.name` = `$.user.name`).
+
+use anyhow::{anyhow, bail, Result};
+use regex::Regex;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+/// Largest integer JSONPath allows for indexes and slice bounds (2^53 - 1)
+const MAX_INT: i64 = 9_007_199_254_740_991;
+
+/// A parsed JSONPath query
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    query: Query,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Query {
+    /// `$` (true) or `@` (false)
+    absolute: bool,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+        step: Option<i64>,
+    },
+    Filter(Box<Logical>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Logical {
+    Or(Vec<Logical>),
+    And(Vec<Logical>),
+    Not(Box<Logical>),
+    Compare(Comparable, CompareOp, Comparable),
+    /// Existence test of a filter query
+    Exists(Query),
+    /// `match()` / `search()` used as a test
+    Function(Function),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Comparable {
+    Literal(Value),
+    /// A singular query: at most one node
+    Query(Query),
+    Function(Function),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Function {
+    Length(Box<Comparable>),
+    Count(Query),
+    Match(Box<Comparable>, Box<Comparable>),
+    Search(Box<Comparable>, Box<Comparable>),
+    Value(Query),
+}
+
+impl JsonPath {
+    /// Parse a JSONPath expression.
+    pub fn parse(path: &str) -> Result<Self> {
+        let trimmed = path.trim();
+        let normalized: Cow<str> = if trimmed.starts_with('$') {
+            Cow::Borrowed(trimmed)
+        } else if trimmed.starts_with('[') || trimmed.starts_with('.') {
+            Cow::Owned(format!("${}", trimmed))
+        } else {
+            Cow::Owned(format!("$.{}", trimmed))
+        };
+
+        let mut parser = Parser::new(&normalized);
+        let query = parser.query()?;
+        if !query.absolute {
+            bail!("JSONPath must start with '$'");
+        }
+        if let Some(c) = parser.peek() {
+            bail!("Unexpected '{}' at position {} in JSONPath '{}'", c, parser.pos, path);
+        }
+        Ok(Self { query })
+    }
+
+    /// All nodes selected by the query, in document order.
+    pub fn query<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        self.query.select(root, root)
+    }
+
+    /// True when the query can select at most one node (only name and index
+    /// selectors, no descendants).
+    pub fn is_singular(&self) -> bool {
+        self.query.is_singular()
+    }
+}
+
+/// Render a node as text: strings without quotes, everything else as JSON.
+pub fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Render query results: `None` for no match, the value for a single match
+/// and a JSON array for several.
+pub fn render_matches(nodes: &[&Value]) -> Option<String> {
+    match nodes {
+        [] => None,
+        [single] => Some(value_to_string(single)),
+        many => Some(Value::Array(many.iter().map(|v| (*v).clone()).collect()).to_string()),
+    }
+}
+
+pub struct JsonPathEvaluator;
+
+impl JsonPathEvaluator {
+    /// Evaluate a JSONPath expression on JSON text.
+    /// Returns the match rendered with `render_matches`, or `None` if nothing
+    /// matched or the JSON or expression is invalid.
+    pub fn evaluate(json: &str, path: &str) -> Option<String> {
+        let value: Value = serde_json::from_str(json).ok()?;
+        let path = JsonPath::parse(path).ok()?;
+        render_matches(&path.query(&value))
+    }
+
+    /// Evaluate JSONPath and return every match as a string.
+    pub fn evaluate_all(json: &str, path: &str) -> Vec<String> {
+        let (Ok(value), Ok(path)) = (serde_json::from_str::<Value>(json), JsonPath::parse(path)) else {
+            return vec![];
+        };
+        path.query(&value).into_iter().map(value_to_string).collect()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Evaluation
+// ─────────────────────────────────────────────────────────────────────────────
+
+impl Query {
+    fn select<'a>(&self, current: &'a Value, root: &'a Value) -> Vec<&'a Value> {
+        let mut nodes = vec![if self.absolute { root } else { current }];
+        for segment in &self.segments {
+            let mut next = Vec::new();
+            for node in nodes {
+                match segment {
+                    Segment::Child(selectors) => {
+                        for selector in selectors {
+                            selector.select(node, root, &mut next);
+                        }
+                    }
+                    Segment::Descendant(selectors) => {
+                        let mut visited = Vec::new();
+                        descendants(node, &mut visited);
+                        for descendant in visited {
+                            for selector in selectors {
+                                selector.select(descendant, root, &mut next);
+                            }
+                        }
+                    }
+                }
+            }
+            nodes = next;
+        }
+        nodes
+    }
+
+    fn is_singular(&self) -> bool {
+        self.segments.iter().all(|segment| match segment {
+            Segment::Child(selectors) => {
+                matches!(selectors.as_slice(), [Selector::Name(_)] | [Selector::Index(_)])
+            }
+            Segment::Descendant(_) => false,
+        })
+    }
+}
+
+/// A node followed by all its descendants, parents before children.
+fn descendants<'a>(node: &'a Value, out: &mut Vec<&'a Value>) {
+    out.push(node);
+    match node {
+        Value::Array(items) => items.iter().for_each(|v| descendants(v, out)),
+        Value::Object(map) => map.values().for_each(|v| descendants(v, out)),
+        _ => {}
+    }
+}
+
+fn normalize_index(index: i64, len: i64) -> i64 {
+    if index >= 0 { index } else { len + index }
+}
+
+impl Selector {
+    fn select<'a>(&self, node: &'a Value, root: &'a Value, out: &mut Vec<&'a Value>) {
+        match self {
+            Selector::Name(name) => {
+                if let Some(v) = node.as_object().and_then(|m| m.get(name)) {
+                    out.push(v);
+                }
+            }
+            Selector::Wildcard => match node {
+                Value::Array(items) => out.extend(items.iter()),
+                Value::Object(map) => out.extend(map.values()),
+                _ => {}
+            },
+            Selector::Index(index) => {
+                if let Some(items) = node.as_array() {
+                    let i = normalize_index(*index, items.len() as i64);
+                    if i >= 0 {
+                        if let Some(v) = items.get(i as usize) {
+                            out.push(v);
+                        }
+                    }
+                }
+            }
+            Selector::Slice { start, end, step } => {
+                if let Some(items) = node.as_array() {
+                    for i in slice_indices(items.len() as i64, *start, *end, step.unwrap_or(1)) {
+                        out.push(&items[i]);
+                    }
+                }
+            }
+            Selector::Filter(expr) => {
+                let children: Vec<&Value> = match node {
+                    Value::Array(items) => items.iter().collect(),
+                    Value::Object(map) => map.values().collect(),
+                    _ => vec![],
+                };
+                out.extend(children.into_iter().filter(|child| expr.test(child, root)));
+            }
+        }
+    }
+}
+
+/// Array positions selected by a slice, per RFC 9535 section 2.3.4.2.2.
+fn slice_indices(len: i64, start: Option<i64>, end: Option<i64>, step: i64) -> Vec<usize> {
+    let mut indices = Vec::new();
+    if step == 0 {
+        return indices;
+    }
+
+    if step > 0 {
+        let lower = start.map(|s| normalize_index(s, len)).unwrap_or(0).clamp(0, len);
+        let upper = end.map(|e| normalize_index(e, len)).unwrap_or(len).clamp(0, len);
+        let mut i = lower;
+        while i < upper {
+            indices.push(i as usize);
+            i += step;
+        }
+    } else {
+        let upper = start.map(|s| normalize_index(s, len)).unwrap_or(len - 1).clamp(-1, len - 1);
+        let lower = end.map(|e| normalize_index(e, len)).unwrap_or(-len - 1).clamp(-1, len - 1);
+        let mut i = upper;
+        while lower < i {
+            indices.push(i as usize);
+            i += step;
+        }
+    }
+    indices
+}
+
+impl Logical {
+    fn test(&self, current: &Value, root: &Value) -> bool {
+        match self {
+            Logical::Or(items) => items.iter().any(|e| e.test(current, root)),
+            Logical::And(items) => items.iter().all(|e| e.test(current, root)),
+            Logical::Not(inner) => !inner.test(current, root),
+            Logical::Exists(query) => !query.select(current, root).is_empty(),
+            Logical::Function(function) => function.test(current, root),
+            Logical::Compare(left, op, right) => {
+                let left = left.value(current, root);
+                let right = right.value(current, root);
+                compare(left.as_deref(), *op, right.as_deref())
+            }
+        }
+    }
+}
+
+impl Comparable {
+    /// The value of a comparable; `None` is the special result "Nothing".
+    fn value<'a>(&'a self, current: &'a Value, root: &'a Value) -> Option<Cow<'a, Value>> {
+        match self {
+            Comparable::Literal(v) => Some(Cow::Borrowed(v)),
+            Comparable::Query(query) => match query.select(current, root).as_slice() {
+                [single] => Some(Cow::Borrowed(*single)),
+                _ => None,
+            },
+            Comparable::Function(function) => function.value(current, root),
+        }
+    }
+}
+
+impl Function {
+    /// Result of a function returning ValueType
+    fn value<'a>(&'a self, current: &'a Value, root: &'a Value) -> Option<Cow<'a, Value>> {
+        match self {
+            Function::Length(arg) => {
+                let len = match arg.value(current, root)?.as_ref() {
+                    Value::String(s) => s.chars().count(),
+                    Value::Array(items) => items.len(),
+                    Value::Object(map) => map.len(),
+                    _ => return None,
+                };
+                Some(Cow::Owned(Value::from(len)))
+            }
+            Function::Count(query) => Some(Cow::Owned(Value::from(query.select(current, root).len()))),
+            Function::Value(query) => match query.select(current, root).as_slice() {
+                [single] => Some(Cow::Borrowed(*single)),
+                _ => None,
+            },
+            Function::Match(..) | Function::Search(..) => None,
+        }
+    }
+
+    /// Result of a function returning LogicalType
+    fn test(&self, current: &Value, root: &Value) -> bool {
+        let (text, pattern, anchored) = match self {
+            Function::Match(text, pattern) => (text, pattern, true),
+            Function::Search(text, pattern) => (text, pattern, false),
+            _ => return false,
+        };
+        let (Some(text), Some(pattern)) = (text.value(current, root), pattern.value(current, root)) else {
+            return false;
+        };
+        let (Value::String(text), Value::String(pattern)) = (text.as_ref(), pattern.as_ref()) else {
+            return false;
+        };
+
+        let pattern = if anchored {
+            format!("^(?:{})$", pattern)
+        } else {
+            pattern.clone()
+        };
+        Regex::new(&pattern).map(|re| re.is_match(text)).unwrap_or(false)
+    }
+}
+
+fn compare(left: Option<&Value>, op: CompareOp, right: Option<&Value>) -> bool {
+    match op {
+        CompareOp::Eq => equal(left, right),
+        CompareOp::Ne => !equal(left, right),
+        CompareOp::Lt => less(left, right),
+        CompareOp::Le => less(left, right) || equal(left, right),
+        CompareOp::Gt => less(right, left),
+        CompareOp::Ge => less(right, left) || equal(left, right),
+    }
+}
+
+fn equal(left: Option<&Value>, right: Option<&Value>) -> bool {
+    match (left, right) {
+        (None, None) => true,
+        (Some(a), Some(b)) => values_equal(a, b),
+        _ => false,
+    }
+}
+
+fn values_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        // 1 == 1.0
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        (Value::Array(x), Value::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(a, b)| values_equal(a, b))
+        }
+        (Value::Object(x), Value::Object(y)) => {
+            x.len() == y.len() && x.iter().all(|(k, v)| y.get(k).is_some_and(|w| values_equal(v, w)))
+        }
+        _ => a == b,
+    }
+}
+
+fn less(left: Option<&Value>, right: Option<&Value>) -> bool {
+    match (left, right) {
+        (Some(Value::Number(x)), Some(Value::Number(y))) => {
+            x.as_f64().partial_cmp(&y.as_f64()) == Some(Ordering::Less)
+        }
+        (Some(Value::String(x)), Some(Value::String(y))) => x < y,
+        _ => false,
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Parsing
+// ─────────────────────────────────────────────────────────────────────────────
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Self {
+        Self {
+            chars: input.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        let matches = s.chars().enumerate().all(|(i, c)| self.peek_at(i) == Some(c));
+        if matches {
+            self.pos += s.chars().count();
+        }
+        matches
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c)))
+        }
+    }
+
+    fn skip_blank(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, message: &str) -> anyhow::Error {
+        match self.peek() {
+            Some(c) => anyhow!("Invalid JSONPath: {} at position {} (found '{}')", message, self.pos, c),
+            None => anyhow!("Invalid JSONPath: {} at end of expression", message),
+        }
+    }
+
+    /// `$` or `@` followed by segments
+    fn query(&mut self) -> Result<Query> {
+        let absolute = match self.peek() {
+            Some('$') => true,
+            Some('@') => false,
+            _ => return Err(self.error("expected '$' or '@'")),
+        };
+        self.pos += 1;
+
+        let mut segments = Vec::new();
+        loop {
+            // Blank space may separate segments
+            let checkpoint = self.pos;
+            self.skip_blank();
+            match (self.peek(), self.peek_at(1)) {
+                (Some('.'), Some('.')) => {
+                    self.pos += 2;
+                    segments.push(Segment::Descendant(self.descendant_selectors()?));
+                }
+                (Some('.'), _) => {
+                    self.pos += 1;
+                    let selector = if self.eat('*') {
+                        Selector::Wildcard
+                    } else {
+                        Selector::Name(self.member_name()?)
+                    };
+                    segments.push(Segment::Child(vec![selector]));
+                }
+                (Some('['), _) => segments.push(Segment::Child(self.bracketed()?)),
+                _ => {
+                    self.pos = checkpoint;
+                    break;
+                }
+            }
+        }
+
+        Ok(Query { absolute, segments })
+    }
+
+    fn descendant_selectors(&mut self) -> Result<Vec<Selector>> {
+        match self.peek() {
+            Some('[') => self.bracketed(),
+            Some('*') => {
+                self.pos += 1;
+                Ok(vec![Selector::Wildcard])
+            }
+            _ => Ok(vec![Selector::Name(self.member_name()?)]),
+        }
+    }
+
+    /// member-name-shorthand: ALPHA / "_" / non-ASCII, then also digits
+    fn member_name(&mut self) -> Result<String> {
+        let start = self.pos;
+        match self.peek() {
+            Some(c) if c.is_ascii_alphabetic() || c == '_' || !c.is_ascii() => self.pos += 1,
+            _ => return Err(self.error("expected a member name")),
+        }
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || c == '_' || !c.is_ascii() {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn bracketed(&mut self) -> Result<Vec<Selector>> {
+        self.expect('[')?;
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_blank();
+            selectors.push(self.selector()?);
+            self.skip_blank();
+            if self.eat(']') {
+                return Ok(selectors);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn selector(&mut self) -> Result<Selector> {
+        match self.peek() {
+            Some('\'' | '"') => Ok(Selector::Name(self.string_literal()?)),
+            Some('*') => {
+                self.pos += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some('?') => {
+                self.pos += 1;
+                self.skip_blank();
+                Ok(Selector::Filter(Box::new(self.logical_or()?)))
+            }
+            Some(':') | Some('-') | Some('0'..='9') => self.index_or_slice(),
+            _ => Err(self.error("expected a selector")),
+        }
+    }
+
+    fn index_or_slice(&mut self) -> Result<Selector> {
+        let start = self.optional_int()?;
+        self.skip_blank();
+        if !self.eat(':') {
+            return start
+                .map(Selector::Index)
+                .ok_or_else(|| self.error("expected an index"));
+        }
+        self.skip_blank();
+        let end = self.optional_int()?;
+        self.skip_blank();
+        let step = if self.eat(':') {
+            self.skip_blank();
+            self.optional_int()?
+        } else {
+            None
+        };
+        Ok(Selector::Slice { start, end, step })
+    }
+
+    fn optional_int(&mut self) -> Result<Option<i64>> {
+        match self.peek() {
+            Some('-' | '0'..='9') => self.int().map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// int = "0" / (["-"] DIGIT1 *DIGIT), within +/- 2^53-1
+    fn int(&mut self) -> Result<i64> {
+        let start = self.pos;
+        let negative = self.eat('-');
+        let digits_start = self.pos;
+        while matches!(self.peek(), Some('0'..='9')) {
+            self.pos += 1;
+        }
+        let digits: String = self.chars[digits_start..self.pos].iter().collect();
+        if digits.is_empty() || (digits.starts_with('0') && (digits.len() > 1 || negative)) {
+            self.pos = start;
+            return Err(self.error("invalid integer"));
+        }
+        let value: i64 = digits.parse().map_err(|_| self.error("integer out of range"))?;
+        if value > MAX_INT {
+            self.pos = start;
+            return Err(self.error("integer out of range"));
+        }
+        Ok(if negative { -value } else { value })
+    }
+
+    fn string_literal(&mut self) -> Result<String> {
+        let quote = self.peek().ok_or_else(|| self.error("expected a string"))?;
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            let c = self.peek().ok_or_else(|| self.error("unterminated string"))?;
+            self.pos += 1;
+            match c {
+                c if c == quote => return Ok(out),
+                '\\' => {
+                    let escaped = self.peek().ok_or_else(|| self.error("unterminated escape"))?;
+                    self.pos += 1;
+                    match escaped {
+                        'b' => out.push('\u{8}'),
+                        'f' => out.push('\u{c}'),
+                        'n' => out.push('\n'),
+                        'r' => out.push('\r'),
+                        't' => out.push('\t'),
+                        '/' => out.push('/'),
+                        '\\' => out.push('\\'),
+                        'u' => out.push(self.unicode_escape()?),
+                        c if c == quote => out.push(c),
+                        _ => return Err(self.error("invalid escape")),
+                    }
+                }
+                c if (c as u32) < 0x20 => return Err(self.error("control character in string")),
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32> {
+        let hex: String = self.chars.get(self.pos..self.pos + 4).unwrap_or_default().iter().collect();
+        let code = u32::from_str_radix(&hex, 16)
+            .ok()
+            .filter(|_| hex.len() == 4)
+            .ok_or_else(|| self.error("invalid \\u escape"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+
+    fn unicode_escape(&mut self) -> Result<char> {
+        let high = self.hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if !self.eat_str("\\u") {
+                return Err(self.error("unpaired surrogate"));
+            }
+            let low = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("unpaired surrogate"));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else if (0xDC00..0xE000).contains(&high) {
+            return Err(self.error("unpaired surrogate"));
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid \\u escape"))
+    }
+
+    fn logical_or(&mut self) -> Result<Logical> {
+        let mut items = vec![self.logical_and()?];
+        loop {
+            self.skip_blank();
+            if !self.eat_str("||") {
+                break;
+            }
+            self.skip_blank();
+            items.push(self.logical_and()?);
+        }
+        Ok(if items.len() == 1 { items.remove(0) } else { Logical::Or(items) })
+    }
+
+    fn logical_and(&mut self) -> Result<Logical> {
+        let mut items = vec![self.basic_expr()?];
+        loop {
+            self.skip_blank();
+            if !self.eat_str("&&") {
+                break;
+            }
+            self.skip_blank();
+            items.push(self.basic_expr()?);
+        }
+        Ok(if items.len() == 1 { items.remove(0) } else { Logical::And(items) })
+    }
+
+    fn basic_expr(&mut self) -> Result<Logical> {
+        // Negated paren-expr or test-expr
+        if self.peek() == Some('!') && self.peek_at(1) != Some('=') {
+            self.pos += 1;
+            self.skip_blank();
+            let inner = if self.eat('(') {
+                self.paren_rest()?
+            } else {
+                self.test_expr(true)?
+            };
+            return Ok(Logical::Not(Box::new(inner)));
+        }
+
+        if self.eat('(') {
+            return self.paren_rest();
+        }
+
+        let left_start = self.pos;
+        let left = self.comparable_or_test()?;
+        self.skip_blank();
+        match self.compare_op() {
+            Some(op) => {
+                self.skip_blank();
+                let left = into_comparable(left).map_err(|e| {
+                    anyhow!("Invalid JSONPath: {} at position {}", e, left_start)
+                })?;
+                let right_start = self.pos;
+                let right = into_comparable(self.comparable_or_test()?).map_err(|e| {
+                    anyhow!("Invalid JSONPath: {} at position {}", e, right_start)
+                })?;
+                Ok(Logical::Compare(left, op, right))
+            }
+            None => match left {
+                Operand::Query(query) => Ok(Logical::Exists(query)),
+                Operand::Function(f @ (Function::Match(..) | Function::Search(..))) => Ok(Logical::Function(f)),
+                Operand::Function(_) => Err(anyhow!(
+                    "Invalid JSONPath: function at position {} must be compared to a value",
+                    left_start
+                )),
+                Operand::Literal(_) => Err(anyhow!(
+                    "Invalid JSONPath: literal at position {} must be compared to a value",
+                    left_start
+                )),
+            },
+        }
+    }
+
+    fn paren_rest(&mut self) -> Result<Logical> {
+        self.skip_blank();
+        let inner = self.logical_or()?;
+        self.skip_blank();
+        self.expect(')')?;
+        Ok(inner)
+    }
+
+    /// Operand after `!`: a filter query or a logical function
+    fn test_expr(&mut self, negated: bool) -> Result<Logical> {
+        let start = self.pos;
+        match self.comparable_or_test()? {
+            Operand::Query(query) => Ok(Logical::Exists(query)),
+            Operand::Function(f @ (Function::Match(..) | Function::Search(..))) => Ok(Logical::Function(f)),
+            _ => Err(anyhow!(
+                "Invalid JSONPath: expected a query or match()/search() {}at position {}",
+                if negated { "after '!' " } else { "" },
+                start
+            )),
+        }
+    }
+
+    fn compare_op(&mut self) -> Option<CompareOp> {
+        for (token, op) in [
+            ("==", CompareOp::Eq),
+            ("!=", CompareOp::Ne),
+            ("<=", CompareOp::Le),
+            (">=", CompareOp::Ge),
+            ("<", CompareOp::Lt),
+            (">", CompareOp::Gt),
+        ] {
+            if self.eat_str(token) {
+                return Some(op);
+            }
+        }
+        None
+    }
+
+    fn comparable_or_test(&mut self) -> Result<Operand> {
+        match self.peek() {
+            Some('$' | '@') => Ok(Operand::Query(self.query()?)),
+            Some('\'' | '"') => Ok(Operand::Literal(Value::String(self.string_literal()?))),
+            Some('-' | '0'..='9') => Ok(Operand::Literal(self.number()?)),
+            Some(c) if c.is_ascii_lowercase() => {
+                for (word, value) in [("true", Value::Bool(true)), ("false", Value::Bool(false)), ("null", Value::Null)] {
+                    let followed_by_name = self
+                        .peek_at(word.len())
+                        .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_');
+                    if !followed_by_name && self.eat_str(word) {
+                        return Ok(Operand::Literal(value));
+                    }
+                }
+                Ok(Operand::Function(self.function()?))
+            }
+            _ => Err(self.error("expected a query, literal or function")),
+        }
+    }
+
+    /// number = (int / "-0") [ frac ] [ exp ]
+    fn number(&mut self) -> Result<Value> {
+        let start = self.pos;
+        self.eat('-');
+        while matches!(self.peek(), Some('0'..='9')) {
+            self.pos += 1;
+        }
+        if self.peek() == Some('.') && matches!(self.peek_at(1), Some('0'..='9')) {
+            self.pos += 1;
+            while matches!(self.peek(), Some('0'..='9')) {
+                self.pos += 1;
+            }
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.pos += 1;
+            }
+            while matches!(self.peek(), Some('0'..='9')) {
+                self.pos += 1;
+            }
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        let digits = text.trim_start_matches('-');
+        let leading_zero = digits.len() > 1 && digits.starts_with('0') && digits.as_bytes()[1].is_ascii_digit();
+        match serde_json::from_str::<Value>(&text) {
+            Ok(value @ Value::Number(_)) if !leading_zero => Ok(value),
+            _ => {
+                self.pos = start;
+                Err(self.error("invalid number"))
+            }
+        }
+    }
+
+    fn function(&mut self) -> Result<Function> {
+        let start = self.pos;
+        while matches!(self.peek(), Some('a'..='z' | '0'..='9' | '_')) {
+            self.pos += 1;
+        }
+        let name: String = self.chars[start..self.pos].iter().collect();
+        if !self.eat('(') {
+            self.pos = start;
+            return Err(self.error("expected a query, literal or function"));
+        }
+
+        let mut args = Vec::new();
+        self.skip_blank();
+        if !self.eat(')') {
+            loop {
+                self.skip_blank();
+                args.push(self.comparable_or_test()?);
+                self.skip_blank();
+                if self.eat(')') {
+                    break;
+                }
+                self.expect(',')?;
+            }
+        }
+
+        let arity_error = |expected: usize| {
+            anyhow!("Invalid JSONPath: {}() takes {} argument(s) at position {}", name, expected, start)
+        };
+        let value_arg = |arg: Operand| {
+            into_comparable(arg).map_err(|e| anyhow!("Invalid JSONPath: {} in {}() at position {}", e, name, start))
+        };
+        let nodes_arg = |arg: Operand| match arg {
+            Operand::Query(query) => Ok(query),
+            _ => Err(anyhow!("Invalid JSONPath: {}() expects a query at position {}", name, start)),
+        };
+
+        match name.as_str() {
+            "length" => {
+                let [arg] = <[Operand; 1]>::try_from(args).map_err(|_| arity_error(1))?;
+                Ok(Function::Length(Box::new(value_arg(arg)?)))
+            }
+            "count" => {
+                let [arg] = <[Operand; 1]>::try_from(args).map_err(|_| arity_error(1))?;
+                Ok(Function::Count(nodes_arg(arg)?))
+            }
+            "value" => {
+                let [arg] = <[Operand; 1]>::try_from(args).map_err(|_| arity_error(1))?;
+                Ok(Function::Value(nodes_arg(arg)?))
+            }
+            "match" | "search" => {
+                let [text, pattern] = <[Operand; 2]>::try_from(args).map_err(|_| arity_error(2))?;
+                let (text, pattern) = (Box::new(value_arg(text)?), Box::new(value_arg(pattern)?));
+                Ok(if name == "match" { Function::Match(text, pattern) } else { Function::Search(text, pattern) })
+            }
+            _ => Err(anyhow!("Invalid JSONPath: unknown function '{}' at position {}", name, start)),
+        }
+    }
+}
+
+/// A filter operand before its role (comparison or test) is known
+enum Operand {
+    Literal(Value),
+    Query(Query),
+    Function(Function),
+}
+
+/// Check an operand can be used where a single value is expected
+fn into_comparable(operand: Operand) -> std::result::Result<Comparable, String> {
+    match operand {
+        Operand::Literal(v) => Ok(Comparable::Literal(v)),
+        Operand::Query(q) if q.is_singular() => Ok(Comparable::Query(q)),
+        Operand::Query(_) => Err("non-singular query used as a value".to_string()),
+        Operand::Function(f @ (Function::Length(_) | Function::Count(_) | Function::Value(_))) => {
+            Ok(Comparable::Function(f))
+        }
+        Operand::Function(_) => Err("match()/search() used as a value".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn store() -> Value {
+        json!({
+            "store": {
+                "book": [
+                    {"category": "reference", "author": "Nigel Rees", "title": "Sayings of the Century", "price": 8.95},
+                    {"category": "fiction", "author": "Evelyn Waugh", "title": "Sword of Honour", "price": 12.99},
+                    {"category": "fiction", "author": "Herman Melville", "title": "Moby Dick", "isbn": "0-553-21311-3", "price": 8.99},
+                    {"category": "fiction", "author": "J. R. R. Tolkien", "title": "The Lord of the Rings", "isbn": "0-395-19395-8", "price": 22.99}
+                ],
+                "bicycle": {"color": "red", "price": 399}
+            }
+        })
+    }
+
+    fn q(path: &str, value: &Value) -> Vec<Value> {
+        JsonPath::parse(path)
+            .unwrap_or_else(|e| panic!("{}: {}", path, e))
+            .query(value)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_rfc_examples() {
+        let doc = store();
+        assert_eq!(q("$.store.book[*].author", &doc).len(), 4);
+        assert_eq!(q("$..author", &doc)[3], json!("J. R. R. Tolkien"));
+        assert_eq!(q("$.store.*", &doc).len(), 2);
+        assert_eq!(q("$.store..price", &doc).len(), 5);
+        assert_eq!(q("$..book[2].author", &doc), vec![json!("Herman Melville")]);
+        assert_eq!(q("$..book[-1].title", &doc), vec![json!("The Lord of the Rings")]);
+        assert_eq!(q("$..book[0,1].title", &doc).len(), 2);
+        assert_eq!(q("$..book[:2].title", &doc).len(), 2);
+        assert_eq!(q("$..book[?@.isbn].title", &doc).len(), 2);
+        assert_eq!(
+            q("$..book[?@.price<10].title", &doc),
+            vec![json!("Sayings of the Century"), json!("Moby Dick")]
+        );
+        assert_eq!(q("$..*", &doc).len(), 27);
+    }
+
+    #[test]
+    fn test_names_indexes_and_slices() {
+        let doc = json!({"a": [0, 1, 2, 3, 4, 5], "o": {"j j": {"k.k": 3}, "'": 1}, "": "empty"});
+        assert_eq!(q("$.a[1:4]", &doc), vec![json!(1), json!(2), json!(3)]);
+        assert_eq!(q("$.a[::2]", &doc), vec![json!(0), json!(2), json!(4)]);
+        assert_eq!(q("$.a[5:1:-2]", &doc), vec![json!(5), json!(3)]);
+        assert_eq!(q("$.a[::-1]", &doc).first(), Some(&json!(5)));
+        assert!(q("$.a[::0]", &doc).is_empty());
+        assert_eq!(q("$.a[-2]", &doc), vec![json!(4)]);
+        assert!(q("$.a[6]", &doc).is_empty());
+        assert_eq!(q("$.o['j j']['k.k']", &doc), vec![json!(3)]);
+        assert_eq!(q(r#"$.o["'"]"#, &doc), vec![json!(1)]);
+        assert_eq!(q("$['']", &doc), vec![json!("empty")]);
+        assert_eq!(q("$[ 'a' ][ 0 ]", &doc), vec![json!(0)]);
+        // Dotted paths without '$' still work
+        assert_eq!(q("a[0]", &doc), vec![json!(0)]);
+    }
+
+    #[test]
+    fn test_filters() {
+        let doc = json!({"items": [
+            {"id": 1, "status": "OK", "qty": 2, "tags": ["a", "b"]},
+            {"id": 2, "status": "FAILED", "qty": 0},
+            {"id": 3, "status": "OK", "qty": 1.0, "tags": []},
+            {"id": 4, "status": null}
+        ], "limit": 1});
+
+        let ids = |path: &str| q(path, &doc);
+        assert_eq!(ids("$.items[?@.status == 'OK'].id"), vec![json!(1), json!(3)]);
+        assert_eq!(ids("$.items[?(@.status=='OK')].id"), vec![json!(1), json!(3)]);
+        assert_eq!(ids("$.items[?@.status == 'OK' && @.qty > 1].id"), vec![json!(1)]);
+        assert_eq!(ids("$.items[?@.qty == 1].id"), vec![json!(3)]);
+        assert_eq!(ids("$.items[?@.qty == $.limit].id"), vec![json!(3)]);
+        assert_eq!(ids("$.items[?@.status == null].id"), vec![json!(4)]);
+        assert_eq!(ids("$.items[?!@.tags].id"), vec![json!(2), json!(4)]);
+        assert_eq!(ids("$.items[?!(@.qty >= 1 || @.status == 'FAILED')].id"), vec![json!(4)]);
+        // Missing members only equal other missing members
+        assert_eq!(ids("$.items[?@.qty == @.missing].id"), vec![json!(4)]);
+    }
+
+    #[test]
+    fn test_functions() {
+        let doc = json!({"items": [
+            {"name": "alpha", "tags": ["x", "y"]},
+            {"name": "beta", "tags": ["x"]},
+            {"name": "gamma"}
+        ]});
+
+        assert_eq!(q("$.items[?length(@.name) == 4].name", &doc), vec![json!("beta")]);
+        assert_eq!(q("$.items[?count(@.tags[*]) > 1].name", &doc), vec![json!("alpha")]);
+        assert_eq!(q("$.items[?match(@.name, 'a.*a')].name", &doc), vec![json!("alpha")]);
+        assert_eq!(q("$.items[?search(@.name, 'a.*a')].name", &doc), vec![json!("alpha"), json!("gamma")]);
+        assert_eq!(q("$.items[?search(@.name, 'et')].name", &doc), vec![json!("beta")]);
+        // value() of several nodes is Nothing
+        assert_eq!(q("$.items[?value(@.tags[*]) == 'x'].name", &doc), vec![json!("beta")]);
+        assert_eq!(q("$.items[?value(@.tags[0]) == 'x'].name", &doc).len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_paths() {
+        for path in [
+            "$.",
+            "$[",
+            "$['a'",
+            "$[01]",
+            "$[-0]",
+            "$.a[?@.b == ]",
+            "$.a[?@.* == 1]",
+            "$.a[?length(@.b)]",
+            "$.a[?match(@.b, 'x') == true]",
+            "$.a[?foo(@.b)]",
+            "$.a[?count(1) == 1]",
+            "$.a b",
+            "$[9007199254740992]",
+            // Array literals are not part of the grammar
+            "$.a[?@.tags == ['a']]",
+        ] {
+            assert!(JsonPath::parse(path).is_err(), "{} should be rejected", path);
+        }
+        assert!(JsonPath::parse("$[?@.a=='\\u00e9']").is_ok());
+    }
+
+    #[test]
+    fn test_evaluator_rendering() {
+        let json = r#"{"order":{"id":7,"lines":[{"sku":"A"},{"sku":"B"}],"note":null}}"#;
+        assert_eq!(JsonPathEvaluator::evaluate(json, "$.order.id").as_deref(), Some("7"));
+        assert_eq!(JsonPathEvaluator::evaluate(json, "$.order.lines[0].sku").as_deref(), Some("A"));
+        assert_eq!(JsonPathEvaluator::evaluate(json, "$..sku").as_deref(), Some(r#"["A","B"]"#));
+        assert_eq!(JsonPathEvaluator::evaluate(json, "$.order.note").as_deref(), Some("null"));
+        assert_eq!(JsonPathEvaluator::evaluate(json, "$.order.missing"), None);
+        assert_eq!(JsonPathEvaluator::evaluate("not json", "$.a"), None);
+        assert_eq!(JsonPathEvaluator::evaluate_all(json, "$..sku"), vec!["A", "B"]);
+        assert!(JsonPath::parse("$.order.lines[0].sku").unwrap().is_singular());
+        assert!(!JsonPath::parse("$..sku").unwrap().is_singular());
+    }
+}
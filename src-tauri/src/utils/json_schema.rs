@@ -0,0 +1,572 @@
+// JSON Schema validation for response bodies.
+//
+// Covers the subset of JSON Schema used by OpenAPI documents: `$ref`, `type`
+// (including `nullable`), `enum`/`const`, object, array, string and numeric
+// keywords, `allOf`/`anyOf`/`oneOf`/`not`, and the common `format`s. Errors
+// carry the JSONPath of the offending value, e.g. `$.items[2].id`.
+
+use chrono::{DateTime, NaiveDate};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Nesting limit for `$ref` chains and combinators, guarding against cycles
+const MAX_DEPTH: usize = 64;
+
+static EMAIL: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[^@\s]+@[^@\s]+\.[^@\s]+$").unwrap());
+static UUID: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$").unwrap()
+});
+static URI: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-zA-Z][a-zA-Z0-9+.-]*:[^\s]*$").unwrap());
+static IDENTIFIER: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").unwrap());
+
+/// A value that does not conform to its schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaError {
+    /// JSONPath of the value, `$` for the document itself
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Validates JSON values against JSON Schemas.
+///
+/// `$ref`s resolve either as JSON pointers into a root document, or through
+/// a table keyed by the `$ref` string (see `OpenApiSpec::schemas`).
+pub struct JsonSchemaValidator<'a> {
+    root: Option<&'a Value>,
+    refs: Option<&'a BTreeMap<String, Value>>,
+}
+
+impl<'a> JsonSchemaValidator<'a> {
+    /// Resolve `$ref`s as JSON pointers into `root`
+    pub fn new(root: &'a Value) -> Self {
+        Self { root: Some(root), refs: None }
+    }
+
+    /// Resolve `$ref`s through a table of referenced schemas
+    pub fn with_refs(refs: &'a BTreeMap<String, Value>) -> Self {
+        Self { root: None, refs: Some(refs) }
+    }
+
+    /// Validate `instance` against `schema`, returning every violation found
+    pub fn validate(&self, schema: &Value, instance: &Value) -> Vec<SchemaError> {
+        let mut errors = Vec::new();
+        self.check(schema, instance, "$", 0, &mut errors);
+        errors
+    }
+
+    fn resolve(&self, reference: &str) -> Option<&'a Value> {
+        if let Some(schema) = self.refs.and_then(|refs| refs.get(reference)) {
+            return Some(schema);
+        }
+        let pointer = reference.strip_prefix('#')?;
+        self.root?.pointer(pointer)
+    }
+
+    fn check(&self, schema: &Value, instance: &Value, path: &str, depth: usize, errors: &mut Vec<SchemaError>) {
+        let error = |errors: &mut Vec<SchemaError>, message: String| {
+            errors.push(SchemaError { path: path.to_string(), message })
+        };
+
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => return error(errors, "no value is allowed here".to_string()),
+            Value::Object(schema) => schema,
+            _ => return,
+        };
+        if depth > MAX_DEPTH {
+            return error(errors, "schema nesting is too deep (recursive $ref?)".to_string());
+        }
+
+        if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+            match self.resolve(reference) {
+                Some(target) => self.check(target, instance, path, depth + 1, errors),
+                None => error(errors, format!("cannot resolve $ref '{}'", reference)),
+            }
+            // OpenAPI 3.0 ignores siblings of $ref
+            return;
+        }
+
+        if instance.is_null() && schema.get("nullable").and_then(|n| n.as_bool()) == Some(true) {
+            return;
+        }
+
+        if let Some(expected) = schema.get("type") {
+            let types: Vec<&str> = match expected {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(ts) => ts.iter().filter_map(|t| t.as_str()).collect(),
+                _ => vec![],
+            };
+            if !types.is_empty() && !types.iter().any(|t| is_type(instance, t)) {
+                return error(errors, format!("expected {}, got {}", types.join(" or "), type_name(instance)));
+            }
+        }
+
+        if let Some(allowed) = schema.get("enum").and_then(|e| e.as_array()) {
+            if !allowed.iter().any(|v| json_equal(v, instance)) {
+                let list: Vec<String> = allowed.iter().map(|v| v.to_string()).collect();
+                error(errors, format!("{} is not one of [{}]", instance, list.join(", ")));
+            }
+        }
+        if let Some(constant) = schema.get("const") {
+            if !json_equal(constant, instance) {
+                error(errors, format!("expected {}, got {}", constant, instance));
+            }
+        }
+
+        match instance {
+            Value::Object(object) => self.check_object(schema, object, path, depth, errors),
+            Value::Array(items) => self.check_array(schema, items, path, depth, errors),
+            Value::String(s) => check_string(schema, s, path, errors),
+            Value::Number(_) => check_number(schema, instance.as_f64().unwrap_or(0.0), path, errors),
+            _ => {}
+        }
+
+        self.check_combinators(schema, instance, path, depth, errors);
+    }
+
+    fn check_object(
+        &self,
+        schema: &Map<String, Value>,
+        object: &Map<String, Value>,
+        path: &str,
+        depth: usize,
+        errors: &mut Vec<SchemaError>,
+    ) {
+        if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
+            for name in required.iter().filter_map(|n| n.as_str()) {
+                if !object.contains_key(name) {
+                    errors.push(SchemaError {
+                        path: path.to_string(),
+                        message: format!("missing required property '{}'", name),
+                    });
+                }
+            }
+        }
+
+        let properties = schema.get("properties").and_then(|p| p.as_object());
+        for (name, value) in object {
+            let child = child_path(path, name);
+            match properties.and_then(|p| p.get(name)) {
+                Some(property) => self.check(property, value, &child, depth + 1, errors),
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => errors.push(SchemaError {
+                        path: child,
+                        message: "property is not allowed".to_string(),
+                    }),
+                    Some(additional @ Value::Object(_)) => self.check(additional, value, &child, depth + 1, errors),
+                    _ => {}
+                },
+            }
+        }
+
+        let count = object.len() as u64;
+        if let Some(min) = schema.get("minProperties").and_then(|m| m.as_u64()) {
+            if count < min {
+                errors.push(SchemaError { path: path.to_string(), message: format!("expected at least {} properties, got {}", min, count) });
+            }
+        }
+        if let Some(max) = schema.get("maxProperties").and_then(|m| m.as_u64()) {
+            if count > max {
+                errors.push(SchemaError { path: path.to_string(), message: format!("expected at most {} properties, got {}", max, count) });
+            }
+        }
+    }
+
+    fn check_array(
+        &self,
+        schema: &Map<String, Value>,
+        items: &[Value],
+        path: &str,
+        depth: usize,
+        errors: &mut Vec<SchemaError>,
+    ) {
+        match schema.get("items") {
+            // Draft-4 tuple form
+            Some(Value::Array(tuple)) => {
+                for (index, (item_schema, item)) in tuple.iter().zip(items).enumerate() {
+                    self.check(item_schema, item, &format!("{}[{}]", path, index), depth + 1, errors);
+                }
+            }
+            Some(item_schema) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.check(item_schema, item, &format!("{}[{}]", path, index), depth + 1, errors);
+                }
+            }
+            None => {}
+        }
+
+        let count = items.len() as u64;
+        if let Some(min) = schema.get("minItems").and_then(|m| m.as_u64()) {
+            if count < min {
+                errors.push(SchemaError { path: path.to_string(), message: format!("expected at least {} items, got {}", min, count) });
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(|m| m.as_u64()) {
+            if count > max {
+                errors.push(SchemaError { path: path.to_string(), message: format!("expected at most {} items, got {}", max, count) });
+            }
+        }
+        if schema.get("uniqueItems").and_then(|u| u.as_bool()) == Some(true) {
+            for (index, item) in items.iter().enumerate() {
+                if let Some(first) = items[..index].iter().position(|other| json_equal(other, item)) {
+                    errors.push(SchemaError {
+                        path: format!("{}[{}]", path, index),
+                        message: format!("duplicate of item {}", first),
+                    });
+                }
+            }
+        }
+    }
+
+    fn check_combinators(
+        &self,
+        schema: &Map<String, Value>,
+        instance: &Value,
+        path: &str,
+        depth: usize,
+        errors: &mut Vec<SchemaError>,
+    ) {
+        if let Some(all) = schema.get("allOf").and_then(|a| a.as_array()) {
+            for sub in all {
+                self.check(sub, instance, path, depth + 1, errors);
+            }
+        }
+
+        if let Some(any) = schema.get("anyOf").and_then(|a| a.as_array()) {
+            let results = self.branch_results(any, instance, path, depth);
+            if !results.iter().any(|r| r.is_empty()) {
+                self.report_closest("anyOf", results, path, errors);
+            }
+        }
+
+        if let Some(one) = schema.get("oneOf").and_then(|o| o.as_array()) {
+            let results = self.branch_results(one, instance, path, depth);
+            let matched = results.iter().filter(|r| r.is_empty()).count();
+            match matched {
+                1 => {}
+                0 => self.report_closest("oneOf", results, path, errors),
+                n => errors.push(SchemaError {
+                    path: path.to_string(),
+                    message: format!("matches {} oneOf schemas, expected exactly one", n),
+                }),
+            }
+        }
+
+        if let Some(not) = schema.get("not") {
+            let mut sub_errors = Vec::new();
+            self.check(not, instance, path, depth + 1, &mut sub_errors);
+            if sub_errors.is_empty() {
+                errors.push(SchemaError { path: path.to_string(), message: "matches a schema listed under 'not'".to_string() });
+            }
+        }
+    }
+
+    fn branch_results(&self, branches: &[Value], instance: &Value, path: &str, depth: usize) -> Vec<Vec<SchemaError>> {
+        branches
+            .iter()
+            .map(|branch| {
+                let mut branch_errors = Vec::new();
+                self.check(branch, instance, path, depth + 1, &mut branch_errors);
+                branch_errors
+            })
+            .collect()
+    }
+
+    /// Report a failed `anyOf`/`oneOf` through the branch with the fewest errors,
+    /// which is usually the one the response was meant to match
+    fn report_closest(&self, keyword: &str, results: Vec<Vec<SchemaError>>, path: &str, errors: &mut Vec<SchemaError>) {
+        let count = results.len();
+        match results.into_iter().min_by_key(|r| r.len()) {
+            Some(closest) if count > 1 => {
+                errors.push(SchemaError {
+                    path: path.to_string(),
+                    message: format!("does not match any of the {} {} schemas; closest match:", count, keyword),
+                });
+                errors.extend(closest);
+            }
+            Some(closest) => errors.extend(closest),
+            None => {}
+        }
+    }
+}
+
+fn check_string(schema: &Map<String, Value>, s: &str, path: &str, errors: &mut Vec<SchemaError>) {
+    let mut error = |message: String| errors.push(SchemaError { path: path.to_string(), message });
+    let length = s.chars().count() as u64;
+
+    if let Some(min) = schema.get("minLength").and_then(|m| m.as_u64()) {
+        if length < min {
+            error(format!("expected at least {} characters, got {}", min, length));
+        }
+    }
+    if let Some(max) = schema.get("maxLength").and_then(|m| m.as_u64()) {
+        if length > max {
+            error(format!("expected at most {} characters, got {}", max, length));
+        }
+    }
+    if let Some(pattern) = schema.get("pattern").and_then(|p| p.as_str()) {
+        match Regex::new(pattern) {
+            Ok(re) if !re.is_match(s) => error(format!("'{}' does not match pattern '{}'", s, pattern)),
+            Ok(_) => {}
+            Err(e) => error(format!("invalid pattern '{}': {}", pattern, e)),
+        }
+    }
+    if let Some(format) = schema.get("format").and_then(|f| f.as_str()) {
+        if !string_matches_format(s, format) {
+            error(format!("'{}' is not a valid {}", s, format));
+        }
+    }
+}
+
+fn check_number(schema: &Map<String, Value>, n: f64, path: &str, errors: &mut Vec<SchemaError>) {
+    let mut error = |message: String| errors.push(SchemaError { path: path.to_string(), message });
+    let number = |key: &str| schema.get(key).and_then(|v| v.as_f64());
+    // OpenAPI 3.0 uses boolean exclusive flags; 3.1 uses numeric bounds
+    let exclusive_flag = |key: &str| schema.get(key).and_then(|v| v.as_bool()) == Some(true);
+
+    if let Some(min) = number("minimum") {
+        if exclusive_flag("exclusiveMinimum") {
+            if n <= min {
+                error(format!("{} is not greater than {}", n, min));
+            }
+        } else if n < min {
+            error(format!("{} is less than the minimum of {}", n, min));
+        }
+    }
+    if let Some(max) = number("maximum") {
+        if exclusive_flag("exclusiveMaximum") {
+            if n >= max {
+                error(format!("{} is not less than {}", n, max));
+            }
+        } else if n > max {
+            error(format!("{} is greater than the maximum of {}", n, max));
+        }
+    }
+    if let Some(min) = number("exclusiveMinimum") {
+        if n <= min {
+            error(format!("{} is not greater than {}", n, min));
+        }
+    }
+    if let Some(max) = number("exclusiveMaximum") {
+        if n >= max {
+            error(format!("{} is not less than {}", n, max));
+        }
+    }
+    if let Some(divisor) = number("multipleOf").filter(|d| *d > 0.0) {
+        let quotient = n / divisor;
+        if (quotient - quotient.round()).abs() > 1e-9 {
+            error(format!("{} is not a multiple of {}", n, divisor));
+        }
+    }
+    if let Some(format) = schema.get("format").and_then(|f| f.as_str()) {
+        let in_range = |min: f64, max: f64| n.fract() == 0.0 && n >= min && n <= max;
+        let valid = match format {
+            "int32" => in_range(i32::MIN as f64, i32::MAX as f64),
+            "int64" => in_range(i64::MIN as f64, i64::MAX as f64),
+            _ => true,
+        };
+        if !valid {
+            error(format!("{} is not a valid {}", n, format));
+        }
+    }
+}
+
+/// Check a string against a `format`; unknown formats always pass
+fn string_matches_format(s: &str, format: &str) -> bool {
+    match format {
+        "date" => NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok(),
+        "date-time" => DateTime::parse_from_rfc3339(s).is_ok(),
+        "email" => EMAIL.is_match(s),
+        "uuid" => UUID.is_match(s),
+        "uri" | "url" => URI.is_match(s),
+        "ipv4" => s.parse::<std::net::Ipv4Addr>().is_ok(),
+        "ipv6" => s.parse::<std::net::Ipv6Addr>().is_ok(),
+        _ => true,
+    }
+}
+
+fn is_type(instance: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "boolean" => instance.is_boolean(),
+        "null" => instance.is_null(),
+        "number" => instance.is_number(),
+        "integer" => instance.as_f64().map(|n| n.fract() == 0.0).unwrap_or(false),
+        _ => true,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// JSON equality where 1 and 1.0 are the same number
+fn json_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        (Value::Array(x), Value::Array(y)) => x.len() == y.len() && x.iter().zip(y).all(|(a, b)| json_equal(a, b)),
+        (Value::Object(x), Value::Object(y)) => {
+            x.len() == y.len() && x.iter().all(|(k, v)| y.get(k).map(|w| json_equal(v, w)).unwrap_or(false))
+        }
+        _ => a == b,
+    }
+}
+
+fn child_path(parent: &str, name: &str) -> String {
+    if IDENTIFIER.is_match(name) {
+        format!("{}.{}", parent, name)
+    } else {
+        format!("{}['{}']", parent, name.replace('\\', "\\\\").replace('\'', "\\'"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn messages(errors: &[SchemaError]) -> Vec<String> {
+        errors.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn test_object_array_and_scalar_keywords() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "items"],
+            "additionalProperties": false,
+            "properties": {
+                "id": { "type": "integer", "format": "int32", "minimum": 1 },
+                "status": { "enum": ["open", "closed"] },
+                "items": {
+                    "type": "array",
+                    "maxItems": 3,
+                    "uniqueItems": true,
+                    "items": { "type": "string", "minLength": 2, "pattern": "^[a-z]+$" }
+                },
+                "price": { "type": "number", "exclusiveMinimum": 0, "multipleOf": 0.01 },
+                "note": { "type": "string", "nullable": true }
+            }
+        });
+        let validator = JsonSchemaValidator::new(&schema);
+
+        let valid = json!({ "id": 7, "status": "open", "items": ["ab", "cd"], "price": 9.99, "note": null });
+        assert!(validator.validate(&schema, &valid).is_empty());
+
+        let invalid = json!({ "id": 0, "status": "lost", "items": ["ab", "X", "ab", "zz"], "price": 0, "odd key": 1 });
+        assert_eq!(
+            messages(&validator.validate(&schema, &invalid)),
+            vec![
+                "$.id: 0 is less than the minimum of 1",
+                "$.items[1]: expected at least 2 characters, got 1",
+                "$.items[1]: 'X' does not match pattern '^[a-z]+$'",
+                "$.items: expected at most 3 items, got 4",
+                "$.items[2]: duplicate of item 0",
+                "$['odd key']: property is not allowed",
+                "$.price: 0 is not greater than 0",
+                "$.status: \"lost\" is not one of [\"open\", \"closed\"]",
+            ]
+        );
+
+        let wrong_type = json!({ "id": "7", "items": [] });
+        assert_eq!(messages(&validator.validate(&schema, &wrong_type)), vec!["$.id: expected integer, got string"]);
+        assert_eq!(
+            messages(&validator.validate(&schema, &json!({}))),
+            vec!["$: missing required property 'id'", "$: missing required property 'items'"]
+        );
+    }
+
+    #[test]
+    fn test_refs_and_combinators() {
+        let document = json!({
+            "components": { "schemas": {
+                "Cat": { "type": "object", "required": ["meows"], "properties": { "meows": { "type": "boolean" } } },
+                "Dog": { "type": "object", "required": ["barks"], "properties": { "barks": { "type": "boolean" } } },
+                "Named": { "type": "object", "required": ["name"], "properties": { "name": { "type": "string" } } },
+                "Pet": {
+                    "allOf": [{ "$ref": "#/components/schemas/Named" }],
+                    "oneOf": [{ "$ref": "#/components/schemas/Cat" }, { "$ref": "#/components/schemas/Dog" }]
+                }
+            } }
+        });
+        let schema = json!({ "type": "array", "items": { "$ref": "#/components/schemas/Pet" } });
+        let validator = JsonSchemaValidator::new(&document);
+
+        let pets = json!([{ "name": "Tom", "meows": true }, { "name": "Rex", "barks": true }]);
+        assert!(validator.validate(&schema, &pets).is_empty());
+
+        let bad = json!([{ "meows": "yes" }, { "name": "Odd", "meows": true, "barks": true }]);
+        assert_eq!(
+            messages(&validator.validate(&schema, &bad)),
+            vec![
+                "$[0]: missing required property 'name'",
+                "$[0]: does not match any of the 2 oneOf schemas; closest match:",
+                "$[0].meows: expected boolean, got string",
+                "$[1]: matches 2 oneOf schemas, expected exactly one",
+            ]
+        );
+
+        // The same schemas through a ref table
+        let refs: BTreeMap<String, Value> = document["components"]["schemas"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .map(|(name, schema)| (format!("#/components/schemas/{}", name), schema.clone()))
+            .collect();
+        let table = JsonSchemaValidator::with_refs(&refs);
+        assert_eq!(table.validate(&schema, &bad).len(), 4);
+        assert_eq!(
+            messages(&table.validate(&json!({ "$ref": "#/missing" }), &json!(1))),
+            vec!["$: cannot resolve $ref '#/missing'"]
+        );
+
+        // Recursive refs stop at the depth limit instead of overflowing
+        let looped = json!({ "definitions": { "A": { "$ref": "#/definitions/A" } } });
+        let errors = JsonSchemaValidator::new(&looped).validate(&json!({ "$ref": "#/definitions/A" }), &json!(1));
+        assert!(errors[0].message.contains("too deep"));
+    }
+
+    #[test]
+    fn test_formats() {
+        let check = |format: &str, value: Value| {
+            let schema = json!({ "format": format });
+            JsonSchemaValidator::new(&schema).validate(&schema, &value).is_empty()
+        };
+
+        assert!(check("date", json!("2024-02-29")));
+        assert!(!check("date", json!("2023-02-29")));
+        assert!(check("date-time", json!("2024-01-15T10:30:00Z")));
+        assert!(!check("date-time", json!("2024-01-15 10:30")));
+        assert!(check("email", json!("user@example.com")));
+        assert!(!check("email", json!("not-an-email")));
+        assert!(check("uuid", json!("123e4567-e89b-12d3-a456-426614174000")));
+        assert!(!check("uuid", json!("123e4567")));
+        assert!(check("uri", json!("https://example.com/a?b=c")));
+        assert!(!check("uri", json!("/relative")));
+        assert!(check("ipv4", json!("10.0.0.1")));
+        assert!(!check("ipv4", json!("10.0.0.256")));
+        assert!(check("ipv6", json!("::1")));
+        assert!(check("int32", json!(2147483647)));
+        assert!(!check("int32", json!(2147483648u64)));
+        assert!(check("custom", json!("anything")));
+    }
+}
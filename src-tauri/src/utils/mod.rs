@@ -4,13 +4,17 @@
 pub mod regex_extractor;
 pub mod wildcard_processor;
 pub mod xpath_evaluator;
+pub mod json_path;
+pub mod json_schema;
 pub mod config;
 pub mod http;
 pub mod template;
 
 pub use regex_extractor::RegexExtractor;
 pub use wildcard_processor::WildcardProcessor;
-pub use xpath_evaluator::XPathEvaluator;
+pub use xpath_evaluator::{XPathEvaluator, XPathOptions};
+pub use json_path::{JsonPath, JsonPathEvaluator};
+pub use json_schema::{JsonSchemaValidator, SchemaError};
 pub use config::resolve_config_dir;
 pub use http::{emit_traffic_event, match_pattern, CONTENT_TYPE_XML, CONTENT_TYPE_PLAIN};
 pub use template::substitute_variables;
@@ -91,7 +91,7 @@ impl WildcardProcessor {
     }
 
     /// Process built-in function wildcards
-    fn process_functions(text: &str) -> String {
+    pub(crate) fn process_functions(text: &str) -> String {
         let mut result = text.to_string();
 
         // {{uuid}} or {{newguid}}
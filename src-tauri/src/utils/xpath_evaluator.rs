@@ -1,7 +1,62 @@
 // XPath evaluator for XML responses — delegates to sxd-xpath.
+//
+// Namespace prefixes declared anywhere in the document are bound
+// automatically, so `//soap:Body/ns:Login` works without `declare namespace`.
+// A few XPath 2.0 functions SoapUI users rely on are added on top of XPath
+// 1.0: `matches`, `lower-case`, `upper-case`, `ends-with`, `compare-dates`
+// and `current-dateTime` (also reachable with the `fn:` prefix).
 
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::borrow::Cow;
+use sxd_document::dom::{ChildOfElement, ChildOfRoot, Document, Element};
 use sxd_document::parser;
-use sxd_xpath::{evaluate_xpath, Value};
+use sxd_document::Package;
+use sxd_xpath::context::Evaluation;
+use sxd_xpath::function::{Args, Error as FunctionError, Function};
+use sxd_xpath::nodeset::Node;
+use sxd_xpath::{Context, Factory, Value};
+
+/// Namespace of the XPath 2.0 function library (`fn:`)
+const XPATH_FUNCTIONS_NS: &str = "http://www.w3.org/2005/xpath-functions";
+
+/// SoapUI prefixes XPath expressions with `declare namespace p='uri';`
+static NAMESPACE_DECLARATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^\s*declare\s+namespace\s+([A-Za-z_][\w.-]*)\s*=\s*(?:'([^']*)'|"([^"]*)")\s*;"#).unwrap()
+});
+
+/// A prefixed name test such as `ns:Login` or `ns:*` (but not `child::`)
+static PREFIXED_NAME: Lazy<Regex> = Lazy::new(|| Regex::new(r"([A-Za-z_][\w.-]*):([A-Za-z_*])").unwrap());
+
+/// Namespace handling for an evaluation
+#[derive(Debug, Clone, Default)]
+pub struct XPathOptions {
+    /// Extra prefix bindings; they override prefixes declared in the document
+    pub namespaces: Vec<(String, String)>,
+    /// WSDL target namespace, bound to `tns` unless the document declares `tns`
+    pub target_namespace: Option<String>,
+    /// Match elements and attributes by local name only, ignoring prefixes
+    /// in the expression and namespaces in the document
+    pub ignore_namespaces: bool,
+}
+
+impl XPathOptions {
+    pub fn with_namespace(mut self, prefix: impl Into<String>, uri: impl Into<String>) -> Self {
+        self.namespaces.push((prefix.into(), uri.into()));
+        self
+    }
+
+    pub fn with_target_namespace(mut self, uri: Option<String>) -> Self {
+        self.target_namespace = uri;
+        self
+    }
+
+    pub fn ignoring_namespaces(mut self, ignore: bool) -> Self {
+        self.ignore_namespaces = ignore;
+        self
+    }
+}
 
 pub struct XPathEvaluator;
 
@@ -10,9 +65,14 @@ impl XPathEvaluator {
     /// Returns the first match as a string, or `None` if the expression
     /// produces no result or the XML cannot be parsed.
     pub fn evaluate(xml: &str, xpath: &str) -> Option<String> {
-        let package = parser::parse(xml).ok()?;
+        Self::evaluate_with(xml, xpath, &XPathOptions::default())
+    }
+
+    /// `evaluate` with explicit namespace options.
+    pub fn evaluate_with(xml: &str, xpath: &str, options: &XPathOptions) -> Option<String> {
+        let package = parse(xml, options)?;
         let document = package.as_document();
-        match evaluate_xpath(&document, xpath).ok()? {
+        match Self::evaluate_value(&document, xpath, options)? {
             Value::String(s) => Some(s),
             Value::Number(n) => Some(n.to_string()),
             Value::Boolean(b) => Some(b.to_string()),
@@ -34,24 +94,434 @@ impl XPathEvaluator {
 
     /// Evaluate XPath and return all matched node string values.
     pub fn evaluate_all(xml: &str, xpath: &str) -> Vec<String> {
-        let package = match parser::parse(xml) {
-            Ok(p) => p,
-            Err(_) => return vec![],
+        Self::evaluate_all_with(xml, xpath, &XPathOptions::default())
+    }
+
+    /// `evaluate_all` with explicit namespace options.
+    pub fn evaluate_all_with(xml: &str, xpath: &str, options: &XPathOptions) -> Vec<String> {
+        let package = match parse(xml, options) {
+            Some(p) => p,
+            None => return vec![],
         };
         let document = package.as_document();
-        match evaluate_xpath(&document, xpath) {
-            Ok(Value::Nodeset(nodes)) => nodes
+        match Self::evaluate_value(&document, xpath, options) {
+            Some(Value::Nodeset(nodes)) => nodes
                 .document_order()
                 .iter()
                 .map(|n| n.string_value())
                 .collect(),
-            Ok(v) => vec![match v {
+            Some(v) => vec![match v {
                 Value::String(s) => s,
                 Value::Number(n) => n.to_string(),
                 Value::Boolean(b) => b.to_string(),
                 _ => String::new(),
             }],
-            Err(_) => vec![],
+            None => vec![],
+        }
+    }
+
+    /// Replace the content of every node matched by `xpath` with `value`:
+    /// elements get their children replaced by a text node, attributes and
+    /// text nodes get their value replaced.
+    ///
+    /// Returns the updated XML and the number of nodes changed, or `None` if
+    /// the XML cannot be parsed or the expression does not select nodes.
+    pub fn set_values(xml: &str, xpath: &str, value: &str) -> Option<(String, usize)> {
+        let package = parser::parse(xml).ok()?;
+        let document = package.as_document();
+
+        let nodes = match Self::evaluate_value(&document, xpath, &XPathOptions::default())? {
+            Value::Nodeset(nodes) => nodes.document_order(),
+            _ => return None,
+        };
+
+        let mut changed = 0;
+        for node in nodes {
+            match node {
+                Node::Element(element) => {
+                    element.set_text(value);
+                }
+                Node::Attribute(attribute) => match attribute.parent() {
+                    Some(parent) => {
+                        parent.set_attribute_value(attribute.name(), value);
+                    }
+                    None => continue,
+                },
+                Node::Text(text) => text.set_text(value),
+                _ => continue,
+            }
+            changed += 1;
+        }
+
+        let mut out = Vec::new();
+        sxd_document::writer::format_document(&document, &mut out).ok()?;
+        let mut updated = String::from_utf8(out).ok()?;
+
+        // The writer always emits a declaration; keep the original shape
+        if !xml.trim_start().starts_with("<?xml") {
+            if let Some(end) = updated.find("?>") {
+                updated = updated[end + 2..].to_string();
+            }
+        }
+
+        Some((updated, changed))
+    }
+
+    /// Compile and evaluate `xpath` against the document root. Prefixes are
+    /// bound from the document, then `options`, then any SoapUI-style
+    /// `declare namespace` prolog.
+    fn evaluate_value<'d>(document: &'d Document<'d>, xpath: &str, options: &XPathOptions) -> Option<Value<'d>> {
+        let (declared, expression) = split_namespace_declarations(xpath);
+        let expression = if options.ignore_namespaces {
+            strip_prefixes(expression)
+        } else {
+            Cow::Borrowed(expression)
+        };
+
+        let compiled = Factory::new().build(&expression).ok()??;
+        let mut context = Context::new();
+        register_functions(&mut context);
+
+        let mut bindings = vec![("fn".to_string(), XPATH_FUNCTIONS_NS.to_string())];
+        if !options.ignore_namespaces {
+            let document_namespaces = document_namespaces(document);
+            if let Some(uri) = &options.target_namespace {
+                if !document_namespaces.iter().any(|(prefix, _)| prefix == "tns") {
+                    bindings.push(("tns".to_string(), uri.clone()));
+                }
+            }
+            bindings.extend(document_namespaces);
+            bindings.extend(options.namespaces.iter().cloned());
+            bindings.extend(declared);
+        }
+
+        // sxd-xpath panics on an unbound prefix; treat it as no match instead
+        if used_prefixes(&expression)
+            .iter()
+            .any(|used| !bindings.iter().any(|(prefix, _)| prefix == used))
+        {
+            return None;
+        }
+
+        for (prefix, uri) in &bindings {
+            context.set_namespace(prefix, uri);
+        }
+
+        compiled.evaluate(&context, document.root()).ok()
+    }
+}
+
+fn parse(xml: &str, options: &XPathOptions) -> Option<Package> {
+    let package = parser::parse(xml).ok()?;
+    if options.ignore_namespaces {
+        for child in package.as_document().root().children() {
+            if let ChildOfRoot::Element(element) = child {
+                strip_namespaces(element);
+            }
+        }
+    }
+    Some(package)
+}
+
+/// Every prefix declared in the document; the first declaration of a prefix wins.
+fn document_namespaces(document: &Document) -> Vec<(String, String)> {
+    fn collect(element: Element, out: &mut Vec<(String, String)>) {
+        for namespace in element.namespaces_in_scope() {
+            if namespace.prefix() != "xml" && !out.iter().any(|(p, _)| p == namespace.prefix()) {
+                out.push((namespace.prefix().to_string(), namespace.uri().to_string()));
+            }
+        }
+        for child in element.children() {
+            if let ChildOfElement::Element(child) = child {
+                collect(child, out);
+            }
+        }
+    }
+
+    let mut namespaces = Vec::new();
+    for child in document.root().children() {
+        if let ChildOfRoot::Element(element) = child {
+            collect(element, &mut namespaces);
+        }
+    }
+    namespaces
+}
+
+/// Rename elements and attributes to their local names, without namespace.
+fn strip_namespaces(element: Element) {
+    element.set_name(element.name().local_part());
+    for attribute in element.attributes() {
+        let name = attribute.name();
+        if name.namespace_uri().is_some() {
+            let value = attribute.value();
+            element.remove_attribute(name);
+            element.set_attribute_value(name.local_part(), value);
+        }
+    }
+    for child in element.children() {
+        if let ChildOfElement::Element(child) = child {
+            strip_namespaces(child);
+        }
+    }
+}
+
+/// Drop namespace prefixes from name tests (`//ns:Login/@ns:id` becomes
+/// `//Login/@id`), leaving string literals untouched.
+fn strip_prefixes(expression: &str) -> Cow<'_, str> {
+    if !expression.contains(':') {
+        return Cow::Borrowed(expression);
+    }
+
+    let mut out = String::with_capacity(expression.len());
+    for (code, literal) in split_literals(expression) {
+        out.push_str(&PREFIXED_NAME.replace_all(code, "$2"));
+        out.push_str(literal);
+    }
+    Cow::Owned(out)
+}
+
+/// Prefixes used in name tests and function names, outside string literals.
+fn used_prefixes(expression: &str) -> Vec<&str> {
+    split_literals(expression)
+        .into_iter()
+        .flat_map(|(code, _)| PREFIXED_NAME.captures_iter(code))
+        .filter_map(|caps| caps.get(1).map(|m| m.as_str()))
+        .collect()
+}
+
+/// Split an expression into (code, following string literal) pairs.
+fn split_literals(expression: &str) -> Vec<(&str, &str)> {
+    let mut parts = Vec::new();
+    let mut rest = expression;
+    while !rest.is_empty() {
+        let (code, literal) = match rest.find(['\'', '"']) {
+            Some(start) => {
+                let quote = &rest[start..start + 1];
+                let end = rest[start + 1..].find(quote).map(|i| start + i + 2).unwrap_or(rest.len());
+                (&rest[..start], &rest[start..end])
+            }
+            None => (rest, ""),
+        };
+        parts.push((code, literal));
+        rest = &rest[code.len() + literal.len()..];
+    }
+    parts
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// XPath 2.0 functions
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn register_functions(context: &mut Context) {
+    fn register<F: Function + Clone + 'static>(context: &mut Context, name: &str, function: F) {
+        context.set_function(name, function.clone());
+        context.set_function((XPATH_FUNCTIONS_NS, name), function);
+    }
+
+    register(context, "matches", Matches);
+    register(context, "lower-case", ChangeCase { upper: false });
+    register(context, "upper-case", ChangeCase { upper: true });
+    register(context, "ends-with", EndsWith);
+    register(context, "compare-dates", CompareDates);
+    register(context, "current-dateTime", CurrentDateTime);
+}
+
+/// `matches(input, pattern[, flags])` with flags `i`, `m`, `s` and `x`
+#[derive(Clone)]
+struct Matches;
+
+impl Function for Matches {
+    fn evaluate<'c, 'd>(&self, _: &Evaluation<'c, 'd>, args: Vec<Value<'d>>) -> Result<Value<'d>, FunctionError> {
+        let mut args = Args(args);
+        args.at_least(2)?;
+        args.at_most(3)?;
+        let flags = if args.len() == 3 { args.pop_string()? } else { String::new() };
+        let pattern = args.pop_string()?;
+        let input = args.pop_string()?;
+
+        if let Some(flag) = flags.chars().find(|c| !"imsx".contains(*c)) {
+            return Err(FunctionError::Other(format!("unsupported matches() flag '{}'", flag)));
         }
+        let pattern = if flags.is_empty() { pattern } else { format!("(?{}){}", flags, pattern) };
+        let regex = Regex::new(&pattern).map_err(|e| FunctionError::Other(e.to_string()))?;
+        Ok(Value::Boolean(regex.is_match(&input)))
+    }
+}
+
+/// `lower-case(string)` / `upper-case(string)`
+#[derive(Clone)]
+struct ChangeCase {
+    upper: bool,
+}
+
+impl Function for ChangeCase {
+    fn evaluate<'c, 'd>(&self, _: &Evaluation<'c, 'd>, args: Vec<Value<'d>>) -> Result<Value<'d>, FunctionError> {
+        let mut args = Args(args);
+        args.exactly(1)?;
+        let input = args.pop_string()?;
+        Ok(Value::String(if self.upper { input.to_uppercase() } else { input.to_lowercase() }))
+    }
+}
+
+/// `ends-with(string, suffix)`
+#[derive(Clone)]
+struct EndsWith;
+
+impl Function for EndsWith {
+    fn evaluate<'c, 'd>(&self, _: &Evaluation<'c, 'd>, args: Vec<Value<'d>>) -> Result<Value<'d>, FunctionError> {
+        let mut args = Args(args);
+        args.exactly(2)?;
+        let suffix = args.pop_string()?;
+        let input = args.pop_string()?;
+        Ok(Value::Boolean(input.ends_with(&suffix)))
+    }
+}
+
+/// `compare-dates(a, b)`: -1, 0 or 1 as `a` is before, equal to or after `b`;
+/// NaN when either is not an `xs:date` / `xs:dateTime`. Values without a
+/// timezone are taken as UTC.
+#[derive(Clone)]
+struct CompareDates;
+
+impl Function for CompareDates {
+    fn evaluate<'c, 'd>(&self, _: &Evaluation<'c, 'd>, args: Vec<Value<'d>>) -> Result<Value<'d>, FunctionError> {
+        let mut args = Args(args);
+        args.exactly(2)?;
+        let right = parse_date_time(args.pop_string()?.trim());
+        let left = parse_date_time(args.pop_string()?.trim());
+        Ok(Value::Number(match (left, right) {
+            (Some(left), Some(right)) => match left.cmp(&right) {
+                std::cmp::Ordering::Less => -1.0,
+                std::cmp::Ordering::Equal => 0.0,
+                std::cmp::Ordering::Greater => 1.0,
+            },
+            _ => f64::NAN,
+        }))
+    }
+}
+
+/// `current-dateTime()` in UTC, for use with `compare-dates`
+#[derive(Clone)]
+struct CurrentDateTime;
+
+impl Function for CurrentDateTime {
+    fn evaluate<'c, 'd>(&self, _: &Evaluation<'c, 'd>, args: Vec<Value<'d>>) -> Result<Value<'d>, FunctionError> {
+        Args(args).exactly(0)?;
+        Ok(Value::String(Utc::now().to_rfc3339()))
+    }
+}
+
+fn parse_date_time(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+        return Some(date_time.with_timezone(&Utc));
+    }
+    if let Ok(date_time) = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Some(date_time.and_utc());
+    }
+    // xs:date, optionally with a trailing `Z`
+    NaiveDate::parse_from_str(value.trim_end_matches('Z'), "%Y-%m-%d")
+        .ok()
+        .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+}
+
+/// Split `declare namespace p='uri';` declarations off the front of an XPath.
+fn split_namespace_declarations(xpath: &str) -> (Vec<(String, String)>, &str) {
+    let mut namespaces = Vec::new();
+    let mut rest = xpath;
+
+    while let Some(caps) = NAMESPACE_DECLARATION.captures(rest) {
+        let uri = caps.get(2).or_else(|| caps.get(3)).map(|m| m.as_str()).unwrap_or("");
+        namespaces.push((caps[1].to_string(), uri.to_string()));
+        rest = &rest[caps.get(0).map(|m| m.end()).unwrap_or(0)..];
+    }
+
+    (namespaces, rest.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENVELOPE: &str = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"><soap:Body><ns:Login xmlns:ns="urn:auth"><ns:user>bob</ns:user><ns:token>?</ns:token></ns:Login></soap:Body></soap:Envelope>"#;
+
+    #[test]
+    fn test_soapui_namespace_declarations() {
+        let xpath = "declare namespace s='http://schemas.xmlsoap.org/soap/envelope/';\ndeclare namespace ns=\"urn:auth\";\n//s:Body/ns:Login/ns:user";
+        assert_eq!(XPathEvaluator::evaluate(ENVELOPE, xpath).as_deref(), Some("bob"));
+    }
+
+    #[test]
+    fn test_set_values_element_and_attribute() {
+        let (updated, changed) = XPathEvaluator::set_values(
+            ENVELOPE,
+            "declare namespace ns='urn:auth'; //ns:token",
+            "abc-123",
+        )
+        .unwrap();
+        assert_eq!(changed, 1);
+        assert!(!updated.starts_with("<?xml"));
+        assert_eq!(
+            XPathEvaluator::evaluate(&updated, "declare namespace ns='urn:auth'; //ns:token").as_deref(),
+            Some("abc-123")
+        );
+
+        let (updated, changed) = XPathEvaluator::set_values("<a><b id=\"1\"/><b id=\"2\"/></a>", "//b/@id", "x").unwrap();
+        assert_eq!(changed, 2);
+        assert_eq!(XPathEvaluator::evaluate_all(&updated, "//b/@id"), vec!["x", "x"]);
+    }
+
+    #[test]
+    fn test_document_prefixes_are_bound() {
+        assert_eq!(XPathEvaluator::evaluate(ENVELOPE, "//soap:Body/ns:Login/ns:user").as_deref(), Some("bob"));
+
+        // Explicit bindings and the WSDL target namespace
+        let options = XPathOptions::default()
+            .with_namespace("auth", "urn:auth")
+            .with_target_namespace(Some("urn:auth".to_string()));
+        assert_eq!(XPathEvaluator::evaluate_with(ENVELOPE, "//auth:user", &options).as_deref(), Some("bob"));
+        assert_eq!(XPathEvaluator::evaluate_with(ENVELOPE, "//tns:user", &options).as_deref(), Some("bob"));
+        // Unbound prefixes are no match rather than an error
+        assert_eq!(XPathEvaluator::evaluate(ENVELOPE, "//tns:user"), None);
+        assert_eq!(used_prefixes("//a:b[c:d = 'e:f']/child::g"), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn test_ignore_namespaces() {
+        let xml = r#"<Envelope xmlns="urn:env"><Body><a:Result xmlns:a="urn:a" a:code="7">done</a:Result></Body></Envelope>"#;
+        let options = XPathOptions::default().ignoring_namespaces(true);
+
+        // The default namespace cannot be addressed without ignoring it
+        assert_eq!(XPathEvaluator::evaluate(xml, "//Result"), None);
+        assert_eq!(XPathEvaluator::evaluate_with(xml, "/Envelope/Body/Result", &options).as_deref(), Some("done"));
+        assert_eq!(XPathEvaluator::evaluate_with(xml, "//x:Result/@x:code", &options).as_deref(), Some("7"));
+        assert_eq!(
+            XPathEvaluator::evaluate_all_with(xml, "//*[text() = 'done' or . = 'a:b']", &options),
+            vec!["done"]
+        );
+        assert_eq!(strip_prefixes("//ns:a[@ns:b='x:y']/child::ns:*"), "//a[@b='x:y']/child::*");
+    }
+
+    #[test]
+    fn test_xpath2_functions() {
+        let xml = r#"<r><code>ERR-042</code><name>Bob</name><from>2024-03-01</from><to>2024-03-01T10:00:00+02:00</to></r>"#;
+        let eval = |xpath: &str| XPathEvaluator::evaluate(xml, xpath);
+
+        assert_eq!(eval("matches(//code, '^ERR-[0-9]+$')").as_deref(), Some("true"));
+        assert_eq!(eval("matches(//code, '^err', 'i')").as_deref(), Some("true"));
+        assert_eq!(eval("fn:matches(//code, '^OK')").as_deref(), Some("false"));
+        assert_eq!(eval("lower-case(//name)").as_deref(), Some("bob"));
+        assert_eq!(eval("upper-case(//name)").as_deref(), Some("BOB"));
+        assert_eq!(eval("ends-with(//code, '042')").as_deref(), Some("true"));
+        assert_eq!(eval("compare-dates(//from, //to)").as_deref(), Some("-1"));
+        assert_eq!(eval("compare-dates(//to, '2024-03-01T08:00:00Z')").as_deref(), Some("0"));
+        assert_eq!(eval("compare-dates(//to, current-dateTime()) < 0").as_deref(), Some("true"));
+        assert_eq!(eval("compare-dates(//name, //to) = compare-dates(//name, //to)").as_deref(), Some("false"));
+        assert_eq!(eval("matches(//code, '[')"), None);
+    }
+
+    #[test]
+    fn test_set_values_rejects_non_nodeset() {
+        assert!(XPathEvaluator::set_values("<a/>", "count(//a)", "1").is_none());
+        assert!(XPathEvaluator::set_values("not xml", "//a", "1").is_none());
     }
 }
@@ -3,9 +3,25 @@
 /// Frontend-facing commands for workflow operations
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use crate::workflow::{WorkflowExecutor, WorkflowStep, WorkflowResult};
+use crate::workflow::{Expression, SavedWorkflow, WorkflowExecutor, WorkflowStep, WorkflowResult};
 use crate::settings_manager;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+use tokio::sync::watch;
+
+#[derive(Debug)]
+struct WorkflowRunData {
+    updates: Vec<serde_json::Value>,
+    done: bool,
+    error: Option<String>,
+    /// Set to `true` to abort the run
+    cancel: watch::Sender<bool>,
+}
+
+// Global workflow run store for streaming updates
+static WORKFLOW_RUN_STORE: Lazy<Arc<Mutex<HashMap<String, WorkflowRunData>>>> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RunWorkflowRequest {
@@ -42,6 +58,172 @@ pub async fn run_workflow(request: RunWorkflowRequest) -> Result<WorkflowResult,
     Ok(result)
 }
 
+/// Start a workflow in the background and return its run ID.
+///
+/// Progress is polled with `get_workflow_run_updates`: a `stepStart` and a
+/// `stepFinish` event per step (nested loop iterations and parallel branches
+/// included), then a final `workflowFinish` event carrying the `WorkflowResult`.
+#[tauri::command]
+pub async fn start_workflow_run(request: RunWorkflowRequest) -> Result<RunWorkflowResponse, String> {
+    let run_id = format!(
+        "wf-{}-{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis(),
+        uuid::Uuid::new_v4().to_string().split('-').next().unwrap()
+    );
+    log::info!("Starting workflow run {}: {}", run_id, request.name);
+
+    let (cancel_tx, cancel_rx) = watch::channel(false);
+    WORKFLOW_RUN_STORE.lock().unwrap().insert(
+        run_id.clone(),
+        WorkflowRunData { updates: Vec::new(), done: false, error: None, cancel: cancel_tx },
+    );
+
+    let events_run_id = run_id.clone();
+    let mut executor = WorkflowExecutor::new()
+        .with_events(Arc::new(move |event| {
+            if let Ok(update) = serde_json::to_value(&event) {
+                push_update(&events_run_id, update);
+            }
+        }))
+        .with_cancellation(cancel_rx);
+    for (key, value) in request.variables.unwrap_or_default() {
+        executor.test_runner.set_variable(key, value);
+    }
+
+    let task_run_id = run_id.clone();
+    tokio::spawn(async move {
+        match executor.execute_workflow(request.name, request.steps).await {
+            Ok(result) => {
+                log::info!("Workflow run {} {}", task_run_id,
+                    if result.aborted { "ABORTED" } else if result.passed { "PASSED" } else { "FAILED" });
+                push_update(&task_run_id, serde_json::json!({
+                    "type": "workflowFinish",
+                    "result": result,
+                }));
+                finish_run(&task_run_id, None);
+            }
+            Err(e) => finish_run(&task_run_id, Some(format!("Workflow execution failed: {}", e))),
+        }
+    });
+
+    Ok(RunWorkflowResponse { run_id })
+}
+
+/// Get workflow run updates (events since `from_index`)
+#[tauri::command]
+pub async fn get_workflow_run_updates(
+    run_id: String,
+    from_index: Option<usize>,
+) -> Result<WorkflowRunUpdatesResponse, String> {
+    let from_idx = from_index.unwrap_or(0);
+    let mut store = WORKFLOW_RUN_STORE.lock().unwrap();
+
+    if let Some(run) = store.get(&run_id) {
+        let safe_index = from_idx.min(run.updates.len());
+        let updates: Vec<serde_json::Value> = run.updates[safe_index..].to_vec();
+        let next_index = safe_index + updates.len();
+        let done = run.done;
+        let error = run.error.clone();
+
+        // Clean up completed runs
+        if done && next_index >= run.updates.len() {
+            store.remove(&run_id);
+        }
+
+        Ok(WorkflowRunUpdatesResponse { updates, next_index, done, error })
+    } else {
+        Ok(WorkflowRunUpdatesResponse {
+            updates: Vec::new(),
+            next_index: from_idx,
+            done: true,
+            error: Some("Run not found".to_string()),
+        })
+    }
+}
+
+/// Abort a workflow run; an empty `run_id` aborts every active run.
+///
+/// Steps in flight (including parallel branches) stop and are reported as
+/// aborted; the run still finishes with a `workflowFinish` event.
+#[tauri::command]
+pub async fn abort_workflow_run(run_id: String) -> Result<(), String> {
+    log::info!("Aborting workflow run: {}", run_id);
+    let store = WORKFLOW_RUN_STORE.lock().unwrap();
+    if run_id.is_empty() {
+        for run in store.values() {
+            let _ = run.cancel.send(true);
+        }
+        return Ok(());
+    }
+    let run = store.get(&run_id).ok_or_else(|| format!("Run not found: {}", run_id))?;
+    let _ = run.cancel.send(true);
+    Ok(())
+}
+
+fn push_update(run_id: &str, update: serde_json::Value) {
+    if let Ok(mut store) = WORKFLOW_RUN_STORE.lock() {
+        if let Some(run) = store.get_mut(run_id) {
+            run.updates.push(update);
+        }
+    }
+}
+
+fn finish_run(run_id: &str, error: Option<String>) {
+    if let Ok(mut store) = WORKFLOW_RUN_STORE.lock() {
+        if let Some(run) = store.get_mut(run_id) {
+            run.done = true;
+            run.error = error;
+        }
+    }
+}
+
+/// Execute a workflow saved with `save_workflow`.
+///
+/// `variables` override the workflow's own initial variables.
+#[tauri::command]
+pub async fn run_saved_workflow(
+    workflow_id: String,
+    variables: Option<HashMap<String, String>>,
+) -> Result<WorkflowResult, String> {
+    let workflow = SavedWorkflow::load(&workflow_id).map_err(|e| e.to_string())?;
+
+    let mut initial = workflow.variables;
+    initial.extend(variables.unwrap_or_default());
+
+    run_workflow(RunWorkflowRequest {
+        name: workflow.name,
+        steps: workflow.steps,
+        variables: Some(initial),
+    }).await
+}
+
+/// Parse every condition expression in a workflow so syntax errors surface
+/// when it is saved rather than when it runs.
+///
+/// Designer conditions (`expression` + `operator` + `expectedValue`) name a
+/// variable in `expression`, so only conditions without an `operator`, or
+/// engine conditions that carry a `variable`, are parsed.
+fn check_condition_expressions(value: &serde_json::Value, step: &str) -> Result<(), String> {
+    match value {
+        serde_json::Value::Object(map) => {
+            let step = map.get("name").and_then(|v| v.as_str()).unwrap_or(step);
+            if let Some(condition) = map.get("condition").and_then(|c| c.as_object()) {
+                let is_expression = !condition.contains_key("operator") || condition.contains_key("variable");
+                if let Some(expression) = condition.get("expression").and_then(|e| e.as_str()).filter(|_| is_expression) {
+                    Expression::parse(expression)
+                        .map_err(|e| format!("Invalid condition in step '{}': {}", step, e))?;
+                }
+            }
+            map.values().try_for_each(|v| check_condition_expressions(v, step))
+        }
+        serde_json::Value::Array(items) => items.iter().try_for_each(|v| check_condition_expressions(v, step)),
+        _ => Ok(()),
+    }
+}
+
 /// Get all workflows from global config
 #[tauri::command]
 pub async fn get_workflows() -> Result<WorkflowsResponse, String> {
@@ -66,6 +248,8 @@ pub async fn save_workflow(workflow: serde_json::Value) -> Result<SaveResponse,
         .and_then(|v| v.as_str())
         .ok_or("Workflow must have an id")?
         .to_string();
+
+    check_condition_expressions(&workflow, "unnamed")?;
     
     settings_manager::save_workflow_internal(workflow)
         .map_err(|e| format!("Failed to save workflow: {}", e))?;
@@ -92,6 +276,21 @@ pub async fn delete_workflow(workflow_id: String) -> Result<SaveResponse, String
     Ok(SaveResponse { success: true })
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunWorkflowResponse {
+    pub run_id: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowRunUpdatesResponse {
+    pub updates: Vec<serde_json::Value>,
+    pub next_index: usize,
+    pub done: bool,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct WorkflowsResponse {
     pub workflows: Vec<serde_json::Value>,
@@ -101,3 +300,108 @@ pub struct WorkflowsResponse {
 pub struct SaveResponse {
     pub success: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::{RequestNode, WorkflowNode};
+
+    #[tokio::test]
+    async fn test_save_rejects_invalid_condition_expressions() {
+        let workflow = serde_json::json!({
+            "id": "wf-1",
+            "name": "Orders",
+            "steps": [
+                { "name": "Designer check", "type": "condition",
+                  "condition": { "id": "c1", "expression": "$.status", "operator": "equals", "expectedValue": "OK" } },
+                { "name": "Retry loop", "type": "loop", "loop_type": { "type": "while",
+                  "condition": { "expression": "status() == 200 &&" } } }
+            ]
+        });
+        assert_eq!(
+            save_workflow(workflow).await.unwrap_err(),
+            "Invalid condition in step 'Retry loop': Unexpected end of expression at column 19"
+        );
+
+        let valid = serde_json::json!({
+            "type": "conditional",
+            "condition": { "expression": "len(jsonpath('$.items')) > 0" },
+            "then_steps": []
+        });
+        assert!(check_condition_expressions(&valid, "unnamed").is_ok());
+    }
+
+    async fn updates_until_done(run_id: &str) -> Vec<serde_json::Value> {
+        let mut updates = Vec::new();
+        for _ in 0..200 {
+            let response = get_workflow_run_updates(run_id.to_string(), Some(updates.len())).await.unwrap();
+            updates.extend(response.updates);
+            if response.done {
+                assert_eq!(response.error, None);
+                return updates;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("workflow run {} did not finish", run_id);
+    }
+
+    #[tokio::test]
+    async fn test_streamed_run_can_be_aborted() {
+        let steps: Vec<WorkflowStep> = serde_json::from_value(serde_json::json!([
+            { "name": "Greet", "type": "setvariable", "variable": "greeting", "value": "hi" },
+            { "name": "Wait", "type": "delay", "ms": 30000 }
+        ]))
+        .unwrap();
+        let response = start_workflow_run(RunWorkflowRequest { name: "Streamed".to_string(), steps, variables: None })
+            .await
+            .unwrap();
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        abort_workflow_run(response.run_id.clone()).await.unwrap();
+        let updates = updates_until_done(&response.run_id).await;
+
+        let types: Vec<&str> = updates.iter().map(|u| u["type"].as_str().unwrap()).collect();
+        assert_eq!(types, vec!["stepStart", "stepFinish", "stepStart", "stepFinish", "workflowFinish"]);
+        assert_eq!(updates[3]["error"], "Workflow aborted");
+        assert_eq!(updates[4]["result"]["aborted"], true);
+        assert_eq!(updates[4]["result"]["variables"]["greeting"], "hi");
+
+        // Finished runs are removed once fully read
+        let gone = get_workflow_run_updates(response.run_id, None).await.unwrap();
+        assert_eq!(gone.error.as_deref(), Some("Run not found"));
+        assert!(abort_workflow_run("wf-missing".to_string()).await.is_err());
+    }
+
+    #[test]
+    fn test_saved_workflow_steps_use_webview_fields() {
+        let workflow = serde_json::json!({
+            "id": "wf1",
+            "name": "Checkout",
+            "steps": [
+                { "id": "s2", "name": "Wait", "type": "delay", "order": 2, "delayMs": 250 },
+                {
+                    "id": "s1", "name": "Login", "type": "request", "order": 1,
+                    "projectName": "Shop", "interfaceName": "AuthService", "operationName": "Login",
+                    "requestBody": "<login/>",
+                    "extractors": [{ "id": "e1", "variable": "token", "type": "xpath", "pattern": "//token", "source": "body" }]
+                }
+            ]
+        });
+
+        let steps = SavedWorkflow::from_value(&workflow).unwrap().steps;
+        assert_eq!(steps[0].name, "Login");
+        match &steps[0].node {
+            WorkflowNode::Request(RequestNode { project, operation, body, extractors, .. }) => {
+                assert_eq!(project, "Shop");
+                assert_eq!(operation.as_deref(), Some("Login"));
+                assert_eq!(body.as_deref(), Some("<login/>"));
+                assert_eq!(extractors[0].path, "//token");
+            }
+            other => panic!("unexpected node {:?}", other),
+        }
+        assert_eq!(steps[1].node, WorkflowNode::Delay { ms: 250 });
+
+        let unsupported = serde_json::json!({ "steps": [{ "name": "Branch", "type": "condition" }] });
+        assert!(SavedWorkflow::from_value(&unsupported).unwrap_err().to_string().contains("Workflow step 'Branch' cannot run"));
+    }
+}
@@ -1,13 +1,31 @@
 /// Workflow Engine
 /// 
-/// Executes workflows with sequential, parallel, conditional, and loop logic
-use anyhow::Result;
+/// Executes workflows with sequential, parallel, conditional, and loop logic.
+/// Leaf nodes send requests and run test cases saved in projects, through the
+/// same runner the webview uses.
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
 use tokio::task::JoinSet;
 use async_recursion::async_recursion;
 
+use crate::testing::data_source;
+use crate::testing::frontend_runner::{self, RunContext};
+use crate::testing::frontend_types::{
+    FrontendRequest, FrontendStepConfig, FrontendTestCase, FrontendTestStep, StepExecutionResult,
+    StepRunRecord,
+};
+use crate::testing::script_engine::{self, DEFAULT_SCRIPT_TIMEOUT_MS};
+use crate::testing::variable_extractor::{ExtractorType, ResponseSnapshot, VariableExtractor};
 use crate::testing::TestRunner;
+use crate::utils::substitute_variables;
+
+use super::expression::{Expression, ExpressionContext};
 
 /// Workflow execution mode
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -32,6 +50,125 @@ pub enum WorkflowNode {
         loop_type: LoopType,
         steps: Vec<WorkflowStep>,
     },
+    /// Send a request saved in a project
+    Request(RequestNode),
+    /// Run a test case saved in a project (once per data source row)
+    TestCase(TestCaseNode),
+    /// Set a variable; `{{name}}` placeholders in the value are substituted
+    SetVariable {
+        variable: String,
+        value: String,
+    },
+    /// Extract a variable from the most recent response
+    Extract {
+        variable: String,
+        /// "xpath" | "jsonpath" | "regex" | "header" | "statuscode" | "cookie" | ...
+        extractor: String,
+        #[serde(default)]
+        path: String,
+        /// "body" (default) | "header" | "status"
+        #[serde(default)]
+        source: Option<String>,
+        #[serde(default)]
+        default_value: Option<String>,
+    },
+    /// Wait before the next step
+    Delay {
+        #[serde(alias = "delayMs")]
+        ms: u64,
+    },
+    /// Run another saved workflow with its own variables
+    #[serde(rename = "callworkflow")]
+    CallWorkflow(CallWorkflowNode),
+    /// Run JavaScript in the script engine; variables it sets are kept and a
+    /// failing script fails the step
+    Script {
+        script: String,
+        #[serde(default, alias = "timeoutMs", skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+    },
+}
+
+/// Reference to a request saved in a project.
+///
+/// Field aliases match the webview's saved workflow steps (`projectName`,
+/// `interfaceName`, `operationName`, `requestBody`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RequestNode {
+    /// Project directory, or the name of a project in the projects folder
+    #[serde(alias = "projectName")]
+    pub project: String,
+    #[serde(default, alias = "interfaceName")]
+    pub interface: Option<String>,
+    /// Operation holding the request; without one, the request is looked up
+    /// by `request_name` in folders and interfaces
+    #[serde(default, alias = "operationName")]
+    pub operation: Option<String>,
+    /// Request name or id; defaults to the operation's first request
+    #[serde(default, alias = "requestName")]
+    pub request_name: Option<String>,
+    /// Replaces the saved request body
+    #[serde(default, alias = "requestBody")]
+    pub body: Option<String>,
+    /// Replaces the saved endpoint
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// Added to (or replacing) the saved headers
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Variables to extract from the response
+    #[serde(default)]
+    pub extractors: Vec<WorkflowExtractor>,
+}
+
+/// Reference to a test case saved in a project
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TestCaseNode {
+    #[serde(alias = "projectName")]
+    pub project: String,
+    /// Suite name or id; any suite when omitted
+    #[serde(default)]
+    pub suite: Option<String>,
+    /// Test case name or id
+    pub test_case: String,
+}
+
+/// Call to a workflow saved with `save_workflow`.
+///
+/// The called workflow starts from its own initial variables plus `inputs`;
+/// it does not see the caller's variables, and only `outputs` flow back.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CallWorkflowNode {
+    /// Workflow id or name
+    #[serde(alias = "workflowId")]
+    pub workflow: String,
+    /// Called workflow variable -> value; `{{name}}` placeholders are
+    /// substituted from the caller's variables
+    #[serde(default)]
+    pub inputs: HashMap<String, String>,
+    /// Caller variable -> called workflow variable, copied when it finishes.
+    /// Variables the called workflow never set are left untouched.
+    #[serde(default)]
+    pub outputs: HashMap<String, String>,
+}
+
+/// Variable extractor of a request node (matches the webview's `WorkflowExtractor`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkflowExtractor {
+    pub variable: String,
+    /// "xpath" | "jsonpath" | "regex" | "header" | ...
+    #[serde(rename = "type")]
+    pub extractor_type: String,
+    #[serde(default, alias = "pattern")]
+    pub path: String,
+    /// "body" (default) | "header" | "status"
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default, alias = "defaultValue")]
+    pub default_value: Option<String>,
+    /// Header name, for header extractors
+    #[serde(default, alias = "headerName")]
+    pub header_name: Option<String>,
 }
 
 /// Workflow step
@@ -42,6 +179,15 @@ pub struct WorkflowStep {
     pub node: WorkflowNode,
     #[serde(default)]
     pub retry: Option<RetryConfig>,
+    /// Steps run when this step fails (after its retries), with the failure
+    /// in `_error`. If they pass the step counts as passed; its `error`
+    /// still records what went wrong.
+    #[serde(default, alias = "onError", skip_serializing_if = "Option::is_none")]
+    pub on_error: Option<Vec<WorkflowStep>>,
+    /// Steps run after the step and its `on_error` steps whatever the outcome,
+    /// even when the run is aborted. A failure here fails the step.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finally: Option<Vec<WorkflowStep>>,
 }
 
 /// Retry configuration
@@ -59,6 +205,9 @@ pub enum LoopType {
     /// Repeat N times
     Repeat {
         count: u32,
+        /// Variable holding the iteration number (besides `_index`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        index_var: Option<String>,
     },
     /// Iterate over collection
     #[serde(rename = "foreach")]
@@ -70,21 +219,40 @@ pub enum LoopType {
     While {
         condition: Condition,
         max_iterations: Option<u32>,
+        /// Variable holding the iteration number (besides `_iteration`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        index_var: Option<String>,
     },
 }
 
 /// Condition for conditional execution
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+///
+/// Either a `variable operator value` triple or an `expression` such as
+/// `status() == 200 && count > 3`; see `workflow::expression`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct Condition {
+    #[serde(default)]
     pub variable: String,
+    #[serde(default)]
     pub operator: ConditionOperator,
+    #[serde(default)]
     pub value: String,
+    /// When set, the variable/operator/value triple is ignored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
+}
+
+impl Condition {
+    pub fn from_expression(expression: impl Into<String>) -> Self {
+        Condition { expression: Some(expression.into()), ..Default::default() }
+    }
 }
 
 /// Condition operators
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ConditionOperator {
+    #[default]
     Equals,
     #[serde(rename = "notequals")]
     NotEquals,
@@ -103,6 +271,8 @@ pub enum ConditionOperator {
 pub struct WorkflowResult {
     pub workflow_name: String,
     pub passed: bool,
+    /// The run was cancelled before every step finished
+    pub aborted: bool,
     pub total_duration_ms: u64,
     pub step_results: Vec<WorkflowStepResult>,
     pub variables: HashMap<String, String>,
@@ -119,16 +289,365 @@ pub struct WorkflowStepResult {
     pub child_results: Option<Vec<WorkflowStepResult>>,
 }
 
+/// Progress of a workflow run.
+///
+/// `path` locates the step in the workflow: indexes separated by `.`, with
+/// `#n` marking loop iteration `n`. For example `2#3.0` is the first child of
+/// step 2 in its fourth iteration; parallel branches use their index.
+/// `on_error` and `finally` steps of step `1` are `1!error.n` and `1!finally.n`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum WorkflowEvent {
+    #[serde(rename_all = "camelCase")]
+    StepStart {
+        path: String,
+        step_name: String,
+        node_type: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    StepFinish {
+        path: String,
+        step_name: String,
+        node_type: String,
+        passed: bool,
+        duration_ms: u64,
+        error: Option<String>,
+    },
+}
+
+/// Receives the events of a run; shared with parallel branches
+pub type WorkflowEventSink = Arc<dyn Fn(WorkflowEvent) + Send + Sync>;
+
+/// Error reported for steps stopped by an abort
+const ABORTED: &str = "Workflow aborted";
+
+/// Deepest chain of `CallWorkflow` nodes
+const MAX_CALL_DEPTH: usize = 16;
+
+/// Error of a node whose children ran but failed. Failing through an error
+/// (rather than `Ok(false)`) lets retries and `on_error` apply, while the
+/// children that ran are still reported under the step.
+#[derive(Debug)]
+struct StepFailure {
+    message: String,
+    child_results: Vec<WorkflowStepResult>,
+}
+
+impl std::fmt::Display for StepFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for StepFailure {}
+
+/// A workflow saved with `save_workflow`, in the shape the engine runs
+#[derive(Debug, Clone)]
+pub struct SavedWorkflow {
+    pub id: String,
+    pub name: String,
+    pub steps: Vec<WorkflowStep>,
+    /// Initial variables
+    pub variables: HashMap<String, String>,
+}
+
+impl SavedWorkflow {
+    /// Read a saved workflow; its steps run in `order` when they carry one
+    pub fn from_value(workflow: &Value) -> Result<Self> {
+        let id = workflow.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let name = workflow.get("name").and_then(|v| v.as_str()).unwrap_or(&id).to_string();
+
+        let steps = match workflow.get("steps").and_then(|v| v.as_array()) {
+            Some(steps) => saved_steps(steps)?,
+            None => Vec::new(),
+        };
+
+        let variables = workflow
+            .get("variables")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+
+        Ok(SavedWorkflow { id, name, steps, variables })
+    }
+
+    /// Load a workflow from the global config by id, or else by name
+    pub fn load(reference: &str) -> Result<Self> {
+        let workflows = crate::settings_manager::get_workflows_internal()
+            .map_err(|e| anyhow!("Failed to get workflows: {}", e))?;
+        let field = |w: &&Value, key: &str| w.get(key).and_then(|v| v.as_str()) == Some(reference);
+        let workflow = workflows
+            .iter()
+            .find(|w| field(w, "id"))
+            .or_else(|| workflows.iter().find(|w| field(w, "name")))
+            .ok_or_else(|| anyhow!("Workflow '{}' not found", reference))?;
+        Self::from_value(workflow)
+    }
+}
+
+/// Convert steps saved by the webview, in `order`.
+///
+/// A `condition` step runs the steps after it only when it holds; with
+/// `trueStepId` / `falseStepId` it runs the steps they name instead, and
+/// those steps no longer run on their own. A `loop` step runs its
+/// `loopSteps`. Other steps are read as workflow steps.
+fn saved_steps(values: &[Value]) -> Result<Vec<WorkflowStep>> {
+    let mut values: Vec<&Value> = values.iter().collect();
+    values.sort_by_key(|s| s.get("order").and_then(|v| v.as_i64()).unwrap_or(0));
+
+    let by_id: HashMap<&str, &Value> = values
+        .iter()
+        .filter_map(|s| Some((s.get("id")?.as_str()?, *s)))
+        .collect();
+    let branch_targets: HashSet<&str> = values
+        .iter()
+        .filter(|s| saved_type(s) == "condition")
+        .flat_map(|s| [branch_id(s, "trueStepId"), branch_id(s, "falseStepId")])
+        .flatten()
+        .collect();
+
+    // Built back to front, so a condition can take the steps after it
+    let mut reversed = Vec::new();
+    for value in values.iter().rev() {
+        if value.get("id").and_then(|v| v.as_str()).is_some_and(|id| branch_targets.contains(id)) {
+            continue;
+        }
+        let step = if saved_type(value) == "condition" && branch_id(value, "trueStepId").is_none() {
+            let rest = std::mem::take(&mut reversed).into_iter().rev().collect();
+            saved_condition_step(value, &by_id, rest, &mut Vec::new())?
+        } else {
+            saved_step(value, &by_id, &mut Vec::new())?
+        };
+        reversed.push(step);
+    }
+    reversed.reverse();
+    Ok(reversed)
+}
+
+/// Convert one saved step. `branching` holds the ids of the condition steps
+/// being converted, to reject conditions that branch back to themselves.
+fn saved_step<'a>(
+    value: &'a Value,
+    by_id: &HashMap<&str, &'a Value>,
+    branching: &mut Vec<&'a str>,
+) -> Result<WorkflowStep> {
+    let name = saved_name(value);
+    match saved_type(value) {
+        "condition" => saved_condition_step(value, by_id, Vec::new(), branching),
+        "loop" => {
+            let loop_type = saved_loop(value.get("loop").unwrap_or(&Value::Null))
+                .map_err(|e| anyhow!("Workflow step '{}' cannot run: {}", name, e))?;
+            let steps = match value.get("loopSteps").and_then(|v| v.as_array()) {
+                Some(steps) => saved_steps(steps)?,
+                None => Vec::new(),
+            };
+            Ok(WorkflowStep {
+                name: name.to_string(),
+                node: WorkflowNode::Loop { loop_type, steps },
+                retry: None,
+                on_error: None,
+                finally: None,
+            })
+        }
+        _ => serde_json::from_value(value.clone())
+            .map_err(|e| anyhow!("Workflow step '{}' cannot run: {}", name, e)),
+    }
+}
+
+/// Convert a saved condition step; `rest` runs when it holds and it has no
+/// `trueStepId`
+fn saved_condition_step<'a>(
+    value: &'a Value,
+    by_id: &HashMap<&str, &'a Value>,
+    rest: Vec<WorkflowStep>,
+    branching: &mut Vec<&'a str>,
+) -> Result<WorkflowStep> {
+    let name = saved_name(value);
+    let id = value.get("id").and_then(|v| v.as_str()).unwrap_or_default();
+    if branching.contains(&id) {
+        return Err(anyhow!("Workflow step '{}' cannot run: its condition branches back to itself", name));
+    }
+    let condition = saved_condition(value.get("condition").unwrap_or(&Value::Null))
+        .map_err(|e| anyhow!("Workflow step '{}' cannot run: {}", name, e))?;
+
+    branching.push(id);
+    let mut branch = |key: &str| -> Result<Option<Vec<WorkflowStep>>> {
+        let Some(target) = branch_id(value, key) else {
+            return Ok(None);
+        };
+        let target = by_id
+            .get(target)
+            .ok_or_else(|| anyhow!("Workflow step '{}' cannot run: branch step '{}' not found", name, target))?;
+        Ok(Some(vec![saved_step(target, by_id, branching)?]))
+    };
+    let then_steps = branch("trueStepId")?.unwrap_or(rest);
+    let else_steps = branch("falseStepId")?;
+    branching.pop();
+
+    Ok(WorkflowStep {
+        name: name.to_string(),
+        node: WorkflowNode::Conditional { condition, then_steps, else_steps },
+        retry: None,
+        on_error: None,
+        finally: None,
+    })
+}
+
+/// Turn the webview's `{ expression, operator, expectedValue }` into an
+/// expression. An `equals` without an expected value uses the expression as
+/// it is, so it can hold a full expression such as `status() == 200`.
+fn saved_condition(condition: &Value) -> Result<Condition, String> {
+    let text = |key: &str| condition.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+    let expression = text("expression").trim();
+    if expression.is_empty() {
+        return Err("its condition has no expression".to_string());
+    }
+    let expected = text("expectedValue");
+    let value = format!("'{}'", expected.replace('\\', "\\\\").replace('\'', "\\'"));
+
+    let source = match text("operator") {
+        "equals" | "" if expected.is_empty() => expression.to_string(),
+        "equals" | "" => format!("({}) == {}", expression, value),
+        "notEquals" => format!("({}) != {}", expression, value),
+        "contains" => format!("({}) contains {}", expression, value),
+        "notContains" => format!("not (({}) contains {})", expression, value),
+        "greaterThan" => format!("({}) > {}", expression, value),
+        "lessThan" => format!("({}) < {}", expression, value),
+        "exists" => format!("exists({})", expression),
+        "notExists" => format!("not exists({})", expression),
+        other => return Err(format!("unknown condition operator '{}'", other)),
+    };
+    Expression::parse(&source).map_err(|e| format!("invalid condition '{}': {}", source, e))?;
+    Ok(Condition::from_expression(source))
+}
+
+/// Turn the webview's loop settings into a loop type
+fn saved_loop(config: &Value) -> Result<LoopType, String> {
+    let text = |key: &str| config.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty());
+    let max_iterations = config.get("maxIterations").and_then(|v| v.as_u64()).map(|n| n as u32);
+    let index_var = text("iteratorVariable").map(str::to_string);
+
+    match text("type").unwrap_or("count") {
+        "count" => {
+            let count = config.get("count").and_then(|v| v.as_u64()).unwrap_or(1) as u32;
+            Ok(LoopType::Repeat { count: max_iterations.map_or(count, |max| count.min(max)), index_var })
+        }
+        "list" => Ok(LoopType::ForEach {
+            collection_var: text("listVariable").ok_or("its list loop has no list variable")?.to_string(),
+            item_var: index_var.unwrap_or_else(|| "item".to_string()),
+        }),
+        "while" => Ok(LoopType::While {
+            condition: saved_condition(config.get("condition").unwrap_or(&Value::Null))?,
+            max_iterations,
+            index_var,
+        }),
+        other => Err(format!("unknown loop type '{}'", other)),
+    }
+}
+
+fn saved_type(step: &Value) -> &str {
+    step.get("type").and_then(|v| v.as_str()).unwrap_or_default()
+}
+
+fn saved_name(step: &Value) -> &str {
+    step.get("name").and_then(|v| v.as_str()).unwrap_or("unnamed")
+}
+
+/// A condition step's `trueStepId` or `falseStepId`, when set
+fn branch_id<'a>(step: &'a Value, key: &str) -> Option<&'a str> {
+    step.get("condition")?.get(key)?.as_str().filter(|id| !id.is_empty())
+}
+
+/// A project loaded for request and test case nodes
+#[derive(Debug, Clone)]
+struct LoadedProject {
+    project: Value,
+    /// Base directory for data sources; `None` for projects added in memory
+    dir: Option<PathBuf>,
+}
+
 /// Workflow executor
 pub struct WorkflowExecutor {
     pub test_runner: TestRunner,
+    /// Endpoint for requests that have none of their own
+    pub fallback_endpoint: Option<String>,
+    /// Projects referenced by leaf nodes, keyed by reference
+    projects: HashMap<String, LoadedProject>,
+    /// The most recent response, read by Extract nodes
+    last_response: Option<StepExecutionResult>,
+    events: Option<WorkflowEventSink>,
+    /// Becomes `true` when the run is aborted
+    cancel: Option<watch::Receiver<bool>>,
+    /// Workflows referenced by `CallWorkflow` nodes, keyed by reference
+    workflows: HashMap<String, SavedWorkflow>,
+    /// Ids of the workflows being called, outermost first
+    call_stack: Vec<String>,
 }
 
 impl WorkflowExecutor {
     pub fn new() -> Self {
         Self {
             test_runner: TestRunner::new(),
+            fallback_endpoint: None,
+            projects: HashMap::new(),
+            last_response: None,
+            events: None,
+            cancel: None,
+            workflows: HashMap::new(),
+            call_stack: Vec::new(),
+        }
+    }
+
+    /// Report step start/finish events to `sink`
+    pub fn with_events(mut self, sink: WorkflowEventSink) -> Self {
+        self.events = Some(sink);
+        self
+    }
+
+    /// Stop the run once `cancel` becomes `true`. In-flight steps, including
+    /// parallel branches, are interrupted and reported as aborted.
+    pub fn with_cancellation(mut self, cancel: watch::Receiver<bool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().map(|c| *c.borrow()).unwrap_or(false)
+    }
+
+    fn emit(&self, event: WorkflowEvent) {
+        if let Some(sink) = &self.events {
+            sink(event);
+        }
+    }
+
+    /// Make a project available under `reference` without loading it from disk
+    pub fn add_project(&mut self, reference: impl Into<String>, project: Value) {
+        self.projects.insert(reference.into(), LoadedProject { project, dir: None });
+    }
+
+    /// Make a workflow available to `CallWorkflow` nodes by id and by name
+    /// without reading the global config
+    pub fn add_workflow(&mut self, workflow: SavedWorkflow) {
+        self.workflows.insert(workflow.name.clone(), workflow.clone());
+        self.workflows.insert(workflow.id.clone(), workflow);
+    }
+
+    /// An executor for a parallel branch: same variables, projects and last response
+    fn fork(&self) -> Self {
+        let mut executor = Self {
+            test_runner: TestRunner::new(),
+            fallback_endpoint: self.fallback_endpoint.clone(),
+            projects: self.projects.clone(),
+            last_response: self.last_response.clone(),
+            events: self.events.clone(),
+            cancel: self.cancel.clone(),
+            workflows: self.workflows.clone(),
+            call_stack: self.call_stack.clone(),
+        };
+        for (k, v) in &self.test_runner.variables {
+            executor.test_runner.set_variable(k.clone(), v.clone());
         }
+        executor
     }
     
     /// Execute a workflow
@@ -141,8 +660,11 @@ impl WorkflowExecutor {
         let mut step_results = Vec::new();
         let mut all_passed = true;
         
-        for step in steps {
-            let result = self.execute_step(&step).await?;
+        for (i, step) in steps.iter().enumerate() {
+            if self.is_cancelled() {
+                break;
+            }
+            let result = self.execute_step(step, &i.to_string()).await?;
             if !result.passed {
                 all_passed = false;
             }
@@ -150,10 +672,12 @@ impl WorkflowExecutor {
         }
         
         let total_duration_ms = start.elapsed().as_millis() as u64;
+        let aborted = self.is_cancelled();
         
         Ok(WorkflowResult {
             workflow_name: name,
-            passed: all_passed,
+            passed: all_passed && !aborted,
+            aborted,
             total_duration_ms,
             step_results,
             variables: self.test_runner.variables.clone(),
@@ -162,11 +686,87 @@ impl WorkflowExecutor {
     
     /// Execute a single workflow step
     #[async_recursion]
-    async fn execute_step(&mut self, step: &WorkflowStep) -> Result<WorkflowStepResult> {
+    async fn execute_step(&mut self, step: &WorkflowStep, path: &str) -> Result<WorkflowStepResult> {
         let start = std::time::Instant::now();
-        
+        let node_type = self.get_node_type(&step.node);
+        self.emit(WorkflowEvent::StepStart {
+            path: path.to_string(),
+            step_name: step.name.clone(),
+            node_type: node_type.clone(),
+        });
+
+        let result = self.run_attempts(step, path).await;
+        let mut result = match result {
+            Ok((passed, child_results)) => WorkflowStepResult {
+                step_name: step.name.clone(),
+                node_type,
+                passed,
+                duration_ms: start.elapsed().as_millis() as u64,
+                // Children of an aborted parallel node report their own abort
+                error: (!passed && self.is_cancelled()).then(|| ABORTED.to_string()),
+                child_results,
+            },
+            Err(e) => {
+                let (error, child_results) = match e.downcast::<StepFailure>() {
+                    Ok(failure) => (failure.message, Some(failure.child_results)),
+                    Err(e) => (e.to_string(), None),
+                };
+                WorkflowStepResult {
+                    step_name: step.name.clone(),
+                    node_type,
+                    passed: false,
+                    duration_ms: start.elapsed().as_millis() as u64,
+                    error: Some(error),
+                    child_results,
+                }
+            }
+        };
+
+        if !result.passed && !self.is_cancelled() {
+            if let Some(on_error) = &step.on_error {
+                let error = result.error.clone().unwrap_or_else(|| "Step failed".to_string());
+                self.test_runner.set_variable("_error".to_string(), error);
+                // An abort while handling leaves the step failed
+                if let Ok((passed, children)) = self.execute_sequential(on_error, &format!("{}!error", path)).await {
+                    result.passed = passed;
+                    result.child_results.get_or_insert_with(Vec::new).extend(children.into_iter().flatten());
+                }
+            }
+        }
+        if let Some(finally) = &step.finally {
+            // Teardown runs even after an abort
+            let cancel = self.cancel.take();
+            let outcome = self.execute_sequential(finally, &format!("{}!finally", path)).await;
+            self.cancel = cancel;
+            let (passed, children) = outcome?;
+            if !passed {
+                result.passed = false;
+                result.error.get_or_insert_with(|| "Finally steps failed".to_string());
+            }
+            result.child_results.get_or_insert_with(Vec::new).extend(children.into_iter().flatten());
+        }
+        result.duration_ms = start.elapsed().as_millis() as u64;
+
+        self.emit(WorkflowEvent::StepFinish {
+            path: path.to_string(),
+            step_name: result.step_name.clone(),
+            node_type: result.node_type.clone(),
+            passed: result.passed,
+            duration_ms: result.duration_ms,
+            error: result.error.clone(),
+        });
+        Ok(result)
+    }
+
+    /// Run a step's node, retrying per its retry config. Returns the last
+    /// error when every attempt failed, or an abort error once cancelled.
+    async fn run_attempts(
+        &mut self,
+        step: &WorkflowStep,
+        path: &str,
+    ) -> Result<(bool, Option<Vec<WorkflowStepResult>>)> {
         // Handle retries
-        let max_attempts = step.retry.as_ref().map(|r| r.max_attempts).unwrap_or(1);
+        let max_attempts = step.retry.as_ref().map(|r| r.max_attempts).unwrap_or(1).max(1);
         let mut last_error = None;
         
         for attempt in 0..max_attempts {
@@ -178,21 +778,30 @@ impl WorkflowExecutor {
                     } else {
                         retry_config.delay_ms
                     };
-                    tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+                    let cancel = self.cancel.clone();
+                    tokio::select! {
+                        _ = tokio::time::sleep(std::time::Duration::from_millis(delay)) => {}
+                        _ = cancelled(cancel) => {}
+                    }
                 }
             }
-            
-            match self.execute_node(&step.node).await {
-                Ok(result) => {
-                    return Ok(WorkflowStepResult {
-                        step_name: step.name.clone(),
-                        node_type: self.get_node_type(&step.node),
-                        passed: result.0,
-                        duration_ms: start.elapsed().as_millis() as u64,
-                        error: None,
-                        child_results: result.1,
-                    });
+            if self.is_cancelled() {
+                return Err(anyhow!(ABORTED));
+            }
+
+            // Leaves are interrupted mid-flight; composite nodes stop through
+            // their children so every branch reports its own abort
+            let outcome = if is_leaf(&step.node) {
+                let cancel = self.cancel.clone();
+                tokio::select! {
+                    outcome = self.execute_node(&step.node, path) => outcome,
+                    _ = cancelled(cancel) => return Err(anyhow!(ABORTED)),
                 }
+            } else {
+                self.execute_node(&step.node, path).await
+            };
+            match outcome {
+                Ok(result) => return Ok(result),
                 Err(e) => {
                     last_error = Some(e);
                 }
@@ -200,14 +809,7 @@ impl WorkflowExecutor {
         }
         
         // All retries failed
-        Ok(WorkflowStepResult {
-            step_name: step.name.clone(),
-            node_type: self.get_node_type(&step.node),
-            passed: false,
-            duration_ms: start.elapsed().as_millis() as u64,
-            error: last_error.map(|e| e.to_string()),
-            child_results: None,
-        })
+        Err(last_error.unwrap_or_else(|| anyhow!("Step did not run")))
     }
     
     /// Execute a workflow node
@@ -215,21 +817,291 @@ impl WorkflowExecutor {
     async fn execute_node(
         &mut self,
         node: &WorkflowNode,
+        path: &str,
     ) -> Result<(bool, Option<Vec<WorkflowStepResult>>)> {
         match node {
             WorkflowNode::Sequential { steps } => {
-                self.execute_sequential(steps).await
+                self.execute_sequential(steps, path).await
             }
             WorkflowNode::Parallel { steps } => {
-                self.execute_parallel(steps).await
+                self.execute_parallel(steps, path).await
             }
             WorkflowNode::Conditional { condition, then_steps, else_steps } => {
-                self.execute_conditional(condition, then_steps, else_steps.as_ref()).await
+                self.execute_conditional(condition, then_steps, else_steps.as_ref(), path).await
             }
             WorkflowNode::Loop { loop_type, steps } => {
-                self.execute_loop(loop_type, steps).await
+                self.execute_loop(loop_type, steps, path).await
+            }
+            WorkflowNode::Request(request) => {
+                self.execute_request(request).await
+            }
+            WorkflowNode::TestCase(test_case) => {
+                self.execute_test_case(test_case).await
+            }
+            WorkflowNode::SetVariable { variable, value } => {
+                let value = substitute_variables(value, &self.test_runner.variables);
+                self.test_runner.set_variable(variable.clone(), value);
+                Ok((true, None))
+            }
+            WorkflowNode::Extract { variable, extractor, path, source, default_value } => {
+                let extractor = WorkflowExtractor {
+                    variable: variable.clone(),
+                    extractor_type: extractor.clone(),
+                    path: path.clone(),
+                    source: source.clone(),
+                    default_value: default_value.clone(),
+                    header_name: None,
+                };
+                let response = self.last_response.clone()
+                    .ok_or_else(|| anyhow!("No response to extract '{}' from", variable))?;
+                let value = extract(&extractor, &response)?;
+                self.test_runner.set_variable(variable.clone(), value);
+                Ok((true, None))
+            }
+            WorkflowNode::Delay { ms } => {
+                tokio::time::sleep(std::time::Duration::from_millis(*ms)).await;
+                Ok((true, None))
+            }
+            WorkflowNode::CallWorkflow(call) => {
+                self.execute_call(call, path).await
+            }
+            WorkflowNode::Script { script, timeout_ms } => {
+                self.execute_script(script, *timeout_ms).await
+            }
+        }
+    }
+
+    /// Run a script against the workflow variables and the last response; a
+    /// failing script is an error so the step's retry policy applies
+    async fn execute_script(
+        &mut self,
+        script: &str,
+        timeout_ms: Option<u64>,
+    ) -> Result<(bool, Option<Vec<WorkflowStepResult>>)> {
+        let variables = self.test_runner.variables.clone();
+        let response = self.last_response.as_ref().map(frontend_runner::script_response);
+        let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_SCRIPT_TIMEOUT_MS));
+
+        let (source, vars_for_script) = (script.to_string(), variables.clone());
+        let outcome = tokio::task::spawn_blocking(move || {
+            script_engine::run_script(&source, &vars_for_script, response.as_ref(), timeout)
+        })
+        .await?;
+
+        for line in &outcome.logs {
+            log::info!("[Workflow] script: {}", line);
+        }
+        for (name, value) in outcome.changed_variables(&variables) {
+            self.test_runner.set_variable(name, value);
+        }
+        if !outcome.passed {
+            return Err(anyhow!(outcome.error.unwrap_or_else(|| "Script failed".to_string())));
+        }
+        Ok((true, None))
+    }
+
+    /// Run a saved workflow in an executor of its own, mapping inputs in and
+    /// outputs back. Its steps are reported as child results.
+    #[async_recursion]
+    async fn execute_call(
+        &mut self,
+        node: &CallWorkflowNode,
+        path: &str,
+    ) -> Result<(bool, Option<Vec<WorkflowStepResult>>)> {
+        let workflow = match self.workflows.get(&node.workflow) {
+            Some(workflow) => workflow.clone(),
+            None => {
+                let workflow = SavedWorkflow::load(&node.workflow)?;
+                self.workflows.insert(node.workflow.clone(), workflow.clone());
+                workflow
+            }
+        };
+        if self.call_stack.contains(&workflow.id) {
+            let mut chain = self.call_stack.clone();
+            chain.push(workflow.id.clone());
+            return Err(anyhow!("Workflow '{}' calls itself ({})", workflow.name, chain.join(" -> ")));
+        }
+        if self.call_stack.len() >= MAX_CALL_DEPTH {
+            return Err(anyhow!("Workflow calls nested deeper than {}", MAX_CALL_DEPTH));
+        }
+
+        let mut callee = self.fork();
+        callee.test_runner = TestRunner::new();
+        for (k, v) in &workflow.variables {
+            callee.test_runner.set_variable(k.clone(), v.clone());
+        }
+        for (k, v) in &node.inputs {
+            callee.test_runner.set_variable(k.clone(), substitute_variables(v, &self.test_runner.variables));
+        }
+        callee.call_stack.push(workflow.id.clone());
+
+        let (passed, children) = callee.execute_sequential(&workflow.steps, path).await?;
+
+        for (caller_var, callee_var) in &node.outputs {
+            if let Some(value) = callee.test_runner.get_variable(callee_var) {
+                self.test_runner.set_variable(caller_var.clone(), value.clone());
             }
         }
+        if callee.last_response.is_some() {
+            self.last_response = callee.last_response.take();
+        }
+        Ok((passed, children))
+    }
+
+    /// Send a saved request; a failed request (or assertion) is an error so the
+    /// step's retry policy applies
+    async fn execute_request(
+        &mut self,
+        node: &RequestNode,
+    ) -> Result<(bool, Option<Vec<WorkflowStepResult>>)> {
+        let loaded = self.load_project(&node.project).await?;
+        let (request, operation) = find_request(&loaded.project, node)?;
+
+        let mut req: FrontendRequest = serde_json::from_value(request.clone())
+            .map_err(|e| anyhow!("Invalid request in project '{}': {}", node.project, e))?;
+        if let Some(body) = &node.body {
+            req.request = Some(body.clone());
+        }
+        if let Some(endpoint) = node.endpoint.as_ref().filter(|e| !e.is_empty()) {
+            req.endpoint = Some(endpoint.clone());
+        }
+        if !node.headers.is_empty() {
+            req.headers.get_or_insert_with(HashMap::new).extend(node.headers.clone());
+        }
+        if req.target_namespace.is_none() {
+            req.target_namespace = operation
+                .and_then(|op| op.get("targetNamespace"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+        }
+        let fallback_endpoint = operation
+            .and_then(|op| op.get("originalEndpoint"))
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .or_else(|| self.fallback_endpoint.clone());
+
+        let name = req.name.clone().unwrap_or_else(|| "request".to_string());
+        let step = FrontendTestStep {
+            id: name.clone(),
+            name,
+            step_type: "request".to_string(),
+            config: FrontendStepConfig {
+                request: Some(req),
+                ..Default::default()
+            },
+        };
+        let mut context = RunContext::new(self.test_runner.variables.clone());
+        let mut result = frontend_runner::run_step(&step, fallback_endpoint.as_deref(), &mut context).await?;
+
+        for extractor in &node.extractors {
+            match extract(extractor, &result) {
+                Ok(value) => {
+                    result.extracted_variables.insert(extractor.variable.clone(), value);
+                }
+                Err(e) => log::debug!("[Workflow] Extractor '{}' found no value: {}", extractor.variable, e),
+            }
+        }
+        for (k, v) in &result.extracted_variables {
+            self.test_runner.set_variable(k.clone(), v.clone());
+        }
+
+        let passed = result.passed;
+        let error = result.error.clone();
+        if result.response_body.is_some() {
+            self.last_response = Some(result);
+        }
+        if !passed {
+            return Err(anyhow!(error.unwrap_or_else(|| "Request failed".to_string())));
+        }
+        Ok((true, None))
+    }
+
+    /// Run a saved test case, once per data source row. Each step of the case
+    /// is reported as a child result; a failing case is an error naming the
+    /// failed steps (and rows).
+    async fn execute_test_case(
+        &mut self,
+        node: &TestCaseNode,
+    ) -> Result<(bool, Option<Vec<WorkflowStepResult>>)> {
+        let loaded = self.load_project(&node.project).await?;
+        let case_value = find_test_case(&loaded.project, node)?;
+        let test_case: FrontendTestCase = serde_json::from_value(case_value.clone())
+            .map_err(|e| anyhow!("Invalid test case '{}': {}", node.test_case, e))?;
+
+        let rows = data_source::iterations(test_case.data_source.as_ref(), loaded.dir.as_deref())?;
+        let data_driven = rows.len() > 1 || rows.iter().any(|r| r.is_some());
+        let mut children = Vec::new();
+        let mut failed = Vec::new();
+        let mut all_passed = true;
+
+        for row in rows {
+            let mut variables = self.test_runner.variables.clone();
+            if let Some(row) = &row {
+                variables.extend(row.values.clone());
+            }
+            let mut context = RunContext::new(variables);
+            let result = frontend_runner::run_test_case(
+                &test_case,
+                self.fallback_endpoint.as_deref(),
+                &mut context,
+            ).await;
+
+            for (k, v) in context.string_variables() {
+                self.test_runner.set_variable(k, v);
+            }
+            if context.last_response.is_some() {
+                self.last_response = context.last_response.take();
+            }
+            all_passed &= result.passed;
+
+            let steps: Vec<WorkflowStepResult> = result.steps.iter().map(step_run_result).collect();
+            let label = row.as_ref().filter(|_| data_driven).map(|row| row.label(&test_case.name));
+            for step in steps.iter().filter(|s| !s.passed) {
+                failed.push(match &label {
+                    Some(label) => format!("{}: {}", label, step.step_name),
+                    None => step.step_name.clone(),
+                });
+            }
+            match label {
+                Some(label) => children.push(WorkflowStepResult {
+                    step_name: label,
+                    node_type: "testcase".to_string(),
+                    passed: result.passed,
+                    duration_ms: result.duration_ms,
+                    error: None,
+                    child_results: Some(steps),
+                }),
+                None => children.extend(steps),
+            }
+        }
+
+        if !all_passed {
+            let message = match failed.is_empty() {
+                true => format!("Test case '{}' failed", test_case.name),
+                false => format!("Test case '{}' failed: {}", test_case.name, failed.join(", ")),
+            };
+            return Err(StepFailure {
+                message,
+                child_results: children,
+            }
+            .into());
+        }
+        Ok((true, Some(children)))
+    }
+
+    /// Load a referenced project once per executor
+    async fn load_project(&mut self, reference: &str) -> Result<LoadedProject> {
+        if let Some(loaded) = self.projects.get(reference) {
+            return Ok(loaded.clone());
+        }
+        let dir = crate::project_storage::resolve_project_dir(reference).map_err(|e| anyhow!(e))?;
+        let project = crate::project_storage::load_project_internal(&dir.to_string_lossy())
+            .await
+            .map_err(|e| anyhow!(e))?;
+        let loaded = LoadedProject { project, dir: Some(dir) };
+        self.projects.insert(reference.to_string(), loaded.clone());
+        Ok(loaded)
     }
     
     /// Execute steps sequentially
@@ -237,12 +1109,16 @@ impl WorkflowExecutor {
     async fn execute_sequential(
         &mut self,
         steps: &[WorkflowStep],
+        parent: &str,
     ) -> Result<(bool, Option<Vec<WorkflowStepResult>>)> {
         let mut results = Vec::new();
         let mut all_passed = true;
         
-        for step in steps {
-            let result = self.execute_step(step).await?;
+        for (i, step) in steps.iter().enumerate() {
+            if self.is_cancelled() {
+                return Err(anyhow!(ABORTED));
+            }
+            let result = self.execute_step(step, &format!("{}.{}", parent, i)).await?;
             if !result.passed {
                 all_passed = false;
             }
@@ -257,21 +1133,18 @@ impl WorkflowExecutor {
     async fn execute_parallel(
         &mut self,
         steps: &[WorkflowStep],
+        parent: &str,
     ) -> Result<(bool, Option<Vec<WorkflowStepResult>>)> {
         let mut join_set = JoinSet::new();
         
         // Clone necessary data for each parallel task
-        for step in steps {
+        for (i, step) in steps.iter().enumerate() {
             let step = step.clone();
-            let mut executor = WorkflowExecutor::new();
-            
-            // Copy current variables to new executor
-            for (k, v) in &self.test_runner.variables {
-                executor.test_runner.set_variable(k.clone(), v.clone());
-            }
+            let mut executor = self.fork();
+            let path = format!("{}.{}", parent, i);
             
             join_set.spawn(async move {
-                executor.execute_step(&step).await
+                executor.execute_step(&step, &path).await
             });
         }
         
@@ -296,13 +1169,14 @@ impl WorkflowExecutor {
         condition: &Condition,
         then_steps: &[WorkflowStep],
         else_steps: Option<&Vec<WorkflowStep>>,
+        path: &str,
     ) -> Result<(bool, Option<Vec<WorkflowStepResult>>)> {
-        let condition_met = self.evaluate_condition(condition);
+        let condition_met = self.evaluate_condition(condition)?;
         
         if condition_met {
-            self.execute_sequential(then_steps).await
+            self.execute_sequential(then_steps, path).await
         } else if let Some(else_steps) = else_steps {
-            self.execute_sequential(else_steps).await
+            self.execute_sequential(else_steps, path).await
         } else {
             Ok((true, None))
         }
@@ -314,15 +1188,19 @@ impl WorkflowExecutor {
         &mut self,
         loop_type: &LoopType,
         steps: &[WorkflowStep],
+        path: &str,
     ) -> Result<(bool, Option<Vec<WorkflowStepResult>>)> {
         let mut all_results = Vec::new();
         let mut all_passed = true;
         
         match loop_type {
-            LoopType::Repeat { count } => {
+            LoopType::Repeat { count, index_var } => {
                 for i in 0..*count {
                     self.test_runner.set_variable("_index".to_string(), i.to_string());
-                    let (passed, results) = self.execute_sequential(steps).await?;
+                    if let Some(var) = index_var {
+                        self.test_runner.set_variable(var.clone(), i.to_string());
+                    }
+                    let (passed, results) = self.execute_sequential(steps, &format!("{}#{}", path, i)).await?;
                     if !passed {
                         all_passed = false;
                     }
@@ -339,7 +1217,7 @@ impl WorkflowExecutor {
                         self.test_runner.set_variable(item_var.clone(), item.trim().to_string());
                         self.test_runner.set_variable("_index".to_string(), i.to_string());
                         
-                        let (passed, results) = self.execute_sequential(steps).await?;
+                        let (passed, results) = self.execute_sequential(steps, &format!("{}#{}", path, i)).await?;
                         if !passed {
                             all_passed = false;
                         }
@@ -349,14 +1227,17 @@ impl WorkflowExecutor {
                     }
                 }
             }
-            LoopType::While { condition, max_iterations } => {
+            LoopType::While { condition, max_iterations, index_var } => {
                 let mut iterations = 0;
                 let max = max_iterations.unwrap_or(1000);
                 
-                while self.evaluate_condition(condition) && iterations < max {
+                while iterations < max && self.evaluate_condition(condition)? {
                     self.test_runner.set_variable("_iteration".to_string(), iterations.to_string());
+                    if let Some(var) = index_var {
+                        self.test_runner.set_variable(var.clone(), iterations.to_string());
+                    }
                     
-                    let (passed, results) = self.execute_sequential(steps).await?;
+                    let (passed, results) = self.execute_sequential(steps, &format!("{}#{}", path, iterations)).await?;
                     if !passed {
                         all_passed = false;
                     }
@@ -373,10 +1254,20 @@ impl WorkflowExecutor {
     }
     
     /// Evaluate a condition
-    fn evaluate_condition(&self, condition: &Condition) -> bool {
+    fn evaluate_condition(&self, condition: &Condition) -> Result<bool> {
+        if let Some(expression) = &condition.expression {
+            let context = ExpressionContext {
+                variables: &self.test_runner.variables,
+                response: self.last_response.as_ref(),
+            };
+            return Expression::parse(expression)
+                .and_then(|e| e.evaluate(&context))
+                .map_err(|e| anyhow!("Condition '{}': {}", expression, e));
+        }
+
         let var_value = self.test_runner.get_variable(&condition.variable);
         
-        match condition.operator {
+        Ok(match condition.operator {
             ConditionOperator::Exists => var_value.is_some(),
             ConditionOperator::NotExists => var_value.is_none(),
             _ => {
@@ -403,7 +1294,7 @@ impl WorkflowExecutor {
                     false
                 }
             }
-        }
+        })
     }
     
     fn get_node_type(&self, node: &WorkflowNode) -> String {
@@ -412,7 +1303,141 @@ impl WorkflowExecutor {
             WorkflowNode::Parallel { .. } => "parallel".to_string(),
             WorkflowNode::Conditional { .. } => "conditional".to_string(),
             WorkflowNode::Loop { .. } => "loop".to_string(),
+            WorkflowNode::Request(_) => "request".to_string(),
+            WorkflowNode::TestCase(_) => "testcase".to_string(),
+            WorkflowNode::SetVariable { .. } => "setvariable".to_string(),
+            WorkflowNode::Extract { .. } => "extract".to_string(),
+            WorkflowNode::Delay { .. } => "delay".to_string(),
+            WorkflowNode::CallWorkflow(_) => "callworkflow".to_string(),
+            WorkflowNode::Script { .. } => "script".to_string(),
+        }
+    }
+}
+
+fn is_leaf(node: &WorkflowNode) -> bool {
+    !matches!(
+        node,
+        WorkflowNode::Sequential { .. }
+            | WorkflowNode::Parallel { .. }
+            | WorkflowNode::Conditional { .. }
+            | WorkflowNode::Loop { .. }
+            | WorkflowNode::CallWorkflow(_)
+    )
+}
+
+/// Resolves once `cancel` becomes `true`; never without a receiver
+async fn cancelled(cancel: Option<watch::Receiver<bool>>) {
+    match cancel {
+        Some(mut cancel) => {
+            if cancel.wait_for(|aborted| *aborted).await.is_err() {
+                // Sender gone without aborting: the run can no longer be cancelled
+                std::future::pending::<()>().await;
+            }
+        }
+        None => std::future::pending::<()>().await,
+    }
+}
+
+/// True when `item`'s name, display name or id equals `reference`
+fn matches_ref(item: &Value, reference: &str) -> bool {
+    ["name", "displayName", "id"]
+        .iter()
+        .any(|key| item.get(key).and_then(|v| v.as_str()) == Some(reference))
+}
+
+fn array<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value.get(key).and_then(|v| v.as_array()).map(|v| v.as_slice()).unwrap_or(&[])
+}
+
+/// Find the referenced request and the operation it belongs to (if any)
+fn find_request<'a>(project: &'a Value, node: &RequestNode) -> Result<(&'a Value, Option<&'a Value>)> {
+    let interfaces: Vec<&Value> = array(project, "interfaces")
+        .iter()
+        .filter(|i| node.interface.as_deref().map(|name| matches_ref(i, name)).unwrap_or(true))
+        .collect();
+    if let Some(name) = &node.interface {
+        if interfaces.is_empty() {
+            return Err(anyhow!("Interface '{}' not found in project '{}'", name, node.project));
+        }
+    }
+
+    if let Some(op_name) = &node.operation {
+        let operation = interfaces
+            .iter()
+            .flat_map(|i| array(i, "operations"))
+            .find(|op| matches_ref(op, op_name))
+            .ok_or_else(|| anyhow!("Operation '{}' not found in project '{}'", op_name, node.project))?;
+        let requests = array(operation, "requests");
+        let request = match &node.request_name {
+            Some(name) => requests.iter().find(|r| matches_ref(r, name)),
+            None => requests.first(),
         }
+        .ok_or_else(|| anyhow!(
+            "Request '{}' not found in operation '{}'",
+            node.request_name.as_deref().unwrap_or("(first)"),
+            op_name
+        ))?;
+        return Ok((request, Some(operation)));
+    }
+
+    let name = node.request_name.as_deref()
+        .ok_or_else(|| anyhow!("Request step needs an operation or a request name"))?;
+    for operation in interfaces.iter().flat_map(|i| array(i, "operations")) {
+        if let Some(request) = array(operation, "requests").iter().find(|r| matches_ref(r, name)) {
+            return Ok((request, Some(operation)));
+        }
+    }
+    find_in_folders(array(project, "folders"), name)
+        .map(|request| (request, None))
+        .ok_or_else(|| anyhow!("Request '{}' not found in project '{}'", name, node.project))
+}
+
+fn find_in_folders<'a>(folders: &'a [Value], name: &str) -> Option<&'a Value> {
+    folders.iter().find_map(|folder| {
+        array(folder, "requests")
+            .iter()
+            .find(|r| matches_ref(r, name))
+            .or_else(|| find_in_folders(array(folder, "folders"), name))
+    })
+}
+
+fn find_test_case<'a>(project: &'a Value, node: &TestCaseNode) -> Result<&'a Value> {
+    array(project, "testSuites")
+        .iter()
+        .filter(|s| node.suite.as_deref().map(|name| matches_ref(s, name)).unwrap_or(true))
+        .flat_map(|s| array(s, "testCases"))
+        .find(|c| matches_ref(c, &node.test_case))
+        .ok_or_else(|| anyhow!("Test case '{}' not found in project '{}'", node.test_case, node.project))
+}
+
+/// Apply an extractor to a step's response
+fn extract(extractor: &WorkflowExtractor, response: &StepExecutionResult) -> Result<String> {
+    let path = extractor.header_name.as_deref()
+        .filter(|h| !h.is_empty())
+        .unwrap_or(&extractor.path);
+    let kind = ExtractorType::from_config(&extractor.extractor_type, extractor.source.as_deref(), path)?;
+
+    let body = response.response_body.as_deref().unwrap_or("");
+    let mut snapshot = ResponseSnapshot::new(body)
+        .with_headers(response.response_headers.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+        .with_elapsed_ms(response.duration_ms);
+    if let Some(status) = response.status_code {
+        snapshot = snapshot.with_status(status);
+    }
+
+    VariableExtractor::extract_from(&kind, &snapshot)
+        .or_else(|e| extractor.default_value.clone().ok_or(e))
+}
+
+/// Report a test case step as a workflow step result
+fn step_run_result(record: &StepRunRecord) -> WorkflowStepResult {
+    WorkflowStepResult {
+        step_name: record.step_name.clone(),
+        node_type: record.step_type.clone(),
+        passed: record.result.passed,
+        duration_ms: record.result.duration_ms,
+        error: record.result.error.clone(),
+        child_results: None,
     }
 }
 
@@ -425,7 +1450,667 @@ impl Default for WorkflowExecutor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve `POST /orders` and `GET /orders/{id}` on localhost, returning the
+    /// base URL and a receiver for the request bodies seen
+    async fn order_server() -> (String, tokio::sync::mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let mut data = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    // Read the head, then as much body as Content-Length announces
+                    loop {
+                        let n = stream.read(&mut buf).await.unwrap();
+                        data.extend_from_slice(&buf[..n]);
+                        let text = String::from_utf8_lossy(&data).to_string();
+                        if let Some(end) = text.find("\r\n\r\n") {
+                            let length = text[..end]
+                                .lines()
+                                .find_map(|l| l.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                                .unwrap_or(0);
+                            if data.len() >= end + 4 + length || n == 0 {
+                                break;
+                            }
+                        }
+                        if n == 0 {
+                            break;
+                        }
+                    }
+                    let text = String::from_utf8_lossy(&data).to_string();
+                    let body = text.split_once("\r\n\r\n").map(|(_, b)| b.to_string()).unwrap_or_default();
+                    let _ = tx.send(body);
+
+                    let (status, payload) = if text.starts_with("POST /orders ") {
+                        ("201 Created", r#"{"id":"42"}"#)
+                    } else if text.starts_with("GET /orders/42 ") {
+                        ("200 OK", r#"{"id":"42","status":"OPEN"}"#)
+                    } else {
+                        ("404 Not Found", "{}")
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        payload.len(),
+                        payload
+                    );
+                    stream.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+
+        (base, rx)
+    }
+
+    /// An address nothing listens on
+    async fn closed_port() -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap()
+    }
+
+    fn shop_project(base: &str) -> Value {
+        serde_json::json!({
+            "name": "Shop",
+            "interfaces": [{
+                "name": "OrderService",
+                "operations": [{
+                    "name": "CreateOrder",
+                    "originalEndpoint": format!("{}/orders", base),
+                    "requests": [{
+                        "name": "Default",
+                        "request": "{\"sku\":\"{{sku}}\"}",
+                        "method": "POST",
+                        "contentType": "application/json"
+                    }]
+                }]
+            }],
+            "testSuites": [{
+                "id": "suite1",
+                "name": "Smoke",
+                "testCases": [{
+                    "id": "tc1",
+                    "name": "Get order",
+                    "steps": [{
+                        "id": "s1",
+                        "name": "Fetch",
+                        "type": "request",
+                        "config": { "request": {
+                            "name": "Fetch",
+                            "endpoint": format!("{}/orders/{{{{orderId}}}}", base),
+                            "method": "GET",
+                            "assertions": [{ "type": "JSONPath Match", "configuration": { "jsonPath": "$.status", "expectedContent": "OPEN" } }],
+                            "extractors": [{ "id": "x1", "variable": "orderStatus", "type": "JSONPath", "path": "$.status" }]
+                        } }
+                    }]
+                }]
+            }]
+        })
+    }
+
+    fn step(name: &str, node: WorkflowNode) -> WorkflowStep {
+        WorkflowStep { name: name.to_string(), node, retry: None, on_error: None, finally: None }
+    }
+
+    #[tokio::test]
+    async fn test_leaf_nodes_drive_requests_and_test_cases() {
+        let (base, mut bodies) = order_server().await;
+        let mut executor = WorkflowExecutor::new();
+        executor.add_project("Shop", shop_project(&base));
+        executor.test_runner.set_variable("n".to_string(), "7".to_string());
+
+        let steps = vec![
+            step("Pick SKU", WorkflowNode::SetVariable { variable: "sku".to_string(), value: "A-{{n}}".to_string() }),
+            step("Create", WorkflowNode::Request(RequestNode {
+                project: "Shop".to_string(),
+                interface: Some("OrderService".to_string()),
+                operation: Some("CreateOrder".to_string()),
+                request_name: None,
+                body: None,
+                endpoint: None,
+                headers: HashMap::new(),
+                extractors: vec![WorkflowExtractor {
+                    variable: "orderId".to_string(),
+                    extractor_type: "jsonpath".to_string(),
+                    path: "$.id".to_string(),
+                    source: None,
+                    default_value: None,
+                    header_name: None,
+                }],
+            })),
+            step("Created status", WorkflowNode::Extract {
+                variable: "createdStatus".to_string(),
+                extractor: "statuscode".to_string(),
+                path: String::new(),
+                source: None,
+                default_value: None,
+            }),
+            step("Pause", WorkflowNode::Delay { ms: 1 }),
+            step("Check", WorkflowNode::TestCase(TestCaseNode {
+                project: "Shop".to_string(),
+                suite: Some("Smoke".to_string()),
+                test_case: "Get order".to_string(),
+            })),
+        ];
+
+        let result = executor.execute_workflow("Order flow".to_string(), steps).await.unwrap();
+
+        assert!(result.passed, "{:?}", result.step_results);
+        assert_eq!(bodies.recv().await.unwrap(), r#"{"sku":"A-7"}"#);
+        assert_eq!(result.variables["orderId"], "42");
+        assert_eq!(result.variables["createdStatus"], "201");
+        assert_eq!(result.variables["orderStatus"], "OPEN");
+
+        let check = &result.step_results[4];
+        assert_eq!(check.node_type, "testcase");
+        let children = check.child_results.as_ref().unwrap();
+        assert_eq!((children[0].step_name.as_str(), children[0].passed), ("Fetch", true));
+    }
+
+    #[tokio::test]
+    async fn test_request_failures_are_retried_and_reported() {
+        let (base, _bodies) = order_server().await;
+        let mut executor = WorkflowExecutor::new();
+        executor.add_project("Shop", shop_project(&base));
+
+        let missing = step("Missing", WorkflowNode::Request(RequestNode {
+            project: "Shop".to_string(),
+            interface: None,
+            operation: Some("DeleteOrder".to_string()),
+            request_name: None,
+            body: None,
+            endpoint: None,
+            headers: HashMap::new(),
+            extractors: vec![],
+        }));
+        let wrong_endpoint = WorkflowStep {
+            retry: Some(RetryConfig { max_attempts: 2, delay_ms: 1, backoff_multiplier: None }),
+            ..step("Refused", WorkflowNode::Request(RequestNode {
+                project: "Shop".to_string(),
+                interface: None,
+                operation: None,
+                request_name: Some("Default".to_string()),
+                body: None,
+                endpoint: Some(format!("http://{}/orders", closed_port().await)),
+                headers: HashMap::new(),
+                extractors: vec![],
+            }))
+        };
+
+        let result = executor
+            .execute_workflow("Failures".to_string(), vec![missing, wrong_endpoint])
+            .await
+            .unwrap();
+
+        assert!(!result.passed);
+        assert_eq!(
+            result.step_results[0].error.as_deref(),
+            Some("Operation 'DeleteOrder' not found in project 'Shop'")
+        );
+        let refused = &result.step_results[1];
+        assert!(!refused.passed);
+        assert!(refused.error.as_deref().unwrap().starts_with("HTTP request failed"), "{:?}", refused.error);
+
+        let extract_without_response = WorkflowExecutor::new()
+            .execute_workflow(
+                "No response".to_string(),
+                vec![step("Extract", WorkflowNode::Extract {
+                    variable: "id".to_string(),
+                    extractor: "jsonpath".to_string(),
+                    path: "$.id".to_string(),
+                    source: None,
+                    default_value: None,
+                })],
+            )
+            .await
+            .unwrap();
+        assert_eq!(extract_without_response.step_results[0].error.as_deref(), Some("No response to extract 'id' from"));
+    }
     
+    fn set(name: &str, variable: &str, value: &str) -> WorkflowStep {
+        step(name, WorkflowNode::SetVariable { variable: variable.to_string(), value: value.to_string() })
+    }
+
+    #[tokio::test]
+    async fn test_failing_test_case_is_retried_and_handled() {
+        let mut executor = WorkflowExecutor::new();
+        executor.add_project("Shop", shop_project(&format!("http://{}", closed_port().await)));
+
+        let check = WorkflowStep {
+            retry: Some(RetryConfig { max_attempts: 2, delay_ms: 1, backoff_multiplier: None }),
+            on_error: Some(vec![set("Note", "handled", "{{_error}}")]),
+            ..step("Check", WorkflowNode::TestCase(TestCaseNode {
+                project: "Shop".to_string(),
+                suite: None,
+                test_case: "Get order".to_string(),
+            }))
+        };
+        let result = executor.execute_workflow("Failing case".to_string(), vec![check]).await.unwrap();
+
+        let check = &result.step_results[0];
+        assert_eq!(check.error.as_deref(), Some("Test case 'Get order' failed: Fetch"));
+        assert_eq!(result.variables["handled"], "Test case 'Get order' failed: Fetch");
+        // The case's steps are still reported, followed by the handler
+        let children: Vec<(&str, bool)> =
+            check.child_results.as_ref().unwrap().iter().map(|c| (c.step_name.as_str(), c.passed)).collect();
+        assert_eq!(children, vec![("Fetch", false), ("Note", true)]);
+    }
+
+    #[tokio::test]
+    async fn test_expression_conditions() {
+        let mut executor = WorkflowExecutor::new();
+        let steps = vec![
+            set("Start", "trail", ""),
+            step("Grow", WorkflowNode::Loop {
+                loop_type: LoopType::While {
+                    condition: Condition::from_expression("len(trail) < 6 && !(trail matches 'x')"),
+                    max_iterations: Some(10),
+                    index_var: None,
+                },
+                steps: vec![set("Append", "trail", "{{trail}}ab")],
+            }),
+            step("Branch", WorkflowNode::Conditional {
+                condition: Condition::from_expression("trail == 'ababab' && _iteration == 2"),
+                then_steps: vec![set("Then", "branch", "then")],
+                else_steps: Some(vec![set("Else", "branch", "else")]),
+            }),
+            step("Broken", WorkflowNode::Conditional {
+                condition: Condition::from_expression("number(trail) > 1"),
+                then_steps: vec![],
+                else_steps: None,
+            }),
+        ];
+
+        let result = executor.execute_workflow("Expressions".to_string(), steps).await.unwrap();
+
+        assert_eq!(result.variables["trail"], "ababab");
+        assert_eq!(result.variables["branch"], "then");
+        assert!(!result.passed);
+        assert_eq!(
+            result.step_results[3].error.as_deref(),
+            Some("Condition 'number(trail) > 1': 'ababab' is not a number")
+        );
+    }
+
+    async fn run_saved(workflow: Value, variables: &[(&str, &str)]) -> WorkflowResult {
+        let workflow = SavedWorkflow::from_value(&workflow).unwrap();
+        let mut executor = WorkflowExecutor::new();
+        for (name, value) in variables {
+            executor.test_runner.set_variable(name.to_string(), value.to_string());
+        }
+        executor.execute_workflow(workflow.name, workflow.steps).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_saved_loop_and_script_steps_run() {
+        let workflow = serde_json::json!({
+            "id": "wf-loops",
+            "name": "Loops",
+            "steps": [
+                {
+                    "id": "s2", "name": "Count", "type": "loop", "order": 2,
+                    "loop": { "type": "count", "count": 5, "maxIterations": 3, "iteratorVariable": "i" },
+                    "loopSteps": [
+                        { "id": "n1", "name": "Trail", "type": "script", "order": 0, "script": "vars.trail = (vars.trail || '') + vars.i;" }
+                    ]
+                },
+                { "id": "s1", "name": "Greet", "type": "script", "order": 1, "script": "vars.greeting = 'hi ' + vars.user;" },
+                {
+                    "id": "s3", "name": "Each", "type": "loop", "order": 3,
+                    "loop": { "type": "list", "listVariable": "items", "iteratorVariable": "item", "maxIterations": 100 },
+                    "loopSteps": [
+                        { "id": "n2", "name": "Seen", "type": "script", "order": 0, "script": "vars.seen = (vars.seen || '') + vars.item;" }
+                    ]
+                },
+                {
+                    "id": "s4", "name": "Until", "type": "loop", "order": 4,
+                    "loop": {
+                        "type": "while", "maxIterations": 10,
+                        "condition": { "id": "c1", "expression": "{{n}}", "operator": "lessThan", "expectedValue": "4" }
+                    },
+                    "loopSteps": [
+                        { "id": "n3", "name": "Bump", "type": "script", "order": 0, "script": "vars.n = Number(vars.n) + 1;" }
+                    ]
+                },
+                { "id": "s5", "name": "Check", "type": "script", "order": 5, "script": "assert.equal(vars.n, '5', 'n');" }
+            ]
+        });
+
+        let result = run_saved(workflow, &[("user", "ana"), ("items", "a, b"), ("n", "0")]).await;
+
+        assert_eq!(result.variables["greeting"], "hi ana");
+        assert_eq!(result.variables["trail"], "012");
+        assert_eq!(result.variables["seen"], "ab");
+        assert_eq!(result.variables["n"], "4");
+        let names: Vec<_> = result.step_results.iter().map(|r| (r.step_name.as_str(), r.node_type.as_str())).collect();
+        assert_eq!(names, [("Greet", "script"), ("Count", "loop"), ("Each", "loop"), ("Until", "loop"), ("Check", "script")]);
+        assert!(!result.passed);
+        assert!(result.step_results[4].error.as_deref().unwrap().contains("n"));
+        assert!(result.step_results[..4].iter().all(|r| r.passed));
+    }
+
+    #[tokio::test]
+    async fn test_saved_condition_steps_run() {
+        let guard = serde_json::json!({
+            "id": "wf-guard",
+            "name": "Guard",
+            "steps": [
+                { "id": "s1", "name": "Before", "type": "script", "order": 1, "script": "vars.before = 'yes';" },
+                {
+                    "id": "s2", "name": "Is OK", "type": "condition", "order": 2,
+                    "condition": { "id": "c1", "expression": "{{status}}", "operator": "equals", "expectedValue": "200" }
+                },
+                { "id": "s3", "name": "After", "type": "script", "order": 3, "script": "vars.after = 'yes';" }
+            ]
+        });
+        let ok = run_saved(guard.clone(), &[("status", "200")]).await;
+        assert_eq!(ok.variables.get("after").map(String::as_str), Some("yes"));
+        let failed = run_saved(guard, &[("status", "500")]).await;
+        assert_eq!(failed.variables.get("before").map(String::as_str), Some("yes"));
+        assert_eq!(failed.variables.get("after"), None);
+        assert!(failed.passed);
+
+        let branches = serde_json::json!({
+            "id": "wf-branches",
+            "name": "Branches",
+            "steps": [
+                {
+                    "id": "s1", "name": "Has token", "type": "condition", "order": 1,
+                    "condition": { "id": "c1", "expression": "exists(token) and len(token) > 2", "operator": "equals", "trueStepId": "s3", "falseStepId": "s2" }
+                },
+                { "id": "s2", "name": "Login", "type": "script", "order": 2, "script": "vars.path = 'login';" },
+                { "id": "s3", "name": "Reuse", "type": "script", "order": 3, "script": "vars.path = 'reuse';" },
+                {
+                    "id": "s4", "name": "Not admin", "type": "condition", "order": 4,
+                    "condition": { "id": "c2", "expression": "{{role}}", "operator": "notContains", "expectedValue": "admin", "falseStepId": "s5" }
+                },
+                { "id": "s5", "name": "Admin", "type": "script", "order": 5, "script": "vars.admin = 'yes';" },
+                { "id": "s6", "name": "Done", "type": "script", "order": 6, "script": "vars.done = 'yes';" }
+            ]
+        });
+        let steps = SavedWorkflow::from_value(&branches).unwrap().steps;
+        let names: Vec<_> = steps.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Has token", "Not admin"]);
+
+        let reuse = run_saved(branches.clone(), &[("token", "abc"), ("role", "super-admin")]).await;
+        assert_eq!(reuse.variables["path"], "reuse");
+        assert_eq!(reuse.variables["admin"], "yes");
+        assert_eq!(reuse.variables.get("done"), None);
+
+        let login = run_saved(branches, &[("role", "user")]).await;
+        assert_eq!(login.variables["path"], "login");
+        assert_eq!(login.variables.get("admin"), None);
+        assert_eq!(login.variables["done"], "yes");
+    }
+
+    #[test]
+    fn test_saved_conditions_are_checked_when_loaded() {
+        let load = |steps: Value| SavedWorkflow::from_value(&serde_json::json!({ "steps": steps })).map(|w| w.steps);
+        let error = |steps: Value| load(steps).unwrap_err().to_string();
+
+        let steps = load(serde_json::json!([
+            { "id": "s1", "name": "Quoted", "type": "condition", "condition": { "expression": "{{name}}", "operator": "equals", "expectedValue": "O'Brien" } }
+        ]))
+        .unwrap();
+        match &steps[0].node {
+            WorkflowNode::Conditional { condition, .. } => {
+                assert_eq!(condition.expression.as_deref(), Some(r"({{name}}) == 'O\'Brien'"));
+            }
+            other => panic!("unexpected node {:?}", other),
+        }
+
+        assert_eq!(
+            error(serde_json::json!([{ "id": "s1", "name": "Empty", "type": "condition", "condition": { "expression": " ", "operator": "equals" } }])),
+            "Workflow step 'Empty' cannot run: its condition has no expression"
+        );
+        assert!(error(serde_json::json!([{ "id": "s1", "name": "Typo", "type": "condition", "condition": { "expression": "a ==", "operator": "equals" } }]))
+            .starts_with("Workflow step 'Typo' cannot run: invalid condition 'a =='"));
+        assert_eq!(
+            error(serde_json::json!([
+                { "id": "s1", "name": "Start", "type": "condition", "order": 1, "condition": { "expression": "a", "trueStepId": "s2" } },
+                { "id": "s2", "name": "Ping", "type": "condition", "order": 2, "condition": { "expression": "b", "trueStepId": "s3" } },
+                { "id": "s3", "name": "Pong", "type": "condition", "order": 3, "condition": { "expression": "c", "trueStepId": "s2" } }
+            ])),
+            "Workflow step 'Ping' cannot run: its condition branches back to itself"
+        );
+        assert_eq!(
+            error(serde_json::json!([{ "id": "s1", "name": "Gone", "type": "condition", "condition": { "expression": "a", "falseStepId": "s9" } }])),
+            "Workflow step 'Gone' cannot run: branch step 's9' not found"
+        );
+        assert_eq!(
+            error(serde_json::json!([{ "id": "s1", "name": "Each", "type": "loop", "loop": { "type": "list", "maxIterations": 5 } }])),
+            "Workflow step 'Each' cannot run: its list loop has no list variable"
+        );
+    }
+
+    fn extract_without_response(name: &str) -> WorkflowStep {
+        step(name, WorkflowNode::Extract {
+            variable: "id".to_string(),
+            extractor: "jsonpath".to_string(),
+            path: "$.id".to_string(),
+            source: None,
+            default_value: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_call_workflow_maps_inputs_and_outputs() {
+        let mut executor = WorkflowExecutor::new();
+        executor.add_workflow(SavedWorkflow {
+            id: "wf-create".to_string(),
+            name: "Create user".to_string(),
+            steps: vec![set("Build id", "id", "{{prefix}}-{{name}}"), set("Scratch", "secret", "x")],
+            variables: [("prefix".to_string(), "u".to_string())].into(),
+        });
+        executor.add_workflow(SavedWorkflow {
+            id: "wf-loop".to_string(),
+            name: "Loop".to_string(),
+            steps: vec![step("Again", WorkflowNode::CallWorkflow(CallWorkflowNode {
+                workflow: "Loop".to_string(),
+                inputs: HashMap::new(),
+                outputs: HashMap::new(),
+            }))],
+            variables: HashMap::new(),
+        });
+        executor.test_runner.set_variable("who".to_string(), "ann".to_string());
+
+        let steps = vec![
+            step("Create", WorkflowNode::CallWorkflow(CallWorkflowNode {
+                workflow: "wf-create".to_string(),
+                inputs: [("name".to_string(), "{{who}}".to_string())].into(),
+                outputs: [("userId".to_string(), "id".to_string()), ("other".to_string(), "missing".to_string())].into(),
+            })),
+            step("Recurse", WorkflowNode::CallWorkflow(CallWorkflowNode {
+                workflow: "wf-loop".to_string(),
+                inputs: HashMap::new(),
+                outputs: HashMap::new(),
+            })),
+        ];
+        let result = executor.execute_workflow("Caller".to_string(), steps).await.unwrap();
+
+        assert_eq!(result.variables["userId"], "u-ann");
+        for leaked in ["secret", "prefix", "name", "id", "other"] {
+            assert!(!result.variables.contains_key(leaked), "{} leaked", leaked);
+        }
+        let create = &result.step_results[0];
+        assert!(create.passed);
+        assert_eq!(create.node_type, "callworkflow");
+        assert_eq!(create.child_results.as_ref().unwrap().len(), 2);
+
+        let recurse = &result.step_results[1];
+        let inner = &recurse.child_results.as_ref().unwrap()[0];
+        assert_eq!(inner.error.as_deref(), Some("Workflow 'Loop' calls itself (wf-loop -> wf-loop)"));
+        assert!(!result.passed);
+    }
+
+    #[tokio::test]
+    async fn test_on_error_and_finally() {
+        let (mut executor, events) = recording_executor();
+        let steps = vec![
+            WorkflowStep {
+                on_error: Some(vec![set("Note", "handled", "{{_error}}")]),
+                finally: Some(vec![set("Cleanup", "cleaned", "yes")]),
+                ..extract_without_response("Handled")
+            },
+            WorkflowStep {
+                finally: Some(vec![set("Cleanup again", "cleanups", "2")]),
+                ..extract_without_response("Unhandled")
+            },
+            WorkflowStep {
+                finally: Some(vec![extract_without_response("Broken teardown")]),
+                ..set("Fine", "fine", "yes")
+            },
+        ];
+        let result = executor.execute_workflow("Handlers".to_string(), steps).await.unwrap();
+
+        let handled = &result.step_results[0];
+        assert!(handled.passed);
+        assert_eq!(handled.error.as_deref(), Some("No response to extract 'id' from"));
+        let children: Vec<&str> = handled.child_results.as_ref().unwrap().iter().map(|c| c.step_name.as_str()).collect();
+        assert_eq!(children, vec!["Note", "Cleanup"]);
+        assert_eq!(result.variables["handled"], "No response to extract 'id' from");
+        assert_eq!(result.variables["cleaned"], "yes");
+
+        assert!(!result.step_results[1].passed);
+        assert_eq!(result.variables["cleanups"], "2");
+
+        let fine = &result.step_results[2];
+        assert!(!fine.passed);
+        assert_eq!(fine.error.as_deref(), Some("Finally steps failed"));
+        assert!(!result.passed);
+
+        let paths: Vec<String> = events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|e| match e {
+                WorkflowEvent::StepStart { path, .. } => Some(path.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(paths, vec!["0", "0!error.0", "0!finally.0", "1", "1!finally.0", "2", "2!finally.0"]);
+    }
+
+    #[tokio::test]
+    async fn test_finally_runs_after_abort() {
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        let mut executor = WorkflowExecutor::new().with_cancellation(cancel_rx);
+        let steps = vec![WorkflowStep {
+            on_error: Some(vec![set("Not on abort", "handled", "yes")]),
+            finally: Some(vec![set("Teardown", "cleaned", "yes")]),
+            ..step("Slow", WorkflowNode::Delay { ms: 30_000 })
+        }];
+
+        let run = tokio::spawn(async move { executor.execute_workflow("Abort".to_string(), steps).await });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        cancel_tx.send(true).unwrap();
+        let result = run.await.unwrap().unwrap();
+
+        assert!(result.aborted);
+        assert_eq!(result.variables.get("cleaned").map(String::as_str), Some("yes"));
+        assert!(!result.variables.contains_key("handled"));
+        assert_eq!(result.step_results[0].error.as_deref(), Some("Workflow aborted"));
+    }
+
+    fn recording_executor() -> (WorkflowExecutor, Arc<std::sync::Mutex<Vec<WorkflowEvent>>>) {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let executor = WorkflowExecutor::new().with_events(Arc::new(move |event| sink.lock().unwrap().push(event)));
+        (executor, events)
+    }
+
+    #[tokio::test]
+    async fn test_events_cover_loop_iterations_and_branches() {
+        let (mut executor, events) = recording_executor();
+        let steps = vec![
+            step("Twice", WorkflowNode::Loop {
+                loop_type: LoopType::Repeat { count: 2, index_var: None },
+                steps: vec![set("Mark", "last", "{{_index}}")],
+            }),
+            step("Fan out", WorkflowNode::Parallel {
+                steps: vec![set("A", "a", "1"), set("B", "b", "2")],
+            }),
+        ];
+
+        let result = executor.execute_workflow("Events".to_string(), steps).await.unwrap();
+        assert!(result.passed && !result.aborted);
+        assert_eq!(result.variables["last"], "1");
+
+        let events = events.lock().unwrap();
+        let starts: Vec<(&str, &str)> = events
+            .iter()
+            .filter_map(|e| match e {
+                WorkflowEvent::StepStart { path, step_name, .. } => Some((path.as_str(), step_name.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(&starts[..3], &[("0", "Twice"), ("0#0.0", "Mark"), ("0#1.0", "Mark")]);
+        assert_eq!(starts[3], ("1", "Fan out"));
+        let mut branches = starts[4..].to_vec();
+        branches.sort();
+        assert_eq!(branches, vec![("1.0", "A"), ("1.1", "B")]);
+
+        // Every start has a matching finish, and a parent finishes after its children
+        assert_eq!(events.len(), 12);
+        assert!(matches!(
+            &events[4],
+            WorkflowEvent::StepFinish { path, node_type, passed: true, error: None, .. }
+                if path == "0#1.0" && node_type == "setvariable"
+        ));
+        assert!(matches!(&events[5], WorkflowEvent::StepFinish { path, .. } if path == "0"));
+        assert!(matches!(&events[11], WorkflowEvent::StepFinish { path, passed: true, .. } if path == "1"));
+
+        let json = serde_json::to_value(&events[0]).unwrap();
+        assert_eq!(json, serde_json::json!({ "type": "stepStart", "path": "0", "stepName": "Twice", "nodeType": "loop" }));
+    }
+
+    #[tokio::test]
+    async fn test_abort_stops_in_flight_branches() {
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        let (executor, events) = recording_executor();
+        let mut executor = executor.with_cancellation(cancel_rx);
+        let steps = vec![
+            step("Wait in parallel", WorkflowNode::Parallel {
+                steps: vec![
+                    step("Slow A", WorkflowNode::Delay { ms: 30_000 }),
+                    WorkflowStep {
+                        retry: Some(RetryConfig { max_attempts: 5, delay_ms: 30_000, backoff_multiplier: None }),
+                        ..step("Slow B", WorkflowNode::Delay { ms: 30_000 })
+                    },
+                ],
+            }),
+            set("Never", "reached", "yes"),
+        ];
+
+        let run = tokio::spawn(async move { executor.execute_workflow("Abort".to_string(), steps).await });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let started = std::time::Instant::now();
+        cancel_tx.send(true).unwrap();
+        let result = run.await.unwrap().unwrap();
+
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        assert!(result.aborted && !result.passed);
+        assert_eq!(result.step_results.len(), 1);
+        assert_eq!(result.step_results[0].error.as_deref(), Some("Workflow aborted"));
+        assert!(!result.variables.contains_key("reached"));
+
+        let aborted_branches = events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| matches!(e, WorkflowEvent::StepFinish { path, error: Some(error), .. }
+                if path.starts_with("0.") && error == "Workflow aborted"))
+            .count();
+        assert_eq!(aborted_branches, 2);
+    }
+
     #[test]
     fn test_evaluate_condition_equals() {
         let mut executor = WorkflowExecutor::new();
@@ -435,9 +2120,10 @@ mod tests {
             variable: "status".to_string(),
             operator: ConditionOperator::Equals,
             value: "success".to_string(),
+            expression: None,
         };
         
-        assert!(executor.evaluate_condition(&condition));
+        assert!(executor.evaluate_condition(&condition).unwrap());
     }
     
     #[test]
@@ -449,9 +2135,10 @@ mod tests {
             variable: "count".to_string(),
             operator: ConditionOperator::GreaterThan,
             value: "5".to_string(),
+            expression: None,
         };
         
-        assert!(executor.evaluate_condition(&condition));
+        assert!(executor.evaluate_condition(&condition).unwrap());
     }
     
     #[test]
@@ -463,9 +2150,10 @@ mod tests {
             variable: "token".to_string(),
             operator: ConditionOperator::Exists,
             value: "".to_string(),
+            expression: None,
         };
         
-        assert!(executor.evaluate_condition(&condition));
+        assert!(executor.evaluate_condition(&condition).unwrap());
     }
     
     #[test]
@@ -476,8 +2164,9 @@ mod tests {
             variable: "missing".to_string(),
             operator: ConditionOperator::NotExists,
             value: "".to_string(),
+            expression: None,
         };
         
-        assert!(executor.evaluate_condition(&condition));
+        assert!(executor.evaluate_condition(&condition).unwrap());
     }
 }
@@ -0,0 +1,713 @@
+// Workflow condition expressions
+//
+// A small expression language for `Conditional` steps and `While` loops:
+//
+//   status() == 200 && (count > 3 || matches(token, '^[A-F0-9]+$'))
+//   len(jsonpath('$.items')) >= 1 and not exists(xpath('//Fault'))
+//   date(expires) > date('2024-01-01')
+//
+// Bare names (and `{{name}}`) are workflow variables; `xpath`, `jsonpath`,
+// `header`, `status`, `body` and `duration` read the last response.
+// Expressions are parsed up front so syntax errors, unknown functions, wrong
+// argument counts and invalid literal regexes are reported before a run.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use regex::Regex;
+use serde_json::Value as JsonValue;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::testing::frontend_types::StepExecutionResult;
+use crate::utils::{JsonPathEvaluator, XPathEvaluator};
+
+/// A parsed condition expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    root: Expr,
+}
+
+/// What an expression can see while it is evaluated
+pub struct ExpressionContext<'a> {
+    pub variables: &'a HashMap<String, String>,
+    /// The most recent response of the workflow, if a request has run
+    pub response: Option<&'a StepExecutionResult>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Literal(Val),
+    Variable(String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Matches,
+    Contains,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Len,
+    Matches,
+    Contains,
+    Exists,
+    Number,
+    Date,
+    Lower,
+    Upper,
+    XPath,
+    JsonPath,
+    Header,
+    Status,
+    Body,
+    Duration,
+}
+
+impl Function {
+    fn lookup(name: &str) -> Option<(Function, usize)> {
+        Some(match name {
+            "len" => (Function::Len, 1),
+            "matches" => (Function::Matches, 2),
+            "contains" => (Function::Contains, 2),
+            "exists" => (Function::Exists, 1),
+            "number" => (Function::Number, 1),
+            "date" => (Function::Date, 1),
+            "lower" => (Function::Lower, 1),
+            "upper" => (Function::Upper, 1),
+            "xpath" => (Function::XPath, 1),
+            "jsonpath" => (Function::JsonPath, 1),
+            "header" => (Function::Header, 1),
+            "status" => (Function::Status, 0),
+            "body" => (Function::Body, 0),
+            "duration" => (Function::Duration, 0),
+            _ => return None,
+        })
+    }
+}
+
+/// Runtime value of a sub-expression
+#[derive(Debug, Clone, PartialEq)]
+enum Val {
+    Null,
+    Bool(bool),
+    Num(f64),
+    Str(String),
+}
+
+impl Val {
+    fn truthy(&self) -> bool {
+        match self {
+            Val::Null => false,
+            Val::Bool(b) => *b,
+            Val::Num(n) => *n != 0.0,
+            Val::Str(s) => !s.is_empty() && s != "false",
+        }
+    }
+
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            Val::Num(n) => Some(*n),
+            Val::Str(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn as_date(&self) -> Option<i64> {
+        match self {
+            Val::Str(s) => parse_date(s),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Val {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Val::Null => Ok(()),
+            Val::Bool(b) => write!(f, "{}", b),
+            Val::Num(n) if n.fract() == 0.0 && n.abs() < 1e15 => write!(f, "{}", *n as i64),
+            Val::Num(n) => write!(f, "{}", n),
+            Val::Str(s) => f.write_str(s),
+        }
+    }
+}
+
+impl Expression {
+    /// Parse an expression, reporting the column of the first syntax error
+    pub fn parse(source: &str) -> Result<Self> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0, len: source.len() };
+        let root = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(Expression { root }),
+            Some((token, column)) => Err(anyhow!("Unexpected {} at column {}", token, column)),
+        }
+    }
+
+    /// Evaluate to a boolean; non-boolean results use their truthiness
+    /// (null, 0, "" and "false" are false)
+    pub fn evaluate(&self, context: &ExpressionContext) -> Result<bool> {
+        Ok(eval(&self.root, context)?.truthy())
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tokenizer
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Ident(String),
+    /// `{{name}}`, the template form of a variable reference
+    Template(String),
+    And,
+    Or,
+    Not,
+    Op(CompareOp),
+    LParen,
+    RParen,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "number {}", Val::Num(*n)),
+            Token::Str(s) => write!(f, "string '{}'", s),
+            Token::Ident(name) => write!(f, "'{}'", name),
+            Token::Template(name) => write!(f, "'{{{{{}}}}}'", name),
+            Token::And => f.write_str("'&&'"),
+            Token::Or => f.write_str("'||'"),
+            Token::Not => f.write_str("'!'"),
+            Token::Op(op) => write!(f, "'{}'", match op {
+                CompareOp::Eq => "==",
+                CompareOp::Ne => "!=",
+                CompareOp::Lt => "<",
+                CompareOp::Le => "<=",
+                CompareOp::Gt => ">",
+                CompareOp::Ge => ">=",
+                CompareOp::Matches => "matches",
+                CompareOp::Contains => "contains",
+            }),
+            Token::LParen => f.write_str("'('"),
+            Token::RParen => f.write_str("')'"),
+            Token::Comma => f.write_str("','"),
+        }
+    }
+}
+
+/// Split the source into tokens paired with their 1-based column
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<(usize, char)> = source.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (offset, c) = chars[i];
+        let column = offset + 1;
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let (token, width) = match (c, next) {
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('=', Some('=')) => (Token::Op(CompareOp::Eq), 2),
+            ('!', Some('=')) => (Token::Op(CompareOp::Ne), 2),
+            ('<', Some('=')) => (Token::Op(CompareOp::Le), 2),
+            ('>', Some('=')) => (Token::Op(CompareOp::Ge), 2),
+            ('!', _) => (Token::Not, 1),
+            ('<', _) => (Token::Op(CompareOp::Lt), 1),
+            ('>', _) => (Token::Op(CompareOp::Gt), 1),
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            (',', _) => (Token::Comma, 1),
+            ('{', Some('{')) => {
+                let rest = &source[offset + 2..];
+                let end = rest
+                    .find("}}")
+                    .ok_or_else(|| anyhow!("Unterminated '{{{{' at column {}", column))?;
+                let name = rest[..end].trim();
+                if name.is_empty() {
+                    return Err(anyhow!("Empty variable reference at column {}", column));
+                }
+                let width = rest[..end + 2].chars().count() + 2;
+                (Token::Template(name.to_string()), width)
+            }
+            ('\'', _) | ('"', _) => {
+                let mut value = String::new();
+                let mut j = i + 1;
+                loop {
+                    match chars.get(j) {
+                        None => return Err(anyhow!("Unterminated string starting at column {}", column)),
+                        Some((_, '\\')) => {
+                            match chars.get(j + 1) {
+                                Some((_, escaped)) => value.push(*escaped),
+                                None => return Err(anyhow!("Unterminated string starting at column {}", column)),
+                            }
+                            j += 2;
+                        }
+                        Some((_, q)) if *q == c => break,
+                        Some((_, other)) => {
+                            value.push(*other);
+                            j += 1;
+                        }
+                    }
+                }
+                (Token::Str(value), j + 1 - i)
+            }
+            (c, next) if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                let mut j = i + 1;
+                while chars.get(j).is_some_and(|(_, d)| d.is_ascii_digit() || *d == '.') {
+                    j += 1;
+                }
+                let end = chars.get(j).map_or(source.len(), |(o, _)| *o);
+                let text = &source[offset..end];
+                let number = text
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid number '{}' at column {}", text, column))?;
+                (Token::Number(number), j - i)
+            }
+            (c, _) if c.is_alphabetic() || c == '_' || c == '$' => {
+                let mut j = i + 1;
+                while chars
+                    .get(j)
+                    .is_some_and(|(_, d)| d.is_alphanumeric() || matches!(d, '_' | '.' | '$'))
+                {
+                    j += 1;
+                }
+                let end = chars.get(j).map_or(source.len(), |(o, _)| *o);
+                let token = match &source[offset..end] {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    "matches" => Token::Op(CompareOp::Matches),
+                    "contains" => Token::Op(CompareOp::Contains),
+                    word => Token::Ident(word.to_string()),
+                };
+                (token, j - i)
+            }
+            (c, _) => return Err(anyhow!("Unexpected character '{}' at column {}", c, column)),
+        };
+
+        tokens.push((token, column));
+        i += width;
+    }
+
+    Ok(tokens)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Parser
+// ─────────────────────────────────────────────────────────────────────────────
+//
+//   or      := and (('||' | 'or') and)*
+//   and     := unary (('&&' | 'and') unary)*
+//   unary   := ('!' | 'not') unary | compare
+//   compare := primary (op primary)?
+//   primary := literal | name | '{{' name '}}' | name '(' args ')' | '(' or ')'
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    /// Source length, for errors at the end of input
+    len: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&(Token, usize)> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<(Token, usize)> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek().map(|(t, _)| t) == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &Token) -> Result<()> {
+        match self.next() {
+            Some((t, _)) if &t == token => Ok(()),
+            Some((t, column)) => Err(anyhow!("Expected {} but found {} at column {}", token, t, column)),
+            None => Err(anyhow!("Expected {} at end of expression", token)),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut left = self.parse_and()?;
+        while self.eat(&Token::Or) {
+            left = Expr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut left = self.parse_unary()?;
+        while self.eat(&Token::And) {
+            left = Expr::And(Box::new(left), Box::new(self.parse_unary()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        if self.eat(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_compare()
+    }
+
+    fn parse_compare(&mut self) -> Result<Expr> {
+        let left = self.parse_primary()?;
+        let op = match self.peek() {
+            Some((Token::Op(op), _)) => *op,
+            _ => return Ok(left),
+        };
+        let column = self.peek().map(|(_, c)| *c).unwrap_or(self.len + 1);
+        self.pos += 1;
+        let right = self.parse_primary()?;
+        if op == CompareOp::Matches {
+            check_literal_regex(&right, column)?;
+        }
+        Ok(Expr::Compare(Box::new(left), op, Box::new(right)))
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr> {
+        let (token, column) = self
+            .next()
+            .ok_or_else(|| anyhow!("Unexpected end of expression at column {}", self.len + 1))?;
+        match token {
+            Token::Number(n) => Ok(Expr::Literal(Val::Num(n))),
+            Token::Str(s) => Ok(Expr::Literal(Val::Str(s))),
+            Token::Template(name) => Ok(Expr::Variable(name)),
+            Token::LParen => {
+                let inner = self.parse_or()?;
+                self.expect(&Token::RParen)?;
+                Ok(inner)
+            }
+            Token::Ident(name) if self.eat(&Token::LParen) => self.parse_call(&name, column),
+            Token::Ident(name) => Ok(match name.as_str() {
+                "true" => Expr::Literal(Val::Bool(true)),
+                "false" => Expr::Literal(Val::Bool(false)),
+                "null" => Expr::Literal(Val::Null),
+                _ => Expr::Variable(name),
+            }),
+            // `contains(...)` and `matches(...)` are also functions
+            Token::Op(op @ (CompareOp::Contains | CompareOp::Matches)) if self.eat(&Token::LParen) => {
+                let name = if op == CompareOp::Contains { "contains" } else { "matches" };
+                self.parse_call(name, column)
+            }
+            other => Err(anyhow!("Unexpected {} at column {}", other, column)),
+        }
+    }
+
+    /// Parse the arguments of `name(`, after the opening parenthesis
+    fn parse_call(&mut self, name: &str, column: usize) -> Result<Expr> {
+        let (function, arity) = Function::lookup(name)
+            .ok_or_else(|| anyhow!("Unknown function '{}' at column {}", name, column))?;
+
+        let mut args = Vec::new();
+        if !self.eat(&Token::RParen) {
+            loop {
+                args.push(self.parse_or()?);
+                if self.eat(&Token::RParen) {
+                    break;
+                }
+                self.expect(&Token::Comma)?;
+            }
+        }
+
+        if args.len() != arity {
+            return Err(anyhow!(
+                "{}() takes {} argument{} but {} given at column {}",
+                name,
+                arity,
+                if arity == 1 { "" } else { "s" },
+                args.len(),
+                column
+            ));
+        }
+        if function == Function::Matches {
+            check_literal_regex(&args[1], column)?;
+        }
+        Ok(Expr::Call(function, args))
+    }
+}
+
+/// Reject an invalid regex at parse time when the pattern is a literal
+fn check_literal_regex(pattern: &Expr, column: usize) -> Result<()> {
+    if let Expr::Literal(Val::Str(pattern)) = pattern {
+        Regex::new(pattern).map_err(|e| anyhow!("Invalid regex '{}' at column {}: {}", pattern, column, e))?;
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Evaluation
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn eval(expr: &Expr, context: &ExpressionContext) -> Result<Val> {
+    Ok(match expr {
+        Expr::Literal(value) => value.clone(),
+        Expr::Variable(name) => context
+            .variables
+            .get(name)
+            .map(|v| Val::Str(v.clone()))
+            .unwrap_or(Val::Null),
+        Expr::Not(inner) => Val::Bool(!eval(inner, context)?.truthy()),
+        Expr::And(left, right) => Val::Bool(eval(left, context)?.truthy() && eval(right, context)?.truthy()),
+        Expr::Or(left, right) => Val::Bool(eval(left, context)?.truthy() || eval(right, context)?.truthy()),
+        Expr::Compare(left, op, right) => {
+            let (left, right) = (eval(left, context)?, eval(right, context)?);
+            Val::Bool(compare(&left, *op, &right)?)
+        }
+        Expr::Call(function, args) => {
+            let args = args.iter().map(|a| eval(a, context)).collect::<Result<Vec<_>>>()?;
+            call(*function, &args, context)?
+        }
+    })
+}
+
+fn compare(left: &Val, op: CompareOp, right: &Val) -> Result<bool> {
+    let ordering = match op {
+        CompareOp::Matches => return regex_matches(left, right),
+        CompareOp::Contains => return Ok(left.to_string().contains(&right.to_string())),
+        _ => order(left, right),
+    };
+    Ok(match op {
+        CompareOp::Eq => ordering == Some(Ordering::Equal),
+        CompareOp::Ne => ordering != Some(Ordering::Equal),
+        CompareOp::Lt => ordering == Some(Ordering::Less),
+        CompareOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        CompareOp::Gt => ordering == Some(Ordering::Greater),
+        CompareOp::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        CompareOp::Matches | CompareOp::Contains => unreachable!(),
+    })
+}
+
+/// Numbers compare numerically, dates chronologically, everything else as
+/// text; null is only equal to null and never ordered
+fn order(left: &Val, right: &Val) -> Option<Ordering> {
+    match (left, right) {
+        (Val::Null, Val::Null) => Some(Ordering::Equal),
+        (Val::Null, _) | (_, Val::Null) => None,
+        (Val::Bool(a), Val::Bool(b)) => Some(a.cmp(b)),
+        _ => {
+            if let (Some(a), Some(b)) = (left.as_number(), right.as_number()) {
+                return a.partial_cmp(&b);
+            }
+            if let (Some(a), Some(b)) = (left.as_date(), right.as_date()) {
+                return Some(a.cmp(&b));
+            }
+            Some(left.to_string().cmp(&right.to_string()))
+        }
+    }
+}
+
+fn regex_matches(value: &Val, pattern: &Val) -> Result<bool> {
+    let pattern = pattern.to_string();
+    let regex = Regex::new(&pattern).map_err(|e| anyhow!("Invalid regex '{}': {}", pattern, e))?;
+    Ok(*value != Val::Null && regex.is_match(&value.to_string()))
+}
+
+fn call(function: Function, args: &[Val], context: &ExpressionContext) -> Result<Val> {
+    let response = context.response;
+    let response_body = || response.and_then(|r| r.response_body.as_deref());
+
+    Ok(match function {
+        Function::Len => Val::Num(match &args[0] {
+            Val::Null => 0.0,
+            Val::Str(s) => match serde_json::from_str::<JsonValue>(s) {
+                Ok(JsonValue::Array(items)) => items.len() as f64,
+                Ok(JsonValue::Object(fields)) => fields.len() as f64,
+                _ => s.chars().count() as f64,
+            },
+            other => other.to_string().chars().count() as f64,
+        }),
+        Function::Matches => Val::Bool(regex_matches(&args[0], &args[1])?),
+        Function::Contains => Val::Bool(compare(&args[0], CompareOp::Contains, &args[1])?),
+        Function::Exists => Val::Bool(args[0] != Val::Null),
+        Function::Number => match args[0].as_number() {
+            Some(n) => Val::Num(n),
+            None => return Err(anyhow!("'{}' is not a number", args[0])),
+        },
+        Function::Date => match parse_date(&args[0].to_string()) {
+            Some(millis) => Val::Num(millis as f64),
+            None => return Err(anyhow!("'{}' is not a date", args[0])),
+        },
+        Function::Lower => Val::Str(args[0].to_string().to_lowercase()),
+        Function::Upper => Val::Str(args[0].to_string().to_uppercase()),
+        Function::XPath => response_body()
+            .and_then(|body| XPathEvaluator::evaluate(body, &args[0].to_string()))
+            .map(Val::Str)
+            .unwrap_or(Val::Null),
+        Function::JsonPath => response_body()
+            .and_then(|body| JsonPathEvaluator::evaluate(body, &args[0].to_string()))
+            .map(Val::Str)
+            .unwrap_or(Val::Null),
+        Function::Header => {
+            let name = args[0].to_string();
+            response
+                .and_then(|r| r.response_headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(&name)))
+                .map(|(_, v)| Val::Str(v.clone()))
+                .unwrap_or(Val::Null)
+        }
+        Function::Status => response
+            .and_then(|r| r.status_code)
+            .map(|s| Val::Num(s as f64))
+            .unwrap_or(Val::Null),
+        Function::Body => response_body().map(|b| Val::Str(b.to_string())).unwrap_or(Val::Null),
+        Function::Duration => response.map(|r| Val::Num(r.duration_ms as f64)).unwrap_or(Val::Null),
+    })
+}
+
+/// Milliseconds since the epoch for RFC 3339 timestamps, `YYYY-MM-DD` dates
+/// and `YYYY-MM-DDTHH:MM:SS` local times (taken as UTC)
+fn parse_date(text: &str) -> Option<i64> {
+    let text = text.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(text) {
+        return Some(dt.timestamp_millis());
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Some(dt.and_utc().timestamp_millis());
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn eval_with(source: &str, variables: &HashMap<String, String>, response: Option<&StepExecutionResult>) -> bool {
+        Expression::parse(source)
+            .unwrap()
+            .evaluate(&ExpressionContext { variables, response })
+            .unwrap()
+    }
+
+    fn check(source: &str, variables: &[(&str, &str)]) -> bool {
+        eval_with(source, &vars(variables), None)
+    }
+
+    #[test]
+    fn test_boolean_operators_and_precedence() {
+        let v = [("status", "200"), ("count", "5")];
+        assert!(check("status == 200 && count > 3", &v));
+        assert!(!check("status == 200 && count > 5", &v));
+        assert!(check("status != 200 || count >= 5", &v));
+        assert!(check("!(count < 3) and not missing", &v));
+        // && binds tighter than ||
+        assert!(check("count == 1 || count == 5 && status == 200", &v));
+        assert!(!check("(count == 1 || count == 5) && status == 500", &v));
+        assert!(check("{{count}} <= 5", &v));
+    }
+
+    #[test]
+    fn test_comparisons_by_type() {
+        let v = [("version", "10"), ("name", "beta"), ("expires", "2024-03-01T12:00:00Z"), ("flag", "false")];
+        // Numeric, not lexicographic
+        assert!(check("version > 9", &v));
+        assert!(check("version == '10.0'", &v));
+        // Text
+        assert!(check("name > 'alpha' && name == \"beta\"", &v));
+        // Dates, including mixed forms
+        assert!(check("expires > '2024-02-29'", &v));
+        assert!(check("date(expires) < date('2024-03-01T13:00:00+00:00')", &v));
+        // Null handling and truthiness
+        assert!(check("missing == null && !exists(missing)", &v));
+        assert!(!check("missing < 3", &v));
+        assert!(!check("flag", &v));
+        assert!(check("name", &v));
+    }
+
+    #[test]
+    fn test_matches_len_and_contains() {
+        let v = [("token", "AB12"), ("ids", "[1,2,3]"), ("name", "hello")];
+        assert!(check("token matches '^[A-F0-9]+$'", &v));
+        assert!(check("matches(lower(token), '^ab')", &v));
+        assert!(!check("matches(missing, '.*')", &v));
+        assert!(check("len(ids) == 3 && len(name) == 5 && len(missing) == 0", &v));
+        assert!(check("name contains 'ell' && contains(upper(name), 'HE')", &v));
+    }
+
+    #[test]
+    fn test_response_functions() {
+        let response = StepExecutionResult {
+            passed: true,
+            request_body: None,
+            response_body: Some(r#"{"items": [{"id": 1}, {"id": 2}], "state": "ready"}"#.to_string()),
+            status_code: Some(201),
+            duration_ms: 120,
+            assertion_results: vec![],
+            extracted_variables: HashMap::new(),
+            error: None,
+            response_headers: [("Content-Type".to_string(), "application/json".to_string())].into(),
+            logs: vec![],
+            transfer_results: vec![],
+        };
+        let variables = vars(&[]);
+        let check = |source: &str| eval_with(source, &variables, Some(&response));
+
+        assert!(check("status() == 201 && duration() < 500"));
+        assert!(check("len(jsonpath('$.items')) == 2 && jsonpath('$.state') == 'ready'"));
+        assert!(check("header('content-type') contains 'json'"));
+        assert!(check("!exists(jsonpath('$.missing'))"));
+        assert!(check("body() contains 'ready'"));
+
+        let xml = StepExecutionResult {
+            response_body: Some("<r><total>7</total></r>".to_string()),
+            ..response.clone()
+        };
+        assert!(eval_with("xpath('//total') > 5", &variables, Some(&xml)));
+
+        // No response yet: accessors are null
+        assert!(eval_with("status() == null && !exists(xpath('//total'))", &variables, None));
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_column() {
+        let error = |source: &str| Expression::parse(source).unwrap_err().to_string();
+        assert_eq!(error("status == "), "Unexpected end of expression at column 11");
+        assert_eq!(error("(a == 1"), "Expected ')' at end of expression");
+        assert_eq!(error("a == 1 b"), "Unexpected 'b' at column 8");
+        assert_eq!(error("a = 1"), "Unexpected character '=' at column 3");
+        assert_eq!(error("size(a) > 1"), "Unknown function 'size' at column 1");
+        assert_eq!(error("len(a, b) > 1"), "len() takes 1 argument but 2 given at column 1");
+        assert_eq!(error("name == 'open"), "Unterminated string starting at column 9");
+        assert!(error("a matches '(['").starts_with("Invalid regex '([' at column 3"));
+        assert!(error("matches(a, '[')").starts_with("Invalid regex '[' at column 1"));
+    }
+}
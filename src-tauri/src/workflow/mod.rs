@@ -1,13 +1,21 @@
 // Workflow module
-// Handles workflow execution with sequential, parallel, conditional, and loop logic
+// Handles workflow execution with sequential, parallel, conditional, and loop logic,
+// and leaf nodes that send project requests and run test cases.
+// Conditions may be written in a small expression language (see `expression`)
 
 pub mod engine;
+pub mod expression;
 pub mod commands;
 
 pub use engine::{
     WorkflowExecutor, WorkflowNode, WorkflowStep, WorkflowResult, WorkflowStepResult,
-    LoopType, Condition, ConditionOperator, RetryConfig,
+    LoopType, Condition, ConditionOperator, RetryConfig, RequestNode, TestCaseNode,
+    WorkflowExtractor, WorkflowEvent, WorkflowEventSink, CallWorkflowNode, SavedWorkflow,
 };
+pub use expression::{Expression, ExpressionContext};
 
 // Re-export commands
-pub use commands::run_workflow;
+pub use commands::{
+    run_workflow, run_saved_workflow, start_workflow_run, get_workflow_run_updates,
+    abort_workflow_run,
+};
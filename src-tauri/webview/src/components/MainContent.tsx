@@ -2471,6 +2471,7 @@ const MainContent: React.FC = () => {
                                                         'Content-Type': portName.includes('12') ? 'application/soap+xml' : 'text/xml'
                                                     },
                                                     request: generateInitialXmlForOperation(op),
+                                                    targetNamespace: op.targetNamespace || svc.targetNamespace,
                                                     requestType: 'soap' as const,
                                                     bodyType: 'xml' as const
                                                 }]
@@ -214,6 +214,7 @@ export function useMessageHandler(state: MessageHandlerState) {
                                                 'Content-Type': portName.includes('12') ? 'application/soap+xml' : 'text/xml'
                                             },
                                             request: generateInitialXmlForOperation(op),
+                                            targetNamespace: op.targetNamespace || svc.targetNamespace,
                                             requestType: 'soap', // Explicitly set for WSDL operations
                                             bodyType: 'xml' // Explicitly set for WSDL operations
                                         }]
@@ -353,6 +353,7 @@ export function useTestCaseHandlers({
 
         // Find case
         let testCase: TestCase | null = null;
+        let projectDir: string | undefined;
         for (const p of projects) {
             if (p.testSuites) {
                 for (const s of p.testSuites) {
@@ -360,7 +361,10 @@ export function useTestCaseHandlers({
                     if (testCase) break;
                 }
             }
-            if (testCase) break;
+            if (testCase) {
+                projectDir = p.fileName;
+                break;
+            }
         }
 
         if (!testCase) {
@@ -379,6 +383,7 @@ export function useTestCaseHandlers({
                         caseId,
                         testCase,
                         fallbackEndpoint: testCase.steps[0]?.config?.request?.endpoint || '',
+                        projectDir,
                         stream: true
                     });
 
@@ -631,7 +631,8 @@ async function tryRustCommand(message: BridgeMessage): Promise<any | null> {
                     testCase: message.testCase,
                     variables: message.environment || {},
                     stream: message.stream !== false,
-                    fallbackEndpoint: message.fallbackEndpoint || ''
+                    fallbackEndpoint: message.fallbackEndpoint || '',
+                    projectDir: message.projectDir
                 }
             });
             // Streaming: response = { runId: '...' }
@@ -956,6 +957,7 @@ async function tryRustCommand(message: BridgeMessage): Promise<any | null> {
             return await tauriInvoke('start_coordinator', {
                 port: message.port,
                 expectedWorkers: message.expectedWorkers,
+                token: message.token,
             });
         }
 